@@ -1,7 +1,5 @@
 use std::any::Any;
-use std::borrow::Cow;
 use std::rc::Rc;
-use phf::phf_map;
 
 use mvc_lib::error::error_view_middleware::ErrorViewMiddleware;
 use mvc_lib::error::error_viewmodel_service::ErrorViewModelService;
@@ -11,7 +9,7 @@ use mvc_lib::auth::iauthroles_dbset_provider::GenericAuthRolesDbSetProvider;
 
 use mvc_lib::core::type_info::TypeInfo;
 
-use mvc_lib::controllers::icontroller::IController;
+use mvc_lib::services::routemap_service::RouteMapService;
 
 use mvc_lib::controller_action_features::local_host_only::LocalHostOnlyControllerActionFeatureMiddleware;
 use mvc_lib::middleware::authorize_controller_action_middleware::AuthorizeControllerActionFeatureMiddleware;
@@ -57,45 +55,22 @@ pub fn add_views(services: &mut ServiceCollection) {
     services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IView>(), new_dev_views_service, ServiceScope::Singleton));
 }
 
-static HTTP_OPTIONS: HttpOptions = HttpOptions { ip: Cow::Borrowed("127.0.0.1"), port: 8080, port_https: 8181 };
-const SERVING_PATHS: [&'static str; 1] = ["wwwroot/"];
-static SERVING_FILES: phf::Map<&'static str, &'static str> = phf_map! {
-    "/stacks.min.css" => "ts/node_modules/@stackoverflow/stacks/dist/css/stacks.min.css",
-    "/stacks.css" => "ts/node_modules/@stackoverflow/stacks/dist/css/stacks.css",
-};
-static FILE_PROVIDER_OPTIONS: FileProviderControllerOptions = FileProviderControllerOptions { serving_directories: &SERVING_PATHS, serving_files: &SERVING_FILES };
-
 // this is called when the program is configuring options (before it is started).
+// the options are bound from appsettings.json, which can be overridden by appsettings.{environment}.json,
+// RSHTML_ environment variables such as RSHTML_HTTP__PORT=8000, or arguments such as --http:port=8000.
 // services: the service collection to add options to.
 // args: the command line arguments.
 pub fn on_configure(services: &mut ServiceCollection, _args: Rc<Vec<String>>) -> () {
-    services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn IHttpOptions>(), |_| vec![Box::new(Rc::new(HTTP_OPTIONS.clone()) as Rc<dyn IHttpOptions>)], ServiceScope::Singleton));
-    services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn IFileProviderControllerOptions>(), |_| vec![Box::new(Rc::new(FILE_PROVIDER_OPTIONS.clone()) as Rc<dyn IFileProviderControllerOptions>)], ServiceScope::Singleton));
-
-    // services.add_instance::<HttpOptions, dyn IHttpOptions>(TypeInfo::rc_of::<dyn IHttpOptions>(), &HTTP_OPTIONS);
-    // services.add_instance::<FileProviderControllerOptions, dyn IFileProviderControllerOptions>(TypeInfo::rc_of::<dyn IFileProviderControllerOptions>(), &FILE_PROVIDER_OPTIONS);
-
-    services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn ILogHttpRequestsOptions>(), |_| vec![Box::new(Rc::new(LogHttpRequestsOptions {
-        // log_request: true,
-        // log_response: true,
-        log_request: false,
-        log_response: false,
-        // log_request_headers: true,
-        // log_response_headers: true,
-        // log_request_cookies: true,
-        // log_response_cookies: true,
-        log_request_headers: false,
-        log_response_headers: false,
-        log_request_cookies: false,
-        log_response_cookies: false,
-    }) as Rc<dyn ILogHttpRequestsOptions>)], ServiceScope::Singleton));
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IHttpOptions>(), HttpOptions::new_service_from_configuration, ServiceScope::Singleton));
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IFileProviderControllerOptions>(), FileProviderControllerOptions::new_service_from_configuration, ServiceScope::Singleton));
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ILogHttpRequestsOptions>(), LogHttpRequestsOptions::new_service_from_configuration, ServiceScope::Singleton));
 }
 
 // add controllers to the service collection. Eventually this will be done automatically.
 // services: the service collection to add the controllers to.
 pub fn add_controllers(services: &mut ServiceCollection) {
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), HomeController::new_service, ServiceScope::Singleton));
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), DevController::new_service, ServiceScope::Singleton));
+    RouteMapService::add_controller(services, nameof::name_of_type!(HomeController), HomeController::new_service);
+    RouteMapService::add_controller(services, nameof::name_of_type!(DevController), DevController::new_service);
 }
 
 // this is called when the program is configuring services (before it is started).
@@ -14,7 +14,7 @@ mvc_macro_lib::rusthtml_view_macro! {
         let controller_methods = model.methods;
     }
     
-    @html.link(url.url_action(false, Some(false), None, Some("controllers"), Some("Dev"), None, None).as_str(), "< Back to controllers list", None)
+    @html.link(url.url_action(false, Some(false), None, Some("controllers"), Some("Dev"), None, None)?.as_str(), "< Back to controllers list", None)
 
     <h1>@title</h1>
 
@@ -59,7 +59,7 @@ mvc_macro_lib::rusthtml_view_macro! {
     <ol>
     @for route in actions {
         let link_text = route.0;
-        let link_href = url.url_action(false, Some(false), None, Some("route_details"), Some("Dev"), None, Some(&RouteValuesBuilder::build_area(&route.1)));
+        let link_href = url.url_action(false, Some(false), None, Some("route_details"), Some("Dev"), None, Some(&RouteValuesBuilder::build_area(&route.1)))?;
         <li>
             @html.link(&link_href, &link_text, None)
         </li>
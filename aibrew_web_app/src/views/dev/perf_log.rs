@@ -6,7 +6,7 @@ mvc_macro_lib::rusthtml_view_macro! {
         view_context.insert_str("Title", "Performance Log - Dev".to_string());
     }
 
-    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None).as_str(), "< Back to dev routes", None)
+    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?.as_str(), "< Back to dev routes", None)
 
     <h1>@view_context.get_str("Title")</h1>
 
@@ -11,7 +11,7 @@ mvc_macro_lib::rusthtml_view_macro! {
         let controller_features = model.controller_features;
     }
     
-    @html.link(url.url_action(false, Some(false), None, Some("routes"), Some("Dev"), None, None).as_str(), "< Back to routes list", None)
+    @html.link(url.url_action(false, Some(false), None, Some("routes"), Some("Dev"), None, None)?.as_str(), "< Back to routes list", None)
 
     <h1>@title</h1>
 
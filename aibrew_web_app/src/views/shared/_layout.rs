@@ -63,7 +63,7 @@ mvc_macro_lib::rusthtml_view_macro! {
                 <environment include="Development">
                     @let is_dev_controller = is_same_action("*", "Dev", "", &page_action, &page_controller, &page_area) || is_same_action("*", "AuthRoles", "", &page_action, &page_controller, &page_area);
                     @let dev_class = format!("s-navigation--item {}", if is_dev_controller { "is-selected" } else { "" });
-                    @let dev_href = url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None);
+                    @let dev_href = url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?;
                     <li><a class=@dev_class href=@dev_href>@"Dev Tools"</a></li>
                 </environment>
             </ul>
@@ -0,0 +1,106 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+
+// the scopes a service can be registered with, matching mvc_lib::services::service_scope::ServiceScope.
+const SERVICE_SCOPES: [&str; 7] = ["Singleton", "Host", "Request", "Area", "Controller", "Scope", "AlwaysNew"];
+
+// expands #[injectable] on the impl block of a service type, generating new_service and add_to_services.
+// the constructor is called with one dependency per parameter, in the order of its parameters.
+pub(crate) fn impl_injectable(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let impl_block: syn::ItemImpl = syn::parse2(item)?;
+    let self_ty = &impl_block.self_ty;
+    let (impl_generics, _, where_clause) = impl_block.generics.split_for_impl();
+
+    let mut service_type: syn::Type = syn::parse_quote!(Self);
+    let mut scope = syn::Ident::new("Singleton", Span::call_site());
+    let mut constructor = syn::Ident::new("new", Span::call_site());
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("as") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            service_type = value.parse()?;
+        } else if meta.path.is_ident("scope") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            if !SERVICE_SCOPES.contains(&value.value().as_str()) {
+                return Err(meta.error(format!("unknown service scope \"{}\", expected one of {}", value.value(), SERVICE_SCOPES.join(", "))));
+            }
+            scope = syn::Ident::new(&value.value(), value.span());
+        } else if meta.path.is_ident("constructor") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            constructor = value.parse()?;
+        } else {
+            return Err(meta.error("expected `as`, `scope` or `constructor`"));
+        }
+        Ok(())
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+
+    let signature = impl_block.items.iter()
+        .find_map(|x| match x {
+            syn::ImplItem::Fn(method) if method.sig.ident == constructor => Some(&method.sig),
+            _ => None,
+        })
+        .ok_or_else(|| syn::Error::new_spanned(self_ty, format!("#[injectable] needs the constructor `{}` in the impl block", constructor)))?;
+
+    let mut arguments = vec![];
+    for input in signature.inputs.iter() {
+        match input {
+            syn::FnArg::Typed(parameter) => arguments.push(get_dependency(&parameter.ty)?),
+            syn::FnArg::Receiver(receiver) => return Err(syn::Error::new_spanned(receiver, "the constructor of an injectable service cannot take self")),
+        }
+    }
+
+    Ok(quote! {
+        #impl_block
+
+        impl #impl_generics #self_ty #where_clause {
+            pub fn new_service(services: &dyn mvc_lib::services::service_collection::IServiceCollection) -> Vec<Box<dyn std::any::Any>> {
+                vec![Box::new(std::rc::Rc::new(Self::#constructor(#(#arguments),*)) as std::rc::Rc<#service_type>)]
+            }
+
+            pub fn add_to_services(services: &mut mvc_lib::services::service_collection::ServiceCollection) {
+                services.add(mvc_lib::services::service_descriptor::ServiceDescriptor::new(
+                    mvc_lib::core::type_info::TypeInfo::rc_of::<#service_type>(),
+                    Self::new_service,
+                    mvc_lib::services::service_scope::ServiceScope::#scope));
+            }
+        }
+    })
+}
+
+// get the expression that gets a dependency from the services, based on the type of the constructor parameter.
+// Rc<T> is required, Vec<Rc<T>> gets every registered T and Option<Rc<T>> is None when T is not registered.
+fn get_dependency(ty: &syn::Type) -> syn::Result<TokenStream> {
+    if let Some(inner) = get_rc_type(ty) {
+        return Ok(quote! { mvc_lib::services::service_collection::ServiceCollectionExtensions::get_required_single::<#inner>(services) });
+    }
+    if let Some(inner) = get_generic_argument(ty, "Vec").and_then(get_rc_type) {
+        return Ok(quote! { mvc_lib::services::service_collection::ServiceCollectionExtensions::get_required_multiple::<#inner>(services) });
+    }
+    if let Some(inner) = get_generic_argument(ty, "Option").and_then(get_rc_type) {
+        return Ok(quote! { mvc_lib::services::service_collection::ServiceCollectionExtensions::try_get_single::<#inner>(services).ok().flatten() });
+    }
+    Err(syn::Error::new_spanned(ty, "constructor parameters of an injectable service must be Rc<T>, Vec<Rc<T>> or Option<Rc<T>>"))
+}
+
+fn get_rc_type(ty: &syn::Type) -> Option<&syn::Type> {
+    get_generic_argument(ty, "Rc")
+}
+
+// get the type argument of a type such as Rc<T>, if the last segment of its path has the given name.
+fn get_generic_argument<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => match arguments.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
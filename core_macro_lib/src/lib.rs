@@ -13,6 +13,7 @@ mod reflect_properties_macro;
 mod ihaz_attributes_macro;
 mod imodel_macro;
 mod iviewmodel_macro;
+mod injectable_macro;
 
 
 #[proc_macro]
@@ -131,6 +132,18 @@ pub fn ihaz_attributes_derive(input: proc_macro::TokenStream) -> proc_macro::Tok
     ihaz_attributes_macro::impl_ihaz_attributes(&ast).into()
 }
 
+// attribute macro for generating new_service and add_to_services for a service, used on the impl block with its constructor.
+// the impl block can be marked #[injectable(as = "dyn ITrait", scope = "Singleton", constructor = "new")], all of which are optional.
+// the constructor is called with a dependency for each of its parameters: Rc<T> parameters are required services,
+// Vec<Rc<T>> parameters are every registered service of a type and Option<Rc<T>> parameters are optional services.
+#[proc_macro_attribute]
+pub fn injectable(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match injectable_macro::impl_injectable(attr.into(), item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 // proc macro for gathering all the attributes used in a type and storing them in a field.
 #[proc_macro_attribute]
 pub fn reflect_attributes(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
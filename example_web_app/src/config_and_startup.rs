@@ -1,20 +1,25 @@
-use std::any::Any;
-use std::borrow::Cow;
 use std::rc::Rc;
-use phf::phf_map;
 
 use mvc_lib::error::error_view_middleware::ErrorViewMiddleware;
 use mvc_lib::error::error_viewmodel_service::ErrorViewModelService;
 use mvc_lib::error::log_error_handler::LogErrorHandler;
 
+use mvc_lib::auth::authorization_policy::AuthorizationPolicy;
+use mvc_lib::auth::cookie_authentication_handler::CookieAuthenticationHandler;
+use mvc_lib::auth::jwt_bearer_authentication_handler::JwtBearerAuthenticationHandler;
 use mvc_lib::auth::iauthroles_dbset_provider::GenericAuthRolesDbSetProvider;
+use mvc_lib::auth::user_manager::UserManager;
 
 use mvc_lib::core::type_info::TypeInfo;
 
-use mvc_lib::controllers::icontroller::IController;
+use mvc_lib::services::routemap_service::RouteMapService;
 
 use mvc_lib::controller_action_features::local_host_only::LocalHostOnlyControllerActionFeatureMiddleware;
+use mvc_lib::middleware::authentication_middleware::AuthenticationMiddleware;
 use mvc_lib::middleware::authorize_controller_action_middleware::AuthorizeControllerActionFeatureMiddleware;
+use mvc_lib::middleware::map_when::MapWhenMiddleware;
+use mvc_lib::middleware::session_middleware::SessionMiddleware;
+use mvc_lib::session::json_file_session_store::JsonFileSessionStore;
 
 use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection};
 use mvc_lib::services::service_scope::ServiceScope;
@@ -26,9 +31,13 @@ use mvc_lib::options::http_options::{IHttpOptions, HttpOptions};
 use mvc_lib::options::file_provider_controller_options::{IFileProviderControllerOptions, FileProviderControllerOptions};
 use mvc_lib::options::logging_services_options::{ ILogHttpRequestsOptions, LogHttpRequestsOptions };
 
-use mvc_lib::view::iview::IView;
+use mvc_lib::view::view_renderer::ViewRenderer;
 
+use crate::view_models::account::AccountInputModelBinder;
 use crate::view_models::dev::log_add::LogAddInputModelBinder;
+use crate::views::account::change_password::view_account_change_password;
+use crate::views::account::login::view_account_login;
+use crate::views::account::register::view_account_register;
 use crate::views::authroles::index::view_authroles_index;
 use crate::views::authroles::add::view_authroles_add;
 use crate::views::dev::index::view_dev_index;
@@ -42,6 +51,7 @@ use crate::views::dev::controllers::view_dev_controllers;
 use crate::views::dev::controller_details::view_dev_controller_details;
 use crate::views::dev::routes::view_dev_routes;
 use crate::views::dev::route_details::view_dev_route_details;
+use crate::views::dev::services::view_dev_services;
 use crate::views::dev::sysinfo::view_dev_sysinfo;
 use crate::views::home::index::view_home_index;
 use crate::views::learn::index::view_learn_index;
@@ -49,82 +59,56 @@ use crate::views::learn::details::view_learn_details;
 use crate::views::shared::_layout::view_shared__layout;
 use crate::views::shared::error::view_error;
 
-use crate::controllers::home_controller::HomeController;
 use crate::controllers::learn_controller::LearnController;
 use crate::controllers::dev_controller::DevController;
-use crate::controllers::authroles_controller::AuthRolesController;
 
 
-// add views to the service collection. Eventually this will be done automatically.
+// add views to the service collection with their paths as keys. Eventually this will be done automatically.
 // services: the service collection to add the views to.
 pub fn add_views(services: &mut ServiceCollection) {
-    fn new_dev_views_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![
-            view_authroles_index::new_service(),
-            view_authroles_add::new_service(),
-            view_dev_index::new_service(),
-            view_dev_log::new_service(),
-            view_dev_log_add::new_service(),
-            view_dev_log_clear::new_service(),
-            view_dev_perf_log::new_service(),
-            view_dev_views::new_service(),
-            view_dev_view_details::new_service(),
-            view_dev_controllers::new_service(),
-            view_dev_controller_details::new_service(),
-            view_dev_routes::new_service(),
-            view_dev_route_details::new_service(),
-            view_dev_sysinfo::new_service(),
-            view_home_index::new_service(),
-            view_learn_index::new_service(),
-            view_learn_details::new_service(),
-            view_shared__layout::new_service(),
-            view_error::new_service(),
-        ]
-    }
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IView>(), new_dev_views_service, ServiceScope::Singleton));
+    ViewRenderer::add_view(services, "account/change_password.rs", view_account_change_password::new_service);
+    ViewRenderer::add_view(services, "account/login.rs", view_account_login::new_service);
+    ViewRenderer::add_view(services, "account/register.rs", view_account_register::new_service);
+    ViewRenderer::add_view(services, "authroles/index.rs", view_authroles_index::new_service);
+    ViewRenderer::add_view(services, "authroles/add.rs", view_authroles_add::new_service);
+    ViewRenderer::add_view(services, "dev/index.rs", view_dev_index::new_service);
+    ViewRenderer::add_view(services, "dev/log.rs", view_dev_log::new_service);
+    ViewRenderer::add_view(services, "dev/log_add.rs", view_dev_log_add::new_service);
+    ViewRenderer::add_view(services, "dev/log_clear.rs", view_dev_log_clear::new_service);
+    ViewRenderer::add_view(services, "dev/perf_log.rs", view_dev_perf_log::new_service);
+    ViewRenderer::add_view(services, "dev/views.rs", view_dev_views::new_service);
+    ViewRenderer::add_view(services, "dev/view_details.rs", view_dev_view_details::new_service);
+    ViewRenderer::add_view(services, "dev/controllers.rs", view_dev_controllers::new_service);
+    ViewRenderer::add_view(services, "dev/controller_details.rs", view_dev_controller_details::new_service);
+    ViewRenderer::add_view(services, "dev/routes.rs", view_dev_routes::new_service);
+    ViewRenderer::add_view(services, "dev/route_details.rs", view_dev_route_details::new_service);
+    ViewRenderer::add_view(services, "dev/services.rs", view_dev_services::new_service);
+    ViewRenderer::add_view(services, "dev/sysinfo.rs", view_dev_sysinfo::new_service);
+    ViewRenderer::add_view(services, "home/index.rs", view_home_index::new_service);
+    ViewRenderer::add_view(services, "learn/index.rs", view_learn_index::new_service);
+    ViewRenderer::add_view(services, "learn/details.rs", view_learn_details::new_service);
+    ViewRenderer::add_view(services, "shared/_layout.rs", view_shared__layout::new_service);
+    ViewRenderer::add_view(services, "shared/error.rs", view_error::new_service);
 }
 
-static HTTP_OPTIONS: HttpOptions = HttpOptions { ip: Cow::Borrowed("127.0.0.1"), port: 8080, port_https: 8181 };
-const SERVING_PATHS: [&'static str; 1] = ["wwwroot/"];
-static SERVING_FILES: phf::Map<&'static str, &'static str> = phf_map! {
-    "/stacks.min.css" => "ts/node_modules/@stackoverflow/stacks/dist/css/stacks.min.css",
-    "/stacks.css" => "ts/node_modules/@stackoverflow/stacks/dist/css/stacks.css",
-};
-static FILE_PROVIDER_OPTIONS: FileProviderControllerOptions = FileProviderControllerOptions { serving_directories: &SERVING_PATHS, serving_files: &SERVING_FILES };
-
 // this is called when the program is configuring options (before it is started).
+// the options are bound from appsettings.json, which can be overridden by appsettings.{environment}.json,
+// RSHTML_ environment variables such as RSHTML_HTTP__PORT=8000, or arguments such as --http:port=8000.
 // services: the service collection to add options to.
 // args: the command line arguments.
 pub fn on_configure(services: &mut ServiceCollection, _args: Rc<Vec<String>>) -> () {
-    services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn IHttpOptions>(), |_| vec![Box::new(Rc::new(HTTP_OPTIONS.clone()) as Rc<dyn IHttpOptions>)], ServiceScope::Singleton));
-    services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn IFileProviderControllerOptions>(), |_| vec![Box::new(Rc::new(FILE_PROVIDER_OPTIONS.clone()) as Rc<dyn IFileProviderControllerOptions>)], ServiceScope::Singleton));
-
-    // services.add_instance::<HttpOptions, dyn IHttpOptions>(TypeInfo::rc_of::<dyn IHttpOptions>(), &HTTP_OPTIONS);
-    // services.add_instance::<FileProviderControllerOptions, dyn IFileProviderControllerOptions>(TypeInfo::rc_of::<dyn IFileProviderControllerOptions>(), &FILE_PROVIDER_OPTIONS);
-
-    services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn ILogHttpRequestsOptions>(), |_| vec![Box::new(Rc::new(LogHttpRequestsOptions {
-        // log_request: true,
-        // log_response: true,
-        log_request: false,
-        log_response: false,
-        // log_request_headers: true,
-        // log_response_headers: true,
-        // log_request_cookies: true,
-        // log_response_cookies: true,
-        log_request_headers: false,
-        log_response_headers: false,
-        log_request_cookies: false,
-        log_response_cookies: false,
-    }) as Rc<dyn ILogHttpRequestsOptions>)], ServiceScope::Singleton));
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IHttpOptions>(), HttpOptions::new_service_from_configuration, ServiceScope::Singleton));
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IFileProviderControllerOptions>(), FileProviderControllerOptions::new_service_from_configuration, ServiceScope::Singleton));
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ILogHttpRequestsOptions>(), LogHttpRequestsOptions::new_service_from_configuration, ServiceScope::Singleton));
 }
 
-// add controllers to the service collection. Eventually this will be done automatically.
+// add controllers to the service collection. controllers marked #[controller] are discovered automatically,
+// the rest are added by hand.
 // services: the service collection to add the controllers to.
 pub fn add_controllers(services: &mut ServiceCollection) {
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), HomeController::new_service, ServiceScope::Singleton));
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), LearnController::new_service, ServiceScope::Singleton));
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), DevController::new_service, ServiceScope::Singleton));
-    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), AuthRolesController::new_service, ServiceScope::Singleton));
+    DefaultServices::add_discovered_controllers(services);
+    RouteMapService::add_controller(services, nameof::name_of_type!(LearnController), LearnController::new_service);
+    RouteMapService::add_controller(services, nameof::name_of_type!(DevController), DevController::new_service);
 }
 
 // this is called when the program is configuring services (before it is started).
@@ -142,11 +126,18 @@ pub fn on_configure_services(services: &mut ServiceCollection) -> () {
     GenericAuthRolesDbSetProvider::add_to_services(services);
 
     AuthorizationService::add_to_services(services);
+    // only users of the local user store have a password to change, not the callers of the API with bearer tokens.
+    AuthorizationPolicy::add_to_services(services, |_| AuthorizationPolicy::new("local_user").require_claim(UserManager::USER_ID_CLAIM, vec![]));
+    CookieAuthenticationHandler::add_to_services(services);
+    JwtBearerAuthenticationHandler::add_to_services(services);
+    UserManager::add_to_services(services);
+    JsonFileSessionStore::add_to_services(services);
 
     DefaultServices::add_request_decoders(services);
     DefaultServices::add_response_encoders(services);
 
     LogAddInputModelBinder::add_to_services(services);
+    AccountInputModelBinder::add_to_services(services);
     DefaultServices::add_model_validators(services);
 
     add_views(services);
@@ -162,9 +153,19 @@ pub fn on_configure_services(services: &mut ServiceCollection) -> () {
     DefaultServices::use_response_encoders(services);
     DefaultServices::use_model_validation(services);
 
+    // keep the session of each visitor in the data folder, so it lasts when the program restarts.
+    SessionMiddleware::add_to_services(services);
+
+    // authenticate every request with the auth cookie, so the authorization below can challenge requests without one.
+    AuthenticationMiddleware::add_to_services(services);
 
+    // every action with #[authorize] is checked, wherever it is routed.
     AuthorizeControllerActionFeatureMiddleware::add_to_services(services);
-    LocalHostOnlyControllerActionFeatureMiddleware::add_to_services(services);
+
+    // the dev pages are also only served to requests from the local host.
+    MapWhenMiddleware::map(services, "/dev", vec![
+        LocalHostOnlyControllerActionFeatureMiddleware::new_service,
+    ]);
 
     ErrorViewMiddleware::add_to_services(services);
     DefaultServices::add_execute_controller_action(services);
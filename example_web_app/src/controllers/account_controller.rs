@@ -0,0 +1,170 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+use http::StatusCode;
+
+use mvc_lib::action_results::iaction_result::IActionResult;
+use mvc_lib::action_results::http_result::HttpRedirectResult;
+use mvc_lib::action_results::view_result::ViewResult;
+use mvc_lib::auth::auth_user_json_file_dbset::JsonAuthUser;
+use mvc_lib::auth::cookie_authentication_handler::CookieAuthenticationHandler;
+use mvc_lib::auth::user_manager::{IUserManager, UserManager};
+use mvc_lib::contexts::controller_context::IControllerContext;
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::model_binder::model_validation_result::ModelValidationResult;
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollectionExtensions};
+
+use mvc_lib::controllers::icontroller::IController;
+
+use mvc_lib::model_binder::imodel::IModel;
+use mvc_macro_lib::controller;
+use core_macro_lib::{IModel, IHazAttributes, reflect_attributes, reflect_properties, reflect_methods};
+use mvc_lib::model_binder::ihaz_attributes::IHazAttributes;
+use mvc_lib::model_binder::imodel_attribute::IAttribute;
+use mvc_lib::model_binder::imodel_property::IModelProperty;
+use mvc_lib::model_binder::imodel_method::IModelMethod;
+use mvc_lib::model_binder::reflected_attribute::ReflectedAttribute;
+use mvc_lib::model_binder::reflected_property::ReflectedProperty;
+use mvc_lib::model_binder::reflected_method::ReflectedMethod;
+
+use crate::view_models::account::{AccountInputModel, AccountViewModel};
+
+
+// this is the controller for registering, signing in and out, and changing passwords with the local user store.
+#[reflect_attributes]
+#[reflect_properties]
+#[derive(Clone, IHazAttributes, IModel)]
+pub struct AccountController {
+    // this is the user manager for the local user store
+    user_manager: Rc<dyn IUserManager>,
+}
+
+#[controller]
+#[reflect_methods]
+impl AccountController {
+    // create a new instance of the controller.
+    // user_manager: the user manager for the local user store
+    pub fn new(user_manager: Rc<dyn IUserManager>) -> Self {
+        Self { user_manager: user_manager }
+    }
+
+    // create a new instance of the controller as a service for a service collection.
+    // services: the collection of available services.
+    // returns: a new instance of the controller as a service in a vector.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn IUserManager>(services),
+        )) as Rc<dyn IController>)]
+    }
+
+    // get the url to go back to after signing in, which must be on this site so the login page cannot redirect elsewhere.
+    // url: the url asked for, such as the ReturnUrl of the query string.
+    fn get_safe_return_url(url: Option<String>) -> String {
+        match url {
+            Some(url) if url.starts_with('/') && !url.starts_with("//") && !url.starts_with("/\\") => url,
+            _ => "/".to_string(),
+        }
+    }
+
+    // get the ReturnUrl of the query string that the login challenge added.
+    fn get_return_url(controller_ctx: &dyn IControllerContext) -> String {
+        let url = controller_ctx.get_request_context().get_url();
+        Self::get_safe_return_url(url.query_pairs().find(|x| x.0 == "ReturnUrl").map(|x| x.1.to_string()))
+    }
+
+    // get the input of a posted account form, or empty input if it could not be read.
+    fn get_input(model_result: ModelValidationResult<AccountInputModel>) -> AccountInputModel {
+        match model_result {
+            ModelValidationResult::Ok(model) => model,
+            _ => AccountInputModel::default(),
+        }
+    }
+
+    // sign in the user and go back to the url.
+    fn sign_in(user: &JsonAuthUser, url: String, user_manager: &dyn IUserManager, controller_ctx: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        controller_ctx.sign_in(user_manager.get_claims(user), services)?;
+        Ok(Some(Rc::new(HttpRedirectResult::new_with_status(url, StatusCode::FOUND))))
+    }
+
+    // get the login view, which goes back to the page that needed it after signing in.
+    #[http_get("/account/login", name = "login")]
+    pub fn get_login(_controller: &Self, controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let view_model = Rc::new(AccountViewModel::new(String::new(), Self::get_return_url(controller_ctx), None));
+        Ok(Some(Rc::new(ViewResult::new("account/login.rs".to_string(), view_model))))
+    }
+
+    // post the login view, which checks the password and signs in.
+    #[http_post("/account/login", name = "login_post")]
+    pub fn post_login(controller: &Self, model_result: ModelValidationResult<AccountInputModel>, controller_ctx: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let input = Self::get_input(model_result);
+        let return_url = Self::get_safe_return_url(Some(input.return_url.clone()));
+        match controller.user_manager.check_password_sign_in(&input.user_name, &input.password, CookieAuthenticationHandler::now()) {
+            Ok(user) => Self::sign_in(&user, return_url, controller.user_manager.as_ref(), controller_ctx, services),
+            Err(e) => {
+                let view_model = Rc::new(AccountViewModel::new_error(input.user_name, return_url, &e.to_string()));
+                Ok(Some(Rc::new(ViewResult::new("account/login.rs".to_string(), view_model))))
+            },
+        }
+    }
+
+    // get the register view, which creates a new user.
+    #[http_get("/account/register", name = "register")]
+    pub fn get_register(_controller: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let view_model = Rc::new(AccountViewModel::new(String::new(), "/".to_string(), None));
+        Ok(Some(Rc::new(ViewResult::new("account/register.rs".to_string(), view_model))))
+    }
+
+    // post the register view, which creates the user and signs them in.
+    #[http_post("/account/register", name = "register_post")]
+    pub fn post_register(controller: &Self, model_result: ModelValidationResult<AccountInputModel>, controller_ctx: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let input = Self::get_input(model_result);
+        match controller.user_manager.create(&input.user_name, &input.password) {
+            Ok(user) => Self::sign_in(&user, "/".to_string(), controller.user_manager.as_ref(), controller_ctx, services),
+            Err(e) => {
+                let view_model = Rc::new(AccountViewModel::new_error(input.user_name, "/".to_string(), &e.to_string()));
+                Ok(Some(Rc::new(ViewResult::new("account/register.rs".to_string(), view_model))))
+            },
+        }
+    }
+
+    // get the change password view. only users that are signed in to the local user store can change their password.
+    #[authorize(policy = "local_user")]
+    #[http_get("/account/change-password", name = "change_password")]
+    pub fn get_change_password(_controller: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let view_model = Rc::new(AccountViewModel::new(String::new(), "/".to_string(), None));
+        Ok(Some(Rc::new(ViewResult::new("account/change_password.rs".to_string(), view_model))))
+    }
+
+    // post the change password view, which changes the password and security stamp of the user.
+    // the other sessions of the user are signed out by the new security stamp, so this one is signed in again.
+    #[authorize(policy = "local_user")]
+    #[http_post("/account/change-password", name = "change_password_post")]
+    pub fn post_change_password(controller: &Self, model_result: ModelValidationResult<AccountInputModel>, controller_ctx: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let input = Self::get_input(model_result);
+        let user_id = controller_ctx.get_request_context().get_auth_claims().iter()
+            .find(|x| x.get_name() == UserManager::USER_ID_CLAIM)
+            .and_then(|x| x.get_tokens().get(UserManager::USER_ID_CLAIM).cloned())
+            .unwrap_or_default();
+        match controller.user_manager.change_password(&user_id, &input.password, &input.new_password) {
+            Ok(user) => {
+                controller_ctx.sign_in(controller.user_manager.get_claims(&user), services)?;
+                let view_model = Rc::new(AccountViewModel::new_ok(user.user_name, "/".to_string(), "Your password was changed."));
+                Ok(Some(Rc::new(ViewResult::new("account/change_password.rs".to_string(), view_model))))
+            },
+            Err(e) => {
+                let view_model = Rc::new(AccountViewModel::new_error(String::new(), "/".to_string(), &e.to_string()));
+                Ok(Some(Rc::new(ViewResult::new("account/change_password.rs".to_string(), view_model))))
+            },
+        }
+    }
+
+    // this is the logout action, which signs out and goes back to the home page.
+    // it only accepts POST, so a link or image on another site cannot sign the user out.
+    #[http_post("/account/logout", name = "logout")]
+    pub fn post_logout(_controller: &Self, controller_ctx: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        controller_ctx.sign_out(services)?;
+        Ok(Some(Rc::new(HttpRedirectResult::new_with_status("/".to_string(), StatusCode::FOUND))))
+    }
+}
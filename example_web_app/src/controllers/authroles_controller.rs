@@ -9,11 +9,9 @@ use core_macro_lib::IModel;
 use core_macro_lib::reflect_attributes;
 use core_macro_lib::reflect_methods;
 use core_macro_lib::reflect_properties;
-use http::Method;
 use mvc_lib::action_results::iaction_result::IActionResult;
 use mvc_lib::contexts::controller_context::IControllerContext;
 use mvc_lib::controller_action_features::authorize::BypassOnLocalActionFilter;
-use mvc_lib::controllers::icontroller_extensions::IControllerExtensions;
 use mvc_lib::core::type_info::TypeInfo;
 use mvc_lib::auth::auth_role_json_file_dbset::AuthRoleJsonFileDbSet;
 use mvc_lib::auth::auth_role_json_file_dbset::JsonAuthRole;
@@ -39,11 +37,10 @@ use mvc_lib::action_results::view_result::ViewResult;
 use mvc_lib::controllers::icontroller::IController;
 
 use mvc_lib::controller_action_features::controller_action_feature::IControllerActionFeature;
-use mvc_lib::controller_actions::builder::ControllerActionsBuilder;
-use mvc_lib::controller_actions::controller_action::IControllerAction;
 
 use mvc_lib::controller_action_features::local_host_only::LocalHostOnlyControllerActionFeature;
 use mvc_lib::controller_action_features::authorize::AuthorizeControllerActionFeature;
+use mvc_macro_lib::controller;
 
 use crate::view_models::authroles::index::IndexViewModel;
 use crate::view_models::authroles::add::AddViewModel;
@@ -62,6 +59,7 @@ pub struct AuthRolesController {
     _auth_service: Rc<dyn IAuthorizationService>,
 }
 
+#[controller(features = "get_controller_features")]
 #[reflect_methods]
 impl AuthRolesController {
     // create a new instance of the controller.
@@ -97,7 +95,19 @@ impl AuthRolesController {
             .iter().cloned().collect()
     }
 
+    // get the features for the whole controller.
+    // the controller is only available on localhost or using the admin, dev, or owner roles.
+    pub fn get_controller_features(self: &Self) -> Vec<Rc<dyn IControllerActionFeature>> {
+        vec![
+            AuthorizeControllerActionFeature::new_service_parse("admin,dev,owner".to_string(), None, Some(vec![
+                Box::new(BypassOnLocalActionFilter::new())
+            ])),
+            LocalHostOnlyControllerActionFeature::new_service()
+        ]
+    }
+
     // get the index view, which shows all the roles.
+    #[http_get("/dev/auth-roles", name = "index")]
     pub fn get_index(self: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
         let roles = self.get_roles();
         let view_model = Rc::new(IndexViewModel::new(roles));
@@ -105,12 +115,14 @@ impl AuthRolesController {
     }
 
     // get the add role view, which allows the user to add a new role.
+    #[http_get("/dev/auth-roles/add", name = "add")]
     pub fn get_add(self: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
         let view_model = Rc::new(AddViewModel::new(String::new(), None));
         Ok(Some(Rc::new(ViewResult::new("authroles/add.rs".to_string(), view_model))))
     }
 
     // post the add role view, which allows the user to add a new role.
+    #[http_post("/dev/auth-roles/add", name = "add_post")]
     pub fn post_add(self: &Self, _: ModelValidationResult<LogAddInputModel>, controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
         let _input_model = controller_ctx.get_request_context().get_model_validation_result();
         let new_role = controller_ctx.get_request_context().get_query().get("role"); // to do: this needs to use query parameter
@@ -133,50 +145,4 @@ impl AuthRolesController {
         );
         Ok(Some(Rc::new(ViewResult::new("authroles/add.rs".to_string(), view_model))))
     }
-}
-
-impl IController for AuthRolesController {
-    fn get_route_area(self: &Self) -> String {
-        String::new()
-    }
-
-    fn get_type_name(self: &Self) -> &'static str {
-        nameof::name_of_type!(AuthRolesController)
-    }
-
-    fn get_actions(self: &Self) -> Vec<Rc<dyn IControllerAction>> {
-        let actions_builder = ControllerActionsBuilder::new(self);
-        let controller_name = IControllerExtensions::get_name(self);
-        
-        actions_builder.add("/dev/auth-roles")
-            .methods(&[Method::GET])
-            .set_name("index")
-            .set_controller_name(controller_name.clone().into())
-            .set_member_fn(None, Some(Box::new(Self::get_index)));
-
-
-        actions_builder.add("/dev/auth-roles/add")
-            .methods(&[Method::GET])
-            .set_name("add")
-            .set_controller_name(controller_name.clone().into())
-            .set_member_fn(None, Some(Box::new(Self::get_add)));
-
-
-        actions_builder.add("/dev/auth-roles/add")
-                .methods(&[Method::POST])
-                .set_name("add_post")
-                .set_controller_name(controller_name.clone().into())
-                .set_member_fn_specific_model_type(Box::new(Self::post_add));
-
-        actions_builder.build()
-    }
-
-    fn get_features(self: &Self) -> Vec<Rc<dyn IControllerActionFeature>> {
-        vec![
-            AuthorizeControllerActionFeature::new_service_parse("admin,dev,owner".to_string(), None, Some(vec![
-                Box::new(BypassOnLocalActionFilter::new())
-            ])),
-            LocalHostOnlyControllerActionFeature::new_service()
-        ]
-    }
 }
\ No newline at end of file
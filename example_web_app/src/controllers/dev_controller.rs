@@ -31,6 +31,7 @@ use mvc_lib::model_binder::model_validation_result::ModelValidationResult;
 use mvc_lib::services::routemap_service::IRouteMapService;
 use mvc_lib::services::service_collection::IServiceCollection;
 use mvc_lib::services::service_collection::ServiceCollectionExtensions;
+use mvc_lib::services::service_validator::ServiceValidationResult;
 
 use mvc_lib::contexts::controller_context::IControllerContext;
 
@@ -61,6 +62,7 @@ use crate::view_models::dev::views::ViewsViewModel;
 use crate::view_models::dev::view_details::ViewDetailsViewModel;
 use crate::view_models::dev::routes::RoutesViewModel;
 use crate::view_models::dev::route_details::RouteDetailsViewModel;
+use crate::view_models::dev::services::ServicesViewModel;
 use crate::view_models::dev::sys_info::SysInfoViewModel;
 
 
@@ -186,6 +188,14 @@ impl DevController {
         return Ok(Some(Rc::new(ViewResult::new("dev/route_details.rs".to_string(), view_model))));
     }
 
+    // this action returns a view of the registered services and the services they depend on.
+    // the graph is built once when the program is configured, since building it creates every service.
+    pub fn services(&self, _controller_ctx: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        let service_validation = ServiceCollectionExtensions::get_required_single::<ServiceValidationResult>(services);
+        let view_model = ServicesViewModel::new(service_validation.graph.clone(), service_validation.errors.clone());
+        Ok(Some(Rc::new(ViewResult::new("dev/services.rs".to_string(), Rc::new(view_model)))))
+    }
+
     // this action returns a view of the system information.
     pub fn sys_info(&self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
         let view_model = Rc::new(SysInfoViewModel::new());
@@ -271,6 +281,7 @@ impl IController for DevController {
             rc_controller_action!(controllers),
             rc_controller_action!(routes),
             rc_controller_action!(views),
+            rc_controller_action!(services),
             rc_controller_action!(sys_info),
             rc_controller_action!(log),
             rc_controller_action_validate_typed!(log_add),
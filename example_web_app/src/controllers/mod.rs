@@ -1,3 +1,4 @@
+pub mod account_controller;
 pub mod authroles_controller;
 pub mod dev_controller;
 pub mod home_controller;
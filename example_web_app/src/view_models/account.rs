@@ -0,0 +1,142 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use core_macro_lib::IHazAttributes;
+use core_macro_lib::IModel;
+use core_macro_lib::IViewModel;
+use core_macro_lib::reflect_attributes;
+use core_macro_lib::reflect_methods;
+use core_macro_lib::reflect_properties;
+use mvc_lib::contexts::irequest_context::IRequestContext;
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::model_binder::imodel::AnyIModel;
+use mvc_lib::model_binder::imodel::IModel;
+use mvc_lib::model_binder::iviewmodel::IViewModel;
+use mvc_lib::model_binder::ihaz_attributes::IHazAttributes;
+use mvc_lib::model_binder::imodel_attribute::IAttribute;
+use mvc_lib::model_binder::imodel_property::IModelProperty;
+use mvc_lib::model_binder::imodel_method::IModelMethod;
+use mvc_lib::model_binder::reflected_attribute::ReflectedAttribute;
+use mvc_lib::model_binder::reflected_property::ReflectedProperty;
+use mvc_lib::model_binder::reflected_method::ReflectedMethod;
+use mvc_lib::model_binder::imodel_binder::IModelBinder;
+use mvc_lib::model_binder::model_validation_result::ModelValidationResult;
+use mvc_lib::model_binder::url_encoded_model::UrlEncodedModel;
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection};
+use mvc_lib::services::service_descriptor::ServiceDescriptor;
+use mvc_lib::services::service_scope::ServiceScope;
+
+use crate::view_models::authroles::add::ViewModelValidationResult;
+
+
+// this is the input model for the login, register and change password forms.
+#[reflect_attributes]
+#[reflect_properties]
+#[derive(Clone, Debug, IHazAttributes, IModel, IViewModel)]
+pub struct AccountInputModel {
+    // the name of the user
+    pub user_name: String,
+    // the password of the user, or their current password when changing it
+    pub password: String,
+    // the new password when changing it
+    pub new_password: String,
+    // the url to go back to after signing in
+    pub return_url: String,
+}
+
+#[reflect_methods]
+impl AccountInputModel {
+    pub fn new(user_name: String, password: String, new_password: String, return_url: String) -> Self {
+        Self { user_name: user_name, password: password, new_password: new_password, return_url: return_url }
+    }
+
+    pub fn default() -> Self {
+        Self::new(String::default(), String::default(), String::default(), String::default())
+    }
+}
+
+// this is the model binder for the account forms. the values are decoded, since passwords can contain any character.
+pub struct AccountInputModelBinder {
+
+}
+
+impl AccountInputModelBinder {
+    pub fn new() -> Self {
+        Self { }
+    }
+
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IModelBinder>)]
+    }
+
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IModelBinder>(), AccountInputModelBinder::new_service, ServiceScope::Singleton));
+    }
+}
+
+impl IModelBinder for AccountInputModelBinder {
+    fn type_info(self: &Self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<AccountInputModel>())
+    }
+
+    fn matches(self: &Self, request_context: &dyn IRequestContext) -> bool {
+        request_context.get_method() == http::Method::POST
+    }
+
+    fn bind_model(self: &Self, request_context: &dyn IRequestContext) -> ModelValidationResult<AnyIModel> {
+        let content_type = match request_context.get_content_type() {
+            Some(content_type) => content_type,
+            None => return ModelValidationResult::OtherError(Rc::new(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Missing request body.".to_string()))),
+        };
+        let form = UrlEncodedModel::new_from_body(content_type, request_context);
+        let model = AccountInputModel::new(
+            form.get_decoded("user_name").unwrap_or_default(),
+            form.get_decoded("password").unwrap_or_default(),
+            form.get_decoded("new_password").unwrap_or_default(),
+            form.get_decoded("return_url").unwrap_or_default(),
+        );
+        ModelValidationResult::Ok(AnyIModel::new(Rc::new(model)))
+    }
+}
+
+
+// this is the view model for the login, register and change password views.
+#[derive(Clone, Debug, IHazAttributes, IModel, IViewModel)]
+#[reflect_attributes]
+#[reflect_properties]
+pub struct AccountViewModel {
+    // the name of the user to fill in the form with
+    pub user_name: String,
+    // the url to go back to after signing in
+    pub return_url: String,
+    // the validation result
+    pub validation_result: Option<ViewModelValidationResult>
+}
+
+#[reflect_methods]
+impl AccountViewModel {
+    // create a new instance of the view model
+    // user_name: the name of the user to fill in the form with
+    // return_url: the url to go back to after signing in
+    // validation_result: the validation result
+    pub fn new(user_name: String, return_url: String, validation_result: Option<ViewModelValidationResult>) -> Self {
+        Self { user_name: user_name, return_url: return_url, validation_result: validation_result }
+    }
+
+    // create a new instance of the view model with a message
+    // user_name: the name of the user to fill in the form with
+    // return_url: the url to go back to after signing in
+    // message: the message to display to the user
+    pub fn new_ok(user_name: String, return_url: String, message: &str) -> Self {
+        Self::new(user_name, return_url, Some(ViewModelValidationResult::new(false, message.to_string())))
+    }
+
+    // create a new instance of the view model with an error
+    // user_name: the name of the user to fill in the form with
+    // return_url: the url to go back to after signing in
+    // message: the message to display to the user
+    pub fn new_error(user_name: String, return_url: String, message: &str) -> Self {
+        Self::new(user_name, return_url, Some(ViewModelValidationResult::new(true, message.to_string())))
+    }
+}
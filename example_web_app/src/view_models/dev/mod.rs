@@ -6,6 +6,7 @@ pub mod log_clear;
 pub mod perf_log;
 pub mod routes;
 pub mod route_details;
+pub mod services;
 pub mod sys_info;
 pub mod views;
 pub mod view_details;
\ No newline at end of file
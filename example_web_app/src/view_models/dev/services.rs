@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mvc_lib::core::type_info::TypeInfo;
+
+use mvc_lib::model_binder::imodel::IModel;
+use mvc_lib::model_binder::iviewmodel::IViewModel;
+use core_macro_lib::{IModel, IViewModel, IHazAttributes, reflect_attributes, reflect_properties, reflect_methods};
+use mvc_lib::model_binder::ihaz_attributes::IHazAttributes;
+use mvc_lib::model_binder::imodel_attribute::IAttribute;
+use mvc_lib::model_binder::imodel_property::IModelProperty;
+use mvc_lib::model_binder::imodel_method::IModelMethod;
+use mvc_lib::model_binder::reflected_attribute::ReflectedAttribute;
+use mvc_lib::model_binder::reflected_property::ReflectedProperty;
+use mvc_lib::model_binder::reflected_method::ReflectedMethod;
+use mvc_lib::services::service_validator::{ServiceGraph, ServiceValidationErrors};
+
+
+// this is the view model for the services view
+#[reflect_attributes]
+#[reflect_properties]
+#[derive(Clone, Debug, IHazAttributes, IModel, IViewModel)]
+pub struct ServicesViewModel {
+    pub graph: ServiceGraph,
+    pub errors: Vec<String>,
+}
+
+#[reflect_methods]
+impl ServicesViewModel {
+    // create a new instance of the view model
+    pub fn new(graph: ServiceGraph, errors: Option<ServiceValidationErrors>) -> Self {
+        Self {
+            graph: graph,
+            errors: errors.map(|x| x.0.iter().map(|e| e.to_string()).collect()).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+mvc_macro_lib::rusthtml_view_macro! {
+    @use mvc_lib::view::rusthtml::helpers::stacks_html_helpers::StacksHtmlHelpers
+    @use crate::view_models::account::AccountViewModel
+    @viewstart "account/_view_start.rshtml"
+    @name "account_change_password"
+    @model AccountViewModel
+    @inject StacksHtmlHelpers::<AccountViewModel>: custom_html
+    @{
+        view_context.insert_str("Title", "Change Password".to_string());
+    }
+    
+    <h1>@view_context.get_str("Title")</h1>
+    
+    @if let Some(validation_result) = &model.validation_result {
+        let html_class = if validation_result.has_errors { "fc-error" } else { "fc-success" };
+        <p class=@html_class>@validation_result.message.clone()</p>
+    }
+
+    @custom_html.form(http::method::Method::POST, url.url_action(false, Some(false), None, Some("change_password"), Some("Account"), None, None)?.into(), Some(&HashMap::new()), || -> HtmlString {
+        @custom_html.label("password", "Current Password", None)
+        @custom_html.input("password", "password", "", None)
+        @custom_html.label("new_password", "New Password", None)
+        @custom_html.input("new_password", "password", "", None)
+        
+        @custom_html.submit("Change Password", None)
+    })
+
+    @custom_html.form(http::method::Method::POST, url.url_action(false, Some(false), None, Some("logout"), Some("Account"), None, None)?.into(), Some(&HashMap::new()), || -> HtmlString {
+        @custom_html.submit("Log out", None)
+    })
+}
@@ -0,0 +1,30 @@
+mvc_macro_lib::rusthtml_view_macro! {
+    @use mvc_lib::view::rusthtml::helpers::stacks_html_helpers::StacksHtmlHelpers
+    @use crate::view_models::account::AccountViewModel
+    @viewstart "account/_view_start.rshtml"
+    @name "account_login"
+    @model AccountViewModel
+    @inject StacksHtmlHelpers::<AccountViewModel>: custom_html
+    @{
+        view_context.insert_str("Title", "Log In".to_string());
+    }
+    
+    <h1>@view_context.get_str("Title")</h1>
+    
+    @if let Some(validation_result) = &model.validation_result {
+        let html_class = if validation_result.has_errors { "fc-error" } else { "fc-success" };
+        <p class=@html_class>@validation_result.message.clone()</p>
+    }
+
+    @custom_html.form(http::method::Method::POST, url.url_action(false, Some(false), None, Some("login"), Some("Account"), None, None)?.into(), Some(&HashMap::new()), || -> HtmlString {
+        @custom_html.label("user_name", "User Name", None)
+        @custom_html.input("user_name", "text", model.user_name.as_str(), None)
+        @custom_html.label("password", "Password", None)
+        @custom_html.input("password", "password", "", None)
+        @custom_html.hidden("return_url", model.return_url.as_str(), None)
+        
+        @custom_html.submit("Log In", None)
+    })
+
+    @custom_html.link(url.url_action(false, Some(false), None, Some("register"), Some("Account"), None, None)?.as_str(), "Register a new account", None)
+}
@@ -7,7 +7,7 @@ mvc_macro_lib::rusthtml_view_macro! {
         view_context.insert_str("Title", "Auth Roles - Dev".to_string());
     }
     
-    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None).as_str(), "< Back to dev routes list", None)
+    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?.as_str(), "< Back to dev routes list", None)
     
     <h1>@view_context.get_str("Title")</h1>
     
@@ -17,5 +17,5 @@ mvc_macro_lib::rusthtml_view_macro! {
             <li>@&role.name</li>
         }
     </ol>
-    @html.link(url.url_action(false, Some(false), None, Some("add"), Some("AuthRoles"), None, None).as_str(), "+ Add New", None)
+    @html.link(url.url_action(false, Some(false), None, Some("add"), Some("AuthRoles"), None, None)?.as_str(), "+ Add New", None)
 }
\ No newline at end of file
@@ -6,12 +6,12 @@ mvc_macro_lib::rusthtml_view_macro! {
         view_context.insert_str("Title", "Log - Dev".to_string());
     }
     
-    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None).as_str(), "< Back to dev routes list", None)
+    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?.as_str(), "< Back to dev routes list", None)
     
     <h1>@view_context.get_str("Title")</h1>
     @if model.supports_read {
         <p>@format!("There are {} log entries", model.logs.len())</p>
-        @html.link(url.url_action(false, Some(false), None, Some("log_add"), Some("Dev"), None, None).as_str(), "Add log message", None)
+        @html.link(url.url_action(false, Some(false), None, Some("log_add"), Some("Dev"), None, None)?.as_str(), "Add log message", None)
 
         //<ul>
             // @for log in model.logs.iter() {
@@ -1,4 +1,3 @@
-
 pub mod index;
 pub mod controllers;
 pub mod controller_details;
@@ -10,4 +9,5 @@ pub mod views;
 pub mod view_details;
 pub mod routes;
 pub mod route_details;
+pub mod services;
 pub mod sysinfo;
\ No newline at end of file
@@ -0,0 +1,36 @@
+mvc_macro_lib::rusthtml_view_macro! {
+    @viewstart "dev/_view_start.rshtml"
+    @name "dev_services"
+    @model crate::view_models::dev::services::ServicesViewModel
+    @{
+        view_context.insert_str("Title", "Services - Dev".to_string());
+    }
+    
+    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?.as_str(), "< Back to dev routes list", None)
+
+    <h1>@view_context.get_str("Title")</h1>
+
+    @if model.errors.len() > 0 {
+        <h3>@format!("{} problems were found:", model.errors.len())</h3>
+        <ul>
+        @for error in model.errors.iter() {
+            <li>@error</li>
+        }
+        </ul>
+    }
+
+    <p>@format!("In total there are {} services:", model.graph.nodes.len())</p>
+    <ul>
+    @for node in model.graph.nodes.iter() {
+        <li>
+            @format!("{} ({:?})", node.type_name, node.scope)
+            <ul>
+            @for dependency in node.dependencies.iter() {
+                let scope = dependency.scope.map(|x| format!("{:?}", x)).unwrap_or("not registered".to_string());
+                <li>@format!("{} ({})", dependency.type_name, scope)</li>
+            }
+            </ul>
+        </li>
+    }
+    </ul>
+}
@@ -12,7 +12,7 @@ mvc_macro_lib::rusthtml_view_macro! {
         sys.refresh_all();
     }
     
-    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None).as_str(), "< Back to dev routes list", None)
+    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?.as_str(), "< Back to dev routes list", None)
     
     <h1>@view_context.get_str("Title")</h1>
 
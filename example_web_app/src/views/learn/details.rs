@@ -6,7 +6,7 @@ mvc_macro_lib::rusthtml_view_macro! {
         view_context.insert_str("Title", "Learn Rust HTML (rshtml)".to_string());
     }
     
-    @html.link(url.url_action(false, Some(false), None, Some("index"), Some("Learn"), None, None).as_str(), "< Back to learning index", None)
+    @html.link(url.route("index", &Default::default())?.as_str(), "< Back to learning index", None)
     
     <h1>@&view_context.get_str("Title")</h1>
 
@@ -11,7 +11,7 @@ mvc_macro_lib::rusthtml_view_macro! {
     <ul>
     @for doc_name in model.learn_docs.iter() {
         // doc_name as text and as href id
-        let href = url.url_action(false, Some(false), None, Some("details"), Some("Learn"), None, Some(&RouteValuesBuilder::build_area(doc_name)));
+        let href = url.route("details", &RouteValuesBuilder::build_area(doc_name))?;
         <li>
             @html.link(href.as_str(), doc_name, None)
         </li>
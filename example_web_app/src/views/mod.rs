@@ -1,3 +1,4 @@
+pub mod account;
 pub mod authroles;
 pub mod dev;
 pub mod home;
@@ -59,7 +59,7 @@ mvc_macro_lib::rusthtml_view_macro! {
             <ul class="s-navigation ml8 fw-nowrap sm:d-none">
                 @let home_class = format!("s-navigation--item {}", is_same_action_is_selected("*", "Home", "", &page_action, &page_controller, &page_area));
                 @let learn_class = format!("s-navigation--item {}", is_same_action_is_selected("*", "Learn", "", &page_action, &page_controller, &page_area));
-                @let learn_href = url.url_action(false, Some(false), None, Some("index"), Some("Learn"), None, None);
+                @let learn_href = url.url_action(false, Some(false), None, Some("index"), Some("Learn"), None, None)?;
                 
                 <li><a class=@home_class href="/">Home</a></li>
                 <li><a class=@learn_class href=@learn_href>Learn</a></li>
@@ -67,7 +67,7 @@ mvc_macro_lib::rusthtml_view_macro! {
                 <environment include="Development">
                     @let is_dev_controller = is_same_action("*", "Dev", "", &page_action, &page_controller, &page_area) || is_same_action("*", "AuthRoles", "", &page_action, &page_controller, &page_area);
                     @let dev_class = format!("s-navigation--item {}", if is_dev_controller { "is-selected" } else { "" });
-                    @let dev_href = url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None);
+                    @let dev_href = url.url_action(false, Some(false), None, Some("index"), Some("Dev"), None, None)?;
                     <li><a class=@dev_class href=@dev_href>@"Dev Tools"</a></li>
                 </environment>
             </ul>
@@ -3,7 +3,11 @@ mvc_macro_lib::rusthtml_view_macro! {
     @name "error"
     @model mvc_lib::error::error_viewmodel_service::BasicErrorViewModel
     @{
-        view_context.insert_str("Title", "Error".to_string());
+        // a request that was not authorized is titled with its status code, such as "403 Forbidden".
+        view_context.insert_str("Title", match model.get_status_code() {
+            http::StatusCode::INTERNAL_SERVER_ERROR => "Error".to_string(),
+            status_code => status_code.to_string(),
+        });
     }
     <h1>@view_context.get_str("Title")</h1>
     <h3>@format!("{}", model.error)</h3>
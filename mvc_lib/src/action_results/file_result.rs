@@ -1,16 +1,19 @@
 use std::borrow::Cow;
 use std::fs::File;
-use std::io::Read;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
 use std::path::Path;
 use std::rc::Rc;
 
-use http::StatusCode;
+use http::{Method, StatusCode};
 
+use crate::contexts::ihttpconnection_context::IHttpConnectionContext;
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 
 use crate::action_results::iaction_result::IActionResult;
 
+use crate::http::byte_range::ByteRange;
+use crate::http::cache_validators::CacheValidators;
 use crate::services::service_collection::IServiceCollection;
 
 // this is a struct that holds the file path and the content type
@@ -18,6 +21,8 @@ use crate::services::service_collection::IServiceCollection;
 pub struct FileResult {
     pub path: Cow<'static, str>,
     pub content_type: String,
+    // the Cache-Control header to send with the file, if any.
+    pub cache_control: Option<String>,
 }
 
 impl FileResult {
@@ -27,7 +32,14 @@ impl FileResult {
     // content_type: Option<String> - the content type of the file
     // returns: FileResult - the FileResult
     pub fn new(path: Cow<'static, str>, content_type: Option<String>) -> Self {
-        Self { path: path.clone(), content_type: content_type.unwrap_or(Self::extension_to_content_type(path).to_string()) }
+        Self { path: path.clone(), content_type: content_type.unwrap_or(Self::extension_to_content_type(path).to_string()), cache_control: None }
+    }
+
+    // set the Cache-Control header to send with the file.
+    // cache_control: the value of the header, such as "public, max-age=3600".
+    pub fn with_cache_control(mut self, cache_control: Option<String>) -> Self {
+        self.cache_control = cache_control;
+        self
     }
 
     // this function takes a path and returns the content type based on the extension
@@ -40,7 +52,7 @@ impl FileResult {
         match Path::new(path.as_ref())
             .extension()
             .and_then(std::ffi::OsStr::to_str)
-            .expect("Could not get extension") {
+            .unwrap_or("") {
                 "png" => mime::IMAGE_PNG,
                 "jpg" => mime::IMAGE_JPEG,
                 "bmp" => mime::IMAGE_BMP,
@@ -50,9 +62,66 @@ impl FileResult {
                 "js" => mime::TEXT_JAVASCRIPT,
                 "json" => mime::APPLICATION_JSON,
                 "html" => mime::TEXT_HTML,
+                "pdf" => mime::APPLICATION_PDF,
+                "mp4" => "video/mp4".parse().unwrap(),
+                "webm" => "video/webm".parse().unwrap(),
+                "mp3" => "audio/mpeg".parse().unwrap(),
                 _ => mime::TEXT_PLAIN,
             }
     }
+
+    // write part of a file to the response body.
+    // file: the file to read from.
+    // start: the offset of the first byte to write.
+    // length: the number of bytes to write.
+    // body_stream: the connection to write the bytes to.
+    fn write_file_range(file: &mut File, start: u64, length: u64, body_stream: &dyn IHttpConnectionContext) -> std::io::Result<()> {
+        file.seek(SeekFrom::Start(start))?;
+        let mut remaining = length;
+        let mut buffer = [0; 4096];
+        while remaining > 0 {
+            let max_read = std::cmp::min(remaining, buffer.len() as u64) as usize;
+            let num_read = file.read(&mut buffer[..max_read])?;
+            if num_read == 0 {
+                return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "file is shorter than when the response started"));
+            }
+            body_stream.write(&buffer[..num_read])?;
+            remaining -= num_read as u64;
+        }
+        Ok(())
+    }
+
+    // write a response with several ranges of the file as a "multipart/byteranges" body.
+    // file: the file to read from.
+    // ranges: the ranges of the file to write.
+    // total_length: the length of the whole file.
+    // write_body: false if only the headers are sent.
+    fn write_multipart_ranges(self: &Self, file: &mut File, ranges: &Vec<ByteRange>, total_length: u64, response_context: &dyn IResponseContext, write_body: bool) -> std::io::Result<()> {
+        let boundary = uuid::Uuid::new_v4().simple().to_string();
+        let part_headers: Vec<String> = ranges
+            .iter()
+            .map(|x| format!("\r\n--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n", boundary, self.content_type, x.to_content_range(total_length)))
+            .collect();
+        let closing = format!("\r\n--{}--\r\n", boundary);
+        let content_length = part_headers.iter().map(|x| x.len() as u64).sum::<u64>()
+            + ranges.iter().map(|x| x.len()).sum::<u64>()
+            + closing.len() as u64;
+
+        response_context.set_status_code(StatusCode::PARTIAL_CONTENT);
+        response_context.add_header_string("Content-Type".to_string(), format!("multipart/byteranges; boundary={}", boundary));
+        response_context.add_header_string("Content-Length".to_string(), content_length.to_string());
+        if !write_body {
+            return Ok(());
+        }
+
+        let body_stream = response_context.get_connection_context();
+        for (range, part_header) in ranges.iter().zip(part_headers.iter()) {
+            body_stream.write(part_header.as_bytes())?;
+            Self::write_file_range(file, range.start, range.len(), body_stream)?;
+        }
+        body_stream.write(closing.as_bytes())?;
+        Ok(())
+    }
 }
 
 impl IActionResult for FileResult {
@@ -60,35 +129,73 @@ impl IActionResult for FileResult {
         StatusCode::OK
     }
 
-    fn configure_response(self: &Self, response_context: &dyn IResponseContext, _request_context: &dyn IRequestContext, _services: &dyn IServiceCollection) -> Result<(), Rc<dyn std::error::Error>> {
-        match File::open(self.path.as_ref()) {
-            Ok(f) => {
-                response_context.set_status_code(StatusCode::OK);
-                response_context.add_header_str("Content-Type", &self.content_type);
-                let mut reader = std::io::BufReader::new(f);
-                let body_stream = response_context.get_connection_context();
-                let mut buffer = [0; 4096];
-                loop {
-                    match reader.read(&mut buffer) {
-                        Ok(num_read) => {
-                            if num_read == 0 {
-                                break;
-                            }
-                            match body_stream.write(&buffer[0..num_read]) {
-                                Ok(_) => {},
-                                Err(_error) => break,
-                            }
-                        },
-                        Err(_error) => break,
-                    }
-                }
-                Ok(())
-            },
+    fn configure_response(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, _services: &dyn IServiceCollection) -> Result<(), Rc<dyn std::error::Error>> {
+        let (mut file, metadata) = match File::open(self.path.as_ref()).and_then(|f| f.metadata().map(|m| (f, m))) {
+            Ok(file_and_metadata) => file_and_metadata,
             Err(_error) => {
                 // println!("Error opening file: {}", error);
                 response_context.set_status_code(StatusCode::NOT_FOUND);
-                Ok(())
+                return Ok(());
             }
+        };
+
+        let total_length = metadata.len();
+        let validators = CacheValidators::from_metadata(&metadata);
+        response_context.add_header_str("ETag", &validators.etag);
+        response_context.add_header_string("Last-Modified".to_string(), validators.get_last_modified_string());
+        if let Some(cache_control) = self.cache_control.as_ref() {
+            response_context.add_header_str("Cache-Control", cache_control);
+        }
+
+        let request_headers = request_context.get_headers();
+        if validators.is_not_modified(&request_headers) {
+            response_context.set_status_code(StatusCode::NOT_MODIFIED);
+            return Ok(());
+        }
+
+        response_context.add_header_str("Accept-Ranges", "bytes");
+        let method = request_context.get_method();
+        let ranges = match request_headers.get(http::header::RANGE).and_then(|x| x.to_str().ok()) {
+            Some(range) if method == Method::GET && validators.is_range_current(&request_headers) => ByteRange::parse(range, total_length),
+            _ => None,
+        };
+        let write_body = method != Method::HEAD;
+
+        let result = match ranges {
+            None => {
+                response_context.set_status_code(StatusCode::OK);
+                response_context.add_header_str("Content-Type", &self.content_type);
+                // the length lets small files skip compression and be sent without chunks.
+                response_context.add_header_string("Content-Length".to_string(), total_length.to_string());
+                if write_body { Self::write_file_range(&mut file, 0, total_length, response_context.get_connection_context()) } else { Ok(()) }
+            },
+            Some(ranges) if ranges.len() == 0 => {
+                response_context.set_status_code(StatusCode::RANGE_NOT_SATISFIABLE);
+                response_context.add_header_string("Content-Range".to_string(), format!("bytes */{}", total_length));
+                response_context.add_header_str("Content-Length", "0");
+                Ok(())
+            },
+            Some(ranges) if ranges.len() == 1 => {
+                let range = ranges[0];
+                response_context.set_status_code(StatusCode::PARTIAL_CONTENT);
+                response_context.add_header_str("Content-Type", &self.content_type);
+                response_context.add_header_string("Content-Range".to_string(), range.to_content_range(total_length));
+                response_context.add_header_string("Content-Length".to_string(), range.len().to_string());
+                Self::write_file_range(&mut file, range.start, range.len(), response_context.get_connection_context())
+            },
+            Some(ranges) => self.write_multipart_ranges(&mut file, &ranges, total_length, response_context, write_body),
+        };
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => {
+                // the body was only partly written, so the connection cannot be used for another request.
+                response_context.get_connection_context().set_keep_alive(false);
+                match error.kind() {
+                    // the client may close the connection part way through, which is not an error of the response.
+                    ErrorKind::BrokenPipe | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => Ok(()),
+                    _ => Err(Rc::new(error)),
+                }
+            },
         }
     }
-}
\ No newline at end of file
+}
@@ -16,11 +16,19 @@ use crate::services::service_collection::ServiceCollectionExtensions;
 #[derive(Debug)]
 pub struct HttpRedirectResult {
     pub redirect_target: String,
+    // the redirect status code, such as 307 Temporary Redirect or 302 Found.
+    pub status_code: StatusCode,
 }
 
 impl HttpRedirectResult {
     pub fn new(redirect_target: String) -> Self {
-        Self { redirect_target: redirect_target }
+        Self::new_with_status(redirect_target, StatusCode::TEMPORARY_REDIRECT)
+    }
+
+    // create a redirect with a status code other than 307 Temporary Redirect, such as 302 Found
+    // so the browser follows a redirect after a POST with a GET.
+    pub fn new_with_status(redirect_target: String, status_code: StatusCode) -> Self {
+        Self { redirect_target: redirect_target, status_code: status_code }
     }
 
     pub fn default() -> Self {
@@ -30,7 +38,7 @@ impl HttpRedirectResult {
 
 impl IActionResult for HttpRedirectResult {
     fn get_statuscode(self: &Self) -> StatusCode {
-        StatusCode::TEMPORARY_REDIRECT
+        self.status_code
     }
 
     fn configure_response(self: &Self, response_context: &dyn IResponseContext, _request_context: &dyn IRequestContext, _services: &dyn IServiceCollection) -> Result<(), Rc<dyn std::error::Error>> {
@@ -64,7 +72,9 @@ impl IActionResult for RedirectToActionResult {
         let route_map_service = ServiceCollectionExtensions::get_required_single::<dyn IRouteMapService>(services);
         let action = route_map_service.get_mapper().get_action(self.action_name.as_str(), self.controller_name.as_str(), self.area_name.as_str());
         // generate the redirect url from the route values.
-        let redirect_url = action.as_ref().get_route_pattern().gen_url(self.route_values.as_ref().unwrap());
+        let redirect_url = action.as_ref().get_route_pattern()
+            .gen_url_with_services(&self.route_values.clone().unwrap_or_default(), Some(services))
+            .map_err(|err| Rc::new(err) as Rc<dyn std::error::Error>)?;
         // configure the response to redirect to the redirect url.
         response_context.add_header_string("Location".to_string(), redirect_url);
         Ok(())
@@ -128,4 +138,30 @@ impl IActionResult for OkResult {
             Err(err) => Err(Rc::new(err)),
         }
     }
-}
\ No newline at end of file
+}
+
+// this is a struct that holds a status code and a message to write as the response body.
+#[derive(Clone, Debug)]
+pub struct StatusCodeResult {
+    pub status_code: StatusCode,
+    pub message: String,
+}
+
+impl StatusCodeResult {
+    pub fn new(status_code: StatusCode, message: String) -> Self {
+        Self { status_code: status_code, message: message }
+    }
+}
+
+impl IActionResult for StatusCodeResult {
+    fn get_statuscode(self: &Self) -> StatusCode {
+        self.status_code
+    }
+
+    fn configure_response(self: &Self, response_context: &dyn IResponseContext, _request_context: &dyn IRequestContext, _services: &dyn IServiceCollection) -> Result<(), Rc<dyn std::error::Error>> {
+        match response_context.get_connection_context().write_str(self.message.as_str()) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Rc::new(err)),
+        }
+    }
+}
@@ -56,7 +56,7 @@ impl IActionResult for RedirectActionResult {
             self.route_values.as_ref(),
             Some(request_context),
             services,
-        );
+        ).map_err(|err| std::rc::Rc::new(err) as std::rc::Rc<dyn std::error::Error>)?;
         response_context.add_header_string("Location".to_string(), url);
         Ok(())
     }
@@ -18,21 +18,29 @@ use crate::services::service_collection::{IServiceCollection, ServiceCollectionE
 pub struct ViewResult {
     pub path: String,
     pub model: Option<Rc<dyn IViewModel>>,
+    // the status code the view is written with, which is 200 unless it is an error view.
+    pub status_code: StatusCode,
 }
 
 impl ViewResult {
     pub fn new(path: String, model: Rc<dyn IViewModel>) -> Self {
-        Self { path: path, model: Some(model) }
+        Self { path: path, model: Some(model), status_code: StatusCode::OK }
     }
 
     // this function creates a new ViewResult with no model
     pub fn new_no_model(path: String) -> Self {
-        Self { path: path, model: None }
+        Self { path: path, model: None, status_code: StatusCode::OK }
     }
 
     // this function creates a new ViewResult with a specified model and default path
     pub fn new_default_path(model: Rc<dyn IViewModel>) -> Self {
-        Self { path: "".to_string(), model: Some(model) }
+        Self { path: "".to_string(), model: Some(model), status_code: StatusCode::OK }
+    }
+
+    // set the status code the view is written with, such as 403 for an error view of a request that is not allowed.
+    pub fn with_status_code(mut self, status_code: StatusCode) -> Self {
+        self.status_code = status_code;
+        self
     }
 
     // write the view result to the response body
@@ -52,7 +60,7 @@ impl ViewResult {
 
 impl IActionResult for ViewResult {
     fn get_statuscode(self: &Self) -> StatusCode {
-        StatusCode::OK
+        self.status_code
     }
 
     fn configure_response(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<(), Rc<dyn std::error::Error>> {
@@ -1,7 +1,133 @@
-use std::vec::Vec;
+use std::cell::RefCell;
+use std::error::Error;
+use std::panic::AssertUnwindSafe;
+use std::rc::Rc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
-// trait for a background service that can be run in a separate thread than the main thread
+use crate::core::cancellation_token::CancellationToken;
+use crate::diagnostics::logging::logging_service::ILoggingService;
+
+
+// trait for a background service that can be run in a separate thread than the main thread.
+// background services are added to the service collection as dyn BackgroundService and are started
+// on their own threads when the web program starts. each thread configures its own services, so a
+// background service only ever shares state with the services resolved on its own thread.
 pub trait BackgroundService {
+    // get the name of the background service, used when logging.
+    fn get_name(self: &Self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+
+    // get how often the background service is run.
+    // if None, the service is run once and should return when the cancellation token is cancelled.
+    // if Some, the service is run again every interval until the cancellation token is cancelled.
+    fn get_interval(self: &Self) -> Option<Duration> {
+        None
+    }
+
     // run the background service
-    fn run(self: &Self, args: Vec<String>);
-}
\ No newline at end of file
+    // cancellation_token: cancelled when the web program is stopping.
+    fn run(self: &Self, cancellation_token: &CancellationToken) -> Result<(), Rc<dyn Error>>;
+}
+
+// this struct runs background services on their own threads and stops them when the program stops.
+pub struct BackgroundServiceHost {
+    // cancelled when the background services should stop.
+    cancellation_token: CancellationToken,
+    // the threads running background services.
+    threads: RefCell<Vec<JoinHandle<()>>>,
+}
+
+impl BackgroundServiceHost {
+    // create a new background service host with no running services.
+    pub fn new() -> Self {
+        Self {
+            cancellation_token: CancellationToken::new(),
+            threads: RefCell::new(Vec::new()),
+        }
+    }
+
+    // get the cancellation token that is cancelled when the background services should stop.
+    pub fn get_cancellation_token(self: &Self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    // get the number of threads started by the host.
+    pub fn count(self: &Self) -> usize {
+        self.threads.borrow().len()
+    }
+
+    // start a thread for a background service.
+    // name: the name of the thread.
+    // run: called on the new thread with the cancellation token of the host.
+    pub fn spawn<F>(self: &Self, name: String, run: F) where F: FnOnce(CancellationToken) + Send + 'static {
+        let cancellation_token = self.get_cancellation_token();
+        let result = std::thread::Builder::new()
+            .name(name.clone())
+            .spawn(move || run(cancellation_token));
+        match result {
+            Ok(thread) => self.threads.borrow_mut().push(thread),
+            Err(e) => println!("could not start background service thread {}: {}", name, e),
+        }
+    }
+
+    // ask the background services to stop.
+    pub fn cancel(self: &Self) {
+        self.cancellation_token.cancel();
+    }
+
+    // ask the background services to stop and wait up to timeout for them to finish.
+    // timeout: how long to wait for the background services.
+    // returns: true if every background service finished in time.
+    pub fn stop(self: &Self, timeout: Duration) -> bool {
+        self.cancel();
+        let deadline = Instant::now() + timeout;
+        let mut threads = self.threads.replace(Vec::new());
+        loop {
+            let (finished, busy): (Vec<JoinHandle<()>>, Vec<JoinHandle<()>>) = threads.into_iter().partition(|x| x.is_finished());
+            for thread in finished {
+                let _ = thread.join();
+            }
+
+            threads = busy;
+            if threads.len() == 0 {
+                return true;
+            } else if Instant::now() >= deadline {
+                println!("{} background services did not stop within {:?}", threads.len(), timeout);
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    // run a background service until it is done or the cancellation token is cancelled.
+    // failures and panics are reported to the logger and do not stop a periodic service from running again.
+    // service: the background service to run.
+    // logger: where failures are reported, or None to print them.
+    // cancellation_token: cancelled when the service should stop.
+    pub fn run_service(service: &dyn BackgroundService, logger: Option<Rc<dyn ILoggingService>>, cancellation_token: &CancellationToken) {
+        let name = service.get_name();
+        let log_error = |message: String| match logger.as_ref() {
+            Some(logger) => logger.log_error(&message),
+            None => println!("{}", message),
+        };
+
+        loop {
+            match std::panic::catch_unwind(AssertUnwindSafe(|| service.run(cancellation_token))) {
+                Ok(Ok(_)) => {},
+                Ok(Err(e)) => log_error(format!("background service {} failed: {}", name, e)),
+                Err(_) => log_error(format!("background service {} panicked", name)),
+            }
+
+            match service.get_interval() {
+                Some(interval) => {
+                    if cancellation_token.wait(interval) {
+                        break;
+                    }
+                },
+                None => break,
+            }
+        }
+    }
+}
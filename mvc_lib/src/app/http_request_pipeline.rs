@@ -1,19 +1,22 @@
-use std::any::Any;
 use std::error::Error;
 use std::result::Result;
 use std::rc::Rc;
 
+use core_macro_lib::injectable;
+
 use crate::app::ihttp_request_pipeline::IHttpRequestPipeline;
 use crate::contexts::ihttpconnection_context::IHttpConnectionContext;
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 use crate::diagnostics::logging::logging_service::ILoggingService;
-use crate::diagnostics::logging::logging_service::LoggingService;
 use crate::error::error_handler_service::IErrorHandlerService;
+use crate::errors::BadRequestError;
 use crate::errors::RequestError;
+use crate::http::chunked_body_stream::ChunkedBodyStreamFormat;
+use crate::http::http_body_content::ContentType;
+use crate::http::http_body_format_service::IHttpBodyFormatService;
 
 use crate::services::service_collection::IServiceCollection;
-use crate::services::service_collection::ServiceCollection;
 use crate::services::service_collection::ServiceCollectionExtensions;
 use crate::services::request_middleware_service::IRequestMiddlewareService;
 
@@ -21,18 +24,16 @@ use crate::options::http_options::IHttpOptions;
 
 use crate::contexts::request_context::RequestContext;
 use crate::contexts::response_context::ResponseContext;
-use crate::services::service_descriptor::ServiceDescriptor;
-use crate::services::service_scope::ServiceScope;
 
 // this is a struct that implements IHttpRequestPipeline.
 pub struct HttpRequestPipeline {
-    #[allow(dead_code)]
     options: Rc<dyn IHttpOptions>,
     _logger_service: Rc<dyn ILoggingService>,
     // times_called: RefCell<i32>,
     error_handler_service: Rc<dyn IErrorHandlerService>,
 }
 
+#[injectable(as = "dyn IHttpRequestPipeline", scope = "Request")]
 impl HttpRequestPipeline {
     pub fn new(
         options: Rc<dyn IHttpOptions>,
@@ -49,21 +50,6 @@ impl HttpRequestPipeline {
         }
     }
 
-    // creates HTTP request pipeline as a service.
-    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![Box::new(Rc::new(Self::new(
-            ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(services.clone()),
-            LoggingService::get_service(services),
-            // ServiceCollectionExtensions::get_required_multiple::<dyn IHttpErrorHandler>(services),
-            ServiceCollectionExtensions::get_required_single::<dyn IErrorHandlerService>(services.clone()),
-        )) as Rc<dyn IHttpRequestPipeline>)]
-    }
-
-    // adds the HTTP request pipeline to the given service collection.
-    pub fn add_to_services(services: &mut ServiceCollection) {
-        services.add(ServiceDescriptor::new_from::<dyn IHttpRequestPipeline, Self>(Self::new_service, ServiceScope::Request));
-    }
-
     /// Process the request using the middleware.
     /// 
     /// # Arguments
@@ -92,6 +78,113 @@ impl HttpRequestPipeline {
         Ok(())
     }
 
+    // Decide whether the connection stays open after the response and tell the client.
+    // The connection context starts out allowing keep-alive if the host allows it for this connection,
+    // and the request can then only turn it off.
+    // HTTP/1.1 connections are persistent unless the client sends "Connection: close",
+    // and HTTP/1.0 connections are closed unless the client sends "Connection: keep-alive".
+    fn negotiate_keep_alive(self: &Self, connection_context: &dyn IHttpConnectionContext, request_context: &dyn IRequestContext) {
+        let connection_header = request_context.get_headers()
+            .get("Connection")
+            .and_then(|x| x.to_str().ok())
+            .map(|x| x.to_lowercase())
+            .unwrap_or_default();
+        let connection_options = connection_header.split(',').map(|x| x.trim()).collect::<Vec<&str>>();
+
+        let requested = match request_context.get_http_version() {
+            http::Version::HTTP_11 => !connection_options.contains(&"close"),
+            http::Version::HTTP_10 => connection_options.contains(&"keep-alive"),
+            _ => false,
+        };
+
+        // without a Content-Length or chunked transfer coding the end of the request body cannot be found,
+        // so the connection cannot be reused.
+        let has_unknown_body_length = match Self::get_transfer_encoding(request_context) {
+            Some(transfer_encoding) => transfer_encoding != "chunked",
+            None => false,
+        };
+
+        let keep_alive = connection_context.get_keep_alive() && requested && !has_unknown_body_length;
+        connection_context.set_keep_alive(keep_alive);
+
+        if keep_alive {
+            connection_context.set_header_str("Connection", "keep-alive");
+            if request_context.get_http_version() == http::Version::HTTP_10 {
+                connection_context.set_header_string("Keep-Alive".to_string(), format!("timeout={}", self.options.get_keep_alive_timeout().as_secs()));
+            }
+        } else {
+            connection_context.set_header_str("Connection", "close");
+        }
+    }
+
+    // Get the transfer codings of the request body, which decide how the end of the body is found.
+    // returns: the codings in the Transfer-Encoding headers in lower case and separated by ", ", or None if there is no header.
+    // a body that is only chunked returns "chunked".
+    fn get_transfer_encoding(request_context: &dyn IRequestContext) -> Option<String> {
+        let codings = request_context.get_headers()
+            .get_all("Transfer-Encoding")
+            .iter()
+            .flat_map(|x| x.to_str().unwrap_or_default().split(','))
+            .map(|x| x.trim().to_lowercase())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<String>>();
+        if request_context.get_headers().contains_key("Transfer-Encoding") {
+            Some(codings.join(", "))
+        } else {
+            None
+        }
+    }
+
+    // Limit reads from the connection to the body of the request, so that reading the body cannot read into the next request.
+    // Chunked bodies are decoded using the body format registered for the "chunked" transfer coding.
+    // returns: an error if the Content-Length is larger than the options allow, or the body uses a transfer coding other than chunked.
+    // chunked and decompressed bodies fail while being read instead.
+    fn begin_request_body(self: &Self, connection_context: &dyn IHttpConnectionContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<(), BadRequestError> {
+        let max_body_bytes = self.options.get_max_body_bytes();
+        match Self::get_transfer_encoding(request_context) {
+            Some(transfer_encoding) if transfer_encoding == "chunked" => {
+                let content_type = ContentType::parse(&transfer_encoding);
+                let format = ServiceCollectionExtensions::try_get_single::<dyn IHttpBodyFormatService>(services)
+                    .ok()
+                    .flatten()
+                    .and_then(|x| x.resolve(content_type.clone()))
+                    .unwrap_or_else(|| Rc::new(ChunkedBodyStreamFormat::new()));
+                connection_context.get_tcp_context().begin_request_body_format(format, &content_type);
+                connection_context.get_tcp_context().limit_request_body(max_body_bytes);
+            },
+            Some(_) => {
+                // only the chunked transfer coding is supported, and without it the end of the body could only be found
+                // when the connection closes, which would read a body of any size.
+                return Err(BadRequestError::new(http::StatusCode::NOT_IMPLEMENTED, "Only the chunked transfer coding is supported".to_string()));
+            },
+            None => {
+                let content_length = request_context.get_content_length().unwrap_or(0);
+                if content_length > max_body_bytes {
+                    return Err(BadRequestError::new(http::StatusCode::PAYLOAD_TOO_LARGE, format!("Request body is larger than {} bytes", max_body_bytes)));
+                }
+                connection_context.get_tcp_context().begin_request_body(content_length);
+                // the limit also applies to the body once it is decompressed.
+                connection_context.get_tcp_context().limit_request_body(max_body_bytes);
+            },
+        }
+        Ok(())
+    }
+
+    // Respond to a request that is malformed, too slow, or too large without running the middleware.
+    // The rest of the request is not read, so the connection is closed after the response.
+    // bad_request: the status and message to respond with.
+    fn respond_bad_request(self: &Self, connection_context: &dyn IHttpConnectionContext, bad_request: &BadRequestError) -> std::io::Result<()> {
+        connection_context.set_keep_alive(false);
+        connection_context.set_response_compression(None);
+        connection_context.set_pending_status_code(bad_request.status_code);
+        connection_context.set_header_str("Connection", "close");
+        connection_context.set_header_str("Content-Type", "text/plain");
+        connection_context.set_header_string("Content-Length".to_string(), bad_request.message.len().to_string());
+        connection_context.end_reading_begin_writing()?;
+        connection_context.write_str(&bad_request.message)?;
+        connection_context.end_writing()
+    }
+
     // Set the next middleware service for each middleware service.
     // This creates a linked list of middleware services that can be used to process a request.
     // middleware: the middleware services.
@@ -121,15 +214,28 @@ impl IHttpRequestPipeline for HttpRequestPipeline {
         // println!("HttpRequestPipeline::process_request {}", self.times_called.borrow());
         // *self.times_called.borrow_mut() += 1;
         
-        let request_result = RequestContext::parse(connection_context);
+        let request_result = RequestContext::parse_with_options(connection_context, self.options.as_ref());
         match request_result {
             Ok(request_context) => {
+                self.negotiate_keep_alive(connection_context, &request_context);
+                connection_context.set_pending_http_version(request_context.get_http_version());
+                connection_context.set_pending_request_method(request_context.get_method().clone());
+                if let Err(bad_request) = self.begin_request_body(connection_context, &request_context, services) {
+                    return self.respond_bad_request(connection_context, &bad_request).map_err(|err| Rc::new(err) as Rc<dyn Error>);
+                }
+
                 let response_context = ResponseContext::new(&request_context);
                 match self.process_request_using_middleware(&response_context, &request_context, services) {
                     Ok(_) => {
                     },
                     Err(err) => {
-                        self.error_handler_service.handle_error(err, Some(&request_context), Some(&response_context))?;
+                        // a body that is too large is only found while reading it.
+                        match BadRequestError::from_error(err.as_ref()) {
+                            Some(bad_request) if !connection_context.get_has_started_writing() => {
+                                return self.respond_bad_request(connection_context, bad_request).map_err(|err| Rc::new(err) as Rc<dyn Error>);
+                            },
+                            _ => self.error_handler_service.handle_error(err, Some(&request_context), Some(&response_context))?,
+                        }
                     }
                 }
 
@@ -142,16 +248,25 @@ impl IHttpRequestPipeline for HttpRequestPipeline {
                     }
                 }
 
-                match response_context.connection_context.end_reading_begin_writing() {
+                let end_result = response_context.connection_context.end_reading_begin_writing()
+                    .and_then(|_| response_context.connection_context.end_writing())
+                    .and_then(|_| if connection_context.get_keep_alive() { connection_context.get_tcp_context().end_request_body() } else { Ok(()) });
+                match end_result {
                     Ok(_) => {
                         Ok(())
                     },
                     Err(err) => {
+                        connection_context.set_keep_alive(false);
                         self.error_handler_service.handle_error(Rc::new(err), Some(&request_context), Some(&response_context))
                     }
                 }
             },
             Err(err) => {
+                connection_context.set_keep_alive(false);
+                if let Some(bad_request) = BadRequestError::from_io_error(&err) {
+                    // the client may already be gone, so failing to respond is not an error of its own.
+                    let _ = self.respond_bad_request(connection_context, bad_request);
+                }
                 self.error_handler_service.handle_error(Rc::new(err), None, None)
             }
         }
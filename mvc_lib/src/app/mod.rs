@@ -3,4 +3,5 @@ pub mod web_api_service;
 pub mod http_request_pipeline;
 pub mod ihttp_request_pipeline;
 pub mod ihttp_error_handler;
-pub mod background_service;
\ No newline at end of file
+pub mod background_service;
+pub mod worker_pool;
\ No newline at end of file
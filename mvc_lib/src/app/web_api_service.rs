@@ -1,13 +1,15 @@
-use std::vec::Vec;
+use std::error::Error;
+use std::rc::Rc;
 
 use crate::app::background_service::BackgroundService;
+use crate::core::cancellation_token::CancellationToken;
 
 pub trait WebApiService {
 
 }
 
 impl BackgroundService for dyn WebApiService {
-    fn run(self: &Self, _args: Vec<String>) {
-        
+    fn run(self: &Self, _cancellation_token: &CancellationToken) -> Result<(), Rc<dyn Error>> {
+        Ok(())
     }
-}
\ No newline at end of file
+}
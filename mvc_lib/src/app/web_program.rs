@@ -2,23 +2,42 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::net::{TcpStream, TcpListener, Shutdown};
 use std::option::Option;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use std::vec::Vec;
 
+use rustls::ServerConfig;
+
+use crate::app::background_service::{BackgroundService, BackgroundServiceHost};
 use crate::app::ihttp_request_pipeline::IHttpRequestPipeline;
+use crate::app::worker_pool::{ClientConnection, IConnectionHandler, WorkerPool};
+
+use crate::config::configuration::Configuration;
+use crate::config::configuration_builder::ConfigurationBuilder;
 
 use crate::contexts::httpconnection_context::HttpConnectionContext;
 use crate::contexts::ihttpconnection_context::IHttpConnectionContext;
-use crate::options::http_options::IHttpOptions;
+use crate::contexts::itcpconnection_context::ITcpConnectionContext;
+use crate::contexts::tcp_connection_context::TcpConnectionContext;
+use crate::core::tls_tcpstream::TlsTcpStream;
+use crate::diagnostics::logging::logging_service::ILoggingService;
+use crate::options::http_options::{IHttpOptions, HttpOptions};
 
 use crate::services::default_services::DefaultServices;
+use crate::services::host_environment::HostEnvironment;
+use crate::services::host_application_lifetime::{HostApplicationLifetime, IHostApplicationLifetime};
+use crate::services::routemap_service::IRouteMapService;
 use crate::services::service_collection::IServiceCollection;
 use crate::services::service_collection::ServiceCollection;
 use crate::services::service_collection::ServiceCollectionExtensions;
 use crate::services::service_scope::ServiceScope;
+use crate::services::service_validator::ServiceValidationResult;
 
 // this is a trait for a class that can be used to configure and start a web program.
 pub trait IWebProgram {
     // configure is called by the host to allow the program to configure itself.
+    // the configuration is loaded from appsettings.json, appsettings.{environment}.json, RSHTML_ environment variables and the args.
     fn configure(self: &mut Self, args: Rc<Vec<String>>);
     
     // configure_services is called by the host to allow the program to add
@@ -26,12 +45,20 @@ pub trait IWebProgram {
     fn configure_services(self: &mut Self);
     
     // start is called by the host to allow the program to start itself.
+    // it serves requests until the program is asked to stop.
     fn start(self: &Self, args: Rc<Vec<String>>);
 
+    // stop is called by the host to stop the program once it no longer accepts connections.
+    // it raises the stopping and stopped lifetime events and disposes the services.
+    fn stop(self: &Self);
+
     // main is called by the host to allow the program to configure options, configure services, and start itself.
     fn main(self: &mut Self, args: Rc<Vec<String>>);
 }
 
+// how often the listener checks whether the program was asked to stop while there are no new connections.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 // this is a struct that implements IWebProgram. it uses a builder pattern to configure itself.
 pub struct WebProgram<'a> {
     on_configure_fn: Option<fn(&mut ServiceCollection, Rc<Vec<String>>)>,
@@ -39,6 +66,16 @@ pub struct WebProgram<'a> {
     onstart_fn: Option<fn(&dyn IServiceCollection)>,
     services_builder: RefCell<ServiceCollection<'a>>,
     next_client_connection_id: RefCell<u32>,
+    // the threads serving client connections, once the program has started.
+    worker_pool: RefCell<Option<WorkerPool>>,
+    // the threads running background services, once the program has started.
+    background_services: RefCell<Option<BackgroundServiceHost>>,
+    // the lifetime events of this program. worker threads share the stop request of the main program.
+    lifetime: Rc<HostApplicationLifetime>,
+    // whether to panic if problems are found in the services once they are configured.
+    validate_services: bool,
+    // the service graph, built once the services are configured. the copies made for worker threads share it instead of building it again.
+    service_validation: Option<Arc<ServiceValidationResult>>,
 }
 
 impl <'a> WebProgram<'a> {
@@ -49,9 +86,19 @@ impl <'a> WebProgram<'a> {
             on_configure_services_fn: None,
             onstart_fn: None,
             next_client_connection_id: RefCell::new(0),
+            worker_pool: RefCell::new(None),
+            background_services: RefCell::new(None),
+            lifetime: Rc::new(HostApplicationLifetime::new()),
+            validate_services: false,
+            service_validation: None,
         }
     }
 
+    // get the lifetime events of the program.
+    pub fn get_lifetime(self: &Self) -> Rc<dyn IHostApplicationLifetime> {
+        self.lifetime.clone()
+    }
+
     pub fn on_configure(self: &mut Self, on_configure_fn: fn(&mut ServiceCollection, Rc<Vec<String>>)) -> &mut Self {
         self.on_configure_fn = Some(on_configure_fn);
         self
@@ -62,12 +109,39 @@ impl <'a> WebProgram<'a> {
         self
     }
 
+    // panic with every problem found in the services once they are configured, before the program starts listening.
+    // the services are always checked for missing dependencies, dependency cycles and singletons that depend on request services.
+    pub fn validate_services(self: &mut Self) -> &mut Self {
+        self.validate_services = true;
+        self
+    }
+
     pub fn on_start(self: &mut Self, onstart_fn: fn(&dyn IServiceCollection)) -> &mut Self {
         self.onstart_fn = Some(onstart_fn);
         self
     }
 
-    pub fn client_connected(self: &Self, client: Result<TcpStream, std::io::Error>) {
+    // report actions that have the same route and HTTP methods before the program starts listening,
+    // instead of when the first request for them is routed. the first action added wins at runtime.
+    fn report_route_ambiguities(self: &Self) {
+        let services = self.services_builder.borrow();
+        let route_map = match ServiceCollectionExtensions::try_get_single::<dyn IRouteMapService>(&*services) {
+            Ok(Some(route_map)) => route_map,
+            _ => return,
+        };
+        let logger = ServiceCollectionExtensions::try_get_single::<dyn ILoggingService>(&*services).ok().flatten();
+        for ambiguity in route_map.get_route_table().get_ambiguities() {
+            match logger.as_ref() {
+                Some(logger) => logger.log_error(&ambiguity.to_string()),
+                None => println!("{}", ambiguity),
+            }
+        }
+    }
+
+    // hand an accepted client connection to a worker.
+    // client: the accepted connection, or the error accepting it.
+    // tls_config: the TLS configuration if the client connected to the https listener.
+    pub fn client_connected(self: &Self, client: Result<TcpStream, std::io::Error>, tls_config: Option<Arc<ServerConfig>>) {
         let next_client_connection_id = self.next_client_connection_id.borrow().clone();
         *self.next_client_connection_id.borrow_mut() += 1;
         match client {
@@ -75,30 +149,255 @@ impl <'a> WebProgram<'a> {
                 // stream.set_ttl(100).unwrap();
                 // stream.set_nodelay(true).unwrap();
                 // stream.set_nonblocking(true).unwrap();
-                // stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
-                // stream.set_write_timeout(Some(Duration::from_secs(2))).unwrap();
-                self.client_ready(stream, next_client_connection_id);
+                let connection = ClientConnection { stream: stream, connection_id: next_client_connection_id, tls_config: tls_config };
+                match self.worker_pool.borrow().as_ref() {
+                    Some(worker_pool) => worker_pool.execute(connection),
+                    None => self.client_ready(connection),
+                }
             },
             Err(e) => {
-                panic!("Error: {}", e);
+                // a connection that failed before it was accepted only affects that client.
+                println!("could not accept connection {}: {}", next_client_connection_id, e);
+            }
+        }
+    }
+
+    // ask the program to stop when the process receives SIGINT or SIGTERM.
+    // stop_requested: set when a signal is received.
+    fn handle_stop_signals(stop_requested: Arc<AtomicBool>) {
+        let result = ctrlc::set_handler(move || {
+            println!("Stop signal received, shutting down");
+            stop_requested.store(true, Ordering::SeqCst);
+        });
+        if let Err(e) = result {
+            println!("could not handle stop signals: {}", e);
+        }
+    }
+
+    // bind the https listener and load its certificate, if a certificate and private key are configured.
+    // options: the options with the https port, certificate, and private key.
+    // returns: the https listener and TLS configuration, or None if https is not configured.
+    fn bind_https(options: &dyn IHttpOptions) -> Option<(TcpListener, Arc<ServerConfig>)> {
+        let (certificate_path, private_key_path) = match (options.get_https_certificate_path(), options.get_https_private_key_path()) {
+            (Some(certificate_path), Some(private_key_path)) => (certificate_path, private_key_path),
+            _ => return None,
+        };
+
+        let tls_config = TlsTcpStream::load_server_config(&certificate_path, &private_key_path)
+            .unwrap_or_else(|e| panic!("could not load https certificate {}: {}", certificate_path, e));
+        let ip_and_port = format!("{}:{}", options.get_ip(), options.get_port_https());
+        println!("Hosting https at {}", ip_and_port);
+        Some((TcpListener::bind(ip_and_port).unwrap(), tls_config))
+    }
+
+    // accept connections until the program is asked to stop.
+    // listeners: the listeners to accept connections from, with the TLS configuration of https listeners.
+    fn accept_connections(self: &Self, listeners: &Vec<(TcpListener, Option<Arc<ServerConfig>>)>) -> std::io::Result<()> {
+        // the listeners do not block so the stop request is noticed without waiting for another connection.
+        for (listener, _) in listeners.iter() {
+            listener.set_nonblocking(true)?;
+        }
+
+        while !self.lifetime.is_stopping() {
+            let mut accepted = false;
+            for (listener, tls_config) in listeners.iter() {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        // accepted streams inherit non-blocking mode on some platforms.
+                        let client = stream.set_nonblocking(false).map(|_| stream);
+                        self.client_connected(client, tls_config.clone());
+                        accepted = true;
+                    },
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {},
+                    Err(e) => {
+                        self.client_connected(Err(e), None);
+                    },
+                }
+            }
+
+            if !accepted {
+                std::thread::sleep(ACCEPT_POLL_INTERVAL);
             }
         }
+        Ok(())
+    }
+
+    // call the on start function with the services of this program and raise the started event.
+    fn notify_started(self: &Self) {
+        let services = &self.services_builder.clone().into_inner();
+        if let Some(onstart_fn) = self.onstart_fn {
+            (onstart_fn)(services);
+        }
+        self.lifetime.notify_started();
+    }
+
+    // get a function that creates a configured copy of this program, to be called on another thread.
+    // services are not shared between threads, so each thread configures its own copy with its own singletons.
+    // the copies share the stop request of this program.
+    // args: the command line arguments to configure the copies with.
+    fn new_copy_factory(self: &Self, args: Rc<Vec<String>>) -> impl Fn() -> WebProgram<'static> + Clone + Send + Sync + 'static {
+        let on_configure_fn = self.on_configure_fn;
+        let on_configure_services_fn = self.on_configure_services_fn;
+        let onstart_fn = self.onstart_fn;
+        let service_validation = self.service_validation.clone();
+        let stop_requested = self.lifetime.get_stop_requested();
+        let args = args.as_ref().clone();
+
+        move || {
+            let mut copy = WebProgram::new();
+            copy.on_configure_fn = on_configure_fn;
+            copy.on_configure_services_fn = on_configure_services_fn;
+            copy.onstart_fn = onstart_fn;
+            copy.service_validation = service_validation.clone();
+            copy.lifetime = Rc::new(HostApplicationLifetime::new_shared(stop_requested.clone()));
+            copy.configure(Rc::new(args.clone()));
+            copy.configure_services();
+            copy
+        }
+    }
+
+    // create the worker pool that serves client connections.
+    // each worker configures its own copy of this program, so request scoped services never leave the thread that created them.
+    // the on start function is called on each worker so services on that thread can subscribe to its lifetime events.
+    // args: the command line arguments to configure the workers with.
+    fn start_worker_pool(self: &Self, worker_threads: usize, args: Rc<Vec<String>>) -> WorkerPool {
+        let new_copy = self.new_copy_factory(args);
+        WorkerPool::new(worker_threads, move |_worker_id| {
+            let worker = new_copy();
+            worker.notify_started();
+            Box::new(worker)
+        })
+    }
+
+    // start each background service in the service collection on its own thread.
+    // the services are resolved once here to count them, then each thread resolves its own instance
+    // from its own copy of this program.
+    // args: the command line arguments to configure the background service threads with.
+    fn start_background_services(self: &Self, args: Rc<Vec<String>>) -> BackgroundServiceHost {
+        let background_services = BackgroundServiceHost::new();
+        let count = ServiceCollectionExtensions::get_required_multiple::<dyn BackgroundService>(&*self.services_builder.borrow()).len();
+        let new_copy = self.new_copy_factory(args);
+
+        for index in 0..count {
+            let new_copy = new_copy.clone();
+            background_services.spawn(format!("background-{}", index), move |cancellation_token| {
+                let program = new_copy();
+                {
+                    let services = program.services_builder.borrow();
+                    let service = ServiceCollectionExtensions::get_required_multiple::<dyn BackgroundService>(&*services).swap_remove(index);
+                    let logger = ServiceCollectionExtensions::try_get_single::<dyn ILoggingService>(&*services).ok().flatten();
+                    BackgroundServiceHost::run_service(service.as_ref(), logger, &cancellation_token);
+                }
+                // a service that is done, or that failed and does not run again, only ends its own thread.
+                // its failures were logged by run_service, and the rest of the program keeps running,
+                // so only this thread's copy of the services is disposed of.
+                program.services_builder.borrow_mut().dispose();
+            });
+        }
+        background_services
     }
 
-    fn client_ready(&self, stream: TcpStream, connection_id: u32) {
-        let connection_context = HttpConnectionContext::new_from_stream(stream, connection_id);
+    // serve requests on a client connection until either side wants to close it, then disconnect the client.
+    fn client_ready(&self, connection: ClientConnection) {
+        let ClientConnection { stream, connection_id, tls_config } = connection;
+        let options = ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(&*self.services_builder.borrow());
+
+        // slow clients only hold on to a worker until a read or write times out.
+        let read_timeout = HttpOptions::timeout_or_none(options.get_read_timeout());
+        let write_timeout = HttpOptions::timeout_or_none(options.get_write_timeout());
+        if let Err(e) = stream.set_read_timeout(read_timeout).and_then(|_| stream.set_write_timeout(write_timeout)) {
+            println!("could not set timeouts for connection {}: {}", connection_id, e);
+            return;
+        }
 
+        // a clone of the stream is kept to wait for the next request on a persistent connection.
+        let idle_stream = if options.get_keep_alive() {
+            match stream.try_clone() {
+                Ok(idle_stream) => Some(idle_stream),
+                Err(e) => {
+                    println!("could not configure keep-alive for stream: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let tcp_connection_context = match tls_config {
+            Some(tls_config) => match TlsTcpStream::new(tls_config, stream) {
+                Ok(tls_stream) => Rc::new(TcpConnectionContext::new_from_tls_stream(tls_stream, connection_id)),
+                Err(e) => {
+                    println!("could not start TLS for connection {}: {}", connection_id, e);
+                    return;
+                }
+            },
+            None => Rc::new(TcpConnectionContext::new_from_stream(stream, connection_id)),
+        };
+        let max_requests = options.get_max_requests_per_connection();
+        let mut request_count = 0;
+        loop {
+            request_count += 1;
+
+            // each request gets its own connection context, so the response state starts out fresh.
+            // once the program is stopping, the current request is finished and then the connection is closed.
+            let connection_context = HttpConnectionContext::new(tcp_connection_context.clone());
+            connection_context.set_keep_alive(idle_stream.is_some() && request_count < max_requests && !self.lifetime.is_stopping());
+
+            self.process_request(&connection_context);
+
+            // flush the stream. this will send the response back to the client.
+            match connection_context.flush() {
+                Ok(_) => {},
+                Err(e) => {
+                    println!("could not flush stream: {}", e);
+                    break;
+                }
+            }
+
+            if !connection_context.get_keep_alive() || self.lifetime.is_stopping() {
+                break;
+            }
+
+            // a pipelined request may already have been read from the socket along with the last one.
+            if tcp_connection_context.has_buffered_input() {
+                continue;
+            }
+
+            match idle_stream.as_ref() {
+                Some(idle_stream) => {
+                    if !Self::wait_for_next_request(idle_stream, HttpOptions::timeout_or_none(options.get_keep_alive_timeout()), read_timeout) {
+                        break;
+                    }
+                },
+                None => break,
+            }
+        }
+
+        // shutdown the stream. this disconnects the client.
+        match tcp_connection_context.shutdown(Shutdown::Both) {
+            Ok(_) => {},
+            Err(e) => {
+                println!("could not shutdown stream: {}", e);
+            }
+        }
+    }
+
+    // process a single request on a connection using a new request scope.
+    fn process_request(&self, connection_context: &HttpConnectionContext) {
         let self_services = self.services_builder.borrow().clone();
-        let connection_services = ServiceCollection::new(ServiceScope::Request, &self_services, self_services.get_root().unwrap_or(&self_services));
+        let mut request_services = ServiceCollection::new(ServiceScope::Request, &self_services, self_services.get_root().unwrap_or(&self_services));
 
-        // get the request pipeline from the connection services.
-        let request_pipeline = ServiceCollectionExtensions::get_required_single::<dyn IHttpRequestPipeline>(&connection_services);
+        // get the request pipeline from the request services.
+        let request_pipeline = ServiceCollectionExtensions::get_required_single::<dyn IHttpRequestPipeline>(&request_services);
 
         // invoke the request pipeline to process the request and get the response.
-        match request_pipeline.as_ref().process_request(&connection_context, &connection_services) {
+        match request_pipeline.as_ref().process_request(connection_context, &request_services) {
             Ok(_) => {
                 // the request was processed successfully, call response written event listeners.
-                // todo: call response written event listeners.
+                // nothing is written when the client closed the connection instead of sending another request.
+                if connection_context.get_has_started_writing() {
+                    self.lifetime.notify_response_written(connection_context);
+                }
             },
             Err(e) => {
                 // unhandled error, panic. if the application shouldn't panic, then add middleware to handle the error.
@@ -106,26 +405,38 @@ impl <'a> WebProgram<'a> {
             }
         }
 
-        // flush the stream. this will send the response back to the client.
-        match connection_context.flush() {
-            Ok(_) => {},
-            Err(e) => {
-                println!("could not flush stream: {}", e);
-            }
-        }
-        
-        // shutdown the stream. this disconnects the client.
-        match connection_context.shutdown(Shutdown::Both) {
-            Ok(_) => {},
-            Err(e) => {
-                println!("could not shutdown stream: {}", e);
-            }
+        // the services of the request are disposed of as soon as the response is written.
+        request_services.dispose();
+    }
+
+    // wait until the client sends the next request on a persistent connection.
+    // idle_stream: a clone of the connection's stream.
+    // keep_alive_timeout: how long to wait for the next request.
+    // read_timeout: the timeout for reads while serving the request, which is restored once the request arrives.
+    // returns false if the client closed the connection or the keep-alive timeout passed.
+    fn wait_for_next_request(idle_stream: &TcpStream, keep_alive_timeout: Option<Duration>, read_timeout: Option<Duration>) -> bool {
+        if idle_stream.set_read_timeout(keep_alive_timeout).is_err() {
+            return false;
         }
+        let mut buf = [0; 1];
+        let has_request = match idle_stream.peek(&mut buf) {
+            Ok(n) => n > 0,
+            Err(_) => false,
+        };
+        has_request && idle_stream.set_read_timeout(read_timeout).is_ok()
     }
 }
 
 impl <'a> IWebProgram for WebProgram<'a> {
     fn configure(self: &mut Self, args: Rc<Vec<String>>) {
+        // the configuration and host environment are added first so options can be bound from them in on_configure.
+        let configuration = match ConfigurationBuilder::new_default(&args).and_then(|x| x.build()) {
+            Ok(configuration) => configuration,
+            Err(e) => panic!("could not load configuration: {}", e),
+        };
+        Configuration::add_to_services(&mut self.services_builder.borrow_mut(), Rc::new(configuration));
+        HostEnvironment::add_to_services(&mut self.services_builder.borrow_mut());
+
         (self.on_configure_fn.unwrap())(&mut self.services_builder.borrow_mut(), args);
     }
     
@@ -133,20 +444,75 @@ impl <'a> IWebProgram for WebProgram<'a> {
         (self.on_configure_services_fn.unwrap())(&mut self.services_builder.borrow_mut());
 
         DefaultServices::add_http_request_pipeline(&mut self.services_builder.borrow_mut());
-    }
+        HostApplicationLifetime::add_to_services(&mut self.services_builder.borrow_mut(), self.lifetime.clone());
 
-    fn start(self: &Self, _args: Rc<Vec<String>>) {
-        let services = &self.services_builder.clone().into_inner();
-        (self.onstart_fn.unwrap())(services);
+        // creating every service has side effects, such as creating files, so the graph is only built once.
+        let service_validation = match self.service_validation.clone() {
+            Some(service_validation) => service_validation,
+            None => Arc::new(self.services_builder.borrow().validate()),
+        };
+        if self.validate_services {
+            if let Some(e) = service_validation.errors.as_ref() {
+                panic!("{}", e);
+            }
+        }
+        ServiceValidationResult::add_to_services(&mut self.services_builder.borrow_mut(), service_validation.clone());
+        self.service_validation = Some(service_validation);
+    }
 
-        let options = ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(services);
+    fn start(self: &Self, args: Rc<Vec<String>>) {
+        let options = ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(&*self.services_builder.borrow());
+        self.report_route_ambiguities();
 
         println!("Hosting at {}", options.get_ip_and_port());
-        let listener = TcpListener::bind(options.get_ip_and_port()).unwrap();
+        let mut listeners = vec![(TcpListener::bind(options.get_ip_and_port()).unwrap(), None)];
+        if let Some((listener, tls_config)) = Self::bind_https(options.as_ref()) {
+            listeners.push((listener, Some(tls_config)));
+        }
+        Self::handle_stop_signals(self.lifetime.get_stop_requested());
+
+        let worker_pool = self.start_worker_pool(options.get_worker_threads(), args.clone());
+        println!("Serving connections on {} worker threads", worker_pool.size());
+        self.worker_pool.replace(Some(worker_pool));
 
-        for stream in listener.incoming() {
-            self.client_connected(stream);
+        let background_services = self.start_background_services(args);
+        if background_services.count() > 0 {
+            println!("Running {} background services", background_services.count());
         }
+        self.background_services.replace(Some(background_services));
+
+        self.notify_started();
+        if let Err(e) = self.accept_connections(&listeners) {
+            println!("could not accept connections: {}", e);
+        }
+
+        // stop listening before waiting for the in-flight requests.
+        drop(listeners);
+        self.stop();
+        println!("Stopped");
+    }
+
+    fn stop(self: &Self) {
+        self.lifetime.notify_stopping();
+
+        let background_services = self.background_services.replace(None);
+        if let Some(background_services) = background_services.as_ref() {
+            background_services.cancel();
+        }
+
+        if let Some(worker_pool) = self.worker_pool.replace(None) {
+            let options = ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(&*self.services_builder.borrow());
+            println!("Waiting up to {:?} for in-flight requests", options.get_shutdown_timeout());
+            worker_pool.join_timeout(options.get_shutdown_timeout());
+        }
+
+        if let Some(background_services) = background_services {
+            let options = ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(&*self.services_builder.borrow());
+            background_services.stop(options.get_shutdown_timeout());
+        }
+
+        self.lifetime.notify_stopped();
+        self.services_builder.borrow_mut().dispose();
     }
 
     fn main(self: &mut Self, args: Rc<Vec<String>>) {
@@ -154,4 +520,14 @@ impl <'a> IWebProgram for WebProgram<'a> {
         self.configure_services();
         self.start(args);
     }
-}
\ No newline at end of file
+}
+
+impl <'a> IConnectionHandler for WebProgram<'a> {
+    fn handle_connection(self: &Self, connection: ClientConnection) {
+        self.client_ready(connection);
+    }
+
+    fn worker_stopped(self: &Self) {
+        IWebProgram::stop(self);
+    }
+}
@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+use std::net::TcpStream;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use rustls::ServerConfig;
+
+
+// a client connection waiting to be served.
+pub struct ClientConnection {
+    // the client connection.
+    pub stream: TcpStream,
+    // the id of the client connection.
+    pub connection_id: u32,
+    // the TLS configuration to terminate TLS with, if the connection was accepted on the https listener.
+    pub tls_config: Option<Arc<ServerConfig>>,
+}
+
+// this trait serves client connections on a worker thread.
+pub trait IConnectionHandler {
+    // serve a client connection.
+    // connection: the client connection.
+    fn handle_connection(self: &Self, connection: ClientConnection);
+
+    // called once the pool has stopped and the worker has served its last connection.
+    fn worker_stopped(self: &Self);
+}
+
+// the connection handler owned by one worker thread.
+pub type ConnectionHandler = Box<dyn IConnectionHandler>;
+
+// this struct is a pool of threads that serve client connections concurrently.
+// services are built on Rc and RefCell, so they cannot be shared between threads. instead, each
+// worker creates its own connection handler (and with it its own service collection and singletons)
+// when it starts, and requests served by a worker only ever see that worker's services.
+pub struct WorkerPool {
+    // sends accepted connections to the workers. None once the pool stops accepting connections.
+    sender: RefCell<Option<Sender<ClientConnection>>>,
+    // the worker threads.
+    workers: RefCell<Vec<JoinHandle<()>>>,
+}
+
+impl WorkerPool {
+    // create a new worker pool and start its threads.
+    // size: the number of worker threads, at least one thread is always started.
+    // new_handler: called once on each worker thread with the worker index to create the function
+    // that serves connections on that thread.
+    // returns: the new worker pool.
+    pub fn new<F>(size: usize, new_handler: F) -> Self
+        where F: Fn(usize) -> ConnectionHandler + Send + Sync + 'static {
+        let (sender, receiver) = channel::<ClientConnection>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let new_handler = Arc::new(new_handler);
+
+        let workers = (0..std::cmp::max(size, 1))
+            .map(|worker_id| {
+                let receiver = receiver.clone();
+                let new_handler = new_handler.clone();
+                std::thread::Builder::new()
+                    .name(format!("worker-{}", worker_id))
+                    .spawn(move || Self::run_worker(worker_id, receiver, new_handler.as_ref()))
+                    .expect("could not start worker thread")
+            })
+            .collect();
+
+        Self {
+            sender: RefCell::new(Some(sender)),
+            workers: RefCell::new(workers),
+        }
+    }
+
+    // get the number of worker threads in the pool.
+    pub fn size(&self) -> usize {
+        self.workers.borrow().len()
+    }
+
+    // queue a client connection to be served by the next free worker.
+    // connection: the client connection.
+    pub fn execute(&self, connection: ClientConnection) {
+        let connection_id = connection.connection_id;
+        match self.sender.borrow().as_ref() {
+            Some(sender) => {
+                if let Err(e) = sender.send(connection) {
+                    println!("could not queue connection {}: {}", connection_id, e);
+                }
+            },
+            None => {
+                println!("worker pool is stopped, dropping connection {}", connection_id);
+            }
+        }
+    }
+
+    // stop accepting connections and wait for the workers to finish the connections they were given.
+    pub fn join(&self) {
+        self.sender.replace(None);
+        for worker in self.workers.replace(Vec::new()) {
+            Self::join_worker(worker);
+        }
+    }
+
+    // stop accepting connections and wait up to timeout for the workers to finish the connections they were given.
+    // workers that are still busy after the timeout are left running and no longer belong to the pool.
+    // timeout: how long to wait for the workers.
+    // returns: true if every worker finished in time.
+    pub fn join_timeout(&self, timeout: Duration) -> bool {
+        self.sender.replace(None);
+        let deadline = Instant::now() + timeout;
+        let mut workers = self.workers.replace(Vec::new());
+        loop {
+            let (finished, busy): (Vec<JoinHandle<()>>, Vec<JoinHandle<()>>) = workers.into_iter().partition(|x| x.is_finished());
+            for worker in finished {
+                Self::join_worker(worker);
+            }
+
+            workers = busy;
+            if workers.len() == 0 {
+                return true;
+            } else if Instant::now() >= deadline {
+                println!("{} worker threads did not finish within {:?}", workers.len(), timeout);
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn join_worker(worker: JoinHandle<()>) {
+        if let Err(_) = worker.join() {
+            println!("worker thread exited with a panic");
+        }
+    }
+
+    // serve connections until the pool is stopped.
+    fn run_worker(worker_id: usize, receiver: Arc<Mutex<Receiver<ClientConnection>>>, new_handler: &(dyn Fn(usize) -> ConnectionHandler + Send + Sync)) {
+        let handler = new_handler(worker_id);
+        loop {
+            // the lock is only held while waiting for the next connection, not while serving it.
+            let next = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => break,
+            };
+
+            match next {
+                Ok(connection) => {
+                    let connection_id = connection.connection_id;
+                    // a panic while serving one connection should not take the worker down with it.
+                    let result = std::panic::catch_unwind(AssertUnwindSafe(|| handler.handle_connection(connection)));
+                    if let Err(_) = result {
+                        println!("worker {} recovered from a panic while serving connection {}", worker_id, connection_id);
+                    }
+                },
+                Err(_) => break, // the pool was stopped
+            }
+        }
+        handler.worker_stopped();
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
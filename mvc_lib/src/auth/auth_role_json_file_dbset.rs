@@ -1,4 +1,6 @@
 use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
 
 use crate::auth::iauth_role::IAuthRole;
 
@@ -124,8 +126,8 @@ impl IDbSetAny for AuthRoleJsonFileDbSet {
         IDbSet::entity_type_name(&self.json_dbset)
     }
 
-    fn save_changes(self: &Self) {
-        self.json_dbset.save_changes();
+    fn save_changes(self: &Self) -> Result<(), Rc<dyn Error>> {
+        self.json_dbset.save_changes()
     }
 }
 
@@ -0,0 +1,103 @@
+use crate::entity::json_file_dbset::JsonFileDbSet;
+
+// this struct is used to store a single user in the authuser_dbset.json file.
+// the password is never stored, only a salted hash of it.
+#[derive(Clone, Debug)]
+pub struct JsonAuthUser {
+    // the id of the user, which never changes.
+    pub id: String,
+    // the name the user logs in with.
+    pub user_name: String,
+    // the user name in upper case, so user names are unique ignoring case.
+    pub normalized_user_name: String,
+    // the hash of the password, made by an IPasswordHasher.
+    pub password_hash: String,
+    // a random value that changes when the credentials of the user change, such as their password.
+    // sessions that were signed in with an older security stamp are no longer valid.
+    pub security_stamp: String,
+    // the names of the roles of the user, which are the names of roles in the authrole_dbset.json file.
+    pub roles: Vec<String>,
+    // how many logins in a row have failed since the last one that succeeded.
+    pub access_failed_count: u32,
+    // when the user is locked out until, in seconds since the unix epoch.
+    pub lockout_end: Option<u64>,
+}
+
+impl JsonAuthUser {
+    // this is used to create a new JsonAuthUser struct.
+    // returns a JsonAuthUser struct with a new id and security stamp.
+    pub fn new() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_name: String::new(),
+            normalized_user_name: String::new(),
+            password_hash: String::new(),
+            security_stamp: Self::new_security_stamp(),
+            roles: vec![],
+            access_failed_count: 0,
+            lockout_end: None,
+        }
+    }
+
+    // create a new random security stamp.
+    pub fn new_security_stamp() -> String {
+        uuid::Uuid::new_v4().simple().to_string()
+    }
+
+    // get the user name in the form used to compare user names.
+    // user_name: the user name to normalize.
+    pub fn normalize_user_name(user_name: &str) -> String {
+        user_name.trim().to_uppercase()
+    }
+
+    // get whether the user is locked out.
+    // now: the current time, in seconds since the unix epoch.
+    pub fn is_locked_out(self: &Self, now: u64) -> bool {
+        self.lockout_end.map(|x| now < x).unwrap_or(false)
+    }
+
+    // this is used to parse a serde_json::Value from the authuser_dbset.json file.
+    // v: the serde_json::Value to parse.
+    // returns a JsonAuthUser struct.
+    pub fn parse_json(v: serde_json::Value) -> Self {
+        let get_str = |key: &str| v.get(key).and_then(|x| x.as_str()).unwrap_or_default().to_string();
+        Self {
+            id: get_str("id"),
+            user_name: get_str("user_name"),
+            normalized_user_name: get_str("normalized_user_name"),
+            password_hash: get_str("password_hash"),
+            security_stamp: get_str("security_stamp"),
+            roles: v.get("roles").and_then(|x| x.as_array())
+                .map(|x| x.iter().filter_map(|x| x.as_str().map(|x| x.to_string())).collect())
+                .unwrap_or_default(),
+            access_failed_count: v.get("access_failed_count").and_then(|x| x.as_u64()).unwrap_or_default() as u32,
+            lockout_end: v.get("lockout_end").and_then(|x| x.as_u64()),
+        }
+    }
+
+    // this is used to convert a JsonAuthUser struct to a serde_json::Value.
+    // v: the JsonAuthUser struct to convert.
+    // returns a serde_json::Value.
+    pub fn to_json(v: Self) -> serde_json::Value {
+        serde_json::json!({
+            "id": v.id,
+            "user_name": v.user_name,
+            "normalized_user_name": v.normalized_user_name,
+            "password_hash": v.password_hash,
+            "security_stamp": v.security_stamp,
+            "roles": v.roles,
+            "access_failed_count": v.access_failed_count,
+            "lockout_end": v.lockout_end,
+        })
+    }
+}
+
+// users are the same user if they have the same id, so a changed user can replace the stored one.
+impl PartialEq for JsonAuthUser {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+// this type is used to store the authuser_dbset.json file.
+pub type AuthUserJsonFileDbSet = JsonFileDbSet<JsonAuthUser>;
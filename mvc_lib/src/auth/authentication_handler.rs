@@ -0,0 +1,121 @@
+use std::error::Error;
+use std::rc::Rc;
+
+use crate::contexts::irequest_context::IRequestContext;
+use crate::contexts::iresponse_context::IResponseContext;
+use crate::errors::AuthenticationError;
+use crate::services::authorization_service::IAuthClaim;
+use crate::services::service_collection::{IServiceCollection, ServiceCollectionExtensions};
+
+
+// this enum is the result of authenticating a request.
+pub enum AuthenticateResult {
+    // the request has nothing for the handler, such as no authentication cookie.
+    NoResult,
+    // the request was authenticated with the claims.
+    Success(Vec<Rc<dyn IAuthClaim>>),
+    // the request had something for the handler that was rejected, such as a cookie that was tampered with or has expired.
+    Fail(String),
+}
+
+// this trait is used to authenticate requests for an authentication scheme, such as cookies.
+// authentication finds who made the request, authorization then decides what they are allowed to do.
+pub trait IAuthenticationHandler {
+    // get the name of the authentication scheme, such as "Cookies".
+    fn get_scheme(self: &Self) -> String;
+
+    // authenticate the request.
+    // the handler may update the response, such as to renew or remove a cookie.
+    // request_context: the request to authenticate.
+    // response_context: the response to the request.
+    // returns: the claims of the request if it was authenticated.
+    fn authenticate(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> AuthenticateResult;
+
+    // respond to a request that needs to be authenticated but was not, such as by redirecting to the login page.
+    // request_context: the request that was not authenticated.
+    // response_context: the response to the request.
+    fn challenge(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>>;
+
+    // sign in with the claims, so later requests are authenticated with them.
+    // claims: the claims of the user.
+    // request_context: the request that signed in.
+    // response_context: the response to the request.
+    fn sign_in(self: &Self, claims: Vec<Rc<dyn IAuthClaim>>, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>>;
+
+    // sign out, so later requests are no longer authenticated.
+    // request_context: the request that signed out.
+    // response_context: the response to the request.
+    fn sign_out(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>>;
+
+    // get whether the handler can sign in and out. handlers that only read credentials issued elsewhere,
+    // such as bearer tokens, cannot.
+    fn can_sign_in(self: &Self) -> bool {
+        true
+    }
+}
+
+// this trait is used to check that the claims of an authenticated request are still valid, such as that the
+// password of the user has not changed since they signed in. requests with claims that are no longer valid
+// are signed out by the authentication middleware.
+pub trait IAuthClaimsValidator {
+    // check the claims of an authenticated request.
+    // claims: the claims of the request.
+    // returns: true if the claims are still valid, or the validator has nothing to check in them.
+    fn validate_claims(self: &Self, claims: &Vec<Rc<dyn IAuthClaim>>) -> bool;
+}
+
+// extension methods for authenticating requests with the authentication handlers.
+pub struct AuthenticationExtensions {}
+
+impl AuthenticationExtensions {
+    // get the authentication handlers for the schemes.
+    // services: the services to get the handlers from.
+    // schemes: the names of the schemes, or empty for all the handlers.
+    // returns: the handlers in the order they were added, or an error if there is no handler for one of the schemes.
+    pub fn get_handlers(services: &dyn IServiceCollection, schemes: &Vec<String>) -> Result<Vec<Rc<dyn IAuthenticationHandler>>, Rc<dyn Error>> {
+        let handlers = ServiceCollectionExtensions::get_required_multiple::<dyn IAuthenticationHandler>(services);
+        if schemes.is_empty() {
+            return Ok(handlers);
+        }
+        for scheme in schemes.iter() {
+            if !handlers.iter().any(|x| &x.get_scheme() == scheme) {
+                return Err(Rc::new(AuthenticationError::new(scheme.clone(), "no authentication handler was added for the scheme".to_string())));
+            }
+        }
+        Ok(handlers.into_iter().filter(|x| schemes.contains(&x.get_scheme())).collect())
+    }
+
+    // authenticate the request with the handlers and set the claims of the request.
+    // the handlers are tried in order until one of them authenticates the request. the claims are then checked
+    // by the claims validators, and the request is signed out of the handler if any of them rejects the claims.
+    // handlers: the handlers to authenticate with.
+    // request_context: the request to authenticate.
+    // response_context: the response to the request.
+    // services: the services to get the claims validators from.
+    pub fn authenticate(handlers: &Vec<Rc<dyn IAuthenticationHandler>>, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>> {
+        request_context.set_auth_claims(vec![]);
+        for handler in handlers.iter() {
+            match handler.authenticate(request_context, response_context) {
+                AuthenticateResult::Success(claims) => {
+                    let validators = ServiceCollectionExtensions::get_required_multiple::<dyn IAuthClaimsValidator>(services);
+                    if validators.iter().all(|x| x.validate_claims(&claims)) {
+                        request_context.set_auth_claims(claims);
+                    } else if handler.can_sign_in() {
+                        // the claims are no longer valid, such as after the password of the user changed.
+                        println!("{} authentication rejected: the claims are no longer valid", handler.get_scheme());
+                        handler.sign_out(request_context, response_context)?;
+                    } else {
+                        println!("{} authentication rejected: the claims are no longer valid", handler.get_scheme());
+                    }
+                    break;
+                },
+                AuthenticateResult::Fail(reason) => {
+                    // the request continues without claims, so it is challenged if it needs to be authorized.
+                    println!("{} authentication rejected: {}", handler.get_scheme(), reason);
+                },
+                AuthenticateResult::NoResult => {},
+            }
+        }
+        Ok(())
+    }
+}
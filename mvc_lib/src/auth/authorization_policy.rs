@@ -0,0 +1,298 @@
+use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
+
+use crate::contexts::irequest_context::IRequestContext;
+use crate::core::type_info::TypeInfo;
+use crate::model_binder::imodel::IModel;
+use crate::model_binder::model_validation_result::ModelValidationResult;
+use crate::services::authorization_service::{AuthRejectionReason, AuthResult, IAuthClaim, IAuthRequirement};
+use crate::services::service_collection::{IServiceCollection, ServiceCollection};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this struct is a named policy, which is met when all of its requirements are met.
+// policies are added to the services with add_to_services, and controllers and actions reference them by name
+// with #[authorize(policy = "name")], so what a user must have to call an action is defined in one place.
+pub struct AuthorizationPolicy {
+    // the name features reference the policy by.
+    pub name: String,
+    // the requirements that must all be met.
+    pub requirements: Vec<Rc<dyn IAuthRequirement>>,
+}
+
+impl AuthorizationPolicy {
+    // create a new policy without requirements, which is met by any authenticated user.
+    // name: the name features reference the policy by.
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), requirements: vec![] }
+    }
+
+    // add a requirement to the policy.
+    // requirement: the requirement, which must be met along with the others.
+    pub fn require(mut self, requirement: Rc<dyn IAuthRequirement>) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    // require a claim with one of the values, or with any value if values is empty.
+    // name: the name of the claim, such as "UserId".
+    // values: the values the claim can have.
+    pub fn require_claim(self, name: &str, values: Vec<String>) -> Self {
+        self.require(Rc::new(ClaimAuthRequirement::new(name.to_string(), values)))
+    }
+
+    // require at least one of the roles.
+    pub fn require_any_role(self, roles: Vec<String>) -> Self {
+        self.require(Rc::new(RolesAuthRequirement::new(roles, false)))
+    }
+
+    // require all of the roles.
+    pub fn require_all_roles(self, roles: Vec<String>) -> Self {
+        self.require(Rc::new(RolesAuthRequirement::new(roles, true)))
+    }
+
+    // require a custom check of the claims and request to pass.
+    // name: the name of the requirement, which is shown when it rejects a request.
+    // assertion: the check, which returns whether the requirement is met.
+    pub fn require_assertion<F>(self, name: &str, assertion: F) -> Self
+        where F: Fn(&Vec<Rc<dyn IAuthClaim>>, &dyn IRequestContext) -> bool + 'static {
+        let policy = self.name.clone();
+        self.require(Rc::new(AssertionAuthRequirement::new(policy, name.to_string(), Box::new(assertion))))
+    }
+
+    // require a check of the model bound from the request, such as whether the user owns the item it changes.
+    // a request without a bound model of type T does not meet the requirement.
+    // name: the name of the requirement, which is shown when it rejects a request.
+    // assertion: the check of the claims and the bound model, which returns whether the requirement is met.
+    pub fn require_resource<T: 'static, F>(self, name: &str, assertion: F) -> Self
+        where F: Fn(&Vec<Rc<dyn IAuthClaim>>, &T) -> bool + 'static {
+        let policy = self.name.clone();
+        self.require(Rc::new(ResourceAuthRequirement::<T>::new(policy, name.to_string(), Box::new(assertion))))
+    }
+
+    // add a policy to the services, which the AuthorizationService finds by its name.
+    // services: the service collection.
+    // factory: creates the policy, and can get the services its requirements need.
+    pub fn add_to_services<F>(services: &mut ServiceCollection, factory: F)
+        where F: Fn(&dyn IServiceCollection) -> AuthorizationPolicy + 'static {
+        services.add(ServiceDescriptor::new_closure(
+            TypeInfo::rc_of::<AuthorizationPolicy>(),
+            move |services| vec![Box::new(Rc::new(factory(services))) as Box<dyn Any>],
+            ServiceScope::Singleton));
+    }
+}
+
+impl IAuthRequirement for AuthorizationPolicy {
+    fn invoke(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, roles: Vec<String>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        for requirement in self.requirements.iter() {
+            if let AuthResult::Rejection(reason) = requirement.invoke(auth_claims.clone(), roles.clone(), request_context)? {
+                return Ok(AuthResult::Rejection(reason));
+            }
+        }
+        Ok(AuthResult::Ok)
+    }
+
+    fn get_name(self: &Self) -> String {
+        self.name.clone()
+    }
+
+    fn get_type_info(self: &Self) -> TypeInfo {
+        TypeInfo::of::<AuthorizationPolicy>()
+    }
+
+    fn get_type_name(self: &Self) -> String {
+        nameof::name_of_type!(AuthorizationPolicy).to_string()
+    }
+
+    fn to_string(self: &Self) -> String {
+        format!("{} ({}: {})", self.get_type_name(), self.name, self.requirements.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", "))
+    }
+}
+
+// get the values of the claims with a name.
+fn get_claim_values(auth_claims: &Vec<Rc<dyn IAuthClaim>>, name: &str) -> Vec<String> {
+    auth_claims.iter()
+        .filter(|x| x.get_name() == name)
+        .filter_map(|x| x.get_tokens().get(name).cloned())
+        .collect()
+}
+
+// this struct is used to check if the user has a claim, and optionally that it has one of a set of values.
+pub struct ClaimAuthRequirement {
+    // the name of the claim.
+    pub name: String,
+    // the values the claim can have, or empty if it can have any value.
+    pub values: Vec<String>,
+}
+
+impl ClaimAuthRequirement {
+    pub fn new(name: String, values: Vec<String>) -> Self {
+        Self { name: name, values: values }
+    }
+}
+
+impl IAuthRequirement for ClaimAuthRequirement {
+    fn invoke(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, _roles: Vec<String>, _request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        let found = get_claim_values(&auth_claims, &self.name);
+        if found.iter().any(|x| self.values.is_empty() || self.values.contains(x)) {
+            Ok(AuthResult::Ok)
+        } else {
+            Ok(AuthResult::Rejection(AuthRejectionReason::MissingClaim { name: self.name.clone(), values: self.values.clone() }))
+        }
+    }
+
+    fn get_name(self: &Self) -> String {
+        format!("Claim {}", self.name)
+    }
+
+    fn get_type_info(self: &Self) -> TypeInfo {
+        TypeInfo::of::<ClaimAuthRequirement>()
+    }
+
+    fn get_type_name(self: &Self) -> String {
+        nameof::name_of_type!(ClaimAuthRequirement).to_string()
+    }
+
+    fn to_string(self: &Self) -> String {
+        format!("{} ({}: {:?})", self.get_type_name(), self.name, self.values)
+    }
+}
+
+// this struct is used to check if the user has one or all of a set of roles.
+// unlike the RoleAuthRequirement, the roles are part of the requirement rather than the authorize feature.
+pub struct RolesAuthRequirement {
+    // the roles to check.
+    pub roles: Vec<String>,
+    // whether all of the roles are required, or only one of them.
+    pub all: bool,
+}
+
+impl RolesAuthRequirement {
+    pub fn new(roles: Vec<String>, all: bool) -> Self {
+        Self { roles: roles, all: all }
+    }
+}
+
+impl IAuthRequirement for RolesAuthRequirement {
+    fn invoke(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, _roles: Vec<String>, _request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        let found = get_claim_values(&auth_claims, "Role");
+        let met = if self.all {
+            self.roles.iter().all(|x| found.contains(x))
+        } else {
+            self.roles.iter().any(|x| found.contains(x))
+        };
+        if met {
+            Ok(AuthResult::Ok)
+        } else {
+            Ok(AuthResult::Rejection(AuthRejectionReason::MissingRoles { required: self.roles.clone(), found: found, all: self.all }))
+        }
+    }
+
+    fn get_name(self: &Self) -> String {
+        if self.all { "All roles".to_string() } else { "Any role".to_string() }
+    }
+
+    fn get_type_info(self: &Self) -> TypeInfo {
+        TypeInfo::of::<RolesAuthRequirement>()
+    }
+
+    fn get_type_name(self: &Self) -> String {
+        nameof::name_of_type!(RolesAuthRequirement).to_string()
+    }
+
+    fn to_string(self: &Self) -> String {
+        format!("{} ({}: {:?})", self.get_type_name(), self.get_name(), self.roles)
+    }
+}
+
+// this struct is used to check the claims and request with a custom function.
+pub struct AssertionAuthRequirement {
+    // the name of the policy the requirement is part of.
+    pub policy: String,
+    // the name of the requirement.
+    pub name: String,
+    assertion: Box<dyn Fn(&Vec<Rc<dyn IAuthClaim>>, &dyn IRequestContext) -> bool>,
+}
+
+impl AssertionAuthRequirement {
+    pub fn new(policy: String, name: String, assertion: Box<dyn Fn(&Vec<Rc<dyn IAuthClaim>>, &dyn IRequestContext) -> bool>) -> Self {
+        Self { policy: policy, name: name, assertion: assertion }
+    }
+}
+
+impl IAuthRequirement for AssertionAuthRequirement {
+    fn invoke(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, _roles: Vec<String>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        if (self.assertion)(&auth_claims, request_context) {
+            Ok(AuthResult::Ok)
+        } else {
+            Ok(AuthResult::Rejection(AuthRejectionReason::RequirementNotMet { policy: self.policy.clone(), requirement: self.name.clone() }))
+        }
+    }
+
+    fn get_name(self: &Self) -> String {
+        self.name.clone()
+    }
+
+    fn get_type_info(self: &Self) -> TypeInfo {
+        TypeInfo::of::<AssertionAuthRequirement>()
+    }
+
+    fn get_type_name(self: &Self) -> String {
+        nameof::name_of_type!(AssertionAuthRequirement).to_string()
+    }
+
+    fn to_string(self: &Self) -> String {
+        format!("{} ({})", self.get_type_name(), self.name)
+    }
+}
+
+// this struct is used to check the model bound from the request, which is the resource the action reads or changes.
+// the model is bound by the RequestDecoderMiddleware, which must come before the AuthorizeControllerActionFeatureMiddleware.
+pub struct ResourceAuthRequirement<T: 'static> {
+    // the name of the policy the requirement is part of.
+    pub policy: String,
+    // the name of the requirement.
+    pub name: String,
+    assertion: Box<dyn Fn(&Vec<Rc<dyn IAuthClaim>>, &T) -> bool>,
+}
+
+impl <T: 'static> ResourceAuthRequirement<T> {
+    pub fn new(policy: String, name: String, assertion: Box<dyn Fn(&Vec<Rc<dyn IAuthClaim>>, &T) -> bool>) -> Self {
+        Self { policy: policy, name: name, assertion: assertion }
+    }
+}
+
+impl <T: 'static> IAuthRequirement for ResourceAuthRequirement<T> {
+    fn invoke(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, _roles: Vec<String>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        let met = match request_context.get_model_validation_result() {
+            Some(ModelValidationResult::Ok(model)) => match model.get_underlying_value().downcast_ref::<T>() {
+                Some(resource) => (self.assertion)(&auth_claims, resource),
+                None => false,
+            },
+            _ => false,
+        };
+        if met {
+            Ok(AuthResult::Ok)
+        } else {
+            Ok(AuthResult::Rejection(AuthRejectionReason::RequirementNotMet { policy: self.policy.clone(), requirement: self.name.clone() }))
+        }
+    }
+
+    fn get_name(self: &Self) -> String {
+        self.name.clone()
+    }
+
+    fn get_type_info(self: &Self) -> TypeInfo {
+        TypeInfo::of::<ResourceAuthRequirement<T>>()
+    }
+
+    fn get_type_name(self: &Self) -> String {
+        format!("ResourceAuthRequirement<{}>", std::any::type_name::<T>())
+    }
+
+    fn to_string(self: &Self) -> String {
+        format!("{} ({})", self.get_type_name(), self.name)
+    }
+}
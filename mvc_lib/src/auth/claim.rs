@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::core::type_info::TypeInfo;
+use crate::services::authorization_service::IAuthClaim;
+
+
+// this struct is a claim that holds its values, so it can be written to and read from an authentication ticket.
+// the claims given when signing in are copied to this struct, whatever type they were.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Claim {
+    // the name of the claim, such as "Role".
+    pub name: String,
+    // the tokens of the claim, such as "Role" => "admin".
+    pub tokens: HashMap<String, String>,
+    // whether the claim identifies the user.
+    pub identifier: bool,
+    // whether the claim is a secret, which is only kept in tickets that are encrypted.
+    pub secret: bool,
+}
+
+impl Claim {
+    // create a new claim.
+    // name: the name of the claim.
+    // tokens: the tokens of the claim.
+    pub fn new(name: String, tokens: HashMap<String, String>) -> Self {
+        Self { name: name, tokens: tokens, identifier: false, secret: false }
+    }
+
+    // create a new claim for a role, which is checked by the role requirement.
+    // role: the name of the role.
+    pub fn new_role(role: &str) -> Rc<dyn IAuthClaim> {
+        Rc::new(Self::new("Role".to_string(), HashMap::from([("Role".to_string(), role.to_string())])))
+    }
+
+    // create a new claim that identifies the user, such as their user name.
+    // name: the name of the claim and its token.
+    // value: the value of the token.
+    pub fn new_identifier(name: &str, value: &str) -> Rc<dyn IAuthClaim> {
+        let mut claim = Self::new(name.to_string(), HashMap::from([(name.to_string(), value.to_string())]));
+        claim.identifier = true;
+        Rc::new(claim)
+    }
+
+    // copy the values of a claim.
+    // claim: the claim to copy.
+    pub fn from_claim(claim: &dyn IAuthClaim) -> Self {
+        Self { name: claim.get_name(), tokens: claim.get_tokens(), identifier: claim.is_identifier(), secret: claim.is_secret() }
+    }
+
+    // get the claim as a JSON object.
+    pub fn to_json(self: &Self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "tokens": self.tokens,
+            "identifier": self.identifier,
+            "secret": self.secret,
+        })
+    }
+
+    // read a claim from a JSON object written by to_json.
+    // returns: the claim, or None if the object is not a claim.
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let tokens = value.get("tokens")?.as_object()?.iter()
+            .map(|(key, value)| value.as_str().map(|x| (key.clone(), x.to_string())))
+            .collect::<Option<HashMap<String, String>>>()?;
+        Some(Self {
+            name: value.get("name")?.as_str()?.to_string(),
+            tokens: tokens,
+            identifier: value.get("identifier")?.as_bool()?,
+            secret: value.get("secret")?.as_bool()?,
+        })
+    }
+}
+
+impl IAuthClaim for Claim {
+    fn get_name(self: &Self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tokens(self: &Self) -> HashMap<String, String> {
+        self.tokens.clone()
+    }
+
+    fn is_identifier(self: &Self) -> bool {
+        self.identifier
+    }
+
+    fn is_secret(self: &Self) -> bool {
+        self.secret
+    }
+
+    fn get_type_info(self: &Self) -> TypeInfo {
+        TypeInfo::of::<Claim>()
+    }
+
+    fn get_type_name(self: &Self) -> String {
+        nameof::name_of_type!(Claim).to_string()
+    }
+
+    fn to_string(self: &Self) -> String {
+        format!("{} ({}: {:?})", self.get_type_name(), self.name, self.tokens)
+    }
+}
@@ -0,0 +1,273 @@
+use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use http::StatusCode;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::action_results::http_result::HttpRedirectResult;
+use crate::auth::authentication_handler::{AuthenticateResult, IAuthenticationHandler};
+use crate::auth::claim::Claim;
+use crate::contexts::irequest_context::IRequestContext;
+use crate::contexts::iresponse_context::IResponseContext;
+use crate::core::type_info::TypeInfo;
+use crate::errors::AuthenticationError;
+use crate::options::cookie_authentication_options::{CookieAuthenticationOptions, ICookieAuthenticationOptions};
+use crate::services::authorization_service::IAuthClaim;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this struct holds the claims of a user that signed in and when they are valid until.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthenticationTicket {
+    // the claims of the user.
+    pub claims: Vec<Claim>,
+    // when the ticket was issued, in seconds since the unix epoch.
+    pub issued_at: u64,
+    // when the ticket expires, in seconds since the unix epoch.
+    pub expires_at: u64,
+}
+
+impl AuthenticationTicket {
+    // create a new ticket.
+    // claims: the claims of the user.
+    // issued_at: when the ticket was issued, in seconds since the unix epoch.
+    // expires_at: when the ticket expires, in seconds since the unix epoch.
+    pub fn new(claims: Vec<Claim>, issued_at: u64, expires_at: u64) -> Self {
+        Self { claims: claims, issued_at: issued_at, expires_at: expires_at }
+    }
+
+    // get whether the ticket has expired.
+    // now: the current time, in seconds since the unix epoch.
+    pub fn is_expired(self: &Self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    // get whether half of the time the ticket is valid for has passed, so it should be issued again.
+    // now: the current time, in seconds since the unix epoch.
+    pub fn should_renew(self: &Self, now: u64) -> bool {
+        now.saturating_sub(self.issued_at) >= self.expires_at.saturating_sub(self.issued_at) / 2
+    }
+
+    // get the claims of the ticket for authorization.
+    pub fn get_auth_claims(self: &Self) -> Vec<Rc<dyn IAuthClaim>> {
+        self.claims.iter().map(|x| Rc::new(x.clone()) as Rc<dyn IAuthClaim>).collect()
+    }
+
+    fn to_json(self: &Self) -> serde_json::Value {
+        serde_json::json!({
+            "claims": self.claims.iter().map(|x| x.to_json()).collect::<Vec<serde_json::Value>>(),
+            "iat": self.issued_at,
+            "exp": self.expires_at,
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            claims: value.get("claims")?.as_array()?.iter().map(|x| Claim::from_json(x)).collect::<Option<Vec<Claim>>>()?,
+            issued_at: value.get("iat")?.as_u64()?,
+            expires_at: value.get("exp")?.as_u64()?,
+        })
+    }
+}
+
+// this struct authenticates requests with a cookie that holds an authentication ticket.
+// the ticket is signed with HMAC-SHA256 so it cannot be changed, and the claims are encrypted with AES-256-GCM
+// if the options say so. the cookie value is the base64url ticket, a '.' and the base64url signature.
+// tickets that were tampered with or have expired are rejected and the cookie is removed,
+// so their claims never reach the authorization service.
+pub struct CookieAuthenticationHandler {
+    options: Rc<dyn ICookieAuthenticationOptions>,
+    // the key the cookie is signed with, derived from the secret key.
+    signing_key: hmac::Key,
+    // the key the ticket is encrypted with, derived from the secret key.
+    encryption_key: LessSafeKey,
+    random: SystemRandom,
+}
+
+impl CookieAuthenticationHandler {
+    // the name of the authentication scheme.
+    pub const SCHEME: &'static str = "Cookies";
+
+    // create a new cookie authentication handler.
+    // options: the options for the cookie.
+    pub fn new(options: Rc<dyn ICookieAuthenticationOptions>) -> Self {
+        // separate keys are used for signing and encrypting, so the secret key is never used for both.
+        let secret_key = hmac::Key::new(hmac::HMAC_SHA256, &options.get_secret_key());
+        let signing_key = hmac::Key::new(hmac::HMAC_SHA256, hmac::sign(&secret_key, b"rshtml.auth.signing").as_ref());
+        let encryption_key = UnboundKey::new(&AES_256_GCM, hmac::sign(&secret_key, b"rshtml.auth.encryption").as_ref())
+            .expect("an HMAC-SHA256 tag is a valid AES-256 key");
+        Self {
+            options: options,
+            signing_key: signing_key,
+            encryption_key: LessSafeKey::new(encryption_key),
+            random: SystemRandom::new(),
+        }
+    }
+
+    // create a new cookie authentication handler as a service.
+    // services: the services to get the cookie authentication options from.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn ICookieAuthenticationOptions>(services)
+        )) as Rc<dyn IAuthenticationHandler>)]
+    }
+
+    // add the cookie authentication options from the configuration and the handler to the services.
+    // the AuthenticationMiddleware must also be added to the pipeline to authenticate requests.
+    // services: the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ICookieAuthenticationOptions>(), CookieAuthenticationOptions::new_service_from_configuration, ServiceScope::Singleton));
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IAuthenticationHandler>(), Self::new_service, ServiceScope::Singleton));
+    }
+
+    // get the current time in seconds since the unix epoch.
+    pub fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0)
+    }
+
+    // create a ticket for the claims that is valid for the expire time of the options.
+    // claims: the claims of the user.
+    // now: the current time, in seconds since the unix epoch.
+    pub fn new_ticket(self: &Self, claims: Vec<Claim>, now: u64) -> AuthenticationTicket {
+        AuthenticationTicket::new(claims, now, now + self.options.get_expire_time().as_secs())
+    }
+
+    // sign, and encrypt if the options say so, a ticket to use as the value of the cookie.
+    // secret claims are left out of tickets that are not encrypted.
+    // ticket: the ticket to protect.
+    // returns: the value of the cookie.
+    pub fn protect(self: &Self, ticket: &AuthenticationTicket) -> Result<String, AuthenticationError> {
+        let encrypt = self.options.get_encrypt();
+        let mut ticket = ticket.clone();
+        if !encrypt {
+            ticket.claims.retain(|x| !x.secret);
+        }
+
+        let mut payload = ticket.to_json().to_string().into_bytes();
+        if encrypt {
+            let mut nonce = [0u8; NONCE_LEN];
+            self.random.fill(&mut nonce).map_err(|_| self.error("could not generate a nonce"))?;
+            self.encryption_key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(Self::SCHEME), &mut payload)
+                .map_err(|_| self.error("could not encrypt the ticket"))?;
+            payload = nonce.iter().cloned().chain(payload).collect();
+        }
+
+        let body = URL_SAFE_NO_PAD.encode(payload);
+        let signature = hmac::sign(&self.signing_key, body.as_bytes());
+        Ok(format!("{}.{}", body, URL_SAFE_NO_PAD.encode(signature.as_ref())))
+    }
+
+    // check the signature of the value of a cookie, then decrypt and read the ticket in it.
+    // value: the value of the cookie.
+    // now: the current time, in seconds since the unix epoch.
+    // returns: the ticket, or an error if the value was tampered with, cannot be read or the ticket has expired.
+    pub fn unprotect(self: &Self, value: &str, now: u64) -> Result<AuthenticationTicket, AuthenticationError> {
+        let (body, signature) = value.split_once('.').ok_or_else(|| self.error("the cookie is not a ticket"))?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| self.error("the signature is not base64url"))?;
+        hmac::verify(&self.signing_key, body.as_bytes(), &signature).map_err(|_| self.error("the signature does not match"))?;
+
+        let mut payload = URL_SAFE_NO_PAD.decode(body).map_err(|_| self.error("the ticket is not base64url"))?;
+        if self.options.get_encrypt() {
+            if payload.len() < NONCE_LEN {
+                return Err(self.error("the ticket is not encrypted"));
+            }
+            let mut ciphertext = payload.split_off(NONCE_LEN);
+            let nonce = Nonce::try_assume_unique_for_key(&payload).map_err(|_| self.error("the nonce is not valid"))?;
+            payload = self.encryption_key.open_in_place(nonce, Aad::from(Self::SCHEME), &mut ciphertext)
+                .map_err(|_| self.error("the ticket could not be decrypted"))?
+                .to_vec();
+        }
+
+        let ticket = serde_json::from_slice::<serde_json::Value>(&payload).ok()
+            .and_then(|x| AuthenticationTicket::from_json(&x))
+            .ok_or_else(|| self.error("the ticket could not be read"))?;
+        if ticket.is_expired(now) {
+            return Err(self.error("the ticket has expired"));
+        }
+        Ok(ticket)
+    }
+
+    fn error(self: &Self, message: &str) -> AuthenticationError {
+        AuthenticationError::new(Self::SCHEME.to_string(), message.to_string())
+    }
+
+    // write the cookie to the response.
+    // max_age: how long the cookie is kept for in seconds, or 0 to remove it.
+    fn write_cookie(self: &Self, value: &str, max_age: u64, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) {
+        let secure = self.options.get_secure_cookie() || request_context.get_scheme() == "https";
+        response_context.add_header_string("Set-Cookie".to_string(), format!(
+            "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}",
+            self.options.get_cookie_name(), value, max_age, if secure { "; Secure" } else { "" }));
+    }
+
+    fn write_ticket(self: &Self, ticket: &AuthenticationTicket, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), AuthenticationError> {
+        let value = self.protect(ticket)?;
+        self.write_cookie(&value, ticket.expires_at.saturating_sub(ticket.issued_at), request_context, response_context);
+        Ok(())
+    }
+}
+
+impl IAuthenticationHandler for CookieAuthenticationHandler {
+    fn get_scheme(self: &Self) -> String {
+        Self::SCHEME.to_string()
+    }
+
+    fn authenticate(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> AuthenticateResult {
+        let value = match request_context.get_cookies_parsed().and_then(|x| x.get(&self.options.get_cookie_name()).cloned()) {
+            Some(value) if !value.is_empty() => value,
+            _ => return AuthenticateResult::NoResult,
+        };
+
+        let now = Self::now();
+        match self.unprotect(&value, now) {
+            Ok(ticket) => {
+                if self.options.get_sliding_expiration() && ticket.should_renew(now) {
+                    let renewed = self.new_ticket(ticket.claims.clone(), now);
+                    if let Err(e) = self.write_ticket(&renewed, request_context, response_context) {
+                        return AuthenticateResult::Fail(e.to_string());
+                    }
+                }
+                AuthenticateResult::Success(ticket.get_auth_claims())
+            },
+            Err(e) => {
+                // the browser keeps sending a rejected cookie until it is removed.
+                self.write_cookie("", 0, request_context, response_context);
+                AuthenticateResult::Fail(e.to_string())
+            },
+        }
+    }
+
+    fn challenge(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        let url = request_context.get_url();
+        let return_url = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let location = format!("{}?{}", self.options.get_login_path(), form_urlencoded::Serializer::new(String::new())
+            .append_pair(&self.options.get_return_url_parameter(), &return_url)
+            .finish());
+        response_context.set_action_result(Some(Rc::new(HttpRedirectResult::new_with_status(location, StatusCode::FOUND))));
+        Ok(())
+    }
+
+    fn sign_in(self: &Self, claims: Vec<Rc<dyn IAuthClaim>>, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        let ticket = self.new_ticket(claims.iter().map(|x| Claim::from_claim(x.as_ref())).collect(), Self::now());
+        self.write_ticket(&ticket, request_context, response_context).map_err(|e| Rc::new(e) as Rc<dyn Error>)?;
+        request_context.set_auth_claims(ticket.get_auth_claims());
+        Ok(())
+    }
+
+    fn sign_out(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        self.write_cookie("", 0, request_context, response_context);
+        request_context.set_auth_claims(vec![]);
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use crate::entity::idbset::IDbSet;
+use crate::entity::json_file_dbset::JsonFileDbSet;
+use crate::options::identity_options::IIdentityOptions;
+use crate::services::service_scope::ServiceScope;
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_collection::{ServiceCollection, IServiceCollection, ServiceCollectionExtensions};
+
+use crate::auth::auth_user_json_file_dbset::JsonAuthUser;
+
+// this trait is used to get the authusers dbset
+pub trait IAuthUsersDbSetProvider {
+    // get the authusers dbset
+    fn get_authusers_dbset(self: &Self) -> &dyn IDbSet<JsonAuthUser>;
+}
+
+// this struct implements IAuthUsersDbSetProvider
+pub struct GenericAuthUsersDbSetProvider {
+    authusers_dbset: Box<dyn IDbSet<JsonAuthUser>>,
+}
+
+impl GenericAuthUsersDbSetProvider {
+    // create a new provider for a dbset.
+    // authusers_dbset: the dbset the users are stored in.
+    pub fn new(authusers_dbset: Box<dyn IDbSet<JsonAuthUser>>) -> Self {
+        Self {
+            authusers_dbset: authusers_dbset
+        }
+    }
+
+    // create a new provider for the users file of the identity options, which is created if it does not exist.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        let options = ServiceCollectionExtensions::get_required_single::<dyn IIdentityOptions>(services);
+        let dbset = JsonFileDbSet::open(options.get_users_file_path(), JsonAuthUser::new, JsonAuthUser::parse_json, JsonAuthUser::to_json).unwrap();
+        vec![Box::new(Rc::new(Self::new(Box::new(dbset))) as Rc<dyn IAuthUsersDbSetProvider>)]
+    }
+
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new_from::<dyn IAuthUsersDbSetProvider, Self>(Self::new_service, ServiceScope::Singleton));
+    }
+}
+
+impl IAuthUsersDbSetProvider for GenericAuthUsersDbSetProvider {
+    fn get_authusers_dbset(self: &Self) -> &dyn IDbSet<JsonAuthUser> {
+        self.authusers_dbset.as_ref()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,223 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use http::StatusCode;
+use ring::hmac;
+use ring::signature;
+
+use crate::action_results::http_result::StatusCodeResult;
+use crate::auth::authentication_handler::{AuthenticateResult, IAuthenticationHandler};
+use crate::auth::claim::Claim;
+use crate::auth::cookie_authentication_handler::CookieAuthenticationHandler;
+use crate::contexts::irequest_context::IRequestContext;
+use crate::contexts::iresponse_context::IResponseContext;
+use crate::core::type_info::TypeInfo;
+use crate::errors::AuthenticationError;
+use crate::options::jwt_bearer_options::{IJwtBearerOptions, JwtBearerOptions};
+use crate::services::authorization_service::IAuthClaim;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this struct authenticates requests with a JWT in the "Authorization: Bearer <token>" header, for APIs called by
+// programs rather than browsers. tokens are signed with HS256 or RS256 by the token issuer and are only read here,
+// so the handler cannot sign in or out. a request that is not authenticated is answered with 401 and a
+// WWW-Authenticate header instead of a redirect to a login page.
+// the claims of the token become claims of the request: "sub" and "name" identify the user, "role" and "roles"
+// become Role claims for the role requirement, and the registered claims such as "exp" are left out.
+pub struct JwtBearerAuthenticationHandler {
+    options: Rc<dyn IJwtBearerOptions>,
+    // the keys HS256 tokens are checked with.
+    secret_keys: Vec<hmac::Key>,
+    // the keys RS256 tokens are checked with.
+    public_keys: Vec<signature::UnparsedPublicKey<Vec<u8>>>,
+}
+
+impl JwtBearerAuthenticationHandler {
+    // the name of the authentication scheme.
+    pub const SCHEME: &'static str = "Bearer";
+    // the registered claims, which are checked rather than becoming claims of the request.
+    const REGISTERED_CLAIMS: [&'static str; 6] = ["iss", "aud", "exp", "nbf", "iat", "jti"];
+
+    // create a new JWT bearer authentication handler.
+    // options: the options with the keys tokens are checked with.
+    pub fn new(options: Rc<dyn IJwtBearerOptions>) -> Self {
+        Self {
+            secret_keys: options.get_secret_keys().iter().map(|x| hmac::Key::new(hmac::HMAC_SHA256, x)).collect(),
+            public_keys: options.get_public_keys().into_iter().map(|x| signature::UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA256, x)).collect(),
+            options: options,
+        }
+    }
+
+    // create a new JWT bearer authentication handler as a service.
+    // services: the services to get the JWT bearer options from.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn IJwtBearerOptions>(services)
+        )) as Rc<dyn IAuthenticationHandler>)]
+    }
+
+    // add the JWT bearer options from the configuration and the handler to the services.
+    // the AuthenticationMiddleware must also be added to the pipeline to authenticate requests, and actions that
+    // should answer 401 rather than redirect need #[authorize(schemes = "Bearer")] when other handlers are added first.
+    // services: the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IJwtBearerOptions>(), JwtBearerOptions::new_service_from_configuration, ServiceScope::Singleton));
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IAuthenticationHandler>(), Self::new_service, ServiceScope::Singleton));
+    }
+
+    // get the bearer token of the Authorization header of a request.
+    // returns: the token, or None if the request has no bearer token.
+    pub fn get_token(request_context: &dyn IRequestContext) -> Option<String> {
+        let value = request_context.get_headers().get(http::header::AUTHORIZATION)?.to_str().ok()?.trim();
+        let (scheme, token) = value.split_once(' ')?;
+        if scheme.eq_ignore_ascii_case(Self::SCHEME) && !token.trim().is_empty() {
+            Some(token.trim().to_string())
+        } else {
+            None
+        }
+    }
+
+    // check the signature, lifetime, issuer and audience of a token and read its claims.
+    // token: the token, as "<base64 header>.<base64 payload>.<base64 signature>".
+    // now: the current time, in seconds since the unix epoch.
+    // returns: the claims of the token, or an error if the token is rejected.
+    pub fn validate_token(self: &Self, token: &str, now: u64) -> Result<Vec<Claim>, AuthenticationError> {
+        let parts = token.split('.').collect::<Vec<&str>>();
+        if parts.len() != 3 {
+            return Err(self.error("the token is not a signed JWT"));
+        }
+        let header = Self::decode_json(parts[0]).ok_or_else(|| self.error("the token header could not be read"))?;
+        let signature = URL_SAFE_NO_PAD.decode(parts[2]).map_err(|_| self.error("the token signature could not be read"))?;
+        let signed = &token[..parts[0].len() + 1 + parts[1].len()];
+
+        // the algorithm is only used to pick the keys, so a token cannot choose to be checked with "none" or a weaker key.
+        let verified = match header.get("alg").and_then(|x| x.as_str()) {
+            Some("HS256") if !self.secret_keys.is_empty() => self.secret_keys.iter().any(|x| hmac::verify(x, signed.as_bytes(), &signature).is_ok()),
+            Some("RS256") if !self.public_keys.is_empty() => self.public_keys.iter().any(|x| x.verify(signed.as_bytes(), &signature).is_ok()),
+            Some(alg) => return Err(self.error(&format!("the token algorithm {} is not accepted", alg))),
+            None => return Err(self.error("the token has no algorithm")),
+        };
+        if !verified {
+            return Err(self.error("the token signature is not valid"));
+        }
+
+        let payload = Self::decode_json(parts[1]).ok_or_else(|| self.error("the token payload could not be read"))?;
+        let skew = self.options.get_clock_skew().as_secs();
+        match payload.get("exp").and_then(|x| x.as_u64()) {
+            Some(exp) if now >= exp.saturating_add(skew) => return Err(self.error("the token has expired")),
+            Some(_) => {},
+            None => return Err(self.error("the token has no expiry")),
+        }
+        if let Some(nbf) = payload.get("nbf").and_then(|x| x.as_u64()) {
+            if now.saturating_add(skew) < nbf {
+                return Err(self.error("the token is not valid yet"));
+            }
+        }
+        if let Some(issuer) = self.options.get_issuer() {
+            if payload.get("iss").and_then(|x| x.as_str()) != Some(issuer.as_str()) {
+                return Err(self.error("the token issuer is not accepted"));
+            }
+        }
+        if let Some(audience) = self.options.get_audience() {
+            let accepted = match payload.get("aud") {
+                Some(serde_json::Value::String(aud)) => aud == &audience,
+                Some(serde_json::Value::Array(auds)) => auds.iter().any(|x| x.as_str() == Some(audience.as_str())),
+                _ => false,
+            };
+            if !accepted {
+                return Err(self.error("the token audience is not accepted"));
+            }
+        }
+
+        Ok(Self::get_claims(&payload))
+    }
+
+    // get the claims of the payload of a token, leaving out the registered claims.
+    fn get_claims(payload: &serde_json::Map<String, serde_json::Value>) -> Vec<Claim> {
+        let mut claims = vec![];
+        for (name, value) in payload.iter().filter(|x| !Self::REGISTERED_CLAIMS.contains(&x.0.as_str())) {
+            let values = match value {
+                serde_json::Value::Array(values) => values.iter().map(Self::get_claim_value).collect(),
+                value => vec![Self::get_claim_value(value)],
+            };
+            for value in values {
+                let claim = match name.as_str() {
+                    "role" | "roles" => Claim::from_claim(Claim::new_role(&value).as_ref()),
+                    "sub" | "name" => Claim::from_claim(Claim::new_identifier(name, &value).as_ref()),
+                    _ => Claim::new(name.clone(), HashMap::from([(name.clone(), value)])),
+                };
+                claims.push(claim);
+            }
+        }
+        claims
+    }
+
+    fn get_claim_value(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    fn decode_json(value: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+        match serde_json::from_slice(&bytes).ok()? {
+            serde_json::Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    fn error(self: &Self, message: &str) -> AuthenticationError {
+        AuthenticationError::new(Self::SCHEME.to_string(), message.to_string())
+    }
+}
+
+impl IAuthenticationHandler for JwtBearerAuthenticationHandler {
+    fn get_scheme(self: &Self) -> String {
+        Self::SCHEME.to_string()
+    }
+
+    fn authenticate(self: &Self, request_context: &dyn IRequestContext, _response_context: &dyn IResponseContext) -> AuthenticateResult {
+        let token = match Self::get_token(request_context) {
+            Some(token) => token,
+            None => return AuthenticateResult::NoResult,
+        };
+        match self.validate_token(&token, CookieAuthenticationHandler::now()) {
+            Ok(claims) => AuthenticateResult::Success(claims.into_iter().map(|x| Rc::new(x) as Rc<dyn IAuthClaim>).collect()),
+            Err(e) => AuthenticateResult::Fail(e.message),
+        }
+    }
+
+    fn challenge(self: &Self, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        // a request without a token is only told the scheme, a request with a token is also told why it was rejected.
+        let mut value = format!("{} realm=\"{}\"", Self::SCHEME, self.options.get_realm());
+        if let Some(token) = Self::get_token(request_context) {
+            value.push_str(", error=\"invalid_token\"");
+            if let Err(e) = self.validate_token(&token, CookieAuthenticationHandler::now()) {
+                value.push_str(&format!(", error_description=\"{}\"", e.message.replace('"', "'")));
+            }
+        }
+        response_context.add_header_string(http::header::WWW_AUTHENTICATE.to_string(), value);
+        response_context.set_status_code(StatusCode::UNAUTHORIZED);
+        response_context.set_action_result(Some(Rc::new(StatusCodeResult::new(StatusCode::UNAUTHORIZED, String::new()))));
+        Ok(())
+    }
+
+    fn sign_in(self: &Self, _claims: Vec<Rc<dyn IAuthClaim>>, _request_context: &dyn IRequestContext, _response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        Err(Rc::new(self.error("bearer tokens are issued by the token issuer and cannot be signed in")))
+    }
+
+    fn sign_out(self: &Self, _request_context: &dyn IRequestContext, _response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        Err(Rc::new(self.error("bearer tokens are issued by the token issuer and cannot be signed out")))
+    }
+
+    fn can_sign_in(self: &Self) -> bool {
+        false
+    }
+}
@@ -1,3 +1,12 @@
 pub mod iauth_role;
 pub mod auth_role_json_file_dbset;
-pub mod iauthroles_dbset_provider;
\ No newline at end of file
+pub mod iauthroles_dbset_provider;
+pub mod auth_user_json_file_dbset;
+pub mod iauthusers_dbset_provider;
+pub mod authentication_handler;
+pub mod authorization_policy;
+pub mod claim;
+pub mod cookie_authentication_handler;
+pub mod jwt_bearer_authentication_handler;
+pub mod password_hasher;
+pub mod user_manager;
\ No newline at end of file
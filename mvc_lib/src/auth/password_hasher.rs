@@ -0,0 +1,105 @@
+use std::any::Any;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::options::identity_options::IIdentityOptions;
+use crate::services::service_collection::{IServiceCollection, ServiceCollectionExtensions};
+
+
+// this enum is the result of checking a password against a password hash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PasswordVerificationResult {
+    // the password does not match.
+    Failed,
+    // the password matches.
+    Success,
+    // the password matches, but the hash is weaker than new hashes and should be replaced.
+    SuccessRehashNeeded,
+}
+
+// this trait is used to hash passwords so they are never stored, and to check passwords against the hashes.
+pub trait IPasswordHasher {
+    // hash a password with a new random salt.
+    // password: the password to hash.
+    // returns: the hash, which includes everything needed to check a password against it.
+    fn hash_password(self: &Self, password: &str) -> String;
+
+    // check a password against a hash made by hash_password.
+    // hash: the hash of the password.
+    // password: the password to check.
+    fn verify_hashed_password(self: &Self, hash: &str, password: &str) -> PasswordVerificationResult;
+}
+
+// this struct hashes passwords with PBKDF2-HMAC-SHA256 and a 16 byte random salt.
+// a hash is written as "pbkdf2-sha256$<iterations>$<base64 salt>$<base64 hash>", so the number of iterations
+// can be raised later without making the existing hashes unusable.
+pub struct Pbkdf2PasswordHasher {
+    iterations: NonZeroU32,
+    random: SystemRandom,
+}
+
+impl Pbkdf2PasswordHasher {
+    // the name of the algorithm at the start of a hash.
+    pub const ALGORITHM: &'static str = "pbkdf2-sha256";
+    const SALT_LEN: usize = 16;
+    const HASH_LEN: usize = 32;
+
+    // create a new password hasher.
+    // iterations: how many PBKDF2 iterations new hashes are made with.
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            iterations: NonZeroU32::new(iterations).expect("the number of password hash iterations must not be 0"),
+            random: SystemRandom::new(),
+        }
+    }
+
+    // create a new password hasher as a service with the number of iterations of the identity options.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        let options = ServiceCollectionExtensions::get_required_single::<dyn IIdentityOptions>(services);
+        vec![Box::new(Rc::new(Self::new(options.get_password_hash_iterations())) as Rc<dyn IPasswordHasher>)]
+    }
+
+    // read the iterations, salt and hash of a hash made by hash_password.
+    fn parse(hash: &str) -> Option<(NonZeroU32, Vec<u8>, Vec<u8>)> {
+        let mut parts = hash.split('$');
+        if parts.next()? != Self::ALGORITHM {
+            return None;
+        }
+        let iterations = NonZeroU32::new(parts.next()?.parse().ok()?)?;
+        let salt = STANDARD_NO_PAD.decode(parts.next()?).ok()?;
+        let hash = STANDARD_NO_PAD.decode(parts.next()?).ok()?;
+        if parts.next().is_some() || hash.is_empty() {
+            return None;
+        }
+        Some((iterations, salt, hash))
+    }
+}
+
+impl IPasswordHasher for Pbkdf2PasswordHasher {
+    fn hash_password(self: &Self, password: &str) -> String {
+        let mut salt = [0u8; Self::SALT_LEN];
+        self.random.fill(&mut salt).expect("could not generate a salt for the password hash");
+        let mut hash = [0u8; Self::HASH_LEN];
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, self.iterations, &salt, password.as_bytes(), &mut hash);
+        format!("{}${}${}${}", Self::ALGORITHM, self.iterations, STANDARD_NO_PAD.encode(salt), STANDARD_NO_PAD.encode(hash))
+    }
+
+    fn verify_hashed_password(self: &Self, hash: &str, password: &str) -> PasswordVerificationResult {
+        match Self::parse(hash) {
+            Some((iterations, salt, hash)) => {
+                // verify compares in constant time, so the time taken does not tell how much of the hash matched.
+                match pbkdf2::verify(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, password.as_bytes(), &hash) {
+                    Ok(()) if iterations < self.iterations || hash.len() != Self::HASH_LEN => PasswordVerificationResult::SuccessRehashNeeded,
+                    Ok(()) => PasswordVerificationResult::Success,
+                    Err(_) => PasswordVerificationResult::Failed,
+                }
+            },
+            None => PasswordVerificationResult::Failed,
+        }
+    }
+}
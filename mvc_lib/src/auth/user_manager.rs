@@ -0,0 +1,272 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::auth::auth_role_json_file_dbset::JsonAuthRole;
+use crate::auth::auth_user_json_file_dbset::JsonAuthUser;
+use crate::auth::authentication_handler::IAuthClaimsValidator;
+use crate::auth::claim::Claim;
+use crate::auth::iauthroles_dbset_provider::IAuthRolesDbSetProvider;
+use crate::auth::iauthusers_dbset_provider::{GenericAuthUsersDbSetProvider, IAuthUsersDbSetProvider};
+use crate::auth::password_hasher::{IPasswordHasher, PasswordVerificationResult, Pbkdf2PasswordHasher};
+use crate::core::type_info::TypeInfo;
+use crate::entity::idbset::IDbSet;
+use crate::errors::IdentityError;
+use crate::options::identity_options::{IIdentityOptions, IdentityOptions};
+use crate::services::authorization_service::IAuthClaim;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this trait is used to register users, check their passwords and change their credentials and roles.
+pub trait IUserManager {
+    // find a user by user name, ignoring case.
+    fn find_by_name(self: &Self, user_name: &str) -> Option<JsonAuthUser>;
+    // find a user by id.
+    fn find_by_id(self: &Self, id: &str) -> Option<JsonAuthUser>;
+
+    // register a new user.
+    // user_name: the name the user logs in with, which must not be taken.
+    // password: the password of the user, which must be at least the minimum password length.
+    // returns: the new user, or an error if the user name is taken or the password is too short.
+    fn create(self: &Self, user_name: &str, password: &str) -> Result<JsonAuthUser, IdentityError>;
+
+    // check the password of a user to log in. the user is locked out after too many failed logins in a row.
+    // user_name: the name of the user.
+    // password: the password to check.
+    // now: the current time, in seconds since the unix epoch.
+    // returns: the user, or an error if the password does not match or the user is locked out.
+    fn check_password_sign_in(self: &Self, user_name: &str, password: &str, now: u64) -> Result<JsonAuthUser, IdentityError>;
+
+    // change the password of a user and their security stamp, so their other sessions are signed out.
+    // user_id: the id of the user.
+    // current_password: the password the user has now.
+    // new_password: the new password.
+    // returns: the changed user, or an error if the current password does not match or the new one is too short.
+    fn change_password(self: &Self, user_id: &str, current_password: &str, new_password: &str) -> Result<JsonAuthUser, IdentityError>;
+
+    // add a role to a user. the role must be in the auth roles dbset.
+    // user_id: the id of the user.
+    // role: the name of the role.
+    // returns: the changed user, or an error if the user or role does not exist.
+    fn add_to_role(self: &Self, user_id: &str, role: &str) -> Result<JsonAuthUser, IdentityError>;
+
+    // get the claims to sign in a user with: their id, name, security stamp and roles.
+    fn get_claims(self: &Self, user: &JsonAuthUser) -> Vec<Rc<dyn IAuthClaim>>;
+}
+
+// this struct implements IUserManager with the users in the auth users dbset.
+// the dbset is read again before each change, since each worker thread has its own copy of it.
+pub struct UserManager {
+    users: Rc<dyn IAuthUsersDbSetProvider>,
+    roles: Rc<dyn IAuthRolesDbSetProvider>,
+    password_hasher: Rc<dyn IPasswordHasher>,
+    options: Rc<dyn IIdentityOptions>,
+}
+
+impl UserManager {
+    // the name of the claim with the id of the user.
+    pub const USER_ID_CLAIM: &'static str = "UserId";
+    // the name of the claim with the name of the user.
+    pub const NAME_CLAIM: &'static str = "Name";
+    // the name of the claim with the security stamp of the user when they signed in.
+    pub const SECURITY_STAMP_CLAIM: &'static str = "SecurityStamp";
+
+    // create a new user manager.
+    // users: the provider of the dbset the users are stored in.
+    // roles: the provider of the dbset of the roles users can have.
+    // password_hasher: the hasher for the passwords of the users.
+    // options: the identity options.
+    pub fn new(
+        users: Rc<dyn IAuthUsersDbSetProvider>,
+        roles: Rc<dyn IAuthRolesDbSetProvider>,
+        password_hasher: Rc<dyn IPasswordHasher>,
+        options: Rc<dyn IIdentityOptions>,
+    ) -> Self {
+        Self { users: users, roles: roles, password_hasher: password_hasher, options: options }
+    }
+
+    fn new_from_services(services: &dyn IServiceCollection) -> Self {
+        Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn IAuthUsersDbSetProvider>(services),
+            ServiceCollectionExtensions::get_required_single::<dyn IAuthRolesDbSetProvider>(services),
+            ServiceCollectionExtensions::get_required_single::<dyn IPasswordHasher>(services),
+            ServiceCollectionExtensions::get_required_single::<dyn IIdentityOptions>(services),
+        )
+    }
+
+    // create a new user manager as a service.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_from_services(services)) as Rc<dyn IUserManager>)]
+    }
+
+    // create a new user manager as a service that signs out sessions with an old security stamp.
+    pub fn new_claims_validator_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_from_services(services)) as Rc<dyn IAuthClaimsValidator>)]
+    }
+
+    // add the identity options from the configuration, the users dbset, the password hasher and the user manager to the services.
+    // the auth roles dbset provider and an authentication handler, such as with CookieAuthenticationHandler::add_to_services,
+    // must also be added.
+    // services: the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IIdentityOptions>(), IdentityOptions::new_service_from_configuration, ServiceScope::Singleton));
+        GenericAuthUsersDbSetProvider::add_to_services(services);
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IPasswordHasher>(), Pbkdf2PasswordHasher::new_service, ServiceScope::Singleton));
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IUserManager>(), Self::new_service, ServiceScope::Singleton));
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IAuthClaimsValidator>(), Self::new_claims_validator_service, ServiceScope::Singleton));
+    }
+
+    fn get_dbset(self: &Self) -> &dyn IDbSet<JsonAuthUser> {
+        self.users.get_authusers_dbset()
+    }
+
+    // replace the stored user with the changed one and save the dbset.
+    fn update(self: &Self, user: &JsonAuthUser) -> Result<(), IdentityError> {
+        let dbset = self.get_dbset();
+        dbset.find();
+        dbset.remove(user);
+        dbset.add(user);
+        Self::save_changes(dbset)
+    }
+
+    // save the changes to the users, so they are kept when the program restarts.
+    fn save_changes(dbset: &dyn IDbSet<JsonAuthUser>) -> Result<(), IdentityError> {
+        dbset.upcast().save_changes().map_err(|e| IdentityError::new(format!("Could not save the user: {}", e)))
+    }
+
+    fn validate_password(self: &Self, password: &str) -> Result<(), IdentityError> {
+        let minimum_length = self.options.get_minimum_password_length();
+        if password.chars().count() < minimum_length {
+            return Err(IdentityError::new(format!("Passwords must be at least {} characters.", minimum_length)));
+        }
+        Ok(())
+    }
+
+    fn get_role_names(self: &Self) -> Vec<String> {
+        self.roles.get_authroles_dbset().get_all_any().iter().filter_map(|x| x.downcast_ref::<JsonAuthRole>().map(|x| x.name.clone())).collect()
+    }
+
+    fn get_claim_value(claims: &Vec<Rc<dyn IAuthClaim>>, name: &str) -> Option<String> {
+        claims.iter().find(|x| x.get_name() == name).and_then(|x| x.get_tokens().get(name).cloned())
+    }
+}
+
+impl IUserManager for UserManager {
+    fn find_by_name(self: &Self, user_name: &str) -> Option<JsonAuthUser> {
+        let normalized_user_name = JsonAuthUser::normalize_user_name(user_name);
+        self.get_dbset().find().into_iter().find(|x| x.normalized_user_name == normalized_user_name)
+    }
+
+    fn find_by_id(self: &Self, id: &str) -> Option<JsonAuthUser> {
+        self.get_dbset().find().into_iter().find(|x| x.id == id)
+    }
+
+    fn create(self: &Self, user_name: &str, password: &str) -> Result<JsonAuthUser, IdentityError> {
+        let user_name = user_name.trim();
+        if user_name.is_empty() {
+            return Err(IdentityError::new("User name is required.".to_string()));
+        }
+        self.validate_password(password)?;
+        if self.find_by_name(user_name).is_some() {
+            return Err(IdentityError::new(format!("User name '{}' is already taken.", user_name)));
+        }
+
+        let mut user = JsonAuthUser::new();
+        user.user_name = user_name.to_string();
+        user.normalized_user_name = JsonAuthUser::normalize_user_name(user_name);
+        user.password_hash = self.password_hasher.hash_password(password);
+        let dbset = self.get_dbset();
+        dbset.add(&user);
+        Self::save_changes(dbset)?;
+        Ok(user)
+    }
+
+    fn check_password_sign_in(self: &Self, user_name: &str, password: &str, now: u64) -> Result<JsonAuthUser, IdentityError> {
+        // the same error is returned for a user that does not exist, so it cannot be used to find user names.
+        let invalid = || IdentityError::new("Invalid user name or password.".to_string());
+        let mut user = match self.find_by_name(user_name) {
+            Some(user) => user,
+            None => {
+                // hash the password anyway, so the time taken does not tell whether the user exists.
+                self.password_hasher.hash_password(password);
+                return Err(invalid());
+            },
+        };
+        if user.is_locked_out(now) {
+            return Err(IdentityError::new("The account is locked out. Try again later.".to_string()));
+        }
+
+        match self.password_hasher.verify_hashed_password(&user.password_hash, password) {
+            PasswordVerificationResult::Failed => {
+                user.access_failed_count += 1;
+                let locked_out = user.access_failed_count >= self.options.get_max_failed_access_attempts();
+                if locked_out {
+                    user.access_failed_count = 0;
+                    user.lockout_end = Some(now + self.options.get_lockout_time().as_secs());
+                }
+                self.update(&user)?;
+                Err(if locked_out { IdentityError::new("The account is locked out. Try again later.".to_string()) } else { invalid() })
+            },
+            result => {
+                if result == PasswordVerificationResult::SuccessRehashNeeded {
+                    user.password_hash = self.password_hasher.hash_password(password);
+                }
+                if user.access_failed_count > 0 || user.lockout_end.is_some() || result == PasswordVerificationResult::SuccessRehashNeeded {
+                    user.access_failed_count = 0;
+                    user.lockout_end = None;
+                    self.update(&user)?;
+                }
+                Ok(user)
+            },
+        }
+    }
+
+    fn change_password(self: &Self, user_id: &str, current_password: &str, new_password: &str) -> Result<JsonAuthUser, IdentityError> {
+        let mut user = self.find_by_id(user_id).ok_or_else(|| IdentityError::new("The user does not exist.".to_string()))?;
+        if self.password_hasher.verify_hashed_password(&user.password_hash, current_password) == PasswordVerificationResult::Failed {
+            return Err(IdentityError::new("The current password is incorrect.".to_string()));
+        }
+        self.validate_password(new_password)?;
+
+        user.password_hash = self.password_hasher.hash_password(new_password);
+        user.security_stamp = JsonAuthUser::new_security_stamp();
+        self.update(&user)?;
+        Ok(user)
+    }
+
+    fn add_to_role(self: &Self, user_id: &str, role: &str) -> Result<JsonAuthUser, IdentityError> {
+        let mut user = self.find_by_id(user_id).ok_or_else(|| IdentityError::new("The user does not exist.".to_string()))?;
+        if !self.get_role_names().iter().any(|x| x == role) {
+            return Err(IdentityError::new(format!("The role '{}' does not exist.", role)));
+        }
+        if !user.roles.iter().any(|x| x == role) {
+            user.roles.push(role.to_string());
+            self.update(&user)?;
+        }
+        Ok(user)
+    }
+
+    fn get_claims(self: &Self, user: &JsonAuthUser) -> Vec<Rc<dyn IAuthClaim>> {
+        vec![
+            Claim::new_identifier(Self::USER_ID_CLAIM, &user.id),
+            Claim::new_identifier(Self::NAME_CLAIM, &user.user_name),
+            Rc::new(Claim::new(Self::SECURITY_STAMP_CLAIM.to_string(), HashMap::from([(Self::SECURITY_STAMP_CLAIM.to_string(), user.security_stamp.clone())]))),
+        ].into_iter().chain(user.roles.iter().map(|x| Claim::new_role(x))).collect()
+    }
+}
+
+impl IAuthClaimsValidator for UserManager {
+    fn validate_claims(self: &Self, claims: &Vec<Rc<dyn IAuthClaim>>) -> bool {
+        let user_id = match Self::get_claim_value(claims, Self::USER_ID_CLAIM) {
+            Some(user_id) => user_id,
+            // the claims are not for a user of the store, such as claims from another handler.
+            None => return true,
+        };
+        match self.find_by_id(&user_id) {
+            Some(user) => Self::get_claim_value(claims, Self::SECURITY_STAMP_CLAIM).map(|x| x == user.security_stamp).unwrap_or(false),
+            None => false,
+        }
+    }
+}
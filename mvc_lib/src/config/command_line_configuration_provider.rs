@@ -0,0 +1,44 @@
+use crate::errors::ConfigurationError;
+
+use super::configuration::KEY_DELIMITER;
+use super::configuration_provider::IConfigurationProvider;
+
+
+// this struct implements IConfigurationProvider for command line arguments.
+// values are given as "--key=value" or "--key value", with sections separated by ':' or "__",
+// such as "--http:port=8080". a flag without a value, such as "--verbose", is set to "true".
+// arguments that do not start with "--" are skipped.
+pub struct CommandLineConfigurationProvider {
+    args: Vec<String>,
+}
+
+impl CommandLineConfigurationProvider {
+    // create a new CommandLineConfigurationProvider struct.
+    // args: the command line arguments, which may include the program path.
+    pub fn new(args: Vec<String>) -> Self {
+        Self { args: args }
+    }
+}
+
+impl IConfigurationProvider for CommandLineConfigurationProvider {
+    fn load(self: &Self) -> Result<Vec<(String, String)>, ConfigurationError> {
+        let mut values = Vec::new();
+        let mut args = self.args.iter().peekable();
+        while let Some(arg) = args.next() {
+            let arg = match arg.strip_prefix("--") {
+                Some(x) if !x.is_empty() => x,
+                _ => continue,
+            };
+
+            let (key, value) = match arg.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => match args.next_if(|x| !x.starts_with("--")) {
+                    Some(value) => (arg.to_string(), value.clone()),
+                    None => (arg.to_string(), "true".to_string()),
+                },
+            };
+            values.push((key.replace("__", KEY_DELIMITER), value));
+        }
+        Ok(values)
+    }
+}
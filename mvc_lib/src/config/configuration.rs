@@ -0,0 +1,168 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::core::type_info::TypeInfo;
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// the separator between the sections of a configuration key, such as "http:port".
+pub const KEY_DELIMITER: &'static str = ":";
+
+// this trait represents the configuration of the program, or a section of it.
+// keys are made of sections separated by ':' and are not case sensitive.
+pub trait IConfiguration {
+    // get the full key of this section, or an empty string for the root of the configuration.
+    fn get_path(self: &Self) -> String;
+
+    // get a value from the configuration.
+    // key: the key of the value relative to this section, such as "port" or "https:port".
+    // returns: the value, or None if it is not set.
+    fn get(self: &Self, key: &str) -> Option<String>;
+
+    // get a section of the configuration. the section is empty if no values are set under it.
+    // key: the key of the section relative to this section.
+    fn get_section(self: &Self, key: &str) -> Rc<dyn IConfiguration>;
+
+    // get the keys of the sections directly under this section, in order.
+    // arrays are stored with their indexes as keys, so the children of an array are "0", "1" and so on.
+    fn get_children(self: &Self) -> Vec<String>;
+
+    // whether any values are set in or under this section.
+    fn exists(self: &Self) -> bool;
+}
+
+// this struct implements IConfiguration.
+// sections share the values of the configuration they were taken from.
+#[derive(Clone)]
+pub struct Configuration {
+    // the full keys as they were given and the values, by their lower case full keys.
+    values: Rc<HashMap<String, (String, String)>>,
+    // the lower case full key of this section, or an empty string for the root.
+    path: String,
+}
+
+impl Configuration {
+    // create a new configuration from a list of keys and values.
+    // later values replace earlier values with the same key.
+    // values: the keys and values of the configuration.
+    pub fn new(values: Vec<(String, String)>) -> Self {
+        Self {
+            values: Rc::new(values.into_iter().map(|(k, v)| (k.to_lowercase(), (k, v))).collect()),
+            path: String::new(),
+        }
+    }
+
+    // combine a section path and a key into a full key.
+    pub fn combine(path: &str, key: &str) -> String {
+        if path.is_empty() {
+            key.to_lowercase()
+        } else if key.is_empty() {
+            path.to_lowercase()
+        } else {
+            format!("{}{}{}", path, KEY_DELIMITER, key).to_lowercase()
+        }
+    }
+
+    // add a configuration to the service collection as a singleton.
+    pub fn add_to_services(services: &mut ServiceCollection, configuration: Rc<dyn IConfiguration>) {
+        services.add(ServiceDescriptor::new_closure(
+            TypeInfo::rc_of::<dyn IConfiguration>(),
+            move |_| vec![Box::new(configuration.clone()) as Box<dyn Any>],
+            ServiceScope::Singleton));
+    }
+}
+
+impl IConfiguration for Configuration {
+    fn get_path(self: &Self) -> String {
+        self.path.clone()
+    }
+
+    fn get(self: &Self, key: &str) -> Option<String> {
+        self.values.get(&Self::combine(&self.path, key)).map(|x| x.1.clone())
+    }
+
+    fn get_section(self: &Self, key: &str) -> Rc<dyn IConfiguration> {
+        Rc::new(Self { values: self.values.clone(), path: Self::combine(&self.path, key) })
+    }
+
+    fn get_children(self: &Self) -> Vec<String> {
+        let prefix = if self.path.is_empty() { String::new() } else { format!("{}{}", self.path, KEY_DELIMITER) };
+        let depth = if self.path.is_empty() { 0 } else { self.path.split(KEY_DELIMITER).count() };
+        // children keep the case of the key they were given with, since they may be names such as request paths.
+        let mut children = self.values
+            .iter()
+            .filter(|(k, _)| k.starts_with(&prefix) && k.len() > prefix.len())
+            .filter_map(|(_, (key, _))| key.split(KEY_DELIMITER).nth(depth).map(|x| x.to_string()))
+            .collect::<Vec<String>>();
+        // array indexes are sorted as numbers so "10" comes after "9", and come before names.
+        children.sort_by_key(|x| (x.parse::<usize>().is_err(), x.parse::<usize>().unwrap_or(0), x.to_lowercase()));
+        children.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        children
+    }
+
+    fn exists(self: &Self) -> bool {
+        !self.get_children().is_empty() || self.values.contains_key(&self.path)
+    }
+}
+
+// extension methods for IConfiguration, used to bind values onto option structs.
+// each bind method leaves the target unchanged when the value is not set.
+pub struct ConfigurationExtensions {}
+
+impl ConfigurationExtensions {
+    // get the section with a key from the configuration service, if there is a configuration service.
+    // services: the services to get the configuration from.
+    // key: the key of the section.
+    pub fn try_get_section(services: &dyn IServiceCollection, key: &str) -> Option<Rc<dyn IConfiguration>> {
+        ServiceCollectionExtensions::try_get_single::<dyn IConfiguration>(services)
+            .ok()
+            .flatten()
+            .map(|x| x.get_section(key))
+    }
+
+    // get a value from the configuration and parse it.
+    // returns: the parsed value, None if it is not set, or an error if it could not be parsed.
+    pub fn get_parsed<T: FromStr>(configuration: &dyn IConfiguration, key: &str) -> Result<Option<T>, ConfigurationError> where T::Err: std::fmt::Display {
+        match configuration.get(key) {
+            Some(value) => value.trim().parse::<T>()
+                .map(|x| Some(x))
+                .map_err(|e| ConfigurationError::new(Configuration::combine(&configuration.get_path(), key), format!("could not parse '{}': {}", value, e))),
+            None => Ok(None),
+        }
+    }
+
+    // bind a value that can be parsed from a string, such as a number or bool.
+    pub fn bind<T: FromStr>(configuration: &dyn IConfiguration, key: &str, target: &mut T) -> Result<(), ConfigurationError> where T::Err: std::fmt::Display {
+        if let Some(value) = Self::get_parsed::<T>(configuration, key)? {
+            *target = value;
+        }
+        Ok(())
+    }
+
+    // bind a duration, given in (fractional) seconds.
+    pub fn bind_seconds(configuration: &dyn IConfiguration, key: &str, target: &mut Duration) -> Result<(), ConfigurationError> {
+        if let Some(value) = Self::get_parsed::<f64>(configuration, key)? {
+            *target = Duration::try_from_secs_f64(value)
+                .map_err(|e| ConfigurationError::new(Configuration::combine(&configuration.get_path(), key), e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    // get the values of an array, such as the values under "0", "1" and so on.
+    pub fn get_list(configuration: &dyn IConfiguration, key: &str) -> Vec<String> {
+        let section = configuration.get_section(key);
+        section.get_children().iter().filter_map(|x| section.get(x)).collect()
+    }
+
+    // get the values directly under a section with their keys.
+    pub fn get_map(configuration: &dyn IConfiguration, key: &str) -> Vec<(String, String)> {
+        let section = configuration.get_section(key);
+        section.get_children().into_iter().filter_map(|x| section.get(&x).map(|v| (x, v))).collect()
+    }
+}
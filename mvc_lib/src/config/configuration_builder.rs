@@ -0,0 +1,105 @@
+use std::path::Path;
+
+use crate::errors::ConfigurationError;
+
+use super::command_line_configuration_provider::CommandLineConfigurationProvider;
+use super::configuration::{Configuration, IConfiguration};
+use super::configuration_provider::{IConfigurationProvider, MemoryConfigurationProvider};
+use super::environment_variables_configuration_provider::EnvironmentVariablesConfigurationProvider;
+use super::json_file_configuration_provider::JsonFileConfigurationProvider;
+
+
+// the prefix of the environment variables read by the default configuration.
+pub const ENVIRONMENT_VARIABLE_PREFIX: &'static str = "RSHTML_";
+// the key of the environment name, such as "Development" or "Production".
+pub const ENVIRONMENT_KEY: &'static str = "environment";
+// the key of the directory the application files are read from, such as appsettings.json.
+pub const CONTENT_ROOT_KEY: &'static str = "content_root";
+// the key of the application name.
+pub const APPLICATION_NAME_KEY: &'static str = "application_name";
+// the environment name used when none is configured.
+pub const DEFAULT_ENVIRONMENT: &'static str = "Production";
+
+// this struct builds a configuration from layers of providers.
+// values from providers added later replace values from providers added earlier.
+pub struct ConfigurationBuilder {
+    providers: Vec<Box<dyn IConfigurationProvider>>,
+}
+
+impl ConfigurationBuilder {
+    // create a new ConfigurationBuilder struct without any providers.
+    pub fn new() -> Self {
+        Self { providers: vec![] }
+    }
+
+    // create a new ConfigurationBuilder struct with the default layers:
+    // appsettings.json, appsettings.{environment}.json, environment variables starting with RSHTML_, and the command line.
+    // the environment name is read from the environment variables and command line, such as RSHTML_ENVIRONMENT=Development or --environment=Development.
+    // the files are read from the content root, such as RSHTML_CONTENT_ROOT=/srv/app or --content_root=/srv/app, or the current directory.
+    // args: the command line arguments.
+    pub fn new_default(args: &Vec<String>) -> Result<Self, ConfigurationError> {
+        let environment = Self::get_environment_name(args)?;
+        let content_root = Self::new_host_configuration(args).build()?.get(CONTENT_ROOT_KEY).unwrap_or_default();
+        let json_file_path = |name: String| Path::new(&content_root).join(name).to_string_lossy().to_string();
+        let mut builder = Self::new();
+        builder
+            .add_json_file(&json_file_path("appsettings.json".to_string()), true)
+            .add_json_file(&json_file_path(format!("appsettings.{}.json", environment)), true)
+            .add_environment_variables(ENVIRONMENT_VARIABLE_PREFIX)
+            .add_command_line(args);
+        Ok(builder)
+    }
+
+    // get the environment name from the environment variables and command line.
+    // args: the command line arguments.
+    pub fn get_environment_name(args: &Vec<String>) -> Result<String, ConfigurationError> {
+        Ok(Self::new_host_configuration(args).build()?.get(ENVIRONMENT_KEY).filter(|x| !x.is_empty()).unwrap_or(DEFAULT_ENVIRONMENT.to_string()))
+    }
+
+    // create a new ConfigurationBuilder struct with the layers that are read before the files, which decide where and which files are read.
+    fn new_host_configuration(args: &Vec<String>) -> Self {
+        let mut builder = Self::new();
+        builder
+            .add_environment_variables(ENVIRONMENT_VARIABLE_PREFIX)
+            .add_command_line(args);
+        builder
+    }
+
+    // add a provider as the last layer.
+    pub fn add(self: &mut Self, provider: Box<dyn IConfigurationProvider>) -> &mut Self {
+        self.providers.push(provider);
+        self
+    }
+
+    // add a JSON file.
+    // path: the path to the file.
+    // optional: whether the file can be missing.
+    pub fn add_json_file(self: &mut Self, path: &str, optional: bool) -> &mut Self {
+        self.add(Box::new(JsonFileConfigurationProvider::new(path.to_string(), optional)))
+    }
+
+    // add the environment variables that start with a prefix.
+    pub fn add_environment_variables(self: &mut Self, prefix: &str) -> &mut Self {
+        self.add(Box::new(EnvironmentVariablesConfigurationProvider::new(prefix.to_string())))
+    }
+
+    // add the command line arguments.
+    pub fn add_command_line(self: &mut Self, args: &Vec<String>) -> &mut Self {
+        self.add(Box::new(CommandLineConfigurationProvider::new(args.clone())))
+    }
+
+    // add values that are already known, such as defaults set in code.
+    pub fn add_in_memory(self: &mut Self, values: Vec<(&str, &str)>) -> &mut Self {
+        self.add(Box::new(MemoryConfigurationProvider::new(values.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect())))
+    }
+
+    // load the providers in order and build the configuration.
+    // returns: the configuration, or the first error from a provider.
+    pub fn build(self: &Self) -> Result<Configuration, ConfigurationError> {
+        let mut values = Vec::new();
+        for provider in self.providers.iter() {
+            values.extend(provider.load()?);
+        }
+        Ok(Configuration::new(values))
+    }
+}
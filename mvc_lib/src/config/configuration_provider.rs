@@ -0,0 +1,28 @@
+use crate::errors::ConfigurationError;
+
+
+// this trait represents a source of configuration values, such as a file or the environment variables.
+pub trait IConfigurationProvider {
+    // load the values of the source.
+    // returns: the full keys, with sections separated by ':', and their values.
+    fn load(self: &Self) -> Result<Vec<(String, String)>, ConfigurationError>;
+}
+
+// this struct implements IConfigurationProvider for values that are already known, such as defaults set in code.
+pub struct MemoryConfigurationProvider {
+    values: Vec<(String, String)>,
+}
+
+impl MemoryConfigurationProvider {
+    // create a new MemoryConfigurationProvider struct.
+    // values: the full keys and their values.
+    pub fn new(values: Vec<(String, String)>) -> Self {
+        Self { values: values }
+    }
+}
+
+impl IConfigurationProvider for MemoryConfigurationProvider {
+    fn load(self: &Self) -> Result<Vec<(String, String)>, ConfigurationError> {
+        Ok(self.values.clone())
+    }
+}
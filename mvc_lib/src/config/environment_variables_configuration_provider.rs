@@ -0,0 +1,39 @@
+use crate::errors::ConfigurationError;
+
+use super::configuration::KEY_DELIMITER;
+use super::configuration_provider::IConfigurationProvider;
+
+
+// this struct implements IConfigurationProvider for the environment variables that start with a prefix.
+// the prefix is removed from the keys, and "__" separates sections since ':' is not allowed in most shells,
+// so RSHTML_HTTP__PORT=8080 sets "http:port".
+pub struct EnvironmentVariablesConfigurationProvider {
+    prefix: String,
+}
+
+impl EnvironmentVariablesConfigurationProvider {
+    // create a new EnvironmentVariablesConfigurationProvider struct.
+    // prefix: the prefix of the environment variables to read, such as "RSHTML_". ascii letters in it are not case sensitive.
+    pub fn new(prefix: String) -> Self {
+        Self { prefix: prefix }
+    }
+
+    // get the values from a list of environment variables.
+    // variables: the names and values of the environment variables.
+    pub fn from_variables(self: &Self, variables: impl Iterator<Item=(String, String)>) -> Vec<(String, String)> {
+        let prefix = self.prefix.as_bytes();
+        variables
+            .filter(|(name, _)| name.len() > prefix.len() && name.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix))
+            .map(|(name, value)| (name[prefix.len()..].replace("__", KEY_DELIMITER), value))
+            .collect()
+    }
+}
+
+impl IConfigurationProvider for EnvironmentVariablesConfigurationProvider {
+    fn load(self: &Self) -> Result<Vec<(String, String)>, ConfigurationError> {
+        // variables that are not valid unicode are skipped, since std::env::vars would panic on them.
+        let variables = std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)));
+        Ok(self.from_variables(variables))
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::errors::ConfigurationError;
+
+use super::configuration::KEY_DELIMITER;
+use super::configuration_provider::IConfigurationProvider;
+
+
+// this struct implements IConfigurationProvider for a JSON file, such as appsettings.json.
+// objects become sections, and array items become sections with their indexes as keys.
+pub struct JsonFileConfigurationProvider {
+    path: String,
+    // whether the file can be missing.
+    optional: bool,
+}
+
+impl JsonFileConfigurationProvider {
+    // create a new JsonFileConfigurationProvider struct.
+    // path: the path to the JSON file.
+    // optional: whether the file can be missing. a file that exists but is not valid JSON is always an error.
+    pub fn new(path: String, optional: bool) -> Self {
+        Self { path: path, optional: optional }
+    }
+
+    // flatten a JSON value into full keys and values.
+    // json: the JSON text.
+    // returns: the full keys and their values, or an error if the text is not a JSON object.
+    pub fn parse(json: &str) -> Result<Vec<(String, String)>, String> {
+        let value = serde_json::from_str::<Value>(json).map_err(|e| e.to_string())?;
+        if !value.is_object() {
+            return Err("the root of the configuration must be an object".to_string());
+        }
+
+        let mut values = Vec::new();
+        Self::flatten(&value, String::new(), &mut values);
+        Ok(values)
+    }
+
+    fn flatten(value: &Value, path: String, values: &mut Vec<(String, String)>) {
+        let child_path = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}{}{}", path, KEY_DELIMITER, key) };
+        match value {
+            Value::Object(object) => {
+                for (key, child) in object.iter() {
+                    Self::flatten(child, child_path(key), values);
+                }
+            },
+            Value::Array(array) => {
+                for (index, child) in array.iter().enumerate() {
+                    Self::flatten(child, child_path(&index.to_string()), values);
+                }
+            },
+            Value::Null => values.push((path, String::new())),
+            Value::String(x) => values.push((path, x.clone())),
+            x => values.push((path, x.to_string())),
+        }
+    }
+}
+
+impl IConfigurationProvider for JsonFileConfigurationProvider {
+    fn load(self: &Self) -> Result<Vec<(String, String)>, ConfigurationError> {
+        if self.optional && !Path::new(&self.path).exists() {
+            return Ok(vec![]);
+        }
+
+        let json = std::fs::read_to_string(&self.path)
+            .map_err(|e| ConfigurationError::new(self.path.clone(), e.to_string()))?;
+        Self::parse(&json).map_err(|e| ConfigurationError::new(self.path.clone(), e))
+    }
+}
@@ -0,0 +1,6 @@
+pub mod command_line_configuration_provider;
+pub mod configuration;
+pub mod configuration_builder;
+pub mod configuration_provider;
+pub mod environment_variables_configuration_provider;
+pub mod json_file_configuration_provider;
\ No newline at end of file
@@ -4,12 +4,15 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::rc::Rc;
 
+use crate::auth::authentication_handler::IAuthenticationHandler;
 use crate::contexts::irequest_context::IRequestContext;
 
 use crate::controllers::icontroller::IController;
 use crate::controllers::icontroller_extensions::IControllerExtensions;
 
 use crate::routing::route_data::RouteData;
+use crate::services::authorization_service::IAuthClaim;
+use crate::services::service_collection::{IServiceCollection, ServiceCollectionExtensions};
 
 use super::iresponse_context::IResponseContext;
 
@@ -39,6 +42,16 @@ pub trait IControllerContext {
     // insert a string into the context data.
     fn insert_str(self: &Self, key: &str, value: String) -> String;
 
+    // sign in with the claims using the first authentication handler that can sign in, such as the cookie authentication handler.
+    // claims: the claims of the user.
+    // services: the services to get the authentication handler from.
+    // returns: an error if there is no authentication handler or it could not sign in.
+    fn sign_in(self: &Self, claims: Vec<Rc<dyn IAuthClaim>>, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>>;
+    // sign out using the first authentication handler that can sign in.
+    // services: the services to get the authentication handler from.
+    // returns: an error if there is no authentication handler or it could not sign out.
+    fn sign_out(self: &Self, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>>;
+
     // bind the action model type to the request context body content.
     // fn bind_model(self: &Self, type_info: TypeInfo, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>>;
 
@@ -103,6 +116,12 @@ impl <'a> ControllerContext<'a> {
 
         Ok(Box::new(route_data))
     }
+
+    // get the first authentication handler that can sign users in and out.
+    fn get_authentication_handler(services: &dyn IServiceCollection) -> Result<Rc<dyn IAuthenticationHandler>, Rc<dyn Error>> {
+        ServiceCollectionExtensions::get_required_multiple::<dyn IAuthenticationHandler>(services).into_iter().find(|x| x.can_sign_in())
+            .ok_or_else(|| Rc::new(std::io::Error::new(std::io::ErrorKind::NotFound, "no authentication handler that can sign in was added to the services")) as Rc<dyn Error>)
+    }
 }
 
 impl <'a> IControllerContext for ControllerContext<'a> {
@@ -152,6 +171,14 @@ impl <'a> IControllerContext for ControllerContext<'a> {
         self.insert_string(key.to_string(), value)
     }
 
+    fn sign_in(self: &Self, claims: Vec<Rc<dyn IAuthClaim>>, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>> {
+        Self::get_authentication_handler(services)?.sign_in(claims, self.request_context, self.response_context)
+    }
+
+    fn sign_out(self: &Self, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>> {
+        Self::get_authentication_handler(services)?.sign_out(self.request_context, self.response_context)
+    }
+
     // fn bind_model(self: &Self, type_info: TypeInfo, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>> {
     //     if let Some(body) = self.request_context.get_body_content() {
     //         let model_binder_service = ServiceCollectionExtensions::get_required_single::<dyn IModelBinderService>(services);
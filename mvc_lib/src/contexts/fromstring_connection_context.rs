@@ -1,6 +1,9 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::core::content_length_stream::ContentLengthStream;
+use crate::core::max_length_stream::MaxLengthStream;
+use crate::core::itcp_stream_wrapper::ITcpStreamWrapper;
 use crate::http::http_body_content::ContentType;
 use crate::http::ihttp_body_stream_format::IHttpBodyStreamFormat;
 
@@ -9,23 +12,56 @@ use super::itcpconnection_context::ITcpConnectionContext;
 
 // this is a mock implementation of ITcpConnectionContext that reads from a string and writes to an in-memory buffer.
 pub struct FromStringConnectionContext {
-    data: String,
+    data: Rc<String>,
     connection_id: u32,
     is_shutdown: RefCell<Option<std::net::Shutdown>>,
-    input_position: RefCell<usize>,
+    input_position: Rc<RefCell<usize>>,
+    // the body of the current request, if one was started.
+    request_body: RefCell<Option<Rc<RefCell<dyn ITcpStreamWrapper>>>>,
+    // the stream that the body is read from, which is the request body with any decoders added.
+    body_stream: RefCell<Option<Rc<RefCell<dyn ITcpStreamWrapper>>>>,
+    // the limit of the current request body and the streams that enforce it, before and after decoding.
+    request_body_limit: RefCell<Option<usize>>,
+    limited_streams: RefCell<Vec<Rc<RefCell<MaxLengthStream>>>>,
     output_buffer: RefCell<Vec<u8>>,
+    secure: RefCell<bool>,
 }
 
 impl FromStringConnectionContext {
     pub fn new(data: String, connection_id: u32) -> Self {
         Self {
-            data: data,
+            data: Rc::new(data),
             connection_id: connection_id,
             is_shutdown: RefCell::new(None),
-            input_position: RefCell::new(0),
+            input_position: Rc::new(RefCell::new(0)),
+            request_body: RefCell::new(None),
+            body_stream: RefCell::new(None),
+            request_body_limit: RefCell::new(None),
+            limited_streams: RefCell::new(Vec::new()),
             output_buffer: RefCell::new(Vec::new()),
+            secure: RefCell::new(false),
         }
     }
+
+    // set whether the connection acts as if it is encrypted with TLS.
+    pub fn set_secure(&self, secure: bool) {
+        self.secure.replace(secure);
+    }
+
+    // create a stream that reads the same data as the connection.
+    fn new_source_stream(&self) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
+        Rc::new(RefCell::new(FromStringStream { data: self.data.clone(), input_position: self.input_position.clone() }))
+    }
+
+    // get everything written to the connection so far.
+    pub fn get_output(&self) -> Vec<u8> {
+        self.output_buffer.borrow().clone()
+    }
+
+    // get everything written to the connection so far as a string.
+    pub fn get_output_string(&self) -> String {
+        String::from_utf8_lossy(&self.output_buffer.borrow()).to_string()
+    }
 }
 
 impl ITcpConnectionContext for FromStringConnectionContext {
@@ -41,6 +77,10 @@ impl ITcpConnectionContext for FromStringConnectionContext {
         self.connection_id
     }
 
+    fn is_secure(&self) -> bool {
+        *self.secure.borrow()
+    }
+
     fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
         if self.is_shutdown.borrow().is_some() {
             Err(std::io::Error::new(std::io::ErrorKind::NotConnected, "Already shutdown"))
@@ -61,16 +101,27 @@ impl ITcpConnectionContext for FromStringConnectionContext {
     }
 
     fn write_line(&self,b: &String) -> std::io::Result<usize> {
-        self.output_buffer.borrow_mut().extend_from_slice(b.as_bytes());
-        Ok(b.len())
+        self.write(format!("{}\r\n", b).as_bytes())
     }
 
     fn read(&self,b: &mut[u8]) -> std::io::Result<usize> {
-        b.copy_from_slice(self.data.as_bytes());
-        Ok(self.data.len())
+        if let Some(body_stream) = self.body_stream.borrow().as_ref() {
+            return body_stream.borrow().read(b);
+        }
+
+        let pos = *self.input_position.borrow();
+        let available = &self.data.as_bytes()[std::cmp::min(pos, self.data.len())..];
+        let num_read = std::cmp::min(available.len(), b.len());
+        b[..num_read].copy_from_slice(&available[..num_read]);
+        self.input_position.replace(pos + num_read);
+        Ok(num_read)
     }
 
     fn read_line(&self) -> std::io::Result<String> {
+        if let Some(body_stream) = self.body_stream.borrow().as_ref() {
+            return body_stream.borrow().read_line();
+        }
+
         let pos = *self.input_position.borrow();
         if pos < self.data.len() {
             let line: String = self.data.as_str().chars().skip(pos).take_while(|c| *c != '\r' && *c != '\n').collect();
@@ -90,7 +141,122 @@ impl ITcpConnectionContext for FromStringConnectionContext {
         }
     }
 
-    fn add_stream_decoders(&self, _decoders: &[Rc<dyn IHttpBodyStreamFormat>], _content_type: &ContentType) {
-        todo!()
+    fn add_stream_decoders(&self, decoders: &[Rc<dyn IHttpBodyStreamFormat>], content_type: &ContentType) {
+        let mut stream = self.body_stream.borrow().clone().unwrap_or_else(|| self.new_source_stream());
+        for decoder in decoders.iter() {
+            stream = decoder.decode(stream, content_type);
+        }
+
+        let request_body_limit = *self.request_body_limit.borrow();
+        if let Some(max_length) = request_body_limit.filter(|_| !decoders.is_empty()) {
+            let limited_body = Rc::new(RefCell::new(MaxLengthStream::new(stream, max_length)));
+            self.limited_streams.borrow_mut().push(limited_body.clone());
+            stream = limited_body;
+        }
+        self.body_stream.replace(Some(stream));
+    }
+
+    fn begin_request_body(&self, content_length: usize) {
+        let request_body: Rc<RefCell<dyn ITcpStreamWrapper>> = Rc::new(RefCell::new(ContentLengthStream::new(self.new_source_stream(), content_length)));
+        self.body_stream.replace(Some(request_body.clone()));
+        self.request_body.replace(Some(request_body));
+        self.request_body_limit.replace(None);
+        self.limited_streams.borrow_mut().clear();
+    }
+
+    fn begin_request_body_format(&self, format: Rc<dyn IHttpBodyStreamFormat>, transfer_encoding: &ContentType) {
+        let request_body = format.decode(self.new_source_stream(), transfer_encoding);
+        self.body_stream.replace(Some(request_body.clone()));
+        self.request_body.replace(Some(request_body));
+        self.request_body_limit.replace(None);
+        self.limited_streams.borrow_mut().clear();
+    }
+
+    fn set_read_timeout(&self, _timeout: Option<std::time::Duration>) -> std::io::Result<()> {
+        // reading from a string never waits.
+        Ok(())
+    }
+
+    fn limit_request_body(&self, max_length: usize) {
+        let request_body = self.request_body.borrow().clone();
+        if let Some(request_body) = request_body {
+            let limited_body = Rc::new(RefCell::new(MaxLengthStream::new(request_body, max_length)));
+            self.limited_streams.borrow_mut().push(limited_body.clone());
+            self.body_stream.replace(Some(limited_body.clone()));
+            self.request_body.replace(Some(limited_body));
+            self.request_body_limit.replace(Some(max_length));
+        }
+    }
+
+    fn is_request_body_too_large(&self) -> bool {
+        self.limited_streams.borrow().iter().any(|x| x.borrow().is_exceeded())
     }
-}
\ No newline at end of file
+
+    fn end_request_body(&self) -> std::io::Result<()> {
+        self.body_stream.replace(None);
+        self.request_body_limit.replace(None);
+        self.limited_streams.borrow_mut().clear();
+        if let Some(request_body) = self.request_body.replace(None) {
+            // read and discard the rest of the body
+            let mut buffer = [0; 4096];
+            while request_body.borrow().read(&mut buffer)? > 0 {
+            }
+        }
+        Ok(())
+    }
+}
+
+
+// this struct reads the same string as a FromStringConnectionContext, so that body formats can decode it.
+struct FromStringStream {
+    data: Rc<String>,
+    input_position: Rc<RefCell<usize>>,
+}
+
+impl ITcpStreamWrapper for FromStringStream {
+    fn shutdown(&self, _how: std::net::Shutdown) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        let pos = *self.input_position.borrow();
+        let available = &self.data.as_bytes()[std::cmp::min(pos, self.data.len())..];
+        let num_read = std::cmp::min(available.len(), b.len());
+        b[..num_read].copy_from_slice(&available[..num_read]);
+        self.input_position.replace(pos + num_read);
+        Ok(num_read)
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, _b: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "FromStringStream cannot be written to"))
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        self.write(b.as_bytes())
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        unimplemented!()
+    }
+}
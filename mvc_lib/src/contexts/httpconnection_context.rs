@@ -5,23 +5,55 @@ use std::vec;
 use http::{HeaderName, HeaderValue, HeaderMap};
 use http::status::StatusCode;
 
+use crate::core::itcp_stream_wrapper::ITcpStreamWrapper;
+use crate::core::memory_stream::MemoryStream;
 use crate::http::http_body_content::ContentType;
 use crate::http::ihttp_body_stream_format::IHttpBodyStreamFormat;
+use crate::http::response_encoder_middleware::ResponseCompression;
 
 use super::tcp_connection_context::TcpConnectionContext;
 use super::itcpconnection_context::ITcpConnectionContext;
 use super::ihttpconnection_context::IHttpConnectionContext;
 
 
+// how the end of a response body is marked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HttpBodyFraming {
+    // the body is written as it is, and ends with the Content-Length set by the response or when the connection closes.
+    Raw,
+    // the body is written in chunks with "Transfer-Encoding: chunked".
+    Chunked,
+    // the body is buffered so that it can be sent with a Content-Length header.
+    Buffered,
+    // the body is left out, such as for the response to a HEAD request. only the head is written.
+    Omitted,
+}
+
+// the largest chunk buffered before it is written to the connection.
+const MAX_CHUNK_SIZE: usize = 8192;
+
 // this struct implements IHttpConnectionContext and represents a HTTP connection.
 pub struct HttpConnectionContext {
     pub tcp_connection_context: Rc<dyn ITcpConnectionContext>,
     pub has_started_writing: RefCell<bool>,
 
     pub pending_http_version: RefCell<http::Version>,
+    pub pending_request_method: RefCell<http::Method>,
     pub pending_status_code: RefCell<Option<StatusCode>>,
     pub pending_status_message: RefCell<Option<String>>,
     pub pending_headers: RefCell<HeaderMap>,
+
+    // whether the connection stays open after the response.
+    pub keep_alive: RefCell<bool>,
+    // how the response body is framed, decided when the response starts writing.
+    pub body_framing: RefCell<HttpBodyFraming>,
+    // the part of the body that has not been written yet, either the current chunk or the whole buffered body.
+    pub pending_body: RefCell<Vec<u8>>,
+
+    // how the response body may be compressed, negotiated with the client.
+    pub response_compression: RefCell<Option<Rc<ResponseCompression>>>,
+    // the encoder compressing the response body, and the stream it writes the compressed body to.
+    pub body_encoder: RefCell<Option<(Rc<RefCell<dyn ITcpStreamWrapper>>, Rc<RefCell<MemoryStream>>)>>,
 }
 
 impl HttpConnectionContext {
@@ -30,9 +62,15 @@ impl HttpConnectionContext {
             tcp_connection_context: connection_context,
             has_started_writing: RefCell::new(false),
             pending_http_version: RefCell::new(http::Version::HTTP_11),
+            pending_request_method: RefCell::new(http::Method::GET),
             pending_status_code: RefCell::new(None),
             pending_status_message: RefCell::new(None),
             pending_headers: RefCell::new(HeaderMap::new()),
+            keep_alive: RefCell::new(false),
+            body_framing: RefCell::new(HttpBodyFraming::Raw),
+            pending_body: RefCell::new(Vec::new()),
+            response_compression: RefCell::new(None),
+            body_encoder: RefCell::new(None),
         }
     }
 
@@ -43,6 +81,126 @@ impl HttpConnectionContext {
     pub fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
         self.tcp_connection_context.shutdown(how)
     }
+
+    // write the status line and headers of the response.
+    fn write_head(&self) -> std::io::Result<()> {
+        // write the http version, status code, and status message
+        let status_code = self.get_pending_status_code();
+
+        // source_stream.borrow_mut().
+        self.tcp_connection_context.write_line(&format!("HTTP/1.1 {} {}", status_code.as_str(), self.get_pending_status_message()))?;
+
+        // write the headers
+        for header in self.get_pending_headers().iter() {
+            // write the header name
+            self.tcp_connection_context.write(header.0.as_str().as_bytes())?;
+            self.tcp_connection_context.write(b": ")?;
+            // write the header value
+            self.tcp_connection_context.write(&header.1.as_bytes())?;
+            // write the header new line
+            self.tcp_connection_context.write(b"\r\n")?;
+        }
+        // marker for end of headers and start of body
+        self.tcp_connection_context.write(b"\r\n")?;
+
+        self.flush()
+    }
+
+    // get how the response body is framed.
+    pub fn get_body_framing(&self) -> HttpBodyFraming {
+        *self.body_framing.borrow()
+    }
+
+    // decide how the response body is framed from the pending request and response.
+    // a body with a known length is written as it is. otherwise HTTP/1.1 clients are sent chunks,
+    // and HTTP/1.0 clients on a kept alive connection are sent a buffered body with a Content-Length.
+    fn choose_body_framing(&self) -> HttpBodyFraming {
+        if *self.pending_request_method.borrow() == http::Method::HEAD {
+            HttpBodyFraming::Omitted
+        } else if !self.has_body() || self.pending_headers.borrow().contains_key(http::header::CONTENT_LENGTH) {
+            HttpBodyFraming::Raw
+        } else if *self.pending_http_version.borrow() == http::Version::HTTP_11 {
+            HttpBodyFraming::Chunked
+        } else if self.get_keep_alive() {
+            HttpBodyFraming::Buffered
+        } else {
+            HttpBodyFraming::Raw
+        }
+    }
+
+    // returns true if the pending status code allows a response body.
+    fn has_body(&self) -> bool {
+        let status_code = self.get_pending_status_code();
+        !(status_code.is_informational() || status_code == StatusCode::NO_CONTENT || status_code == StatusCode::NOT_MODIFIED)
+    }
+
+    // start compressing the response body if the client accepts a content coding and the response can be compressed.
+    // this changes the headers, so it is done before the head is written.
+    fn begin_compression(&self) {
+        let compression = match self.response_compression.borrow().clone() {
+            Some(compression) => compression,
+            None => return,
+        };
+        if !self.has_body() {
+            return;
+        }
+
+        let headers = self.get_pending_headers();
+        if compression.is_compressible(&headers) {
+            // caches need to know the body depends on Accept-Encoding, even when it is not compressed.
+            match self.get_pending_header("Vary") {
+                Some(vary) if vary.split(',').any(|x| x.trim() == "*" || x.trim().eq_ignore_ascii_case("Accept-Encoding")) => {},
+                Some(vary) => self.set_header_string("Vary".to_string(), format!("{}, Accept-Encoding", vary)),
+                None => self.set_header_str("Vary", "Accept-Encoding"),
+            }
+        }
+
+        if let Some((content_coding, format)) = compression.get_encoder(&headers) {
+            // the length of the compressed body is not known until it is written.
+            self.pending_headers.borrow_mut().remove(http::header::CONTENT_LENGTH);
+            self.set_header_string("Content-Encoding".to_string(), content_coding.clone());
+            // the compressed body is not byte for byte the same as the uncompressed one, so a strong ETag no longer holds.
+            if let Some(etag) = self.get_pending_header("ETag") {
+                if !etag.starts_with("W/") {
+                    self.set_header_string("ETag".to_string(), format!("W/{}", etag));
+                }
+            }
+
+            let output = Rc::new(RefCell::new(MemoryStream::new()));
+            let encoder = format.encode(output.clone(), &ContentType::parse(&content_coding));
+            self.body_encoder.replace(Some((encoder, output)));
+        }
+    }
+
+    // write part of the (compressed) body using the framing of the response.
+    fn write_body(&self, b: &[u8]) -> std::io::Result<usize> {
+        match self.get_body_framing() {
+            HttpBodyFraming::Raw => self.tcp_connection_context.write(b),
+            HttpBodyFraming::Chunked => {
+                self.pending_body.borrow_mut().extend_from_slice(b);
+                if self.pending_body.borrow().len() >= MAX_CHUNK_SIZE {
+                    self.write_pending_chunk()?;
+                }
+                Ok(b.len())
+            },
+            HttpBodyFraming::Buffered => {
+                self.pending_body.borrow_mut().extend_from_slice(b);
+                Ok(b.len())
+            },
+            HttpBodyFraming::Omitted => Ok(b.len()),
+        }
+    }
+
+    // write the pending body as one chunk.
+    fn write_pending_chunk(&self) -> std::io::Result<()> {
+        let chunk = self.pending_body.replace(Vec::new());
+        if chunk.len() > 0 {
+            self.tcp_connection_context.write(format!("{:x}\r\n", chunk.len()).as_bytes())?;
+            self.tcp_connection_context.write(&chunk)?;
+            self.tcp_connection_context.write(b"\r\n")?;
+        }
+        Ok(())
+    }
 }
 
 impl IHttpConnectionContext for HttpConnectionContext {
@@ -58,7 +216,23 @@ impl IHttpConnectionContext for HttpConnectionContext {
         if !self.get_has_started_writing() {
             self.end_reading_begin_writing()?;
         }
-        self.tcp_connection_context.write(b)
+        if b.len() == 0 {
+            // writing nothing to an encoder would end the compressed body.
+            return Ok(0);
+        }
+
+        let body_encoder = self.body_encoder.borrow().clone();
+        match body_encoder {
+            Some((encoder, output)) => {
+                encoder.borrow().write(b)?;
+                let compressed = output.borrow().take();
+                if compressed.len() > 0 {
+                    self.write_body(&compressed)?;
+                }
+                Ok(b.len())
+            },
+            None => self.write_body(b),
+        }
     }
 
     fn write_str(&self, b: &str) -> std::io::Result<usize> {
@@ -66,10 +240,7 @@ impl IHttpConnectionContext for HttpConnectionContext {
     }
 
     fn write_line(&self, b: &String) -> std::io::Result<usize> {
-        if !self.get_has_started_writing() {
-            self.end_reading_begin_writing()?;
-        }
-        self.tcp_connection_context.write_line(b)
+        self.write(format!("{}\r\n", b).as_bytes())
     }
 
     fn begin_reading(&self) -> std::io::Result<()> {
@@ -89,34 +260,51 @@ impl IHttpConnectionContext for HttpConnectionContext {
     fn begin_writing(&self) -> std::io::Result<()> {
         if !self.get_has_started_writing() {
             self.has_started_writing.replace(true);
-            
-            // write the http version, status code, and status message
-            let status_code = self.get_pending_status_code();
-
-            // source_stream.borrow_mut().
-            self.tcp_connection_context.write_line(&format!("HTTP/1.1 {} {}", status_code.as_str(), self.get_pending_status_message()))?;
-
-            // write the headers
-            for header in self.get_pending_headers().iter() {
-                // write the header name
-                self.tcp_connection_context.write(header.0.as_str().as_bytes())?;
-                self.tcp_connection_context.write(b": ")?;
-                // write the header value
-                self.tcp_connection_context.write(&header.1.as_bytes())?;
-                // write the header new line
-                self.tcp_connection_context.write(b"\r\n")?;
-            }
-            // marker for end of headers and start of body
-            self.tcp_connection_context.write(b"\r\n")?;
 
-            self.flush()?;
+            self.begin_compression();
+            let body_framing = self.choose_body_framing();
+            self.body_framing.replace(body_framing);
+            match body_framing {
+                HttpBodyFraming::Raw | HttpBodyFraming::Omitted => self.write_head()?,
+                HttpBodyFraming::Chunked => {
+                    self.set_header_str("Transfer-Encoding", "chunked");
+                    self.write_head()?;
+                },
+                // the head of a buffered response is written by end_writing, once the body length is known.
+                HttpBodyFraming::Buffered => {},
+            }
         }
 
         Ok(())
     }
 
     fn end_writing(self: &Self) -> std::io::Result<()> {
-        Ok(())
+        self.begin_writing()?;
+        if let Some((encoder, output)) = self.body_encoder.replace(None) {
+            // writing nothing ends the compressed body
+            encoder.borrow().write(&[])?;
+            let compressed = output.borrow().take();
+            if compressed.len() > 0 {
+                self.write_body(&compressed)?;
+            }
+        }
+        match self.get_body_framing() {
+            HttpBodyFraming::Raw | HttpBodyFraming::Omitted => {},
+            HttpBodyFraming::Chunked => {
+                self.write_pending_chunk()?;
+                // the last chunk is empty and has no trailers
+                self.tcp_connection_context.write(b"0\r\n\r\n")?;
+            },
+            HttpBodyFraming::Buffered => {
+                let body = self.pending_body.replace(Vec::new());
+                self.set_header_string("Content-Length".to_string(), body.len().to_string());
+                self.write_head()?;
+                if body.len() > 0 {
+                    self.tcp_connection_context.write(&body)?;
+                }
+            },
+        }
+        self.flush()
     }
 
     fn get_pending_status_code(&self) -> StatusCode {
@@ -134,10 +322,22 @@ impl IHttpConnectionContext for HttpConnectionContext {
         self.pending_headers.borrow().clone()
     }
 
+    fn set_response_compression(&self, compression: Option<Rc<ResponseCompression>>) {
+        self.response_compression.replace(compression);
+    }
+
     fn get_has_started_writing(&self) -> bool {
         *self.has_started_writing.borrow()
     }
 
+    fn get_keep_alive(&self) -> bool {
+        *self.keep_alive.borrow()
+    }
+
+    fn set_keep_alive(&self, keep_alive: bool) {
+        self.keep_alive.replace(keep_alive);
+    }
+
     fn read_bytes(&self) -> std::io::Result<Vec<u8>> {
         let mut b = vec![0; 2048];
         self.read(&mut b)?;
@@ -152,6 +352,14 @@ impl IHttpConnectionContext for HttpConnectionContext {
         Ok(self.tcp_connection_context.read_line()?)
     }
 
+    fn set_pending_http_version(&self, http_version: http::Version) {
+        self.pending_http_version.replace(http_version);
+    }
+
+    fn set_pending_request_method(&self, method: http::Method) {
+        self.pending_request_method.replace(method);
+    }
+
     fn set_pending_status_code(&self, status_code: StatusCode) {
         self.pending_status_code.replace(Some(status_code));
     }
@@ -161,11 +369,19 @@ impl IHttpConnectionContext for HttpConnectionContext {
     }
 
     fn add_header_string(&self, name: String, value: String) {
-        self.pending_headers.borrow_mut().insert(HeaderName::from_bytes(name.as_bytes()).unwrap(), HeaderValue::from_bytes(value.as_bytes()).unwrap());
+        self.add_header_str(&name, &value);
     }
 
     fn add_header_str(&self, name: &str, value: &str) {
-        self.pending_headers.borrow_mut().insert(HeaderName::from_bytes(name.as_bytes()).unwrap(), HeaderValue::from_bytes(value.as_bytes()).unwrap());
+        let name = HeaderName::from_bytes(name.as_bytes()).unwrap();
+        let value = HeaderValue::from_bytes(value.as_bytes()).unwrap();
+        // cookies cannot be folded into one header, so each cookie set by the response keeps its own Set-Cookie header.
+        // other headers replace the value that was added before.
+        if name == http::header::SET_COOKIE {
+            self.pending_headers.borrow_mut().append(name, value);
+        } else {
+            self.pending_headers.borrow_mut().insert(name, value);
+        }
     }
 
     fn get_connection_id(&self) -> u32 {
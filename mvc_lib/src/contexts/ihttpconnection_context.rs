@@ -6,6 +6,7 @@ use mockall::automock;
 
 use crate::http::http_body_content::ContentType;
 use crate::http::ihttp_body_stream_format::IHttpBodyStreamFormat;
+use crate::http::response_encoder_middleware::ResponseCompression;
 
 use super::itcpconnection_context::ITcpConnectionContext;
 
@@ -18,8 +19,19 @@ pub trait IHttpConnectionContext {
     // fn get_stream(&self) -> &RefCell<Rc<dyn ITcpStreamWrapper>>;
     fn add_stream_decoders(&self, decoders: &[Rc<dyn IHttpBodyStreamFormat>], content_type: &ContentType);
 
+    // set how the response body is compressed, or None to send it as it is.
+    fn set_response_compression(&self, compression: Option<Rc<ResponseCompression>>);
+
     fn get_has_started_writing(&self) -> bool;
 
+    // whether the connection stays open for another request after this response.
+    fn get_keep_alive(&self) -> bool;
+    fn set_keep_alive(&self, keep_alive: bool);
+
+    // set the HTTP version of the request, which decides how the response body is framed.
+    fn set_pending_http_version(&self, http_version: http::Version);
+    // set the method of the request. the response to a HEAD request is sent without its body.
+    fn set_pending_request_method(&self, method: http::Method);
     fn set_pending_status_code(&self, status_code: StatusCode);
     fn set_pending_status_message(&self, status_message: String);
     fn get_pending_status_code(&self) -> StatusCode;
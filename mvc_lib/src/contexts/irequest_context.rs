@@ -12,7 +12,9 @@ use crate::model_binder::model_validation_result::ModelValidationResult;
 use crate::routing::route_data::RouteData;
 use crate::services::authorization_service::IAuthClaim;
 use crate::controller_actions::controller_action::IControllerAction;
+use crate::controllers::icontroller::IController;
 use crate::services::service_collection::IServiceCollection;
+use crate::session::session_state::ISession;
 
 use super::ihttpconnection_context::IHttpConnectionContext;
 
@@ -64,9 +66,21 @@ pub trait IRequestContext {
     fn get_controller_action(self: &Self) -> Rc<dyn IControllerAction>;
     // set the controller action of the request
     fn set_controller_action(self: &Self, controller: Option<Rc<dyn IControllerAction>>);
+    // get the controller of the controller action of the request if it exists.
+    // it is found once when the request is routed, so middleware does not need to look it up again.
+    fn get_controller_optional(self: &Self) -> Option<Rc<dyn IController>>;
+    // set the controller of the controller action of the request
+    fn set_controller(self: &Self, controller: Option<Rc<dyn IController>>);
 
     // get the authorization claims of the request
     fn get_auth_claims(self: &Self) -> Vec<Rc<dyn IAuthClaim>>;
+    // set the authorization claims of the request, once it has been authenticated
+    fn set_auth_claims(self: &Self, claims: Vec<Rc<dyn IAuthClaim>>);
+
+    // get the server-side session of the request, which is set by the SessionMiddleware
+    fn get_session(self: &Self) -> Option<Rc<dyn ISession>>;
+    // set the server-side session of the request
+    fn set_session(self: &Self, session: Option<Rc<dyn ISession>>);
 
     // get the context data of the request
     fn get_str(self: &Self, key: &str) -> String;
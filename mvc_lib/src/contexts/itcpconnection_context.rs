@@ -17,8 +17,36 @@ pub trait ITcpConnectionContext {
 
     fn get_connection_id(&self) -> u32;
 
+    // returns true if the connection is encrypted with TLS.
+    fn is_secure(&self) -> bool;
+
+    // decode the request body with the decoders, such as for its Content-Encoding.
+    // if the body was limited, the decoded body is limited to the same length.
     fn add_stream_decoders(&self, decoders: &[Rc<dyn IHttpBodyStreamFormat>], content_type: &ContentType);
 
+    // limit reads from the connection to the request body, which is content_length bytes long.
+    fn begin_request_body(&self, content_length: usize);
+
+    // limit reads from the connection to a request body whose length is given by a transfer coding, such as chunked.
+    // format: decodes the transfer coding of the body.
+    // transfer_encoding: the transfer coding of the body.
+    fn begin_request_body_format(&self, format: Rc<dyn IHttpBodyStreamFormat>, transfer_encoding: &ContentType);
+
+    // fail reading the request body once more than max_length bytes have been read from it,
+    // or from the body decoded from it once decoders are added.
+    fn limit_request_body(&self, max_length: usize);
+
+    // returns true if reading the request body failed because it was longer than its limit.
+    fn is_request_body_too_large(&self) -> bool;
+
+    // discard any unread bytes of the request body and remove the body decoders,
+    // so that the next request on the connection can be read.
+    fn end_request_body(&self) -> std::io::Result<()>;
+
+    // set how long a read from the connection waits for data before failing with a timeout.
+    // timeout: the longest wait, or None to wait forever.
+    fn set_read_timeout(&self, timeout: Option<std::time::Duration>) -> std::io::Result<()>;
+
     fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()>;
 
     fn flush(&self) -> std::io::Result<()>;
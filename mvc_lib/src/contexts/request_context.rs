@@ -3,13 +3,16 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 use regex::Regex;
 
-use http::{ HeaderName, HeaderValue, HeaderMap, Method };
+use http::{ HeaderName, HeaderValue, HeaderMap, Method, StatusCode };
 
 use crate::controller_actions::controller_action::IControllerAction;
+use crate::controllers::icontroller::IController;
 
 use crate::core::query_string::QueryString;
+use crate::errors::BadRequestError;
 
 use crate::http::http_body_content::ContentType;
 use crate::http::http_body_content::IBodyContent;
@@ -21,13 +24,18 @@ use crate::routing::route_data::RouteData;
 
 use crate::services::authorization_service::IAuthClaim;
 use crate::model_binder::model_validation_result::ModelValidationResult;
+use crate::options::http_options::{IHttpOptions, HttpOptions};
 use crate::services::service_collection::IServiceCollection;
 use crate::services::service_collection::ServiceCollectionExtensions;
+use crate::session::session_state::ISession;
 
 use super::ihttpconnection_context::IHttpConnectionContext;
 use super::irequest_context::IRequestContext;
 
 
+// the most bytes the method, spaces, and version add to the request target in the request line.
+const MAX_REQUEST_LINE_OVERHEAD: usize = 32;
+
 // this struct represents a HTTP request and its context.
 // it is created by the server and passed to middleware and the controller action.
 // it is also passed to the view renderer and view.
@@ -68,10 +76,13 @@ pub struct RequestContext<'a> {
     route_data: RefCell<RouteData>,
     // the authorization claims of the request
     auth_claims: RefCell<Vec<Rc<dyn IAuthClaim>>>,
+    // the server-side session of the request
+    session: RefCell<Option<Rc<dyn ISession>>>,
     // the context data of the request
     context_data: RefCell<HashMap<String, String>>,
     // the controller action for the request
     controller_action: RefCell<Option<Rc<dyn IControllerAction>>>,
+    controller: RefCell<Option<Rc<dyn IController>>>,
 }
 
 impl <'a> RequestContext<'a> {
@@ -142,8 +153,10 @@ impl <'a> RequestContext<'a> {
             body_model: RefCell::new(None),
             route_data: RefCell::new(RouteData::new()),
             auth_claims: RefCell::new(Vec::new()),
+            session: RefCell::new(None),
             context_data: RefCell::new(HashMap::new()),
             controller_action: RefCell::new(None),
+            controller: RefCell::new(None),
             decoders: RefCell::new(Vec::new()),
         }
     }
@@ -163,47 +176,46 @@ impl <'a> RequestContext<'a> {
         )
     }
 
-    // parses a HTTP request into a request context.
-    // http_header: the HTTP header of the request
-    // headers: the headers of the request
-    // request_bytes: the body of the request
-    // connection_context: the HTTP connection context of the request
-    // returns: the new request context.
+    // parse the request line and headers of a request from a connection, using the default limits of HttpOptions.
+    // connection_context: the connection to read the request from.
     pub fn parse(connection_context: &'a dyn IHttpConnectionContext) -> Result<RequestContext<'a>, std::io::Error> {
-        let mut request_headers: Vec<String> = vec![];
-        loop {
-            let read_result = connection_context.read_line();
-            match read_result {
-                Ok(line) => {
-                    if line.trim() == "" {
-                        break;
-                    }
-        
-                    request_headers.push(line);
-                },
-                Err(err) => {
-                    println!("Could not read http headers: {}", err);
-                    break;
-                },
-            }
-        }
-
+        Self::parse_with_options(connection_context, &HttpOptions::new_default())
+    }
+
+    // parse the request line and headers of a request from a connection.
+    // requests that are malformed, too slow, or too large return an error carrying a BadRequestError with the status to respond with.
+    // connection_context: the connection to read the request from.
+    // options: the limits for the request line and headers.
+    pub fn parse_with_options(connection_context: &'a dyn IHttpConnectionContext, options: &dyn IHttpOptions) -> Result<RequestContext<'a>, std::io::Error> {
+        let head_result = Self::read_head(connection_context, options);
+        // reads of the request body wait as long as any other read.
+        connection_context.get_tcp_context().set_read_timeout(HttpOptions::timeout_or_none(options.get_read_timeout()))?;
+        let mut request_headers = head_result?;
         if request_headers.len() == 0 {
             return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Could not read http headers: no headers found."));
         }
 
+        let bad_request = |message: String| BadRequestError::new(StatusCode::BAD_REQUEST, message).to_io_error();
         let http_header: String = request_headers.remove(0);
 
-        let method_str = &http_header[..http_header.find(' ').unwrap()];
-        let version_str = &http_header[http_header.rfind(' ').unwrap() + 1..];
+        let (method_end, version_start) = match (http_header.find(' '), http_header.rfind(' ')) {
+            (Some(method_end), Some(version_start)) if method_end < version_start => (method_end, version_start),
+            _ => return Err(bad_request(format!("Invalid request line: {}", http_header))),
+        };
+        let method_str = &http_header[..method_end];
+        let version_str = &http_header[version_start + 1..];
+        let request_target = http_header[method_end + 1..version_start].trim();
+        if request_target.len() > options.get_max_url_length() {
+            return Err(BadRequestError::new(StatusCode::URI_TOO_LONG, format!("Request target is longer than {} bytes", options.get_max_url_length())).to_io_error());
+        }
 
-        let re_method_valid: Regex = Regex::new(r"^GET|HEAD|POST|PUT$").unwrap();
+        let re_method_valid: Regex = Regex::new(r"^(GET|HEAD|POST|PUT|DELETE|PATCH|OPTIONS)$").unwrap();
         let re_header: Regex = Regex::new(r"^([a-zA-Z0-9 _-]+): ").unwrap();
 
         // println!("Received request: {}", http_header);
 
-        if !re_method_valid.is_match(&http_header) {
-            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid HTTP method: {}", method_str)));
+        if !re_method_valid.is_match(method_str) {
+            return Err(bad_request(format!("Invalid HTTP method: {}", method_str)));
         }
 
         let version = match version_str {
@@ -211,38 +223,51 @@ impl <'a> RequestContext<'a> {
             "HTTP/1.0" => http::version::Version::HTTP_10,
             "HTTP/1.1" => http::version::Version::HTTP_11,
             _ => {
-                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Invalid HTTP version {}", version_str)));
+                return Err(bad_request(format!("Invalid HTTP version {}", version_str)));
             }
         };
 
-        let mut errors = vec![];
-        let headers = HeaderMap::from_iter(request_headers.iter().map(|x| {
-            let mut name = re_header.find(x).expect(&format!("Invalid header format: {}", x)).as_str();
-            let value = x[name.len()..].to_string();
-            name = &name[..name.len()-2];
-            let value_str = value.trim();
-
-            match HeaderValue::from_str(value_str) {
-                Ok(header_val) => {
-                    Some((HeaderName::from_bytes(name.as_bytes()).unwrap(), header_val))
-                },
-                Err(err) => {
-                    errors.push(err);
-                    None
+        let mut headers = HeaderMap::new();
+        for x in request_headers.iter() {
+            let name = match re_header.find(x) {
+                Some(name) => name.as_str(),
+                None => return Err(bad_request(format!("Invalid header format: {}", x))),
+            };
+            let value_str = x[name.len()..].trim();
+            let name = &name[..name.len()-2];
+
+            match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value_str)) {
+                (Ok(header_name), Ok(header_val)) => {
+                    headers.append(header_name, header_val);
                 },
+                _ => return Err(bad_request(format!("Could not parse header: {}", x))),
             }
-        }).filter(Option::is_some).map(|x| x.unwrap()));
+        }
 
-        if errors.len() > 0 {
-            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse headers: {:?}", errors)));
+        // the end of the body is found from the Content-Length, so a value that cannot be read, or several that differ,
+        // could make the body end in a different place than the client meant.
+        if let Err(message) = Self::validate_content_length(&headers) {
+            return Err(bad_request(message));
         }
 
-        let host_header_value = headers.get("Host").expect("Host header not found.");
-        let host_header_string = format!("http://{}", host_header_value.to_str().unwrap());
-        let host_header_url = url::Url::parse(host_header_string.as_str()).unwrap();
+        // HTTP/1.1 requires a Host header. older clients are assumed to be asking for the address being served.
+        let host = match headers.get("Host").and_then(|x| x.to_str().ok()) {
+            Some(host) => host.to_string(),
+            None if version == http::version::Version::HTTP_11 => return Err(bad_request("Host header not found.".to_string())),
+            None => options.get_ip_and_port(),
+        };
+        let scheme = if connection_context.get_tcp_context().is_secure() { "https" } else { "http" };
+        let host_header_string = format!("{}://{}", scheme, host);
+        let host_header_url = match url::Url::parse(host_header_string.as_str()) {
+            Ok(host_header_url) => host_header_url,
+            Err(_) => return Err(bad_request(format!("Invalid Host header: {}", host))),
+        };
 
         let request_url = host_header_url;
-        let path_and_query = request_url.join(&http_header[method_str.len() + 1 .. http_header.len() - version_str.len() - 1].trim()).unwrap();
+        let path_and_query = match request_url.join(request_target) {
+            Ok(path_and_query) => path_and_query,
+            Err(_) => return Err(bad_request(format!("Invalid request target: {}", request_target))),
+        };
 
         let path = path_and_query.path();
         let query = path_and_query.query().unwrap_or("");
@@ -260,7 +285,105 @@ impl <'a> RequestContext<'a> {
             headers,
         ))
     }
-    
+
+    // check that the Content-Length headers of a request, if there are any, are the same number of bytes.
+    // headers: the headers of the request.
+    // returns: the reason the request is rejected if they are not.
+    fn validate_content_length(headers: &HeaderMap) -> Result<(), String> {
+        let mut content_length = None;
+        for value in headers.get_all("Content-Length") {
+            for value in value.to_str().map_err(|_| "Invalid Content-Length header".to_string())?.split(',') {
+                let value = value.trim();
+                if value.is_empty() || !value.bytes().all(|x| x.is_ascii_digit()) {
+                    return Err(format!("Invalid Content-Length header: {}", value));
+                }
+                let value = value.parse::<usize>().map_err(|_| format!("Invalid Content-Length header: {}", value))?;
+                if content_length.is_some_and(|x| x != value) {
+                    return Err("Content-Length headers do not match".to_string());
+                }
+                content_length = Some(value);
+            }
+        }
+        Ok(())
+    }
+
+    // read the request line and headers, up to the empty line that ends them.
+    // connection_context: the connection to read from.
+    // options: the limits for the request line and headers.
+    // returns: the lines read, or an error carrying a BadRequestError if the client is too slow or sends too much.
+    fn read_head(connection_context: &dyn IHttpConnectionContext, options: &dyn IHttpOptions) -> Result<Vec<String>, std::io::Error> {
+        // a timeout of zero means the head has no deadline, and reads wait as long as any other read.
+        let deadline = HttpOptions::timeout_or_none(options.get_request_headers_timeout()).map(|x| Instant::now() + x);
+        let read_timeout = HttpOptions::timeout_or_none(options.get_read_timeout());
+        let mut lines: Vec<String> = vec![];
+        let mut header_bytes = 0;
+        loop {
+            let max_length = if lines.len() == 0 {
+                options.get_max_url_length() + MAX_REQUEST_LINE_OVERHEAD
+            } else {
+                options.get_max_header_bytes().saturating_sub(header_bytes)
+            };
+
+            let line = match Self::read_head_line(connection_context, max_length, deadline, read_timeout)? {
+                Some(line) => line,
+                None if lines.len() == 0 => return Err(BadRequestError::new(StatusCode::URI_TOO_LONG, "Request line is too long".to_string()).to_io_error()),
+                None => return Err(BadRequestError::new(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE, format!("Request headers are larger than {} bytes", options.get_max_header_bytes())).to_io_error()),
+            };
+            if line.trim() == "" {
+                break;
+            }
+
+            if lines.len() > 0 {
+                header_bytes += line.len() + 2;
+                if lines.len() > options.get_max_header_count() {
+                    return Err(BadRequestError::new(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE, format!("Request has more than {} headers", options.get_max_header_count())).to_io_error());
+                }
+            }
+            lines.push(line);
+        }
+        Ok(lines)
+    }
+
+    // read one line of the request head, without the \r\n that ends it.
+    // connection_context: the connection to read from.
+    // max_length: the longest line that is accepted.
+    // deadline: when the whole request head has to be read by, if it has to be read by a certain time.
+    // read_timeout: the longest a read waits when there is no deadline.
+    // returns: the line, None if the line is too long, or an error carrying a 408 BadRequestError if the client is too slow.
+    fn read_head_line(connection_context: &dyn IHttpConnectionContext, max_length: usize, deadline: Option<Instant>, read_timeout: Option<Duration>) -> Result<Option<String>, std::io::Error> {
+        let timed_out = || BadRequestError::new(StatusCode::REQUEST_TIMEOUT, "Request headers were not received in time".to_string()).to_io_error();
+        let mut line: Vec<u8> = vec![];
+        loop {
+            // each read only waits until the deadline, so a client that stops sending cannot hold the connection.
+            if let Some(deadline) = deadline {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(timed_out());
+                }
+                let remaining = deadline - now;
+                connection_context.get_tcp_context().set_read_timeout(Some(read_timeout.map_or(remaining, |x| x.min(remaining))))?;
+            }
+
+            let mut buf = [0; 1];
+            match connection_context.read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => {
+                    if buf[0] == b'\n' && line.last() == Some(&b'\r') {
+                        line.pop();
+                        break;
+                    }
+                    line.push(buf[0]);
+                    // the line can also hold the \r before the \n.
+                    if line.len() > max_length + 1 {
+                        return Ok(None);
+                    }
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => return Err(timed_out()),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Some(String::from_utf8_lossy(&line).to_string()))
+    }
 }
 
 impl<'a> IRequestContext for RequestContext<'a> {
@@ -322,6 +445,14 @@ impl<'a> IRequestContext for RequestContext<'a> {
         self.controller_action.replace(controller);
     }
 
+    fn get_controller_optional(self: &Self) -> Option<Rc<dyn IController>> {
+        self.controller.borrow().clone()
+    }
+
+    fn set_controller(self: &Self, controller: Option<Rc<dyn IController>>) {
+        self.controller.replace(controller);
+    }
+
     fn get_string(self: &Self, key: String) -> String {
         match self.route_data.borrow().map.get(&key) {
             Some(v) => v.clone(),
@@ -355,6 +486,18 @@ impl<'a> IRequestContext for RequestContext<'a> {
     fn get_auth_claims(self: &Self) -> Vec<Rc<dyn IAuthClaim>> {
         self.auth_claims.borrow().clone()
     }
+
+    fn set_auth_claims(self: &Self, claims: Vec<Rc<dyn IAuthClaim>>) {
+        self.auth_claims.replace(claims);
+    }
+
+    fn get_session(self: &Self) -> Option<Rc<dyn ISession>> {
+        self.session.borrow().clone()
+    }
+
+    fn set_session(self: &Self, session: Option<Rc<dyn ISession>>) {
+        self.session.replace(session);
+    }
     
     fn get_cookies_parsed(self: &Self) -> Option<HashMap<String, String>> {
         let cookie_header = self.headers.get("cookie");
@@ -424,26 +567,11 @@ impl<'a> IRequestContext for RequestContext<'a> {
     
     // this function is used to get the content length from the headers.
     fn get_content_length(self: &Self) -> Option<usize> {
-        if let Some(content_length_header_val) = self.headers.get("Content-Length") {
-            match content_length_header_val.to_str() {
-                Ok(content_length_str) => {
-                    match content_length_str.parse::<usize>() {
-                        Ok(content_length) => {
-                            println!("found_content_length: {}", content_length);
-                            Some(content_length)
-                        },
-                        Err(e) => {
-                            panic!("Invalid content-length header value: {}", e);
-                        },
-                    }
-                },
-                Err(e) => {
-                    panic!("Invalid content-length header value: {}", e);
-                },
-            }
-        } else {
-            None
-        }
+        // requests with a Content-Length that cannot be read are rejected when they are parsed.
+        self.headers.get("Content-Length")
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.split(',').next())
+            .and_then(|x| x.trim().parse::<usize>().ok())
     }
     
     // this function is used to get the content type from the headers.
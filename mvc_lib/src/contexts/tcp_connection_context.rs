@@ -2,7 +2,10 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::core::buffered_tcpstream::BufferedTcpStream;
+use crate::core::content_length_stream::ContentLengthStream;
+use crate::core::max_length_stream::MaxLengthStream;
 use crate::core::itcp_stream_wrapper::ITcpStreamWrapper;
+use crate::core::tls_tcpstream::TlsTcpStream;
 use crate::http::http_body_content::ContentType;
 use crate::http::ihttp_body_stream_format::IHttpBodyStreamFormat;
 
@@ -11,9 +14,20 @@ use super::itcpconnection_context::ITcpConnectionContext;
 
 // this struct implements ITcpConnectionContext and represents a TCP connection.
 pub struct TcpConnectionContext {
-    // source_stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+    // the undecorated stream, used again for each request on the connection.
+    source_stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+    // the stream that is currently read from. responses are always written to the source stream.
     stream: RefCell<Rc<RefCell<dyn ITcpStreamWrapper>>>,
+    // the body of the current request, if one was started.
+    request_body: RefCell<Option<Rc<RefCell<dyn ITcpStreamWrapper>>>>,
+    // the limit of the current request body and the streams that enforce it, before and after decoding.
+    request_body_limit: RefCell<Option<usize>>,
+    limited_streams: RefCell<Vec<Rc<RefCell<MaxLengthStream>>>>,
     connection_id: u32,
+    // whether the connection is encrypted with TLS.
+    secure: bool,
+    // a handle to the socket for setting timeouts, if the connection is a socket.
+    socket: Option<std::net::TcpStream>,
 }
 
 impl TcpConnectionContext {
@@ -25,14 +39,38 @@ impl TcpConnectionContext {
         connection_id: u32
     ) -> Self {
         Self {
-            // source_stream: source_stream.clone(),
+            source_stream: source_stream.clone(),
             connection_id: connection_id,
             stream: RefCell::new(source_stream),
+            request_body: RefCell::new(None),
+            request_body_limit: RefCell::new(None),
+            limited_streams: RefCell::new(Vec::new()),
+            secure: false,
+            socket: None,
         }
     }
 
     pub fn new_from_stream(stream: std::net::TcpStream, connection_id: u32) -> Self {
-        Self::new(Rc::new(RefCell::new(BufferedTcpStream::new_from_tcp(stream))), connection_id)
+        let socket = stream.try_clone().ok();
+        let mut connection = Self::new(Rc::new(RefCell::new(BufferedTcpStream::new_from_tcp(stream))), connection_id);
+        connection.socket = socket;
+        connection
+    }
+
+    // create a new instance for a connection that is encrypted with TLS.
+    // stream: the TLS stream of the connection.
+    // connection_id: the id of the connection.
+    pub fn new_from_tls_stream(stream: TlsTcpStream, connection_id: u32) -> Self {
+        let socket = stream.try_clone_tcp_stream().ok();
+        let mut connection = Self::new(Rc::new(RefCell::new(stream)), connection_id);
+        connection.secure = true;
+        connection.socket = socket;
+        connection
+    }
+
+    // get whether the next request on the connection was already read from the socket, so there is nothing to wait for.
+    pub fn has_buffered_input(self: &Self) -> bool {
+        self.source_stream.borrow().has_buffered_input().unwrap_or(false)
     }
 }
 
@@ -49,20 +87,24 @@ impl ITcpConnectionContext for TcpConnectionContext {
         self.connection_id
     }
 
+    fn is_secure(&self) -> bool {
+        self.secure
+    }
+
     fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
-        self.stream.borrow().borrow_mut().shutdown(how)
+        self.source_stream.borrow_mut().shutdown(how)
     }
 
     fn flush(&self) -> std::io::Result<()> {
-        self.stream.borrow().borrow_mut().flush()
+        self.source_stream.borrow_mut().flush()
     }
 
     fn write(&self, b: &[u8]) -> std::io::Result<usize> {
-        self.stream.borrow().borrow_mut().write(b)
+        self.source_stream.borrow_mut().write(b)
     }
 
     fn write_line(&self, b: &String) -> std::io::Result<usize> {
-        self.stream.borrow().borrow_mut().write_line(b)
+        self.source_stream.borrow_mut().write_line(b)
     }
 
     fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
@@ -78,5 +120,68 @@ impl ITcpConnectionContext for TcpConnectionContext {
             let decoded_stream = decoder.decode(self.stream.borrow().clone(), content_type);
             self.stream.replace(decoded_stream);
         }
+
+        // a small compressed body can decode to a much larger one, so the decoded body is limited too.
+        let request_body_limit = *self.request_body_limit.borrow();
+        if let Some(max_length) = request_body_limit.filter(|_| !decoders.is_empty()) {
+            let limited_body = Rc::new(RefCell::new(MaxLengthStream::new(self.stream.borrow().clone(), max_length)));
+            self.limited_streams.borrow_mut().push(limited_body.clone());
+            self.stream.replace(limited_body);
+        }
     }
-}
+
+    fn begin_request_body(&self, content_length: usize) {
+        let request_body: Rc<RefCell<dyn ITcpStreamWrapper>> = Rc::new(RefCell::new(ContentLengthStream::new(self.source_stream.clone(), content_length)));
+        self.stream.replace(request_body.clone());
+        self.request_body.replace(Some(request_body));
+        self.request_body_limit.replace(None);
+        self.limited_streams.borrow_mut().clear();
+    }
+
+    fn begin_request_body_format(&self, format: Rc<dyn IHttpBodyStreamFormat>, transfer_encoding: &ContentType) {
+        let request_body = format.decode(self.source_stream.clone(), transfer_encoding);
+        self.stream.replace(request_body.clone());
+        self.request_body.replace(Some(request_body));
+        self.request_body_limit.replace(None);
+        self.limited_streams.borrow_mut().clear();
+    }
+
+    fn set_read_timeout(&self, timeout: Option<std::time::Duration>) -> std::io::Result<()> {
+        match self.socket.as_ref() {
+            Some(socket) => socket.set_read_timeout(timeout),
+            None => Ok(()),
+        }
+    }
+
+    fn limit_request_body(&self, max_length: usize) {
+        let request_body = self.request_body.borrow().clone();
+        if let Some(request_body) = request_body {
+            let limited_body = Rc::new(RefCell::new(MaxLengthStream::new(request_body, max_length)));
+            self.limited_streams.borrow_mut().push(limited_body.clone());
+            self.stream.replace(limited_body.clone());
+            self.request_body.replace(Some(limited_body));
+            self.request_body_limit.replace(Some(max_length));
+        }
+    }
+
+    fn is_request_body_too_large(&self) -> bool {
+        self.limited_streams.borrow().iter().any(|x| x.borrow().is_exceeded())
+    }
+
+    fn end_request_body(&self) -> std::io::Result<()> {
+        let request_body = self.request_body.replace(None);
+        self.request_body_limit.replace(None);
+        self.limited_streams.borrow_mut().clear();
+        self.stream.replace(self.source_stream.clone());
+        match request_body {
+            Some(request_body) => {
+                // read and discard the rest of the body
+                let mut buffer = [0; 4096];
+                while request_body.borrow().read(&mut buffer)? > 0 {
+                }
+                Ok(())
+            },
+            None => Ok(()),
+        }
+    }
+}
\ No newline at end of file
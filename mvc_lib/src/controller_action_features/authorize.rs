@@ -8,16 +8,22 @@ use crate::core::type_info::TypeInfo;
 use crate::contexts::response_context::ResponseContext;
 
 use crate::controller_action_features::controller_action_feature::IControllerActionFeature;
+use crate::controllers::icontroller::IController;
 
 use crate::services::request_middleware_service::MiddlewareResult;
 use crate::services::service_collection::IServiceCollection;
 
 
 
+// this trait decides whether the requirement of an authorize feature applies to a request.
 pub trait IAuthRequirementFilter {
-    fn use_requirement(self: &Self) -> bool;
+    // request_context: the request being authorized.
+    // returns: whether the roles and policy of the feature must be met.
+    fn use_requirement(self: &Self, request_context: &dyn IRequestContext) -> bool;
 }
 
+// this struct skips the requirement for requests from the same machine, so the dev pages can be used locally
+// without signing in while other addresses must have the roles.
 pub struct BypassOnLocalActionFilter {}
 
 impl BypassOnLocalActionFilter {
@@ -27,8 +33,8 @@ impl BypassOnLocalActionFilter {
 }
 
 impl IAuthRequirementFilter for BypassOnLocalActionFilter {
-    fn use_requirement(self: &Self) -> bool {
-        false
+    fn use_requirement(self: &Self, request_context: &dyn IRequestContext) -> bool {
+        !request_context.get_connection_context().get_tcp_context().get_remote_addr().ip().is_loopback()
     }
 }
 
@@ -89,7 +95,10 @@ pub struct AuthorizeControllerActionFeature {
     // the policy that is allowed to call the controller action.
     pub policy: Option<String>,
     // what rules define when the requirement should be applied (default is always)
-    pub filters: Option<Vec<Box<dyn IAuthRequirementFilter>>>
+    pub filters: Option<Vec<Box<dyn IAuthRequirementFilter>>>,
+    // the authentication schemes the request is authenticated and challenged with, such as "Bearer".
+    // empty means the claims from the AuthenticationMiddleware are used and the first handler challenges.
+    pub schemes: Vec<String>,
 }
 
 impl AuthorizeControllerActionFeature {
@@ -105,9 +114,35 @@ impl AuthorizeControllerActionFeature {
             roles: roles,
             policy: policy,
             filters: filters,
+            schemes: vec![],
         }
     }
 
+    // set the authentication schemes the request is authenticated and challenged with.
+    // schemes: the names of the schemes, such as "Bearer" for an API that must not redirect to a login page.
+    pub fn with_schemes(mut self, schemes: Vec<String>) -> Self {
+        self.schemes = schemes;
+        self
+    }
+
+    // get the authentication schemes of the authorize features of the controller and the action of a request.
+    // controller: the controller the request was routed to.
+    // request_context: the request.
+    // returns: the schemes without duplicates, or empty if none of the features have schemes.
+    pub fn get_schemes(controller: &Rc<dyn IController>, request_context: &dyn IRequestContext) -> Vec<String> {
+        let mut schemes: Vec<String> = vec![];
+        for feature in controller.get_features().iter().chain(request_context.get_controller_action().get_features().iter()) {
+            if let Some(feature) = feature.as_any().downcast_ref::<AuthorizeControllerActionFeature>() {
+                for scheme in feature.schemes.iter() {
+                    if !schemes.contains(scheme) {
+                        schemes.push(scheme.clone());
+                    }
+                }
+            }
+        }
+        schemes
+    }
+
     // create a new instance of the feature as a service for a service collection from a comma separated list of roles.
     // roles: the roles that are allowed to call the controller action.
     // policy: the policy that is allowed to call the controller action.
@@ -150,7 +185,7 @@ impl IControllerActionFeature for AuthorizeControllerActionFeature {
     }
 
     fn to_string(self: &Self) -> String {
-        format!("{} (roles: {:?}, policy: {:?})", self.get_name(), self.roles, self.policy)
+        format!("{} (roles: {:?}, policy: {:?}, schemes: {:?})", self.get_name(), self.roles, self.policy, self.schemes)
     }
 
     fn invoke(self: &Self, _request_context: Rc<dyn IRequestContext>, _response_context: Rc<ResponseContext>, _services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
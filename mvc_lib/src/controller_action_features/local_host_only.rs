@@ -16,11 +16,8 @@ use crate::controller_action_features::controller_action_feature::IControllerAct
 use crate::services::request_middleware_service::IRequestMiddlewareService;
 use crate::services::request_middleware_service::MiddlewareResult;
 
-use crate::services::routemap_service::IRouteMapService;
-
 use crate::services::service_collection::IServiceCollection;
 use crate::services::service_collection::ServiceCollection;
-use crate::services::service_collection::ServiceCollectionExtensions;
 use crate::services::service_descriptor::ServiceDescriptor;
 use crate::services::service_scope::ServiceScope;
 
@@ -72,23 +69,19 @@ impl IControllerActionFeature for LocalHostOnlyControllerActionFeature {
 // this is useful for controller actions that are only used by the application itself, a reverse proxy, a load balancer, or a local user.
 // this middleware must be used in conjunction with the LocalHostOnlyControllerActionFeature or else it will do nothing.
 pub struct LocalHostOnlyControllerActionFeatureMiddleware {
-    mapper_service: Rc<dyn IRouteMapService>,
     next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>
 }
 
 impl LocalHostOnlyControllerActionFeatureMiddleware {
     // create a new instance of the middleware.
-    // mapper_service: the route map service. this is used to get the controller action.
-    pub fn new(mapper_service: Rc<dyn IRouteMapService>) -> Self {
-        Self { mapper_service: mapper_service, next: RefCell::new(None) }
+    pub fn new() -> Self {
+        Self { next: RefCell::new(None) }
     }
 
     // create a new instance of the middleware as a service for a service collection.
     // services: the service collection.
-    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![Box::new(Rc::new(Self::new(
-            ServiceCollectionExtensions::get_required_single::<dyn IRouteMapService>(services),
-        )) as Rc<dyn IRequestMiddlewareService>)]
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IRequestMiddlewareService>)]
     }
     
     // this is called by the application to add the middleware to the service collection.
@@ -104,11 +97,8 @@ impl IRequestMiddlewareService for LocalHostOnlyControllerActionFeatureMiddlewar
     }
 
     fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
-        let controller_name = request_context.get_str("ControllerName");
-
-        if controller_name.len() > 0 {
-            let controller = self.mapper_service.get_mapper().get_controller(controller_name.clone());
-
+        // the controller was found when the request was routed.
+        if let Some(controller) = request_context.get_controller_optional() {
             let action_features = request_context.get_controller_action().get_features();
             let controller_features = controller.get_features();
 
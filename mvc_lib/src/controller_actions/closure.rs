@@ -69,7 +69,7 @@ impl ControllerActionClosure {
             name: name,
             controller_name: controller_name,
             area_name: area_name,
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
             closure_fn: closure_fn,
             http_methods_allowed: http_methods_allowed,
             features: features.unwrap_or(vec![]),
@@ -156,7 +156,7 @@ impl ControllerActionClosure {
             name: name,
             controller_name: controller_name,
             area_name: String::new(),
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
             closure_fn: closure_fn,
             http_methods_allowed: http_methods_allowed,
             features: features.unwrap_or(vec![]),
@@ -183,7 +183,7 @@ impl ControllerActionClosure {
             name: name,
             controller_name: controller_name,
             area_name: String::new(),
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
             closure_fn: closure_fn,
             http_methods_allowed: http_methods_allowed,
             features: features.unwrap_or(vec![]),
@@ -210,7 +210,7 @@ impl ControllerActionClosure {
             name: name,
             controller_name: controller_name,
             area_name: String::new(),
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
             closure_fn: Rc::new(|_, x, y| (closure_fn)(x, y)),
             http_methods_allowed: http_methods_allowed,
             features: features.unwrap_or(vec![]),
@@ -241,23 +241,8 @@ impl IControllerAction for ControllerActionClosure {
         Ok(())
     }
 
-    fn is_route_match(self: &Self, request_context: &dyn IRequestContext) -> Result<bool, Rc<dyn Error>> {
-        if !IControllerActionExtensions::is_method_match(self, request_context) {
-            return Ok(false);
-        }
-
-        let path = request_context.get_path().as_str().trim();
-        let route_pattern = self.get_route_pattern();
-
-        // println!("Testing path {} against pattern {}", path, route_pattern.raw);
-
-        if route_pattern.raw.ends_with("..") {
-            Ok(path.starts_with(&route_pattern.raw[..route_pattern.raw.len() - 2]))
-        } else {
-            let r = path == route_pattern.raw;
-            // println!("path == route_pattern.raw -> {}", r);
-            Ok(r)
-        }
+    fn is_route_match(self: &Self, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<bool, Rc<dyn Error>> {
+        Ok(IControllerActionExtensions::is_route_match(self, request_context, services))
     }
 
     fn get_name(self: &Self) -> Cow<'static, str> {
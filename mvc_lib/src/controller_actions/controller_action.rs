@@ -44,7 +44,8 @@ pub trait IControllerAction {
     // get the controller action features for the controller action.
     fn get_features(self: &Self) -> Vec<Rc<dyn IControllerActionFeature>>;
     // get whether or not the action matches the request.
-    fn is_route_match(self: &Self, request_context: &dyn IRequestContext) -> Result<bool, Rc<dyn Error>>;
+    // services: the services to find custom route constraints in.
+    fn is_route_match(self: &Self, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<bool, Rc<dyn Error>>;
     // invoke the controller action for the request and context.
     fn invoke(self: &Self, request_context: &dyn IControllerContext, services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>>;
 }
@@ -52,17 +53,29 @@ pub trait IControllerAction {
 // extension methods for IControllerAction
 pub struct IControllerActionExtensions {}
 impl IControllerActionExtensions {
-    // get whether or not the action matches the request.
+    // get whether or not the action matches the request. GET actions also match HEAD requests.
     // action: the controller action.
     // request_context: the request context.
     // returns: whether or not the action matches the request.
     pub fn is_method_match(action: &dyn IControllerAction, request_context: &dyn IRequestContext) -> bool {
         let http_methods_allowed = action.get_http_methods_allowed();
+        let method = request_context.get_method();
         let r = http_methods_allowed.len() == 0 ||
-            http_methods_allowed.contains(request_context.get_method());
+            http_methods_allowed.contains(method) ||
+            (*method == Method::HEAD && http_methods_allowed.contains(&Method::GET));
 
         // println!("is_method_match: {} is in {:?} = {}", request_context.method.as_ref(), http_methods_allowed, r);
 
         r
     }
+    // get whether or not the method and path of the request match the action.
+    // the path must match the route pattern of the action, including the constraints of its capture groups.
+    // action: the controller action.
+    // request_context: the request context.
+    // services: the services to find custom route constraints in.
+    // returns: whether or not the action matches the request.
+    pub fn is_route_match(action: &dyn IControllerAction, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> bool {
+        Self::is_method_match(action, request_context) &&
+            action.get_route_pattern().try_match_with_services(request_context.get_path(), Some(services)).is_some()
+    }
 }
\ No newline at end of file
@@ -34,6 +34,8 @@ pub struct ControllerActionFileResult {
     pub area_name: String,
     // the route pattern for the controller action.
     pub route_pattern: Rc<ControllerActionRoutePattern>,
+    // the Cache-Control header to send with the file, if any.
+    pub cache_control: Option<String>,
 }
 
 impl ControllerActionFileResult {
@@ -55,7 +57,8 @@ impl ControllerActionFileResult {
             name: name,
             controller_name: controller_name,
             area_name: area_name,
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
+            cache_control: None,
         }
     }
     
@@ -75,14 +78,22 @@ impl ControllerActionFileResult {
             name: name,
             controller_name: controller_name,
             area_name: String::new(),
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
+            cache_control: None,
         }
     }
+
+    // set the Cache-Control header to send with the file.
+    // cache_control: the value of the header, or None to not send one.
+    pub fn with_cache_control(mut self, cache_control: Option<String>) -> Self {
+        self.cache_control = cache_control;
+        self
+    }
 }
 
 impl IControllerAction for ControllerActionFileResult {
     fn invoke(self: &Self, controller_context: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<(), Rc<dyn Error>> {
-        let result_option = Some(Rc::new(FileResult::new(self.file_path.clone(), None)));
+        let result_option = Some(Rc::new(FileResult::new(self.file_path.clone(), None).with_cache_control(self.cache_control.clone())));
         if let Some(result) = result_option {
             controller_context.get_response_context().set_action_result(Some(result));
         }
@@ -90,23 +101,8 @@ impl IControllerAction for ControllerActionFileResult {
         Ok(())
     }
 
-    fn is_route_match(self: &Self, request_context: &dyn IRequestContext) -> Result<bool, Rc<dyn Error>> {
-        if !IControllerActionExtensions::is_method_match(self, request_context) {
-            return Ok(false);
-        }
-
-        let path = request_context.get_path().as_str().trim();
-        let route_pattern = self.get_route_pattern();
-
-        // println!("Testing path {} against pattern {}", path, route_pattern.raw);
-
-        if route_pattern.raw.ends_with("..") {
-            Ok(path.starts_with(&route_pattern.raw[..route_pattern.raw.len() - 2]))
-        } else {
-            let r = path == route_pattern.raw;
-            // println!("path == route_pattern.raw -> {}", r);
-            Ok(r)
-        }
+    fn is_route_match(self: &Self, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<bool, Rc<dyn Error>> {
+        Ok(IControllerActionExtensions::is_route_match(self, request_context, services))
     }
 
     fn get_name(self: &Self) -> Cow<'static, str> {
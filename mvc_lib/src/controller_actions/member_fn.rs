@@ -79,7 +79,7 @@ impl<T: IController> ControllerActionMemberFn<T> {
             name: name,
             controller_name: controller_name,
             area_name: area_name,
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
             member_fn_validated: member_fn_validated,
             member_fn_not_validated: member_fn_not_validated,
             member_fn_validated_typed: None,
@@ -167,7 +167,7 @@ impl<T: IController> ControllerActionMemberFn<T> {
         Self {
             http_methods_allowed: http_methods_allowed,
             features: features.unwrap_or_default(),
-            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern)),
+            route_pattern: Rc::new(ControllerActionRoutePattern::parse(route_pattern).expect("invalid route pattern")),
             name: name,
             controller_name: controller_name,
             area_name: area_name,
@@ -244,23 +244,8 @@ impl<T: 'static + IController> IControllerAction for ControllerActionMemberFn<T>
         Ok(())
     }
 
-    fn is_route_match(self: &Self, request_context: &dyn IRequestContext) -> Result<bool, Rc<dyn Error>> {
-        if !IControllerActionExtensions::is_method_match(self, request_context) {
-            return Ok(false);
-        }
-
-        let path = request_context.get_path().as_str().trim();
-        let route_pattern = self.get_route_pattern();
-
-        // println!("Testing path {} against pattern {}", path, route_pattern.raw);
-
-        if route_pattern.raw.ends_with("..") {
-            Ok(path.starts_with(&route_pattern.raw[..route_pattern.raw.len() - 2]))
-        } else {
-            let r = path == route_pattern.raw;
-            // println!("path == route_pattern.raw -> {}", r);
-            Ok(r)
-        }
+    fn is_route_match(self: &Self, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<bool, Rc<dyn Error>> {
+        Ok(IControllerActionExtensions::is_route_match(self, request_context, services))
     }
 
     fn get_name(self: &Self) -> Cow<'static, str> {
@@ -1,21 +1,118 @@
 use std::{collections::HashMap, borrow::Cow};
 
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+
+use crate::errors::{RoutePatternError, UrlGenerationError};
+use crate::routing::route_constraint::RouteConstraint;
+use crate::services::service_collection::IServiceCollection;
+
+// the characters that are percent-encoded in a value of a path segment: the characters that cannot be in a path,
+// the characters that end or split a path, and % so a value is not decoded into something else.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'/').add(b'<').add(b'>').add(b'?').add(b'`').add(b'{').add(b'}');
+
 // this struct represents a route pattern for a controller action.
 // a route pattern is a string that looks like a url path, but with capture groups.
-// capture groups are denoted by curly braces, and have a name and a type.
-// the type is used to validate the captured value.
+// capture groups are denoted by curly braces, and have a name and optionally constraints, separated by ':'.
+// the constraints are used to validate the captured value.
 // for example, a route pattern might look like this:
 // /users/{id:int}
 // this route pattern would match the url /users/123, but not /users/abc.
-// the route pattern would capture the value 123 and store it in the captures map.
-// the captures map would have a key of "id" and a value of "123".
+// the route pattern would capture the value 123 and store it in the values returned by try_match.
+// the values would have a key of "id" and a value of "123".
+// a capture group can be optional like {id?}, or have a default value like {action=index},
+// in which case it can be left out of the end of the url.
+// a route pattern ending in .. matches any url that starts with the rest of the pattern.
 pub struct ControllerActionRoutePattern {
     // the raw string that was parsed to create this route pattern
     pub raw: Cow<'static, str>,
     // the parts of the route pattern, split by slashes
     pub parts: Vec<String>,
-    // the capture groups of the route pattern, with the name of the capture group as the key and the type of the capture group as the value
+    // the capture groups of the route pattern, with the name of the capture group as the key and the constraints of the capture group as the value
     pub captures: HashMap<String, String>,
+    // the segments of the route pattern, split by slashes
+    pub segments: Vec<RoutePatternSegment>,
+}
+
+// this enum is one segment of a route pattern, between two slashes.
+pub enum RoutePatternSegment {
+    // a segment that must match exactly, like users in /users/{id}.
+    Literal(String),
+    // a segment that captures a value, like {id} in /users/{id}.
+    Capture(RouteCapture),
+    // the .. at the end of a route pattern, which matches the rest of the url.
+    CatchAll,
+}
+
+// this struct is a capture group of a route pattern, like {id:int}, {id?} or {action=index}.
+pub struct RouteCapture {
+    // the name of the value that is captured.
+    pub name: String,
+    // the constraints the value must meet.
+    pub constraints: Vec<RouteConstraint>,
+    // whether or not the segment can be left out of the url.
+    pub optional: bool,
+    // the value used when the segment is left out of the url.
+    pub default: Option<String>,
+}
+
+impl RouteCapture {
+    // get whether or not a value meets every constraint of the capture group.
+    // value: the value captured from the url.
+    // services: the services to find custom constraints in.
+    pub fn is_match(self: &Self, value: &str, services: Option<&dyn IServiceCollection>) -> bool {
+        self.constraints.iter().all(|x| x.is_match(value, services))
+    }
+
+    // get whether or not the segment can be left out of the url.
+    fn is_omittable(self: &Self) -> bool {
+        self.optional || self.default.is_some()
+    }
+
+    // parse the inside of a capture group, like id:int, id? or action=index.
+    fn parse(s: &str) -> Result<Self, String> {
+        let (s, default) = match find_outside_parens(s, '=').first() {
+            Some(i) => (&s[..*i], Some(s[*i + 1..].to_string())),
+            None => (s, None),
+        };
+        let (s, optional) = match s.strip_suffix('?') {
+            Some(s) => (s, true),
+            None => (s, false),
+        };
+        if optional && default.is_some() {
+            return Err(format!("capture group {} cannot be both optional and have a default value", s));
+        }
+
+        let mut start = 0;
+        let mut pieces = vec![];
+        for i in find_outside_parens(s, ':') {
+            pieces.push(&s[start..i]);
+            start = i + 1;
+        }
+        pieces.push(&s[start..]);
+
+        let name = pieces[0];
+        if name.is_empty() || !name.chars().all(|x| x.is_alphanumeric() || x == '_') {
+            return Err(format!("invalid capture group name '{}'", name));
+        }
+
+        let constraints = pieces[1..].iter().map(|x| RouteConstraint::parse(x)).collect::<Result<Vec<RouteConstraint>, String>>()?;
+        Ok(Self { name: name.to_string(), constraints: constraints, optional: optional, default: default })
+    }
+}
+
+// find the positions of a character that are not inside parentheses, such as the arguments of regex(...).
+fn find_outside_parens(s: &str, c: char) -> Vec<usize> {
+    let mut depth = 0;
+    let mut positions = vec![];
+    for (i, x) in s.char_indices() {
+        match x {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if x == c && depth == 0 => positions.push(i),
+            _ => {},
+        }
+    }
+    positions
 }
 
 impl ControllerActionRoutePattern {
@@ -25,135 +122,205 @@ impl ControllerActionRoutePattern {
 
     // parse a string into a route pattern.
     // s: the string to parse
-    pub fn parse(s: Cow<'static, str>) -> Self {
+    // returns: the route pattern, or an error if a capture group or constraint is not valid.
+    pub fn parse(s: Cow<'static, str>) -> Result<Self, RoutePatternError> {
+        let error = |message: String| RoutePatternError::new(s.to_string(), message);
+
+        // split by slashes that are not inside a capture group, since a regex may contain slashes.
+        let mut segment_strs = vec![];
+        let mut current = String::new();
+        let mut brace_depth = 0;
+        for c in s.chars() {
+            match c {
+                '{' => brace_depth += 1,
+                '}' if brace_depth == 0 => return Err(error("unexpected '}'".to_string())),
+                '}' => brace_depth -= 1,
+                '/' if brace_depth == 0 => {
+                    segment_strs.push(std::mem::take(&mut current));
+                    continue;
+                },
+                _ => {},
+            }
+            current.push(c);
+        }
+        if brace_depth > 0 {
+            return Err(error("missing '}'".to_string()));
+        }
+        segment_strs.push(current);
+
+        let mut segments = vec![];
+        for segment_str in segment_strs.into_iter().filter(|x| !x.is_empty()) {
+            let segment = if segment_str == ".." {
+                RoutePatternSegment::CatchAll
+            } else if segment_str.starts_with('{') && segment_str.ends_with('}') && find_outside_parens(&segment_str[1..], '}').len() == 1 {
+                RoutePatternSegment::Capture(RouteCapture::parse(&segment_str[1..segment_str.len() - 1]).map_err(error)?)
+            } else if segment_str.contains('{') || segment_str.contains('}') {
+                return Err(error(format!("capture group in {} must be the whole segment", segment_str)));
+            } else {
+                RoutePatternSegment::Literal(segment_str)
+            };
+
+            match (segments.last(), &segment) {
+                (Some(RoutePatternSegment::CatchAll), _) => return Err(error(".. must be at the end".to_string())),
+                (Some(RoutePatternSegment::Capture(previous)), RoutePatternSegment::Capture(capture)) if previous.is_omittable() && !capture.is_omittable() => {
+                    return Err(error(format!("{} follows optional capture group {}", capture.name, previous.name)));
+                },
+                (Some(RoutePatternSegment::Capture(previous)), RoutePatternSegment::Literal(_) | RoutePatternSegment::CatchAll) if previous.is_omittable() => {
+                    return Err(error(format!("optional capture group {} must be at the end", previous.name)));
+                },
+                _ => {},
+            }
+            if let RoutePatternSegment::Capture(capture) = &segment {
+                if segments.iter().any(|x| matches!(x, RoutePatternSegment::Capture(other) if other.name == capture.name)) {
+                    return Err(error(format!("capture group {} is used more than once", capture.name)));
+                }
+            }
+            segments.push(segment);
+        }
+
         let mut parts = Vec::new();
         let mut captures = HashMap::new();
-        for route_dir in s.split('/') {
-            // parse capture groups, like regex
-            let mut route_dir_it = route_dir.chars();
-            let mut str_match = String::new();
-            loop {
-                let c_option = route_dir_it.next();
-                if let Some(c) = c_option {
-                    match c {
-                        '{' => {
-                            if str_match.len() > 0 {
-                                parts.push(str_match);
-                                str_match = String::new();
-                            }
-
-                            // expect name:type
-                            let mut param_name = String::new();
-                            let mut param_type = String::new();
-                            let mut parsing_name = true;
-                            loop {
-                                let name_c_option = route_dir_it.next();
-                                if let Some(name_c) = name_c_option {
-                                    match name_c {
-                                        '}' => {
-                                            break;
-                                        },
-                                        ':' => {
-                                            if parsing_name {
-                                                parsing_name = false;
-                                            } else {
-                                                panic!("Cannot use ':' more than once in route pattern param")
-                                            }
-                                        },
-                                        _ => {
-                                            if parsing_name {
-                                                param_name.push(name_c);
-                                            } else {
-                                                param_type.push(name_c);
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    panic!("Expected next char");
-                                }
-                            }
-
-                            parts.push(param_name.clone());
-                            captures.insert(param_name, param_type);
-                        },
-                        '.' => {
-                            if str_match.len() > 0 {
-                                str_match.push(c);
-                            } else {
-                                // check if next char is '.' too
-                                if let Some(next_c) = route_dir_it.next() {
-                                    if next_c == '.' {
-                                        parts.push("..".to_string());
-                                        captures.insert("..".to_string(), "..".to_string());
-                                        // println!("Found custom area");
-                                        break;
-                                    } else {
-                                        str_match.push(c);
-                                    }
-                                } else {
-                                    break;
-                                }
-                            }
-                        },
-                        _ => {
-                            str_match.push(c);
-                        }
-                    }
-                } else {
-                    break;
-                }
+        for segment in segments.iter() {
+            match segment {
+                RoutePatternSegment::Literal(text) => parts.push(text.clone()),
+                RoutePatternSegment::Capture(capture) => {
+                    parts.push(capture.name.clone());
+                    captures.insert(capture.name.clone(), capture.constraints.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(":"));
+                },
+                RoutePatternSegment::CatchAll => {
+                    parts.push("..".to_string());
+                    captures.insert("..".to_string(), "..".to_string());
+                },
             }
+        }
+        Ok(Self { raw: s, parts: parts, captures: captures, segments: segments })
+    }
+
+    // match a request path against the route pattern, segment by segment, without custom constraints.
+    // capture groups with a custom constraint do not match; use try_match_with_services for those.
+    // path: the path of the request, such as /users/123.
+    // returns: the values of the capture groups by name, or None if the path does not match.
+    pub fn try_match(self: &Self, path: &str) -> Option<HashMap<String, String>> {
+        self.try_match_with_services(path, None)
+    }
 
-            if str_match.len() > 0 {
-                parts.push(str_match);
+    // match a request path against the route pattern, segment by segment.
+    // capture groups match one segment that meets their constraints, optional capture groups and
+    // capture groups with default values can be left out, and a trailing ".." matches the rest of the path.
+    // captured segments are percent-decoded before their constraints are checked, as gen_url encodes them.
+    // path: the path of the request, such as /users/123.
+    // services: the services to find custom constraints in.
+    // returns: the values of the capture groups by name, or None if the path does not match.
+    pub fn try_match_with_services(self: &Self, path: &str, services: Option<&dyn IServiceCollection>) -> Option<HashMap<String, String>> {
+        let mut values = HashMap::new();
+        let mut path_segments = path.trim().split('/').filter(|x| !x.is_empty()).peekable();
+        for segment in self.segments.iter() {
+            match segment {
+                RoutePatternSegment::CatchAll => {
+                    // the rest of the path must not be empty, so /docs/.. does not match /docs.
+                    path_segments.peek()?;
+                    let rest = path_segments.map(Self::decode_segment).collect::<Option<Vec<String>>>()?;
+                    values.insert("..".to_string(), rest.join("/"));
+                    return Some(values);
+                },
+                RoutePatternSegment::Literal(text) => {
+                    if path_segments.next()? != text {
+                        return None;
+                    }
+                },
+                RoutePatternSegment::Capture(capture) => match path_segments.next() {
+                    Some(value) => {
+                        let value = Self::decode_segment(value)?;
+                        if !capture.is_match(&value, services) {
+                            return None;
+                        }
+                        values.insert(capture.name.clone(), value);
+                    },
+                    None if capture.optional => {},
+                    None => {
+                        values.insert(capture.name.clone(), capture.default.clone()?);
+                    },
+                },
             }
         }
-        // println!("Parts: {:?}", parts);
-        // println!("captures: {:?}", captures);
-        Self { raw: s, parts: parts, captures: captures }
+
+        match path_segments.next() {
+            Some(_) => None,
+            None => Some(values),
+        }
     }
 
-    // generate a url from the route pattern and the route values.
-    // the route values are used to fill in the capture groups.
+    // percent-decode a segment of a request path.
+    // returns: the decoded segment, or None if it does not decode to UTF-8.
+    fn decode_segment(segment: &str) -> Option<String> {
+        percent_decode_str(segment).decode_utf8().ok().map(|x| x.to_string())
+    }
+
+    // generate a url from the route pattern and the route values, without custom constraints.
+    // capture groups with a custom constraint cannot be filled in; use gen_url_with_services for those.
     // for example, if the route pattern is /users/{id:int} and the route values are [("id", "123")], the generated url will be /users/123.
     // route_values: the route values to use to generate the url.
-    // returns: the generated url.
-    pub fn gen_url(self: &Self, route_values: &HashMap<String, String>) -> String {
+    // returns: the generated url, or an error if a route value is missing or does not meet its constraints.
+    pub fn gen_url(self: &Self, route_values: &HashMap<String, String>) -> Result<String, UrlGenerationError> {
+        self.gen_url_with_services(route_values, None)
+    }
+
+    // generate a url from the route pattern and the route values.
+    // the route values are used to fill in the capture groups and are percent-encoded.
+    // route values that are not capture groups are added to the query string, sorted by name.
+    // route_values: the route values to use to generate the url.
+    // services: the services to find custom constraints in.
+    // returns: the generated url, or an error if a route value is missing or does not meet its constraints.
+    pub fn gen_url_with_services(self: &Self, route_values: &HashMap<String, String>, services: Option<&dyn IServiceCollection>) -> Result<String, UrlGenerationError> {
+        let error = |message: String| UrlGenerationError::new(self.raw.to_string(), message);
         let mut result = String::new();
+        // the optional capture group that was left out, since the capture groups after it must be left out too.
+        let mut left_out: Option<&str> = None;
 
-        for part in self.parts.iter() {
+        for segment in self.segments.iter() {
+            let value = match segment {
+                RoutePatternSegment::Literal(text) => text.clone(),
+                RoutePatternSegment::Capture(capture) => match route_values.get(&capture.name).filter(|x| !x.is_empty()) {
+                    Some(value) => {
+                        if let Some(left_out) = left_out {
+                            return Err(error(format!("{} has a value but {} before it does not", capture.name, left_out)));
+                        }
+                        if !capture.is_match(value, services) {
+                            return Err(error(format!("'{}' does not meet the constraints of {}", value, capture.name)));
+                        }
+                        utf8_percent_encode(value, PATH_SEGMENT).to_string()
+                    },
+                    None => match &capture.default {
+                        Some(default) => utf8_percent_encode(default, PATH_SEGMENT).to_string(),
+                        None if capture.optional => {
+                            left_out = Some(&capture.name);
+                            continue;
+                        },
+                        None => return Err(error(format!("missing route value for {}", capture.name))),
+                    },
+                },
+                RoutePatternSegment::CatchAll => match route_values.get("..").filter(|x| !x.is_empty()) {
+                    // the slashes of the rest of the path are kept.
+                    Some(value) => value.split('/').map(|x| utf8_percent_encode(x, PATH_SEGMENT).to_string()).collect::<Vec<String>>().join("/"),
+                    None => return Err(error("missing route value for ..".to_string())),
+                },
+            };
+            result.push_str("/");
+            result.push_str(&value);
+        }
+        if result.is_empty() {
             result.push_str("/");
-            if let Some(_) = self.captures.get(part) {
-                let route_val = route_values.get(part);
-                if let Some(value) = route_val {
-                    result.push_str(value);
-                } else {
-                    panic!("Expected route value for capture group {}", part);
-                }
-            } else {
-                result.push_str(part.as_str());
-            }
         }
 
-        if route_values.len() > 0 {
-            let mut query_string = String::new();
-            let mut first = true;
-            for (key, value) in route_values {
-                // skip special keys
-                if self.captures.contains_key(key) {
-                    continue;
-                }
-
-                if first {
-                    first = false;
-                    query_string.push_str("?");
-                } else {
-                    query_string.push_str("&");
-                }
-                query_string.push_str(&format!("{}={}", key, value));
-            }
-            result.push_str(query_string.as_str());
+        // the query string is sorted so the same route values always generate the same url.
+        let mut query_values = route_values.iter()
+            .filter(|(key, _)| !self.captures.contains_key(key.as_str()))
+            .collect::<Vec<(&String, &String)>>();
+        if !query_values.is_empty() {
+            query_values.sort();
+            result.push_str("?");
+            result.push_str(&form_urlencoded::Serializer::new(String::new()).extend_pairs(query_values).finish());
         }
-        result
+        Ok(result)
     }
-}
\ No newline at end of file
+}
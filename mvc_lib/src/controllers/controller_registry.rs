@@ -0,0 +1,48 @@
+use std::any::Any;
+
+use http::Method;
+
+use crate::services::routemap_service::RouteMapService;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection};
+
+
+// this struct is a controller found at link time. each impl marked #[controller] submits one,
+// so the controllers of every crate linked into the program can be added without listing them by hand.
+pub struct ControllerRegistration {
+    // the type name of the controller, such as "HomeController".
+    pub type_name: &'static str,
+    // creates the controller as a service.
+    pub new_service: fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>,
+}
+
+inventory::collect!(ControllerRegistration);
+
+impl ControllerRegistration {
+    // create a new registration. this is const so it can be submitted to the registry.
+    pub const fn new(type_name: &'static str, new_service: fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>) -> Self {
+        Self {
+            type_name: type_name,
+            new_service: new_service,
+        }
+    }
+
+    // get every registered controller, sorted by type name so the order does not depend on the linker.
+    pub fn get_all() -> Vec<&'static ControllerRegistration> {
+        let mut registrations = inventory::iter::<ControllerRegistration>.into_iter().collect::<Vec<&'static ControllerRegistration>>();
+        registrations.sort_by_key(|x| x.type_name);
+        registrations
+    }
+
+    // add every registered controller to the service collection as a singleton, keyed by the name of the controller.
+    pub fn add_all_to_services(services: &mut ServiceCollection) {
+        for registration in Self::get_all() {
+            RouteMapService::add_controller(services, registration.type_name, registration.new_service);
+        }
+    }
+
+    // parse the names of HTTP methods, as used by the actions generated for #[controller].
+    // methods: the method names, such as "GET" and "POST".
+    pub fn parse_methods(methods: &[&str]) -> Vec<Method> {
+        methods.iter().map(|x| Method::from_bytes(x.as_bytes()).expect("invalid HTTP method")).collect()
+    }
+}
@@ -75,11 +75,12 @@ impl IController for FileProviderController {
 
         mapped_paths
             .into_iter()
-            .map(|x|
+            .map(|x| {
+                let cache_control = self.options.get_cache_control(&x.0);
                 Rc::new(ControllerActionFileResult::new(
                     x.1, x.0, Cow::Owned(String::default()), IControllerExtensions::get_name(self).into(), self.get_route_area(),
-                )) as Rc<dyn IControllerAction>
-            )
+                ).with_cache_control(cache_control)) as Rc<dyn IControllerAction>
+            })
             .collect()
     }
 
@@ -27,7 +27,13 @@ impl IControllerExtensions {
     // controller: the controller.
     // returns: the name of the controller without the "Controller" suffix.
     pub fn get_name_ref(controller: &dyn IController) -> &str {
-        let type_name = controller.get_type_name();
+        Self::get_name_of_type(controller.get_type_name())
+    }
+
+    // gets the name of a controller from its type name, such as "Home" for "HomeController".
+    // type_name: the type name of the controller.
+    // returns: the type name without the "Controller" suffix.
+    pub fn get_name_of_type(type_name: &str) -> &str {
         if type_name.ends_with("Controller") {
             return &type_name[..type_name.len() - "Controller".len()];
         } else {
@@ -1,5 +1,6 @@
 pub mod icontroller;
 pub mod icontroller_extensions;
 pub mod controller_actions_map;
+pub mod controller_registry;
 pub mod file_provider_controller;
 pub mod route_data_controller_action_matcher;
\ No newline at end of file
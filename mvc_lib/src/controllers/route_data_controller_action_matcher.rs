@@ -5,58 +5,46 @@ use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 
 use crate::controller_actions::controller_action::IControllerAction;
-use crate::controllers::controller_actions_map::IControllerActionsMap;
+use crate::routing::route_table::{RouteMatch, RouteTable};
 
 use crate::services::service_collection::IServiceCollection;
 
 
 // this struct is used to match a request to a controller action.
-// matching is done by comparing the request path to the controller action path.
+// matching is done by looking up the request path and method in the route table.
 pub struct RouteDataControllerActionMatcher {
-    // the controller actions map.
-    actions_map: Rc<dyn IControllerActionsMap>,
+    // the route table of the controller actions.
+    route_table: Rc<RouteTable>,
 }
 
 impl RouteDataControllerActionMatcher {
     // create a new instance of the matcher.
-    // actions_map: the controller actions map.
+    // route_table: the route table of the controller actions.
     pub fn new(
-        actions_map: Rc<dyn IControllerActionsMap>,
+        route_table: Rc<RouteTable>,
     ) -> Self {
         Self {
-            actions_map: actions_map,
+            route_table: route_table,
         }
     }
 
-    // create a new instance of the matcher as a service for a service collection.
+    // get the controller action for a request.
     // request_context: the request context for the controller action.
     // response_context: the response context for the controller action.
     // services: the service collection for the controller action.
-    // returns: the controller action or an error.
-    pub fn get_action_for_request(self: &Self, _response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IControllerAction>>, Rc<dyn Error>> {
-        let all_actions = self.actions_map.get_all_actions();
-        let actions: Vec<Rc<dyn IControllerAction>> = all_actions
-            .iter()
-            .filter(|x|
-                match x.is_route_match(request_context.clone()) {
-                    Ok(is_match) => is_match,
-                    Err(_) => false,
-                }
-            )
-            .cloned()
-            .collect();
-
-        match actions.len() {
-            0 => {
-                Ok(None)
-                // panic!("No routes found for {}", request_context.as_ref().path);
-            },
-            1 => {
-                Ok(Some(actions.first().unwrap().clone()))
-            },
-            _ => {
-                panic!("Ambiguous routes: {:?}", actions.iter().map(|x| x.to_string()).collect::<Vec<String>>());
-            }
+    // returns: the controller action, or None if no action matches the path and method.
+    pub fn get_action_for_request(self: &Self, _response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IControllerAction>>, Rc<dyn Error>> {
+        match self.match_request(request_context, services) {
+            RouteMatch::Found(action, _) => Ok(Some(action)),
+            _ => Ok(None),
         }
     }
+
+    // match a request to a controller action, with the values captured from the path.
+    // request_context: the request context.
+    // services: the services to find custom route constraints in.
+    // returns: the action and the values captured from the path, or why no action matched.
+    pub fn match_request(self: &Self, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> RouteMatch {
+        self.route_table.match_request(request_context.get_path(), request_context.get_method(), services)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+
+// this struct is used to tell work running on another thread that it should stop.
+// clones of a token share the same state, so cancelling one cancels all of them.
+#[derive(Clone)]
+pub struct CancellationToken {
+    state: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancellationToken {
+    // create a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new((Mutex::new(false), Condvar::new())),
+        }
+    }
+
+    // cancel the token and wake up anything waiting on it.
+    pub fn cancel(self: &Self) {
+        let (cancelled, condvar) = self.state.as_ref();
+        *cancelled.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        condvar.notify_all();
+    }
+
+    // returns true if the token has been cancelled.
+    pub fn is_cancelled(self: &Self) -> bool {
+        *self.state.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // wait until the token is cancelled or the timeout passes.
+    // timeout: the longest time to wait.
+    // returns: true if the token was cancelled.
+    pub fn wait(self: &Self, timeout: Duration) -> bool {
+        let (cancelled, condvar) = self.state.as_ref();
+        let deadline = Instant::now() + timeout;
+        let mut is_cancelled = cancelled.lock().unwrap_or_else(|e| e.into_inner());
+        while !*is_cancelled {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            is_cancelled = match condvar.wait_timeout(is_cancelled, deadline - now) {
+                Ok((guard, _)) => guard,
+                Err(e) => e.into_inner().0,
+            };
+        }
+        *is_cancelled
+    }
+}
@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::itcp_stream_wrapper::ITcpStreamWrapper;
+
+
+// this struct wraps a stream and only allows reading the number of bytes in the request body.
+// this keeps readers of the body from reading into the next request on a persistent connection.
+pub struct ContentLengthStream {
+    inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+    remaining: RefCell<usize>,
+}
+
+impl ContentLengthStream {
+    // create a new stream that can read at most content_length bytes from the inner stream.
+    // inner_stream: the stream to read from.
+    // content_length: the number of bytes in the request body.
+    pub fn new(inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>, content_length: usize) -> Self {
+        Self {
+            inner_stream: inner_stream,
+            remaining: RefCell::new(content_length),
+        }
+    }
+
+    // get the number of bytes of the body that have not been read yet.
+    pub fn get_remaining(&self) -> usize {
+        *self.remaining.borrow()
+    }
+
+    // read and discard the rest of the body.
+    // returns: the number of bytes discarded.
+    pub fn drain(&self) -> std::io::Result<usize> {
+        let mut total = 0;
+        let mut buffer = [0; 4096];
+        loop {
+            let n = self.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            total += n;
+        }
+        Ok(total)
+    }
+}
+
+impl ITcpStreamWrapper for ContentLengthStream {
+    fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
+        self.inner_stream.borrow().shutdown(how)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.inner_stream.borrow().flush()
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.get_remaining();
+        if remaining == 0 || b.len() == 0 {
+            return Ok(0);
+        }
+
+        let max_read = std::cmp::min(remaining, b.len());
+        let n = self.inner_stream.borrow().read(&mut b[..max_read])?;
+        self.remaining.replace(remaining - n);
+        Ok(n)
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n or the end of the body
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, b: &[u8]) -> std::io::Result<usize> {
+        self.inner_stream.borrow().write(b)
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        self.inner_stream.borrow().write_line(b)
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        self.inner_stream.borrow().remote_addr()
+    }
+}
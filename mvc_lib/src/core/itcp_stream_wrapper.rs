@@ -6,6 +6,11 @@ pub trait ITcpStreamWrapper {
     fn write(&self, b: &[u8]) -> std::io::Result<usize>;
     fn write_line(&self, b: &String) -> std::io::Result<usize>;
     fn remote_addr(&self) -> std::net::SocketAddr;
+    // get whether the stream already read input that has not been read from it yet, such as a pipelined request.
+    // the socket has nothing left to peek for that input. streams that do not buffer what they read have none.
+    fn has_buffered_input(&self) -> std::io::Result<bool> {
+        Ok(false)
+    }
 
 
     // fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()>;
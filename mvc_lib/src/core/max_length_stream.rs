@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use http::StatusCode;
+
+use crate::errors::BadRequestError;
+
+use super::itcp_stream_wrapper::ITcpStreamWrapper;
+
+
+// this struct wraps a request body whose length is not known up front, such as a chunked or decompressed body,
+// and fails reading once more than the maximum number of bytes have been read.
+pub struct MaxLengthStream {
+    inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+    max_length: usize,
+    read_length: RefCell<usize>,
+    // whether a read failed because the body was longer than max_length.
+    exceeded: RefCell<bool>,
+}
+
+impl MaxLengthStream {
+    // create a new stream that can read at most max_length bytes from the inner stream.
+    // inner_stream: the stream to read from.
+    // max_length: the largest number of bytes allowed in the request body.
+    pub fn new(inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>, max_length: usize) -> Self {
+        Self {
+            inner_stream: inner_stream,
+            max_length: max_length,
+            read_length: RefCell::new(0),
+            exceeded: RefCell::new(false),
+        }
+    }
+
+    // get the number of bytes of the body that have been read.
+    pub fn get_read_length(&self) -> usize {
+        *self.read_length.borrow()
+    }
+
+    // get whether reading failed because the body was longer than the maximum length.
+    pub fn is_exceeded(&self) -> bool {
+        *self.exceeded.borrow()
+    }
+}
+
+impl ITcpStreamWrapper for MaxLengthStream {
+    fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
+        self.inner_stream.borrow().shutdown(how)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.inner_stream.borrow().flush()
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner_stream.borrow().read(b)?;
+        let read_length = self.get_read_length() + n;
+        if read_length > self.max_length {
+            self.exceeded.replace(true);
+            return Err(BadRequestError::new(StatusCode::PAYLOAD_TOO_LARGE, format!("Request body is larger than {} bytes", self.max_length)).to_io_error());
+        }
+        self.read_length.replace(read_length);
+        Ok(n)
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n or the end of the body
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, b: &[u8]) -> std::io::Result<usize> {
+        self.inner_stream.borrow().write(b)
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        self.inner_stream.borrow().write_line(b)
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        self.inner_stream.borrow().remote_addr()
+    }
+}
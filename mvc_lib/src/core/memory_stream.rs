@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+
+use super::itcp_stream_wrapper::ITcpStreamWrapper;
+
+
+// this struct is an in-memory stream. reads return the bytes that were written and not read yet.
+pub struct MemoryStream {
+    buffer: RefCell<Vec<u8>>,
+}
+
+impl MemoryStream {
+    // create a new empty stream.
+    pub fn new() -> Self {
+        Self {
+            buffer: RefCell::new(Vec::new()),
+        }
+    }
+
+    // remove and return every byte that has not been read yet.
+    pub fn take(&self) -> Vec<u8> {
+        self.buffer.replace(Vec::new())
+    }
+}
+
+impl ITcpStreamWrapper for MemoryStream {
+    fn shutdown(&self, _how: std::net::Shutdown) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        let mut buffer = self.buffer.borrow_mut();
+        let n = std::cmp::min(buffer.len(), b.len());
+        b[..n].copy_from_slice(&buffer[..n]);
+        buffer.drain(..n);
+        Ok(n)
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, b: &[u8]) -> std::io::Result<usize> {
+        self.buffer.borrow_mut().extend_from_slice(b);
+        Ok(b.len())
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        self.write(format!("{}\r\n", b).as_bytes())
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        unimplemented!()
+    }
+}
@@ -1,8 +1,13 @@
 pub mod buffered_tcpstream;
+pub mod tls_tcpstream;
 pub mod content_type_resolver;
 pub mod type_info;
 pub mod string_extensions;
 pub mod query_string;
 pub mod panic_or_return_error;
 pub mod html_buffer;
-pub mod itcp_stream_wrapper;
\ No newline at end of file
+pub mod itcp_stream_wrapper;
+pub mod content_length_stream;
+pub mod cancellation_token;
+pub mod memory_stream;
+pub mod max_length_stream;
@@ -0,0 +1,149 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+
+use super::itcp_stream_wrapper::ITcpStreamWrapper;
+
+
+// this struct wraps a TCP stream and terminates TLS on it.
+// the handshake happens on the first read, so creating the stream does not block.
+pub struct TlsTcpStream {
+    stream: RefCell<StreamOwned<ServerConnection, TcpStream>>,
+    remote_addr: std::net::SocketAddr,
+}
+
+impl TlsTcpStream {
+    // create a new TLS stream for a client connection.
+    // config: the TLS configuration with the server certificate.
+    // stream: the client connection.
+    // returns: the new stream, or an error if the TLS session could not be created.
+    pub fn new(config: Arc<ServerConfig>, stream: TcpStream) -> std::io::Result<Self> {
+        let remote_addr = stream.peer_addr()?;
+        let connection = ServerConnection::new(config)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("could not create TLS session: {}", e)))?;
+        Ok(Self {
+            stream: RefCell::new(StreamOwned::new(connection, stream)),
+            remote_addr: remote_addr,
+        })
+    }
+
+    // get a handle to the underlying TCP stream, for setting socket options such as timeouts.
+    pub fn try_clone_tcp_stream(&self) -> std::io::Result<TcpStream> {
+        self.stream.borrow().sock.try_clone()
+    }
+
+    // load the TLS configuration for a server from PEM files.
+    // certificate_path: the path to the PEM file with the certificate chain.
+    // private_key_path: the path to the PEM file with the private key of the certificate.
+    // returns: the TLS configuration, or an error if the files could not be read or do not match.
+    pub fn load_server_config(certificate_path: &str, private_key_path: &str) -> std::io::Result<Arc<ServerConfig>> {
+        let certificates = rustls_pemfile::certs(&mut BufReader::new(File::open(certificate_path)?))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        if certificates.len() == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("no certificates found in {}", certificate_path)));
+        }
+
+        let private_key = match rustls_pemfile::private_key(&mut BufReader::new(File::open(private_key_path)?))? {
+            Some(private_key) => private_key,
+            None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("no private key found in {}", private_key_path))),
+        };
+
+        let config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .and_then(|x| x.with_no_client_auth().with_single_cert(certificates, private_key))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("invalid TLS configuration: {}", e)))?;
+        Ok(Arc::new(config))
+    }
+}
+
+impl ITcpStreamWrapper for TlsTcpStream {
+    fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
+        let mut stream = self.stream.borrow_mut();
+        // tell the client the session is over before closing the connection.
+        stream.conn.send_close_notify();
+        let _ = stream.flush();
+        match stream.sock.shutdown(how) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::NotConnected ||
+                    e.kind() == std::io::ErrorKind::BrokenPipe {
+                    return Ok(());
+                }
+                Err(std::io::Error::new(e.kind(), format!("TlsTcpStream::shutdown: {:?}", e)))
+            },
+        }
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        match self.stream.borrow_mut().flush() {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                Err(std::io::Error::new(e.kind(), format!("TlsTcpStream::flush: {:?}", e)))
+            },
+        }
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        match self.stream.borrow_mut().read(b) {
+            Ok(n) => Ok(n),
+            // clients often close the connection without sending close_notify, which is the same as the end of the stream here.
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(0),
+            Err(e) => {
+                Err(std::io::Error::new(e.kind(), format!("TlsTcpStream::read: {:?}", e)))
+            },
+        }
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, b: &[u8]) -> std::io::Result<usize> {
+        match self.stream.borrow_mut().write_all(b) {
+            Ok(()) => {},
+            Err(e) => {
+                return Err(std::io::Error::new(e.kind(), format!("TlsTcpStream::write: {:?}", e)));
+            },
+        }
+        self.flush()?;
+        Ok(b.len())
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        match self.write(format!("{}\r\n", b).as_bytes()) {
+            Ok(n) => Ok(n),
+            Err(e) => {
+                Err(std::io::Error::new(e.kind(), format!("TlsTcpStream::write_line: {:?}", e)))
+            },
+        }
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        self.remote_addr
+    }
+
+    fn has_buffered_input(&self) -> std::io::Result<bool> {
+        // the TLS session may have received and decrypted the next request along with the last one.
+        match self.stream.borrow_mut().conn.process_new_packets() {
+            Ok(state) => Ok(state.plaintext_bytes_to_read() > 0),
+            Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("TlsTcpStream::has_buffered_input: {:?}", e))),
+        }
+    }
+}
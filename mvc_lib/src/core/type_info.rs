@@ -1,4 +1,4 @@
-use std::any::TypeId;
+use std::any::{Any, TypeId};
 use std::borrow::Cow;
 use std::fmt;
 use std::rc::Rc;
@@ -10,12 +10,14 @@ pub struct TypeInfo {
     pub type_id: TypeId,
     // the name of the type
     pub type_name: Cow<'static, str>,
+    // clones a Box<dyn Any> holding a value of the type, for types created with rc_of.
+    pub rc_clone: Option<fn(&dyn Any) -> Option<Box<dyn Any>>>,
 }
 
 impl TypeInfo {
     // create a new type info struct from a type id and type name.
     pub fn new(type_id: TypeId, type_name: Cow<'static, str>) -> Self {
-        Self { type_id: type_id, type_name: type_name }
+        Self { type_id: type_id, type_name: type_name, rc_clone: None }
     }
 
     // create a new type info struct from a type.
@@ -25,7 +27,16 @@ impl TypeInfo {
 
     // create a new type info struct from a type and wrap it in a Rc.
     pub fn rc_of<T: 'static + ?Sized>() -> Box<Self> {
-        Box::new(Self::of::<Rc<T>>())
+        let mut type_info = Self::of::<Rc<T>>();
+        type_info.rc_clone = Some(|x| x.downcast_ref::<Rc<T>>().map(|x| Box::new(x.clone()) as Box<dyn Any>));
+        Box::new(type_info)
+    }
+
+    // clone a value of the type by cloning the Rc holding it.
+    // instance: the value to clone, which has to be of the type.
+    // returns: the clone, or None if the type was not created with rc_of or the value is of another type.
+    pub fn try_clone_rc(&self, instance: &dyn Any) -> Option<Box<dyn Any>> {
+        self.rc_clone.and_then(|rc_clone| rc_clone(instance))
     }
 
     // returns true if the type info is the same as the type info of the type.
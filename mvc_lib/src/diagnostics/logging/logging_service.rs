@@ -2,9 +2,12 @@ use std::any::Any;
 use std::error::Error;
 use std::rc::Rc;
 
+use crate::core::type_info::TypeInfo;
 use crate::options::logging_services_options::ILogHttpRequestsOptions;
 
-use crate::services::service_collection::{ IServiceCollection, ServiceCollectionExtensions };
+use crate::services::service_collection::{ IServiceCollection, ServiceCollection, ServiceCollectionExtensions };
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
 
 use super::ilogger::ILogger;
 use super::iloggersink::ILoggerSink;
@@ -20,21 +23,51 @@ pub struct LoggingService {
 }
 
 impl LoggingService {
+    // options: the options for the service.
+    // sinks: the sinks to send log messages to, or None to log to the console and to "log.txt".
     pub fn new(
-        options: Option<Rc<dyn ILogHttpRequestsOptions>>
+        options: Option<Rc<dyn ILogHttpRequestsOptions>>,
+        sinks: Option<Vec<Rc<dyn ILoggerSink>>>,
     ) -> Self {
         Self { 
             _options: options,
-            logger: Rc::new(super::logger::Logger::new(None)),
+            logger: Rc::new(super::logger::Logger::new(sinks)),
         }
     }
 
     pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        let sinks = Self::load_sinks(services);
         vec![Box::new(Rc::new(Self::new(
             ServiceCollectionExtensions::try_get_single::<dyn ILogHttpRequestsOptions>(services).expect("could not get options"),
+            if sinks.len() > 0 { Some(sinks) } else { None },
         )) as Rc<dyn ILoggingService>)]
     }
 
+    // adds a logger sink to the given service collection, keyed by its id.
+    // adding another sink with the same id replaces the first.
+    // services: the service collection.
+    // id: the id of the sink, such as "console".
+    // new_sink: creates the sink.
+    pub fn add_sink(services: &mut ServiceCollection, id: &str, new_sink: fn() -> Rc<dyn ILoggerSink>) {
+        services.add_keyed(id, ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn ILoggerSink>(), move |_| vec![Box::new(new_sink()) as Box<dyn Any>], ServiceScope::Singleton));
+    }
+
+    // gets the logger sink added with the given id.
+    // services: the service collection.
+    // id: the id of the sink.
+    // returns: the sink, or None if no sink was added with that id.
+    pub fn try_get_sink(services: &dyn IServiceCollection, id: &str) -> Option<Rc<dyn ILoggerSink>> {
+        ServiceCollectionExtensions::try_get_keyed::<dyn ILoggerSink>(services, id)
+    }
+
+    // get the sinks added with their id as the key.
+    fn load_sinks(services: &dyn IServiceCollection) -> Vec<Rc<dyn ILoggerSink>> {
+        ServiceCollectionExtensions::get_keys::<dyn ILoggerSink>(services)
+            .iter()
+            .map(|id| ServiceCollectionExtensions::get_required_keyed::<dyn ILoggerSink>(services, id))
+            .collect()
+    }
+
     pub fn get_service(services: &dyn IServiceCollection) -> Rc<dyn ILoggingService> {
         ServiceCollectionExtensions::get_required_single::<dyn ILoggingService>(services)
     }
@@ -1,7 +1,14 @@
+use std::any::Any;
 use std::cell::RefCell;
+use std::rc::Rc;
 
 use uuid::Timestamp;
 
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
 use super::iperformance_logger::{PerformanceMetricValue, IPerformanceLogger, PerformanceMetricType};
 
 
@@ -16,6 +23,14 @@ impl InMemoryLogger {
             metrics: RefCell::new(Vec::new()),
         }
     }
+
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IPerformanceLogger>)]
+    }
+
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IPerformanceLogger>(), Self::new_service, ServiceScope::Singleton));
+    }
 }
 
 impl IPerformanceLogger for InMemoryLogger {
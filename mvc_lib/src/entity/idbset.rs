@@ -1,4 +1,6 @@
 use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
 
 use crate::core::type_info::TypeInfo;
 
@@ -27,7 +29,8 @@ pub trait IDbSetAny {
     // get the type name for the database set entity type
     fn entity_type_name(self: &Self) -> &'static str;
     // save changes of database set to underlying data store
-    fn save_changes(self: &Self);
+    // returns: an error if the changes could not be saved.
+    fn save_changes(self: &Self) -> Result<(), Rc<dyn Error>>;
 }
 
 // this trait represents a database set of entities. it is used to store and retrieve rows of data.
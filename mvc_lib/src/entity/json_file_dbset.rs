@@ -1,4 +1,6 @@
+use std::error::Error;
 use std::path::Path;
+use std::rc::Rc;
 use std::{any::Any, io::Write};
 use std::cell::RefCell;
 use std::fs::File;
@@ -42,11 +44,9 @@ impl <TEntity: 'static + Clone> JsonFileDbSet<TEntity> {
             items_json: RefCell::new(vec![]),
         };
 
-        let metadata = f.metadata().unwrap();
-        if metadata.len() < 4096 {
-            // cache since it is not that much text
-            my_self.cache_file_to_memory(Some(f));
-        }
+        // the rows are kept in memory, so the whole file is read even when it is large.
+        // saving a set that was never read would replace the rows in the file with none.
+        my_self.cache_file_to_memory(Some(f));
 
         my_self
     }
@@ -64,7 +64,10 @@ impl <TEntity: 'static + Clone> JsonFileDbSet<TEntity> {
                 std::io::Result::Ok(Self::new(file_path, f, factory_method, parse_item_method, jsonify_item_method))
             },
             Ok(false) => {
-                let f = File::create(file_path.clone())?;
+                // a new file starts with no rows, so it can be read like any other.
+                let mut f = File::create(file_path.clone())?;
+                f.write_all(serde_json::json!({ "rows": [] }).to_string().as_bytes())?;
+                let f = File::open(file_path.clone())?;
                 std::io::Result::Ok(Self::new(file_path, f, factory_method, parse_item_method, jsonify_item_method))
             },
             Err(e) => {
@@ -74,11 +77,9 @@ impl <TEntity: 'static + Clone> JsonFileDbSet<TEntity> {
     }
 
     // read the json file and return the items as a Vec<serde_json::Value>
+    // the file is read again, since another thread or program may have saved changes to it.
     fn read(&self) -> <Vec<serde_json::Value> as IntoIterator>::IntoIter {
-        let cached_len = self.items_json.borrow().len();
-        if cached_len > 0 {
-            self.cache_to_memory();
-        }
+        self.cache_to_memory();
         self.items_json.borrow().clone().into_iter()
     }
 
@@ -145,14 +146,16 @@ impl <TEntity: 'static + Clone> JsonFileDbSet<TEntity> {
         }
     }
 
-    fn write_to_file(self: &Self) {
-        let mut file = File::create(self.file_path.clone()).unwrap();
+    // write the items to the json file, replacing the rows in it.
+    // returns: an error if the file could not be written.
+    fn write_to_file(self: &Self) -> std::io::Result<()> {
+        let mut file = File::create(self.file_path.clone())?;
         let json = serde_json::json!({
             "rows": self.items_json.borrow().clone()
         });
-        let json_str = serde_json::to_string_pretty(&json).unwrap();
-        file.write_all(json_str.as_bytes()).unwrap();
-        file.flush().unwrap();
+        let json_str = serde_json::to_string_pretty(&json)?;
+        file.write_all(json_str.as_bytes())?;
+        file.flush()
     }
 }
 
@@ -208,8 +211,8 @@ impl<TEntity: 'static + Clone + PartialEq> IDbSetAny for JsonFileDbSet<TEntity>
         IDbSet::entity_type_name(self)
     }
 
-    fn save_changes(self: &Self) {
-        self.write_to_file();
+    fn save_changes(self: &Self) -> Result<(), Rc<dyn Error>> {
+        self.write_to_file().map_err(|e| Rc::new(e) as Rc<dyn Error>)
     }
 }
 
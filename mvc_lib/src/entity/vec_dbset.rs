@@ -1,4 +1,6 @@
 use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
 
 use crate::core::type_info::TypeInfo;
 
@@ -39,7 +41,8 @@ impl<T> IDbSet<T> for VecDbSet<T> where T: 'static + Clone + PartialEq {
     }
 
     fn find(self: &Self) -> Vec<T> {
-        vec![]
+        // the entities are only kept in memory, so there is nothing newer to read.
+        self.entities.borrow().clone()
     }
 
     fn get_all(self: &Self) -> Vec<T> {
@@ -85,11 +88,11 @@ impl<T> IDbSetAny for VecDbSet<T> where T: 'static + Clone + PartialEq {
     }
 
     fn find_any(self: &Self) -> Vec<Box<dyn std::any::Any>> {
-        todo!()
+        self.get_all_any()
     }
 
     fn get_all_any(self: &Self) -> Vec<Box<dyn std::any::Any>> {
-        todo!()
+        self.entities.borrow().iter().map(|x| Box::new(x.clone()) as Box<dyn std::any::Any>).collect()
     }
 
     fn remove_any(self: &Self, item: Box<dyn std::any::Any>) {
@@ -112,7 +115,7 @@ impl<T> IDbSetAny for VecDbSet<T> where T: 'static + Clone + PartialEq {
         nameof::name_of_type!(T)
     }
 
-    fn save_changes(self: &Self) {
-        
+    fn save_changes(self: &Self) -> Result<(), Rc<dyn Error>> {
+        Ok(())
     }
 }
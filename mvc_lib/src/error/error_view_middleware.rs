@@ -6,6 +6,7 @@ use std::rc::Rc;
 use crate::core::type_info::TypeInfo;
 
 use crate::action_results::view_result::ViewResult;
+use crate::errors::AuthorizationError;
 
 use crate::services::service_collection::{ServiceCollection, ServiceCollectionExtensions};
 use crate::services::service_descriptor::ServiceDescriptor;
@@ -63,7 +64,12 @@ impl IRequestMiddlewareService for ErrorViewMiddleware {
                 Ok(result) => Ok(result),
                 Err(error) => {
                     self.error_handler_service.handle_error(error.clone(), Some(request_context), Some(response_context))?;
-                    response_context.set_action_result(Some(Rc::new(ViewResult::new("/shared/error".to_string(), self.error_viewmodel_service.create_error_viewmodel(error)))));
+                    let mut view_result = ViewResult::new("/shared/error".to_string(), self.error_viewmodel_service.create_error_viewmodel(error.clone()));
+                    // a request that was not authorized keeps its 401 or 403 status code.
+                    if let Some(status_code) = AuthorizationError::get_status_code(error.as_ref()) {
+                        view_result = view_result.with_status_code(status_code);
+                    }
+                    response_context.set_action_result(Some(Rc::new(view_result)));
                     Ok(MiddlewareResult::OkBreak)
                 },
             }
@@ -3,10 +3,13 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::rc::Rc;
 
+use http::StatusCode;
+
 use core_macro_lib::{IModel, IViewModel, reflect_attributes, reflect_properties};
 use core_macro_lib::IHazAttributes;
 use core_macro_lib::reflect_methods;
 use crate::core::type_info::TypeInfo;
+use crate::errors::AuthorizationError;
 
 use crate::model_binder::imodel::IModel;
 use crate::model_binder::iviewmodel::IViewModel;
@@ -68,3 +71,10 @@ impl BasicErrorViewModel {
         }
     }
 }
+
+impl BasicErrorViewModel {
+    // get the status code of the error, such as 401 or 403 for a request that was not authorized, or 500 otherwise.
+    pub fn get_status_code(self: &Self) -> StatusCode {
+        AuthorizationError::get_status_code(self.error.as_ref()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
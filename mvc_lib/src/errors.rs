@@ -1,6 +1,10 @@
 use std::error::Error;
 use std::fmt;
 
+use http::StatusCode;
+
+use crate::services::authorization_service::AuthRejectionReason;
+
 
 // this struct represents an error that occurs when a HTTP request is made.
 #[derive(Debug)]
@@ -12,3 +16,178 @@ impl fmt::Display for RequestError {
     }
 }
 
+
+// this struct represents a request that is malformed or breaks one of the limits of the HTTP layer.
+// the status code, usually a 4xx client error, is sent back to the client before the connection is closed.
+#[derive(Debug, Clone)]
+pub struct BadRequestError {
+    pub status_code: http::StatusCode,
+    pub message: String,
+}
+
+impl BadRequestError {
+    pub fn new(status_code: http::StatusCode, message: String) -> Self {
+        Self { status_code: status_code, message: message }
+    }
+
+    // create an IO error that carries the bad request, so it can be returned from reads and parsing.
+    pub fn to_io_error(self: &Self) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, self.clone())
+    }
+
+    // get the bad request carried by an IO error, if there is one.
+    pub fn from_io_error(error: &std::io::Error) -> Option<&BadRequestError> {
+        error.get_ref().and_then(|x| x.downcast_ref::<BadRequestError>())
+    }
+
+    // get the bad request an error is, or carries if it is an IO error.
+    pub fn from_error<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a BadRequestError> {
+        match error.downcast_ref::<BadRequestError>() {
+            Some(bad_request) => Some(bad_request),
+            None => error.downcast_ref::<std::io::Error>().and_then(|x| Self::from_io_error(x)),
+        }
+    }
+}
+
+impl Error for BadRequestError {}
+impl fmt::Display for BadRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.status_code, self.message)
+    }
+}
+
+
+// this struct represents a configuration value that could not be loaded or could not be bound to an option.
+#[derive(Debug, Clone)]
+pub struct ConfigurationError {
+    // the full key of the value, such as "http:port", or the source that could not be loaded.
+    pub key: String,
+    pub message: String,
+}
+
+impl ConfigurationError {
+    pub fn new(key: String, message: String) -> Self {
+        Self { key: key, message: message }
+    }
+}
+
+impl Error for ConfigurationError {}
+impl fmt::Display for ConfigurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid configuration for {}: {}", self.key, self.message)
+    }
+}
+
+
+// this struct represents a route pattern that could not be parsed, such as /users/{id:range(1)}.
+#[derive(Debug, Clone)]
+pub struct RoutePatternError {
+    // the route pattern that could not be parsed.
+    pub pattern: String,
+    pub message: String,
+}
+
+impl RoutePatternError {
+    pub fn new(pattern: String, message: String) -> Self {
+        Self { pattern: pattern, message: message }
+    }
+}
+
+impl Error for RoutePatternError {}
+impl fmt::Display for RoutePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid route pattern {}: {}", self.pattern, self.message)
+    }
+}
+
+// this struct represents a url that could not be generated, such as when no route matches
+// the route values or a required capture group has no value.
+#[derive(Debug, Clone)]
+pub struct UrlGenerationError {
+    // the route pattern or route name the url was being generated for.
+    pub route: String,
+    pub message: String,
+}
+
+impl UrlGenerationError {
+    pub fn new(route: String, message: String) -> Self {
+        Self { route: route, message: message }
+    }
+}
+
+impl Error for UrlGenerationError {}
+impl fmt::Display for UrlGenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "could not generate url for {}: {}", self.route, self.message)
+    }
+}
+
+
+// this struct represents an authentication ticket or token that was rejected, such as when it was
+// tampered with, could not be read or has expired.
+#[derive(Debug, Clone)]
+pub struct AuthenticationError {
+    // the authentication scheme that rejected the ticket, such as "Cookies".
+    pub scheme: String,
+    pub message: String,
+}
+
+impl AuthenticationError {
+    pub fn new(scheme: String, message: String) -> Self {
+        Self { scheme: scheme, message: message }
+    }
+}
+
+impl Error for AuthenticationError {}
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} authentication failed: {}", self.scheme, self.message)
+    }
+}
+
+
+// this struct represents a change to a user that was not allowed, such as a password that is too short,
+// or a login that failed because the password did not match or the user is locked out.
+#[derive(Debug, Clone)]
+pub struct IdentityError {
+    pub message: String,
+}
+
+impl IdentityError {
+    pub fn new(message: String) -> Self {
+        Self { message: message }
+    }
+}
+
+impl Error for IdentityError {}
+impl fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+
+// this struct represents a request that was not authorized, which is answered with 401 if it was not
+// authenticated and 403 if the user is not allowed.
+#[derive(Debug, Clone)]
+pub struct AuthorizationError {
+    pub reason: AuthRejectionReason,
+}
+
+impl AuthorizationError {
+    pub fn new(reason: AuthRejectionReason) -> Self {
+        Self { reason: reason }
+    }
+
+    // get the status code of an error if it is an AuthorizationError.
+    pub fn get_status_code(error: &(dyn Error + 'static)) -> Option<StatusCode> {
+        error.downcast_ref::<AuthorizationError>().map(|x| x.reason.get_status_code())
+    }
+}
+
+impl Error for AuthorizationError {}
+impl fmt::Display for AuthorizationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.reason.get_status_code(), self.reason)
+    }
+}
@@ -0,0 +1,64 @@
+// this struct is one range of bytes requested with a "Range: bytes=..." header.
+// both ends are included, as in the Content-Range header.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    // the most ranges served from one request. requests for more are served the whole body instead,
+    // so a request cannot make the server send the same bytes many times.
+    pub const MAX_RANGES: usize = 16;
+
+    pub fn new(start: u64, end: u64) -> Self {
+        Self { start: start, end: end }
+    }
+
+    // get the number of bytes in the range.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    // get the value of the Content-Range header for the range.
+    // total_length: the length of the whole body.
+    pub fn to_content_range(&self, total_length: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, total_length)
+    }
+
+    // parse the ranges in a Range header.
+    // header: the value of the Range header.
+    // total_length: the length of the whole body.
+    // returns: None if the header is not a valid byte range header and should be ignored,
+    // otherwise the ranges that overlap the body, which is empty if none of them do.
+    pub fn parse(header: &str, total_length: u64) -> Option<Vec<ByteRange>> {
+        let ranges = header.trim().strip_prefix("bytes=")?;
+        let specs: Vec<&str> = ranges.split(',').map(|x| x.trim()).filter(|x| x.len() > 0).collect();
+        if specs.len() == 0 || specs.len() > Self::MAX_RANGES {
+            return None;
+        }
+
+        let mut satisfiable = vec![];
+        for spec in specs {
+            let (start, end) = spec.split_once('-')?;
+            let (start, end) = (start.trim(), end.trim());
+            if start.len() == 0 {
+                // a suffix range, which is the last bytes of the body.
+                let suffix_length = end.parse::<u64>().ok()?;
+                if suffix_length > 0 && total_length > 0 {
+                    satisfiable.push(ByteRange::new(total_length.saturating_sub(suffix_length), total_length - 1));
+                }
+            } else {
+                let start = start.parse::<u64>().ok()?;
+                let end = if end.len() == 0 { None } else { Some(end.parse::<u64>().ok()?) };
+                if end.map_or(false, |x| x < start) {
+                    return None;
+                }
+                if start < total_length {
+                    satisfiable.push(ByteRange::new(start, end.map_or(total_length - 1, |x| x.min(total_length - 1))));
+                }
+            }
+        }
+        Some(satisfiable)
+    }
+}
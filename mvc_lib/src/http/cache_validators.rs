@@ -0,0 +1,83 @@
+use std::fs::Metadata;
+use std::time::UNIX_EPOCH;
+
+use chrono::{DateTime, TimeZone, Utc};
+use http::HeaderMap;
+
+
+// this struct holds the validators of a response, which let clients and caches ask if their copy is still current.
+#[derive(Clone, Debug)]
+pub struct CacheValidators {
+    // the strong entity tag of the response, including its quotes.
+    pub etag: String,
+    // when the response last changed, to the second.
+    pub last_modified: DateTime<Utc>,
+}
+
+impl CacheValidators {
+    pub fn new(etag: String, last_modified: DateTime<Utc>) -> Self {
+        Self { etag: etag, last_modified: last_modified }
+    }
+
+    // create the validators of a file from its length and modified time, which change whenever the file does.
+    // metadata: the metadata of the file.
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        let modified = metadata.modified().ok()
+            .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        let etag = format!("\"{:x}-{:x}\"", modified.as_nanos(), metadata.len());
+        let last_modified = Utc.timestamp_opt(modified.as_secs() as i64, 0).single().unwrap_or_default();
+        Self::new(etag, last_modified)
+    }
+
+    // get the value of the Last-Modified header.
+    pub fn get_last_modified_string(&self) -> String {
+        Self::format_http_date(&self.last_modified)
+    }
+
+    // returns true if the client's copy is current according to If-None-Match, or If-Modified-Since when there is no If-None-Match.
+    // request_headers: the headers of the request.
+    pub fn is_not_modified(&self, request_headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = request_headers.get(http::header::IF_NONE_MATCH).and_then(|x| x.to_str().ok()) {
+            return Self::etag_list_matches(if_none_match, &self.etag, true);
+        }
+        match request_headers.get(http::header::IF_MODIFIED_SINCE).and_then(|x| x.to_str().ok()).and_then(Self::parse_http_date) {
+            Some(if_modified_since) => self.last_modified <= if_modified_since,
+            None => false,
+        }
+    }
+
+    // returns true if a Range request can be served as a partial response, because If-Range is missing or still matches.
+    // request_headers: the headers of the request.
+    pub fn is_range_current(&self, request_headers: &HeaderMap) -> bool {
+        match request_headers.get(http::header::IF_RANGE).and_then(|x| x.to_str().ok()) {
+            None => true,
+            // If-Range only accepts strong entity tags and exact dates, since the parts have to come from the same body.
+            Some(if_range) if if_range.trim().starts_with('"') || if_range.trim().starts_with("W/") => {
+                if_range.trim() == self.etag
+            },
+            Some(if_range) => Self::parse_http_date(if_range) == Some(self.last_modified),
+        }
+    }
+
+    // returns true if a list of entity tags, such as an If-None-Match header, contains an entity tag.
+    // etag_list: the comma separated entity tags, or "*" to match any.
+    // etag: the entity tag to look for.
+    // weak: whether weak entity tags match their strong versions.
+    pub fn etag_list_matches(etag_list: &str, etag: &str, weak: bool) -> bool {
+        let strip_weak = |x: &str| -> String { if weak { x.trim_start_matches("W/").to_string() } else { x.to_string() } };
+        let etag = strip_weak(etag);
+        etag_list.split(',').map(|x| x.trim()).any(|x| x == "*" || strip_weak(x) == etag)
+    }
+
+    // format a date as an HTTP date, such as "Sun, 06 Nov 1994 08:49:37 GMT".
+    pub fn format_http_date(date: &DateTime<Utc>) -> String {
+        date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+    }
+
+    // parse an HTTP date, such as the value of an If-Modified-Since header.
+    // returns: the date, or None if it is not a valid date.
+    pub fn parse_http_date(date: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc2822(date.trim()).ok().map(|x| x.with_timezone(&Utc))
+    }
+}
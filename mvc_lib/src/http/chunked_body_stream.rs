@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::core::itcp_stream_wrapper::ITcpStreamWrapper;
+use crate::core::type_info::TypeInfo;
+
+use super::http_body_content::ContentType;
+use super::ihttp_body_stream_format::IHttpBodyStreamFormat;
+
+
+// this struct wraps a stream that uses "Transfer-Encoding: chunked".
+// reading returns the data of the chunks and stops after the last chunk, so the next request on the connection is not read.
+// writing sends each call as one chunk, and writing nothing sends the last chunk that ends the body.
+pub struct ChunkedBodyStream {
+    inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+    // the number of bytes left to read in the current chunk.
+    chunk_remaining: RefCell<usize>,
+    // whether the last chunk and the trailers have been read.
+    finished: RefCell<bool>,
+}
+
+impl ChunkedBodyStream {
+    // create a new chunked stream.
+    // inner_stream: the stream the chunks are read from or written to.
+    pub fn new(inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>) -> Self {
+        Self {
+            inner_stream: inner_stream,
+            chunk_remaining: RefCell::new(0),
+            finished: RefCell::new(false),
+        }
+    }
+
+    // returns true if the last chunk of the body has been read.
+    pub fn is_finished(&self) -> bool {
+        *self.finished.borrow()
+    }
+
+    // parse the size line that starts a chunk, ignoring any chunk extensions.
+    // line: the size line without the trailing \r\n.
+    // returns: the size of the chunk in bytes.
+    pub fn parse_chunk_size(line: &str) -> std::io::Result<usize> {
+        let size = line.split(';').next().unwrap_or("").trim();
+        usize::from_str_radix(size, 16)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("invalid chunk size: {:?}", line)))
+    }
+
+    // read the size line of the next chunk, and the trailers if it is the last chunk.
+    fn begin_chunk(&self) -> std::io::Result<()> {
+        let size = Self::parse_chunk_size(&self.inner_stream.borrow().read_line()?)?;
+        if size == 0 {
+            // the trailers end with an empty line. they are not used, so they are discarded.
+            loop {
+                if self.inner_stream.borrow().read_line()?.len() == 0 {
+                    break;
+                }
+            }
+            self.finished.replace(true);
+        } else {
+            self.chunk_remaining.replace(size);
+        }
+        Ok(())
+    }
+}
+
+impl ITcpStreamWrapper for ChunkedBodyStream {
+    fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
+        self.inner_stream.borrow().shutdown(how)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        self.inner_stream.borrow().flush()
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        if b.len() == 0 {
+            return Ok(0);
+        }
+        if *self.chunk_remaining.borrow() == 0 {
+            if self.is_finished() {
+                return Ok(0);
+            }
+            self.begin_chunk()?;
+            if self.is_finished() {
+                return Ok(0);
+            }
+        }
+
+        let remaining = *self.chunk_remaining.borrow();
+        let max_read = std::cmp::min(remaining, b.len());
+        let n = self.inner_stream.borrow().read(&mut b[..max_read])?;
+        if n == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "connection closed in the middle of a chunk"));
+        }
+        self.chunk_remaining.replace(remaining - n);
+
+        if remaining == n {
+            // the data of each chunk is followed by \r\n
+            if self.inner_stream.borrow().read_line()?.len() != 0 {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "chunk data is longer than the chunk size"));
+            }
+        }
+        Ok(n)
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n or the end of the body
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, b: &[u8]) -> std::io::Result<usize> {
+        let inner_stream = self.inner_stream.borrow();
+        inner_stream.write(format!("{:x}\r\n", b.len()).as_bytes())?;
+        if b.len() > 0 {
+            inner_stream.write(b)?;
+            inner_stream.write(b"\r\n")?;
+        } else {
+            // an empty chunk is the last chunk, and it is followed by the (empty) trailers.
+            inner_stream.write(b"\r\n")?;
+        }
+        Ok(b.len())
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        self.write(format!("{}\r\n", b).as_bytes())
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        self.inner_stream.borrow().remote_addr()
+    }
+}
+
+
+// this struct is the body format for "Transfer-Encoding: chunked".
+// the transfer coding is resolved the same way as a content type, using the "chunked" token.
+pub struct ChunkedBodyStreamFormat {
+
+}
+
+impl ChunkedBodyStreamFormat {
+    pub fn new() -> Self {
+        Self {
+
+        }
+    }
+}
+
+impl IHttpBodyStreamFormat for ChunkedBodyStreamFormat {
+    fn matches_content_type(&self, content_type: &ContentType) -> bool {
+        content_type.mime_type == "chunked"
+    }
+
+    fn decode(&self, stream: Rc<RefCell<dyn ITcpStreamWrapper>>, _content_type: &ContentType) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
+        Rc::new(RefCell::new(ChunkedBodyStream::new(stream)))
+    }
+
+    fn encode(self: &Self, stream: Rc<RefCell<dyn ITcpStreamWrapper>>, _content_type: &ContentType) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
+        Rc::new(RefCell::new(ChunkedBodyStream::new(stream)))
+    }
+
+    fn type_info(self: &Self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<Self>())
+    }
+}
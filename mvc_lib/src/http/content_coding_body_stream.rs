@@ -0,0 +1,258 @@
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+
+use crate::core::itcp_stream_wrapper::ITcpStreamWrapper;
+use crate::core::type_info::TypeInfo;
+
+use super::http_body_content::ContentType;
+use super::ihttp_body_stream_format::IHttpBodyStreamFormat;
+
+
+// the content codings that request and response bodies can be compressed with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentCoding {
+    Gzip,
+    Deflate,
+    Brotli,
+}
+
+impl ContentCoding {
+    // the content codings in the order the server prefers them when the client accepts more than one equally.
+    pub const PREFERRED: [ContentCoding; 3] = [ContentCoding::Brotli, ContentCoding::Gzip, ContentCoding::Deflate];
+
+    // parse a content coding from a Content-Encoding or Accept-Encoding token.
+    // token: the content coding token, such as "gzip" or "br".
+    // returns: the content coding, or None if it is not supported.
+    pub fn parse(token: &str) -> Option<Self> {
+        match token.trim().to_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(ContentCoding::Gzip),
+            "deflate" => Some(ContentCoding::Deflate),
+            "br" => Some(ContentCoding::Brotli),
+            _ => None,
+        }
+    }
+
+    // get the token used for the content coding in headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentCoding::Gzip => "gzip",
+            ContentCoding::Deflate => "deflate",
+            ContentCoding::Brotli => "br",
+        }
+    }
+}
+
+// this struct lets the std::io compression readers and writers use an ITcpStreamWrapper.
+struct StreamAdapter {
+    stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+}
+
+impl Read for StreamAdapter {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stream.borrow().read(buf)
+    }
+}
+
+impl Write for StreamAdapter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stream.borrow().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.borrow().flush()
+    }
+}
+
+// the compressor used when writing to a ContentCodingBodyStream.
+enum ContentEncoder {
+    Gzip(GzEncoder<StreamAdapter>),
+    Deflate(ZlibEncoder<StreamAdapter>),
+    Brotli(brotli::CompressorWriter<StreamAdapter>),
+}
+
+impl ContentEncoder {
+    fn new(content_coding: ContentCoding, stream: StreamAdapter) -> Self {
+        match content_coding {
+            ContentCoding::Gzip => ContentEncoder::Gzip(GzEncoder::new(stream, Compression::default())),
+            ContentCoding::Deflate => ContentEncoder::Deflate(ZlibEncoder::new(stream, Compression::default())),
+            // quality 5 and a 4 MiB window is a common trade off between speed and size for dynamic content.
+            ContentCoding::Brotli => ContentEncoder::Brotli(brotli::CompressorWriter::new(stream, 4096, 5, 22)),
+        }
+    }
+
+    fn write_all(&mut self, b: &[u8]) -> std::io::Result<()> {
+        match self {
+            ContentEncoder::Gzip(encoder) => encoder.write_all(b),
+            ContentEncoder::Deflate(encoder) => encoder.write_all(b),
+            ContentEncoder::Brotli(encoder) => encoder.write_all(b),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ContentEncoder::Gzip(encoder) => encoder.flush(),
+            ContentEncoder::Deflate(encoder) => encoder.flush(),
+            ContentEncoder::Brotli(encoder) => encoder.flush(),
+        }
+    }
+
+    // write the end of the compressed data.
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            ContentEncoder::Gzip(encoder) => encoder.finish().map(|_| ()),
+            ContentEncoder::Deflate(encoder) => encoder.finish().map(|_| ()),
+            ContentEncoder::Brotli(encoder) => {
+                encoder.into_inner();
+                Ok(())
+            },
+        }
+    }
+}
+
+// this struct wraps a stream that is compressed with a content coding.
+// reading returns the decompressed data of the inner stream.
+// writing compresses the data into the inner stream, and writing nothing ends the compressed data.
+pub struct ContentCodingBodyStream {
+    inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>,
+    content_coding: ContentCoding,
+    // created on the first read.
+    decoder: RefCell<Option<Box<dyn Read>>>,
+    // created on the first write, and removed when the compressed data ends.
+    encoder: RefCell<Option<ContentEncoder>>,
+    // whether the compressed data written to the stream has ended.
+    finished: RefCell<bool>,
+}
+
+impl ContentCodingBodyStream {
+    // create a new stream for a content coding.
+    // inner_stream: the stream with the compressed data.
+    // content_coding: how the data is compressed.
+    pub fn new(inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>, content_coding: ContentCoding) -> Self {
+        Self {
+            inner_stream: inner_stream,
+            content_coding: content_coding,
+            decoder: RefCell::new(None),
+            encoder: RefCell::new(None),
+            finished: RefCell::new(false),
+        }
+    }
+
+    pub fn get_content_coding(&self) -> ContentCoding {
+        self.content_coding
+    }
+
+    fn new_adapter(&self) -> StreamAdapter {
+        StreamAdapter { stream: self.inner_stream.clone() }
+    }
+}
+
+impl ITcpStreamWrapper for ContentCodingBodyStream {
+    fn shutdown(&self, how: std::net::Shutdown) -> std::io::Result<()> {
+        self.inner_stream.borrow().shutdown(how)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        if let Some(encoder) = self.encoder.borrow_mut().as_mut() {
+            encoder.flush()?;
+        }
+        self.inner_stream.borrow().flush()
+    }
+
+    fn read(&self, b: &mut [u8]) -> std::io::Result<usize> {
+        let mut decoder = self.decoder.borrow_mut();
+        if decoder.is_none() {
+            let adapter = self.new_adapter();
+            decoder.replace(match self.content_coding {
+                ContentCoding::Gzip => Box::new(GzDecoder::new(adapter)) as Box<dyn Read>,
+                ContentCoding::Deflate => Box::new(ZlibDecoder::new(adapter)),
+                ContentCoding::Brotli => Box::new(brotli::Decompressor::new(adapter, 4096)),
+            });
+        }
+        decoder.as_mut().unwrap().read(b)
+    }
+
+    fn read_line(&self) -> std::io::Result<String> {
+        // read until \r\n or the end of the body
+        let mut s: Vec<u8> = vec![];
+        loop {
+            let mut buf = [0; 1];
+            if self.read(&mut buf)? == 0 {
+                break;
+            } else if buf[0] == b'\n' && s.last() == Some(&b'\r') {
+                s.pop();
+                break;
+            } else {
+                s.push(buf[0]);
+            }
+        }
+        Ok(String::from_utf8_lossy(&s).to_string())
+    }
+
+    fn write(&self, b: &[u8]) -> std::io::Result<usize> {
+        if *self.finished.borrow() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "cannot write after the compressed data has ended"));
+        }
+
+        let mut encoder = self.encoder.borrow_mut();
+        if encoder.is_none() {
+            encoder.replace(ContentEncoder::new(self.content_coding, self.new_adapter()));
+        }
+
+        if b.len() > 0 {
+            encoder.as_mut().unwrap().write_all(b)?;
+        } else {
+            self.finished.replace(true);
+            encoder.take().unwrap().finish()?;
+        }
+        Ok(b.len())
+    }
+
+    fn write_line(&self, b: &String) -> std::io::Result<usize> {
+        self.write(format!("{}\r\n", b).as_bytes())
+    }
+
+    fn remote_addr(&self) -> std::net::SocketAddr {
+        self.inner_stream.borrow().remote_addr()
+    }
+}
+
+
+// this struct is the body format for a content coding, such as "Content-Encoding: gzip".
+pub struct ContentCodingBodyStreamFormat {
+    content_coding: ContentCoding,
+}
+
+impl ContentCodingBodyStreamFormat {
+    pub fn new(content_coding: ContentCoding) -> Self {
+        Self {
+            content_coding: content_coding,
+        }
+    }
+
+    pub fn get_content_coding(&self) -> ContentCoding {
+        self.content_coding
+    }
+}
+
+impl IHttpBodyStreamFormat for ContentCodingBodyStreamFormat {
+    fn matches_content_type(&self, content_type: &ContentType) -> bool {
+        ContentCoding::parse(&content_type.mime_type) == Some(self.content_coding)
+    }
+
+    fn decode(&self, stream: Rc<RefCell<dyn ITcpStreamWrapper>>, _content_type: &ContentType) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
+        Rc::new(RefCell::new(ContentCodingBodyStream::new(stream, self.content_coding)))
+    }
+
+    fn encode(self: &Self, stream: Rc<RefCell<dyn ITcpStreamWrapper>>, _content_type: &ContentType) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
+        Rc::new(RefCell::new(ContentCodingBodyStream::new(stream, self.content_coding)))
+    }
+
+    fn type_info(self: &Self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<Self>())
+    }
+}
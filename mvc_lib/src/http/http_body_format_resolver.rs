@@ -6,7 +6,8 @@ use crate::services::service_descriptor::ServiceDescriptor;
 
 use super::ihttp_body_format_resolver::IHttpBodyFormatResolver;
 use super::ihttp_body_stream_format::IHttpBodyStreamFormat;
-use super::request_decoder_middleware::GzipBodyStreamFormat;
+use super::chunked_body_stream::ChunkedBodyStreamFormat;
+use super::content_coding_body_stream::{ContentCoding, ContentCodingBodyStreamFormat};
 use super::http_body_content::ContentType;
 
 
@@ -42,18 +43,18 @@ impl HttpBodyFormatResolver {
 
 impl IHttpBodyFormatResolver for HttpBodyFormatResolver {
     fn resolve(&self, content_type: &ContentType) -> Option<Rc<dyn IHttpBodyStreamFormat>> {
-        match content_type.mime_type.as_str() {
-            "application/gzip" | "gzip" => {
-                Some(Rc::new(GzipBodyStreamFormat::new()) as Rc<dyn IHttpBodyStreamFormat>)
-            },
-            _ => {
-                for format in &self.formats {
-                    if format.matches_content_type(&content_type) {
-                        return Some(format.clone());
-                    }
-                }
-                None
+        if content_type.mime_type == "chunked" {
+            return Some(Rc::new(ChunkedBodyStreamFormat::new()) as Rc<dyn IHttpBodyStreamFormat>);
+        }
+        if let Some(content_coding) = ContentCoding::parse(&content_type.mime_type) {
+            return Some(Rc::new(ContentCodingBodyStreamFormat::new(content_coding)) as Rc<dyn IHttpBodyStreamFormat>);
+        }
+
+        for format in &self.formats {
+            if format.matches_content_type(&content_type) {
+                return Some(format.clone());
             }
         }
+        None
     }
 }
\ No newline at end of file
@@ -1,12 +1,8 @@
-use std::any::Any;
 use std::rc::Rc;
 
+use core_macro_lib::injectable;
 
 use crate::http::http_body_content::ContentType;
-use crate::services::service_collection::IServiceCollection;
-use crate::services::service_collection::ServiceCollection;
-use crate::services::service_collection::ServiceCollectionExtensions;
-use crate::services::service_descriptor::ServiceDescriptor;
 
 use super::ihttp_body_format_resolver::IHttpBodyFormatResolver;
 use super::ihttp_body_stream_format::IHttpBodyStreamFormat;
@@ -87,6 +83,7 @@ pub struct HttpBodyFormatService {
     resolvers: Vec<Rc<dyn IHttpBodyFormatResolver>>,
 }
 
+#[injectable(as = "dyn IHttpBodyFormatService")]
 impl HttpBodyFormatService {
     pub fn new(
         resolvers: Vec<Rc<dyn IHttpBodyFormatResolver>>,
@@ -96,22 +93,6 @@ impl HttpBodyFormatService {
         }
     }
 
-    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![
-            Box::new(Rc::new(Self::new(
-                ServiceCollectionExtensions::get_required_multiple::<dyn IHttpBodyFormatResolver>(services),
-            )) as Rc<dyn IHttpBodyFormatService>)
-        ]
-    }
-
-    pub fn add_to_services(services: &mut ServiceCollection) {
-        services.add(ServiceDescriptor::new(
-            crate::core::type_info::TypeInfo::rc_of::<dyn IHttpBodyFormatService>(),
-            Self::new_service,
-            crate::services::service_scope::ServiceScope::Singleton,
-        ));
-    }
-
     #[allow(dead_code)]
     fn resolve(&self, get_content_type: ContentType) -> Option<Rc<dyn IHttpBodyStreamFormat>> {
         for resolver in &self.resolvers {
@@ -4,5 +4,9 @@ pub mod ihttp_body_format_resolver;
 pub mod ihttp_body_stream_format;
 pub mod http_body_format_resolver;
 pub mod url_encoded_body;
+pub mod chunked_body_stream;
+pub mod content_coding_body_stream;
 pub mod request_decoder_middleware;
-pub mod response_encoder_middleware;
\ No newline at end of file
+pub mod response_encoder_middleware;
+pub mod byte_range;
+pub mod cache_validators;
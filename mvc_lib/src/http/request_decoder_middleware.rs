@@ -4,10 +4,13 @@ use std::error::Error;
 use std::rc::Rc;
 
 
+use http::StatusCode;
+
+use crate::action_results::http_result::StatusCodeResult;
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
-use crate::core::itcp_stream_wrapper::ITcpStreamWrapper;
 use crate::core::type_info::TypeInfo;
+use crate::errors::BadRequestError;
 use crate::services::request_middleware_service::{IRequestMiddlewareService, MiddlewareResult};
 use crate::services::service_scope::ServiceScope;
 use crate::services::service_descriptor::ServiceDescriptor;
@@ -19,17 +22,42 @@ use super::ihttp_body_stream_format::IHttpBodyStreamFormat;
 
 // this middleware is used to decode the request body.
 pub struct RequestDecoderMiddleware {
+    // resolves the decoders for the content codings of the request body
+    body_format_service: Rc<dyn IHttpBodyFormatService>,
     // the next middleware in the pipeline
     next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>,
 }
 
 impl RequestDecoderMiddleware {
-    pub fn new(_body_content_decoder_service: Rc<dyn IHttpBodyFormatService>) -> Self {
+    pub fn new(body_format_service: Rc<dyn IHttpBodyFormatService>) -> Self {
         Self {
+            body_format_service: body_format_service,
             next: RefCell::new(None),
         }
     }
 
+    // get the decoders for the Content-Encoding of the request body, in the order they need to be applied.
+    // codings are listed in the order they were applied by the client, so they are undone in reverse.
+    // returns: the decoders, or the name of the first coding that is not supported.
+    pub fn resolve_content_decoders(self: &Self, request_context: &dyn IRequestContext) -> Result<Vec<Rc<dyn IHttpBodyStreamFormat>>, String> {
+        let content_encoding = match request_context.get_headers().get("Content-Encoding") {
+            Some(content_encoding) => content_encoding.to_str().unwrap_or_default().to_string(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut decoders = Vec::new();
+        for content_coding in content_encoding.split(',').map(|x| x.trim()).rev() {
+            if content_coding.len() == 0 || content_coding.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+            match self.body_format_service.resolve(ContentType::parse(content_coding)) {
+                Some(decoder) => decoders.push(decoder),
+                None => return Err(content_coding.to_string()),
+            }
+        }
+        Ok(decoders)
+    }
+
     // this is the function that will be called by the service collection to create a new instance of the middleware
     pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
         vec![Box::new(Rc::new(Self::new(
@@ -49,8 +77,27 @@ impl IRequestMiddlewareService for RequestDecoderMiddleware {
     }
 
     fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
+        match self.resolve_content_decoders(request_context) {
+            Ok(decoders) => {
+                for decoder in decoders {
+                    request_context.use_decoder(decoder);
+                }
+            },
+            Err(content_coding) => {
+                response_context.set_action_result(Some(Rc::new(StatusCodeResult::new(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    format!("Unsupported Content-Encoding: {}", content_coding),
+                ))));
+                return Ok(MiddlewareResult::OkBreak);
+            },
+        }
+
         request_context.decode_and_bind_body(services);
-        
+        if request_context.get_connection_context().get_tcp_context().is_request_body_too_large() {
+            // the body binders stop at the first read error, so a body that decoded past the limit is rejected here.
+            return Err(Rc::new(BadRequestError::new(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large".to_string())));
+        }
+
         if let Some(next) = self.next.borrow().as_ref() {
             let next_response = next.handle_request(response_context, request_context, services)?;
 
@@ -69,81 +116,3 @@ impl IRequestMiddlewareService for RequestDecoderMiddleware {
         Box::new(TypeInfo::of::<RequestDecoderMiddleware>())
     }
 }
-
-pub struct GzipBodyStream {
-    _inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>
-}
-
-impl GzipBodyStream {
-    // source: Rc<dyn ITcpStreamWrapper>
-    pub fn new(inner_stream: Rc<RefCell<dyn ITcpStreamWrapper>>) -> Self {
-        // let mut d = flate2::Decompress::decompress();
-        // let mut s = String::new();
-        // d.read_to_string(&mut s).unwrap();
-        // println!("{}", s);
-        Self {
-            _inner_stream: inner_stream,
-        }
-    }
-}
-
-impl ITcpStreamWrapper for GzipBodyStream {
-    fn shutdown(&self, _how: std::net::Shutdown) -> std::io::Result<()> {
-        todo!()
-    }
-
-    fn flush(&self) -> std::io::Result<()> {
-        todo!()
-    }
-
-    fn read(&self, _b: &mut [u8]) -> std::io::Result<usize> {
-        todo!()
-    }
-
-    fn read_line(&self) -> std::io::Result<String> {
-        todo!()
-    }
-
-    fn write(&self, _b: &[u8]) -> std::io::Result<usize> {
-        todo!()
-    }
-
-    fn write_line(&self, _b: &String) -> std::io::Result<usize> {
-        todo!()
-    }
-
-    fn remote_addr(&self) -> std::net::SocketAddr {
-        todo!()
-    }
-}
-
-
-pub struct GzipBodyStreamFormat {
-
-}
-
-impl GzipBodyStreamFormat {
-    pub fn new() -> Self {
-        Self {
-
-        }
-    }
-}
-
-impl IHttpBodyStreamFormat for GzipBodyStreamFormat {
-    fn matches_content_type(&self, content_type: &ContentType) -> bool {
-        content_type.mime_type == "application/gzip" || content_type.mime_type == "gzip"
-    }
-
-    fn decode(&self, stream: Rc<RefCell<dyn ITcpStreamWrapper>>, _content_type: &ContentType) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
-        Rc::new(RefCell::new(GzipBodyStream::new(stream)))
-    }
-
-    fn encode(self: &Self, stream: Rc<RefCell<dyn ITcpStreamWrapper>>, _content_type: &ContentType) -> Rc<RefCell<dyn ITcpStreamWrapper>> {
-        Rc::new(RefCell::new(GzipBodyStream::new(stream)))
-    }
-
-    fn type_info(self: &Self) -> Box<TypeInfo> {
-        Box::new(TypeInfo::of::<Self>())
-    }
-}
@@ -2,31 +2,150 @@ use std::cell::RefCell;
 use std::error::Error;
 use std::{rc::Rc, any::Any};
 
+use http::HeaderMap;
+
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 use crate::core::type_info::TypeInfo;
+use crate::options::response_compression_options::{IResponseCompressionOptions, ResponseCompressionOptions};
 use crate::services::request_middleware_service::{IRequestMiddlewareService, MiddlewareResult};
 use crate::services::service_scope::ServiceScope;
 use crate::services::service_descriptor::ServiceDescriptor;
-use crate::services::service_collection::{IServiceCollection, ServiceCollection};
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+
+use super::content_coding_body_stream::ContentCoding;
+use super::http_body_content::ContentType;
+use super::http_body_format_service::IHttpBodyFormatService;
+use super::ihttp_body_stream_format::IHttpBodyStreamFormat;
+
+
+// this struct holds the compression negotiated for a response.
+// the connection context uses it when the response starts writing, once the status and headers are known.
+pub struct ResponseCompression {
+    // the content coding accepted by the client and the format that encodes it, or None if the client accepts none.
+    pub encoder: Option<(String, Rc<dyn IHttpBodyStreamFormat>)>,
+    pub options: Rc<dyn IResponseCompressionOptions>,
+}
+
+impl ResponseCompression {
+    pub fn new(encoder: Option<(String, Rc<dyn IHttpBodyStreamFormat>)>, options: Rc<dyn IResponseCompressionOptions>) -> Self {
+        Self {
+            encoder: encoder,
+            options: options,
+        }
+    }
+
+    // returns true if the Content-Type of a response is one that is compressed.
+    // headers: the headers of the response.
+    pub fn is_compressible(self: &Self, headers: &HeaderMap) -> bool {
+        match headers.get(http::header::CONTENT_TYPE).and_then(|x| x.to_str().ok()) {
+            Some(content_type) => self.options.is_compressible(&ContentType::parse(content_type)),
+            None => false,
+        }
+    }
+
+    // get the content coding and format to compress a response with.
+    // responses that are already encoded, are not compressible, or are known to be smaller than the minimum size are not compressed.
+    // partial responses are not compressed either, since their ranges are of the uncompressed body.
+    // headers: the headers of the response.
+    pub fn get_encoder(self: &Self, headers: &HeaderMap) -> Option<(String, Rc<dyn IHttpBodyStreamFormat>)> {
+        if headers.contains_key(http::header::CONTENT_ENCODING) || headers.contains_key(http::header::CONTENT_RANGE) || !self.is_compressible(headers) {
+            return None;
+        }
+        let content_length = headers.get(http::header::CONTENT_LENGTH)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.parse::<usize>().ok());
+        if content_length.unwrap_or(usize::MAX) < self.options.get_minimum_size() {
+            return None;
+        }
+        self.encoder.clone()
+    }
+}
 
 
 // this middleware is used to encode the response body.
+// it negotiates a content coding from the Accept-Encoding header of the request,
+// and the connection context compresses the response body with it.
 pub struct ResponseEncoderMiddleware {
+    // resolves the encoders for the content codings
+    body_format_service: Rc<dyn IHttpBodyFormatService>,
+    options: Rc<dyn IResponseCompressionOptions>,
     // the next middleware in the pipeline
     next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>
 }
 
 impl ResponseEncoderMiddleware {
-    pub fn new() -> Self {
+    pub fn new(body_format_service: Rc<dyn IHttpBodyFormatService>, options: Rc<dyn IResponseCompressionOptions>) -> Self {
         Self {
+            body_format_service: body_format_service,
+            options: options,
             next: RefCell::new(None)
         }
     }
 
     // this is the function that will be called by the service collection to create a new instance of the middleware
-    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IRequestMiddlewareService>)]
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        let options = ServiceCollectionExtensions::try_get_single::<dyn IResponseCompressionOptions>(services)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| Rc::new(ResponseCompressionOptions::new_defaults()));
+        vec![Box::new(Rc::new(Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn IHttpBodyFormatService>(services),
+            options,
+        )) as Rc<dyn IRequestMiddlewareService>)]
+    }
+
+    // choose the content coding to use from an Accept-Encoding header.
+    // codings with a higher q-value are preferred, and ties are broken by the order of the available codings.
+    // "*" matches any coding not listed, and a q-value of 0 means the coding is not acceptable.
+    // accept_encoding: the value of the Accept-Encoding header.
+    // available: the content codings that can be used, in the order the server prefers them.
+    // returns: the chosen content coding, or None if none of the available codings are acceptable.
+    pub fn negotiate_content_coding(accept_encoding: &str, available: &[&str]) -> Option<String> {
+        let accepted = accept_encoding.split(',')
+            .filter_map(|x| {
+                let mut parts = x.split(';');
+                let content_coding = parts.next().unwrap_or("").trim().to_lowercase();
+                if content_coding.len() == 0 {
+                    return None;
+                }
+                let q = parts
+                    .filter_map(|x| x.trim().strip_prefix("q=").map(|x| x.trim().parse::<f32>().unwrap_or(0.0)))
+                    .next()
+                    .unwrap_or(1.0);
+                Some((content_coding, q))
+            })
+            .collect::<Vec<(String, f32)>>();
+        let get_q = |content_coding: &str| accepted.iter()
+            .find(|x| x.0 == content_coding)
+            .or_else(|| accepted.iter().find(|x| x.0 == "*"))
+            .map(|x| x.1)
+            .unwrap_or(0.0);
+
+        let mut best: Option<(&str, f32)> = None;
+        for content_coding in available.iter() {
+            let q = get_q(content_coding);
+            if q > 0.0 && best.map(|x| q > x.1).unwrap_or(true) {
+                best = Some((content_coding, q));
+            }
+        }
+        best.map(|x| x.0.to_string())
+    }
+
+    // negotiate the compression of the response to a request.
+    pub fn negotiate_compression(self: &Self, request_context: &dyn IRequestContext) -> ResponseCompression {
+        let accept_encoding = request_context.get_headers()
+            .get(http::header::ACCEPT_ENCODING)
+            .and_then(|x| x.to_str().ok())
+            .unwrap_or_default();
+        let available = ContentCoding::PREFERRED.iter()
+            .map(|x| x.as_str())
+            .filter(|x| self.body_format_service.resolve(ContentType::parse(x)).is_some())
+            .collect::<Vec<&str>>();
+
+        let encoder = Self::negotiate_content_coding(accept_encoding, &available)
+            .and_then(|x| self.body_format_service.resolve(ContentType::parse(&x)).map(|format| (x, format)));
+        ResponseCompression::new(encoder, self.options.clone())
     }
 
     // this is called by the application to add the middleware to the service collection
@@ -41,12 +160,8 @@ impl IRequestMiddlewareService for ResponseEncoderMiddleware {
     }
 
     fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
-        // get accept header from request
-        let accept_header = request_context.get_headers().get("Accept").unwrap();
-        let _accept_str = accept_header.to_str().unwrap();
-        
-        // get encoder from service collection
-        // let encoder = ServiceCollectionExtensions::get_required_single::<dyn IHttpBodyStreamFormat>(services).get(accept_str);
+        let compression = self.negotiate_compression(request_context);
+        response_context.get_connection_context().set_response_compression(Some(Rc::new(compression)));
 
         if let Some(next) = self.next.borrow().as_ref() {
             let next_response = next.handle_request(response_context, request_context, services)?;
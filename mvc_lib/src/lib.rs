@@ -1,10 +1,13 @@
 extern crate proc_macro;
 extern crate proc_macro2;
+// lets macros that refer to mvc_lib paths be used inside mvc_lib.
+extern crate self as mvc_lib;
 
 pub mod action_results;
 pub mod app;
 pub mod attributes;
 pub mod auth;
+pub mod config;
 pub mod contexts;
 pub mod controller_action_features;
 pub mod controller_actions;
@@ -20,5 +23,9 @@ pub mod model_binder;
 pub mod middleware;
 pub mod options;
 pub mod services;
+pub mod session;
 pub mod routing;
 pub mod view;
+
+// re-exported so the registrations generated by #[controller] work in crates that do not depend on inventory.
+pub use inventory;
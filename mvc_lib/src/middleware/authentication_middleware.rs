@@ -0,0 +1,68 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+
+use crate::auth::authentication_handler::{AuthenticationExtensions, IAuthenticationHandler};
+use crate::contexts::irequest_context::IRequestContext;
+use crate::contexts::iresponse_context::IResponseContext;
+use crate::core::type_info::TypeInfo;
+
+use crate::services::request_middleware_service::IRequestMiddlewareService;
+use crate::services::request_middleware_service::MiddlewareResult;
+
+use crate::services::service_collection::IServiceCollection;
+use crate::services::service_collection::ServiceCollection;
+use crate::services::service_collection::ServiceCollectionExtensions;
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this middleware authenticates requests with the authentication handlers, such as the cookie authentication handler,
+// and sets the claims of the request for the authorization middleware after it.
+// the handlers are tried in the order they were added until one of them authenticates the request.
+// the claims are then checked by the claims validators, and the request is signed out if any of them rejects the claims.
+pub struct AuthenticationMiddleware {
+    next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>
+}
+
+impl AuthenticationMiddleware {
+    // create a new instance of the middleware.
+    pub fn new() -> Self {
+        Self { next: RefCell::new(None) }
+    }
+
+    // this is the function that will be called by the service collection to create a new instance of the middleware
+    // services - the service collection
+    // returns a vector containing the new instance of the middleware.
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IRequestMiddlewareService>)]
+    }
+
+    // this is called by the application to add the middleware to the service collection
+    // services - the service collection
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IRequestMiddlewareService>(), Self::new_service, ServiceScope::Singleton));
+    }
+}
+
+impl IRequestMiddlewareService for AuthenticationMiddleware {
+    fn set_next(self: &Self, next: Option<Rc<dyn IRequestMiddlewareService>>) {
+        self.next.replace(next);
+    }
+
+    fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
+        let handlers = ServiceCollectionExtensions::get_required_multiple::<dyn IAuthenticationHandler>(services);
+        AuthenticationExtensions::authenticate(&handlers, request_context, response_context, services)?;
+
+        if let Some(next) = self.next.borrow().as_ref() {
+            next.handle_request(response_context, request_context, services)
+        } else {
+            Ok(MiddlewareResult::OkContinue)
+        }
+    }
+
+    fn get_type_info(&self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<AuthenticationMiddleware>())
+    }
+}
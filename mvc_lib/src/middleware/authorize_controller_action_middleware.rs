@@ -3,18 +3,24 @@ use std::cell::RefCell;
 use std::error::Error;
 use std::rc::Rc;
 
+use crate::action_results::http_result::StatusCodeResult;
+use crate::action_results::view_result::ViewResult;
+use crate::auth::authentication_handler::AuthenticationExtensions;
+use crate::controller_action_features::authorize::AuthorizeControllerActionFeature;
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 use crate::core::type_info::TypeInfo;
+use crate::error::error_viewmodel_service::IErrorViewModelService;
+use crate::errors::AuthorizationError;
 
+use crate::services::authorization_service::AuthRejectionReason;
 use crate::services::authorization_service::AuthResult;
 use crate::services::authorization_service::IAuthorizationService;
 
 use crate::services::request_middleware_service::IRequestMiddlewareService;
+use crate::view::view_renderer::IViewRenderer;
 use crate::services::request_middleware_service::MiddlewareResult;
 
-use crate::services::routemap_service::IRouteMapService;
-
 use crate::services::service_collection::IServiceCollection;
 use crate::services::service_collection::ServiceCollection;
 use crate::services::service_collection::ServiceCollectionExtensions;
@@ -24,25 +30,24 @@ use crate::services::service_scope::ServiceScope;
 
 // this middleware is used to authorize a controller action.
 pub struct AuthorizeControllerActionFeatureMiddleware {
-    mapper_service: Rc<dyn IRouteMapService>,
     next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>
 }
 
 impl AuthorizeControllerActionFeatureMiddleware {
+    // the path of the error view a request that is not allowed is answered with, which the ErrorViewMiddleware also uses.
+    const ERROR_VIEW_PATH: &'static str = "/shared/error";
+
     // create a new instance of the middleware.
-    // mapper_service - the route map service. this is used to get the controller action.
     // returns the new instance of the middleware.
-    pub fn new(mapper_service: Rc<dyn IRouteMapService>) -> Self {
-        Self { mapper_service: mapper_service, next: RefCell::new(None) }
+    pub fn new() -> Self {
+        Self { next: RefCell::new(None) }
     }
 
     // this is the function that will be called by the service collection to create a new instance of the middleware
     // services - the service collection
     // returns a vector containing the new instance of the middleware.
-    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![Box::new(Rc::new(Self::new(
-            ServiceCollectionExtensions::get_required_single::<dyn IRouteMapService>(services)
-        )) as Rc<dyn IRequestMiddlewareService>)]
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IRequestMiddlewareService>)]
     }
     
     // this is called by the application to add the middleware to the service collection
@@ -51,6 +56,23 @@ impl AuthorizeControllerActionFeatureMiddleware {
     pub fn add_to_services(services: &mut ServiceCollection) {
         services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IRequestMiddlewareService>(), Self::new_service, ServiceScope::Singleton));
     }
+
+    // get the error view for a request that was not authorized, if the app has an error view model service and an error view.
+    // error: the error the view model is created for.
+    // services: the services to get the error view model service and view renderer from.
+    fn get_error_view_result(error: Rc<dyn Error>, services: &dyn IServiceCollection) -> Result<Option<ViewResult>, Rc<dyn Error>> {
+        let path = Self::ERROR_VIEW_PATH.to_string();
+        let error_viewmodel_service = match ServiceCollectionExtensions::try_get_single::<dyn IErrorViewModelService>(services)? {
+            Some(error_viewmodel_service) => error_viewmodel_service,
+            None => return Ok(None),
+        };
+        match ServiceCollectionExtensions::try_get_single::<dyn IViewRenderer>(services)? {
+            Some(view_renderer) if !view_renderer.get_views(&path, services).is_empty() => {
+                Ok(Some(ViewResult::new(path, error_viewmodel_service.create_error_viewmodel(error))))
+            },
+            _ => Ok(None),
+        }
+    }
 }
 
 impl IRequestMiddlewareService for AuthorizeControllerActionFeatureMiddleware {
@@ -60,17 +82,36 @@ impl IRequestMiddlewareService for AuthorizeControllerActionFeatureMiddleware {
 
     fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
         let auth_service = ServiceCollectionExtensions::get_required_single::<dyn IAuthorizationService>(services);
-        let controller_name = request_context.get_str("ControllerName");
+        // the controller was found when the request was routed.
+        if let Some(controller) = request_context.get_controller_optional() {
+            // an action that names its authentication schemes is authenticated with only those, so the claims
+            // of another scheme, such as a cookie sent to an API that takes bearer tokens, do not authorize it.
+            let schemes = AuthorizeControllerActionFeature::get_schemes(&controller, request_context);
+            let handlers = AuthenticationExtensions::get_handlers(services, &schemes)?;
+            if !schemes.is_empty() {
+                AuthenticationExtensions::authenticate(&handlers, request_context, response_context, services)?;
+            }
 
-        if controller_name.len() > 0 {
-            let controller = self.mapper_service.get_mapper().get_controller(controller_name.clone());
             match auth_service.authenticate_http_request(controller, request_context)? {
                 AuthResult::Ok => {
                     // should make note of authorization somewhere
                 },
                 AuthResult::Rejection(reason) => {
                     println!("Request denied, unauthorized: {:?}", reason);
-                    response_context.set_status_code(http::StatusCode::NOT_FOUND);
+                    // a request that was not authenticated is challenged, such as by redirecting to the login page.
+                    if let AuthRejectionReason::NotAuthenticated = reason {
+                        if let Some(handler) = handlers.first() {
+                            handler.challenge(request_context, response_context)?;
+                            return Ok(MiddlewareResult::OkBreak);
+                        }
+                    }
+                    // otherwise the error view is written with 401 or 403, or just the status code if there is no error view.
+                    let status_code = reason.get_status_code();
+                    let error = Rc::new(AuthorizationError::new(reason)) as Rc<dyn Error>;
+                    match Self::get_error_view_result(error.clone(), services)? {
+                        Some(view_result) => response_context.set_action_result(Some(Rc::new(view_result.with_status_code(status_code)))),
+                        None => response_context.set_action_result(Some(Rc::new(StatusCodeResult::new(status_code, error.to_string())))),
+                    }
                     return Ok(MiddlewareResult::OkBreak); // short circuit middleware
                 },
             }
@@ -0,0 +1,113 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+
+use crate::contexts::irequest_context::IRequestContext;
+use crate::contexts::iresponse_context::IResponseContext;
+use crate::core::type_info::TypeInfo;
+
+use crate::services::request_middleware_service::IRequestMiddlewareService;
+use crate::services::request_middleware_service::MiddlewareResult;
+
+use crate::services::service_collection::IServiceCollection;
+use crate::services::service_collection::ServiceCollection;
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this middleware is a branch of the pipeline. requests the predicate is true for go through the middleware
+// of the branch, which then continues with the rest of the pipeline, so the controller action is still only
+// matched by the routing middleware and executed at the end of the pipeline once.
+// requests the predicate is false for skip the branch.
+// this lets a path prefix or area have its own middleware, such as only serving /dev to the local host.
+pub struct MapWhenMiddleware {
+    // whether or not a request goes through the branch.
+    predicate: Rc<dyn Fn(&dyn IRequestContext) -> bool>,
+    // the middleware of the branch, in order.
+    middleware: Vec<Rc<dyn IRequestMiddlewareService>>,
+    next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>
+}
+
+impl MapWhenMiddleware {
+    // create a new instance of the middleware.
+    // predicate: whether or not a request goes through the branch.
+    // middleware: the middleware of the branch, in order.
+    pub fn new(predicate: Rc<dyn Fn(&dyn IRequestContext) -> bool>, middleware: Vec<Rc<dyn IRequestMiddlewareService>>) -> Self {
+        Self { predicate: predicate, middleware: middleware, next: RefCell::new(None) }
+    }
+
+    // add a branch to the pipeline at this point for requests the predicate is true for.
+    // services: the service collection to add the branch to.
+    // predicate: whether or not a request goes through the branch.
+    // middleware: the new_service functions of the middleware of the branch, in order.
+    pub fn map_when<T>(services: &mut ServiceCollection, predicate: T, middleware: Vec<fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>>)
+        where T: Fn(&dyn IRequestContext) -> bool + 'static {
+        let predicate: Rc<dyn Fn(&dyn IRequestContext) -> bool> = Rc::new(predicate);
+        services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn IRequestMiddlewareService>(), move |services| {
+            let branch = middleware.iter()
+                .flat_map(|new_service| new_service(services))
+                .map(|x| x.downcast::<Rc<dyn IRequestMiddlewareService>>().map(|x| *x).expect("a middleware new_service must create Rc<dyn IRequestMiddlewareService>"))
+                .collect();
+            vec![Box::new(Rc::new(Self::new(predicate.clone(), branch)) as Rc<dyn IRequestMiddlewareService>)]
+        }, ServiceScope::Singleton));
+    }
+
+    // add a branch to the pipeline at this point for requests under a path, such as /dev for /dev and /dev/log.
+    // services: the service collection to add the branch to.
+    // path: the path the requests are under.
+    // middleware: the new_service functions of the middleware of the branch, in order.
+    pub fn map(services: &mut ServiceCollection, path: &str, middleware: Vec<fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>>) {
+        let path = path.trim_end_matches('/').to_string();
+        Self::map_when(services, move |request_context| Self::is_under_path(&request_context.get_path(), &path), middleware);
+    }
+
+    // add a branch to the pipeline at this point for requests routed to an action in an area.
+    // the branch must come after the routing middleware, since that is what finds the action.
+    // services: the service collection to add the branch to.
+    // area_name: the name of the area.
+    // middleware: the new_service functions of the middleware of the branch, in order.
+    pub fn map_area(services: &mut ServiceCollection, area_name: &str, middleware: Vec<fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>>) {
+        let area_name = area_name.to_string();
+        Self::map_when(services, move |request_context| {
+            request_context.get_controller_action_optional().map(|x| x.get_area_name() == area_name).unwrap_or(false)
+        }, middleware);
+    }
+
+    // get whether or not a path is the same as or under another path, ignoring case.
+    // path: the path of the request.
+    // prefix: the path without a trailing slash.
+    pub fn is_under_path(path: &str, prefix: &str) -> bool {
+        let path = path.to_ascii_lowercase();
+        let prefix = prefix.to_ascii_lowercase();
+        prefix.is_empty() || path == prefix || path.starts_with(&format!("{}/", prefix))
+    }
+}
+
+impl IRequestMiddlewareService for MapWhenMiddleware {
+    fn set_next(self: &Self, next: Option<Rc<dyn IRequestMiddlewareService>>) {
+        self.next.replace(next);
+    }
+
+    fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
+        if (self.predicate)(request_context) {
+            if let Some(first) = self.middleware.first() {
+                // the last middleware of the branch continues with the rest of the pipeline.
+                for (i, middleware) in self.middleware.iter().enumerate() {
+                    middleware.set_next(self.middleware.get(i + 1).cloned().or_else(|| self.next.borrow().clone()));
+                }
+                return first.handle_request(response_context, request_context, services);
+            }
+        }
+
+        if let Some(next) = self.next.borrow().as_ref() {
+            next.handle_request(response_context, request_context, services)
+        } else {
+            Ok(MiddlewareResult::OkContinue)
+        }
+    }
+
+    fn get_type_info(&self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<MapWhenMiddleware>())
+    }
+}
@@ -1,2 +1,5 @@
+pub mod authentication_middleware;
 pub mod authorize_controller_action_middleware;
-pub mod redirect_https;
\ No newline at end of file
+pub mod map_when;
+pub mod redirect_https;
+pub mod session_middleware;
\ No newline at end of file
@@ -7,9 +7,10 @@ use crate::action_results::http_result::HttpRedirectResult;
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 use crate::core::type_info::TypeInfo;
+use crate::options::http_options::IHttpOptions;
 use crate::services::request_middleware_service::IRequestMiddlewareService;
 use crate::services::request_middleware_service::MiddlewareResult;
-use crate::services::service_collection::IServiceCollection;
+use crate::services::service_collection::{IServiceCollection, ServiceCollectionExtensions};
 
 
 
@@ -23,16 +24,18 @@ pub trait IRedirectHttpsMiddlewareService: IRequestMiddlewareService {
 pub struct RedirectHttpsMiddlewareService {
     // the next middleware in the pipeline
     next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>,
+    // the options with the port https is served on.
+    options: Rc<dyn IHttpOptions>,
 }
 
 impl RedirectHttpsMiddlewareService {
-    pub fn new() -> Self {
-        Self { next: RefCell::new(None) }
+    pub fn new(options: Rc<dyn IHttpOptions>) -> Self {
+        Self { next: RefCell::new(None), options: options }
     }
 
     // this is the function that will be called by the service collection to create a new instance of the middleware
-    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![Box::new(Rc::new(Self::new()) as Rc<dyn IRequestMiddlewareService>)]
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new(ServiceCollectionExtensions::get_required_single::<dyn IHttpOptions>(services))) as Rc<dyn IRequestMiddlewareService>)]
     }
 }
 
@@ -42,6 +45,11 @@ impl IRedirectHttpsMiddlewareService for RedirectHttpsMiddlewareService {
         if let Err(_) = url.set_scheme("https") {
             return Err(Rc::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Error setting scheme to https"))));
         }
+        // the default port is left out of the url.
+        let port_https = self.options.get_port_https();
+        if let Err(_) = url.set_port(if port_https == 443 { None } else { Some(port_https) }) {
+            return Err(Rc::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Error setting port to {}", port_https))));
+        }
         response_context.set_action_result(Some(Rc::new(HttpRedirectResult::new(url.to_string()))));
         Ok(MiddlewareResult::OkBreak)
     }
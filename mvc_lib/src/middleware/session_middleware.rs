@@ -0,0 +1,179 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::contexts::irequest_context::IRequestContext;
+use crate::contexts::iresponse_context::IResponseContext;
+use crate::core::type_info::TypeInfo;
+use crate::options::session_options::{ISessionOptions, SessionOptions};
+use crate::session::session_record::SessionRecord;
+use crate::session::session_state::{ISession, Session};
+use crate::session::session_store::ISessionStore;
+
+use crate::services::request_middleware_service::IRequestMiddlewareService;
+use crate::services::request_middleware_service::MiddlewareResult;
+
+use crate::services::service_collection::IServiceCollection;
+use crate::services::service_collection::ServiceCollection;
+use crate::services::service_collection::ServiceCollectionExtensions;
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this middleware loads the server-side session of a request from the session store and sets it on the request context,
+// then saves it once the rest of the pipeline has handled the request.
+// the session id is kept in a cookie. a cookie for a session the store does not have, or that expired,
+// is never reused, so a new session always gets a new random id.
+// new sessions are only saved, and their cookie only written, once a value is set, so requests that do not use
+// the session do not fill the store.
+pub struct SessionMiddleware {
+    next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>,
+    options: Rc<dyn ISessionOptions>,
+    store: Rc<dyn ISessionStore>,
+    random: SystemRandom,
+    // when the expired sessions were last removed from the store by this worker thread.
+    last_removed_expired: Cell<u64>,
+}
+
+impl SessionMiddleware {
+    // the length of a session id before it is encoded, in bytes.
+    pub const SESSION_ID_LENGTH: usize = 32;
+
+    // create a new instance of the middleware.
+    // options: the options for the session cookie and timeouts.
+    // store: where sessions are kept between requests.
+    pub fn new(options: Rc<dyn ISessionOptions>, store: Rc<dyn ISessionStore>) -> Self {
+        Self {
+            next: RefCell::new(None),
+            options: options,
+            store: store,
+            random: SystemRandom::new(),
+            last_removed_expired: Cell::new(Self::now()),
+        }
+    }
+
+    // this is the function that will be called by the service collection to create a new instance of the middleware
+    // services - the service collection
+    // returns a vector containing the new instance of the middleware.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn ISessionOptions>(services),
+            ServiceCollectionExtensions::get_required_single::<dyn ISessionStore>(services),
+        )) as Rc<dyn IRequestMiddlewareService>)]
+    }
+
+    // this is called by the application to add the session options from the configuration and the middleware to the service collection.
+    // a session store must also be added, such as with InMemorySessionStore::add_to_services.
+    // services - the service collection
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ISessionOptions>(), SessionOptions::new_service_from_configuration, ServiceScope::Singleton));
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IRequestMiddlewareService>(), Self::new_service, ServiceScope::Singleton));
+    }
+
+    // get the current time in seconds since the unix epoch.
+    pub fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0)
+    }
+
+    // get whether a value can be a session id made by new_session_id, so other values are never looked up in the store.
+    // id: the value of the session cookie.
+    pub fn is_session_id(id: &str) -> bool {
+        URL_SAFE_NO_PAD.decode(id).map(|x| x.len() == Self::SESSION_ID_LENGTH).unwrap_or(false)
+    }
+
+    // create a new random session id.
+    pub fn new_session_id(self: &Self) -> Result<String, Rc<dyn Error>> {
+        let mut id = vec![0u8; Self::SESSION_ID_LENGTH];
+        self.random.fill(&mut id).map_err(|_| Rc::new(std::io::Error::new(std::io::ErrorKind::Other, "could not generate a session id")) as Rc<dyn Error>)?;
+        Ok(URL_SAFE_NO_PAD.encode(id))
+    }
+
+    // load the session of the request, or create a new one if the request has none or it expired.
+    fn load_session(self: &Self, request_context: &dyn IRequestContext, now: u64) -> Result<Session, Rc<dyn Error>> {
+        let id = request_context.get_cookies_parsed()
+            .and_then(|x| x.get(&self.options.get_cookie_name()).cloned())
+            .filter(|x| Self::is_session_id(x));
+        if let Some(id) = id {
+            if let Some(mut record) = self.store.load(&id)? {
+                if !record.is_expired(now) {
+                    record.touch(now, self.options.get_idle_timeout(), self.options.get_absolute_timeout());
+                    return Ok(Session::new(record, false));
+                }
+                self.store.remove(&id)?;
+            }
+        }
+        let record = SessionRecord::new(self.new_session_id()?, now, self.options.get_idle_timeout(), self.options.get_absolute_timeout());
+        Ok(Session::new(record, true))
+    }
+
+    // save the session once the request is handled, or remove it if it was abandoned.
+    fn save_session(self: &Self, session: &Session, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) -> Result<(), Rc<dyn Error>> {
+        if session.is_abandoned() {
+            if !session.is_new() {
+                self.store.remove(&session.get_id())?;
+                self.write_cookie("", Some(0), request_context, response_context);
+            }
+        } else if !session.is_new() {
+            // the session is saved even when its values did not change, so its idle timeout slides forward.
+            self.store.save(&session.get_record())?;
+        } else if session.is_changed() {
+            self.store.save(&session.get_record())?;
+            self.write_cookie(&session.get_id(), None, request_context, response_context);
+        }
+        Ok(())
+    }
+
+    // remove the expired sessions from the store, at most once per idle timeout.
+    fn remove_expired(self: &Self, now: u64) -> Result<(), Rc<dyn Error>> {
+        if now >= self.last_removed_expired.get().saturating_add(self.options.get_idle_timeout().as_secs()) {
+            self.last_removed_expired.set(now);
+            self.store.remove_expired(now)?;
+        }
+        Ok(())
+    }
+
+    // write the session cookie. the cookie has no Max-Age unless it is removed, so it ends when the browser closes,
+    // and the store decides when the session expires.
+    fn write_cookie(self: &Self, value: &str, max_age: Option<u64>, request_context: &dyn IRequestContext, response_context: &dyn IResponseContext) {
+        let secure = self.options.get_secure_cookie() || request_context.get_scheme() == "https";
+        response_context.add_header_string("Set-Cookie".to_string(), format!(
+            "{}={}; Path=/;{} HttpOnly; SameSite=Lax{}",
+            self.options.get_cookie_name(),
+            value,
+            max_age.map(|x| format!(" Max-Age={};", x)).unwrap_or_default(),
+            if secure { "; Secure" } else { "" }));
+    }
+}
+
+impl IRequestMiddlewareService for SessionMiddleware {
+    fn set_next(self: &Self, next: Option<Rc<dyn IRequestMiddlewareService>>) {
+        self.next.replace(next);
+    }
+
+    fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
+        let now = Self::now();
+        self.remove_expired(now)?;
+
+        let session = Rc::new(self.load_session(request_context, now)?);
+        request_context.set_session(Some(session.clone() as Rc<dyn ISession>));
+
+        let result = if let Some(next) = self.next.borrow().as_ref() {
+            next.handle_request(response_context, request_context, services)
+        } else {
+            Ok(MiddlewareResult::OkContinue)
+        };
+
+        self.save_session(&session, request_context, response_context)?;
+        result
+    }
+
+    fn get_type_info(&self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<SessionMiddleware>())
+    }
+}
@@ -39,7 +39,7 @@ impl ModelBinderService {
     }
 
     pub fn add_to_services(services: &mut ServiceCollection) {
-        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IModelBinderService>(), Self::new_service, ServiceScope::Request));
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IModelBinderService>(), Self::new_service, ServiceScope::Singleton));
     }
 }
 
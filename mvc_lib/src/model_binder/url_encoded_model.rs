@@ -64,6 +64,13 @@ impl UrlEncodedModel {
 
         Self::parse_body(content_type, &body_bytes)
     }
+
+    // get a value of the body content with percent-encoding and '+' decoded, such as a password typed into a form.
+    // key: the name of the value.
+    // returns: the first value for the key if found, otherwise None.
+    pub fn get_decoded(self: &Self, key: &str) -> Option<String> {
+        form_urlencoded::parse(self.0.raw_str.as_bytes()).find(|x| x.0 == key).map(|x| x.1.to_string())
+    }
 }
 
 // impl IBodyContent for UrlEncodedModel {
@@ -0,0 +1,148 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::config::configuration::{Configuration, ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
+
+
+// this trait abstracts the options for authenticating requests with a cookie.
+pub trait ICookieAuthenticationOptions {
+    // get the name of the authentication cookie.
+    fn get_cookie_name(self: &Self) -> String;
+    // get the path that requests that are not authenticated are redirected to.
+    fn get_login_path(self: &Self) -> String;
+    // get the name of the query string parameter for the url to go back to after logging in.
+    fn get_return_url_parameter(self: &Self) -> String;
+    // get how long the authentication cookie is valid for after it is issued.
+    fn get_expire_time(self: &Self) -> Duration;
+    // get whether the authentication cookie is issued again once half of its time has passed.
+    fn get_sliding_expiration(self: &Self) -> bool;
+    // get the secret key the authentication cookie is signed and encrypted with.
+    fn get_secret_key(self: &Self) -> Vec<u8>;
+    // get whether the claims in the authentication cookie are encrypted as well as signed.
+    fn get_encrypt(self: &Self) -> bool;
+    // get whether the authentication cookie is only sent over HTTPS, even for requests made over HTTP.
+    fn get_secure_cookie(self: &Self) -> bool;
+}
+
+// this struct implements ICookieAuthenticationOptions.
+#[derive(Debug, Clone)]
+pub struct CookieAuthenticationOptions {
+    pub cookie_name: String,
+    pub login_path: String,
+    pub return_url_parameter: String,
+    pub expire_time: Duration,
+    pub sliding_expiration: bool,
+    // the secret key, which should be at least 32 bytes.
+    pub secret_key: Vec<u8>,
+    pub encrypt: bool,
+    pub secure_cookie: bool,
+}
+
+impl CookieAuthenticationOptions {
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "cookie_authentication";
+    // the smallest secret key that is accepted from the configuration, in bytes.
+    pub const MINIMUM_SECRET_KEY_LENGTH: usize = 32;
+
+    // create a new CookieAuthenticationOptions struct with default values.
+    // the secret key is random, so cookies are no longer valid once the program restarts
+    // unless a secret key is set in the configuration.
+    pub fn new_defaults() -> Self {
+        // the services are created for each worker thread, which must all use the same key.
+        static DEFAULT_SECRET_KEY: OnceLock<Vec<u8>> = OnceLock::new();
+        let secret_key = DEFAULT_SECRET_KEY.get_or_init(|| {
+            let mut secret_key = vec![0u8; Self::MINIMUM_SECRET_KEY_LENGTH];
+            SystemRandom::new().fill(&mut secret_key).expect("could not generate a secret key for cookie authentication");
+            secret_key
+        }).clone();
+        Self {
+            cookie_name: "rshtml.auth".to_string(),
+            login_path: "/account/login".to_string(),
+            return_url_parameter: "ReturnUrl".to_string(),
+            expire_time: Duration::from_secs(60 * 60 * 24 * 14),
+            sliding_expiration: true,
+            secret_key: secret_key,
+            encrypt: true,
+            secure_cookie: false,
+        }
+    }
+
+    // set the options from a configuration section. options that are not set in the section are left unchanged.
+    // configuration: the section to bind from, such as the "cookie_authentication" section.
+    // returns: an error if a value cannot be parsed or the secret key is too short.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        ConfigurationExtensions::bind(configuration, "cookie_name", &mut self.cookie_name)?;
+        ConfigurationExtensions::bind(configuration, "login_path", &mut self.login_path)?;
+        ConfigurationExtensions::bind(configuration, "return_url_parameter", &mut self.return_url_parameter)?;
+        ConfigurationExtensions::bind_seconds(configuration, "expire_time", &mut self.expire_time)?;
+        ConfigurationExtensions::bind(configuration, "sliding_expiration", &mut self.sliding_expiration)?;
+        ConfigurationExtensions::bind(configuration, "encrypt", &mut self.encrypt)?;
+        ConfigurationExtensions::bind(configuration, "secure_cookie", &mut self.secure_cookie)?;
+        if let Some(secret_key) = configuration.get("secret_key") {
+            if secret_key.len() < Self::MINIMUM_SECRET_KEY_LENGTH {
+                return Err(ConfigurationError::new(
+                    Configuration::combine(&configuration.get_path(), "secret_key"),
+                    format!("the secret key must be at least {} bytes", Self::MINIMUM_SECRET_KEY_LENGTH)));
+            }
+            self.secret_key = secret_key.into_bytes();
+        }
+        Ok(())
+    }
+
+    // set the options from the "cookie_authentication" section of the configuration service, if there is one.
+    // panics if a value cannot be parsed or the secret key is too short.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
+    }
+
+    // create a new CookieAuthenticationOptions struct as a service with default values and the "cookie_authentication" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_defaults().with_configuration(services)) as Rc<dyn ICookieAuthenticationOptions>)]
+    }
+}
+
+impl ICookieAuthenticationOptions for CookieAuthenticationOptions {
+    fn get_cookie_name(self: &Self) -> String {
+        self.cookie_name.clone()
+    }
+
+    fn get_login_path(self: &Self) -> String {
+        self.login_path.clone()
+    }
+
+    fn get_return_url_parameter(self: &Self) -> String {
+        self.return_url_parameter.clone()
+    }
+
+    fn get_expire_time(self: &Self) -> Duration {
+        self.expire_time
+    }
+
+    fn get_sliding_expiration(self: &Self) -> bool {
+        self.sliding_expiration
+    }
+
+    fn get_secret_key(self: &Self) -> Vec<u8> {
+        self.secret_key.clone()
+    }
+
+    fn get_encrypt(self: &Self) -> bool {
+        self.encrypt
+    }
+
+    fn get_secure_cookie(self: &Self) -> bool {
+        self.secure_cookie
+    }
+}
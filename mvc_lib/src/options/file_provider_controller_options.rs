@@ -4,7 +4,11 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::rc::Rc;
 
-use glob::glob;
+use glob::{glob, Pattern};
+
+use crate::config::configuration::{ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
 
 // this trait abstracts the file provider controller options.
 pub trait IFileProviderControllerOptions {
@@ -15,18 +19,28 @@ pub trait IFileProviderControllerOptions {
     // get the mapped paths with the alias as the key and the path as the value.
     // recursive: whether to get the paths recursively.
     fn get_mapped_paths(self: &Self, recursive: bool) -> HashMap<Cow<'static, str>, Cow<'static, str>>;
+
+    // get the Cache-Control header to send with a file.
+    // path: the request path the file is mapped to.
+    fn get_cache_control(self: &Self, path: &str) -> Option<String>;
 }
 
 // this struct implements IFileProviderControllerOptions.
 #[derive(Debug, Clone)]
 pub struct FileProviderControllerOptions {
     // the directories to serve files from.
-    pub serving_directories: &'static [&'static str],
+    pub serving_directories: Vec<Cow<'static, str>>,
     // the files to serve mapped to their aliases.
-    pub serving_files: &'static phf::Map<&'static str, &'static str>,
+    pub serving_files: HashMap<Cow<'static, str>, Cow<'static, str>>,
+    // glob patterns of request paths, such as "/css/*" or "*.woff2", mapped to the Cache-Control header for the files they match.
+    // the first pattern that matches is used.
+    pub cache_control: Vec<(Cow<'static, str>, Cow<'static, str>)>,
 }
 
 impl FileProviderControllerOptions {
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "file_provider";
+
     // create a new FileProviderControllerOptions struct from a list of directories to serve files from and a list of files to serve mapped to their aliases.
     // serving_directories: the directories to serve files from.
     // serving_files: the files to serve mapped to their aliases.
@@ -36,15 +50,70 @@ impl FileProviderControllerOptions {
         serving_files: &'static phf::Map<&'static str, &'static str>
     ) -> Self {
         Self {
-            serving_directories: serving_directories,
-            serving_files: serving_files,
+            serving_directories: serving_directories.iter().map(|x| Cow::Borrowed(*x)).collect(),
+            serving_files: serving_files.entries().map(|(k, v)| (Cow::Borrowed(*k), Cow::Borrowed(*v))).collect(),
+            cache_control: vec![],
         }
     }
 
+    // set the Cache-Control headers for the files served.
+    // cache_control: glob patterns of request paths mapped to the Cache-Control header for the files they match.
+    pub fn with_cache_control(mut self, cache_control: &'static [(&'static str, &'static str)]) -> Self {
+        self.cache_control = cache_control.iter().map(|(k, v)| (Cow::Borrowed(*k), Cow::Borrowed(*v))).collect();
+        self
+    }
+
     // create a new FileProviderControllerOptions struct with default values.
     pub fn new_defaults() -> Self {
-        static _EMPTY: phf::Map<&'static str, &'static str> = phf::Map::new();
-        Self { serving_directories: &["wwwroot/"], serving_files: &_EMPTY }
+        Self { serving_directories: vec![Cow::Borrowed("wwwroot/")], serving_files: HashMap::new(), cache_control: vec![] }
+    }
+
+    // set the options from a configuration section, such as:
+    // "file_provider": {
+    //     "serving_directories": [ "wwwroot/" ],
+    //     "serving_files": { "/stacks.css": "ts/node_modules/@stackoverflow/stacks/dist/css/stacks.css" },
+    //     "cache_control": [ { "pattern": "/css/*", "value": "public, max-age=3600" } ]
+    // }
+    // serving_directories and cache_control replace the lists that are already set, and serving_files are added to the files already set.
+    // configuration: the section to bind from, such as the "file_provider" section.
+    // returns: an error if a cache control entry is missing its pattern or value.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        if configuration.get_section("serving_directories").exists() {
+            self.serving_directories = ConfigurationExtensions::get_list(configuration, "serving_directories")
+                .into_iter()
+                .map(|x| Cow::Owned(x))
+                .collect();
+        }
+
+        for (alias, path) in ConfigurationExtensions::get_map(configuration, "serving_files") {
+            self.serving_files.insert(Cow::Owned(alias), Cow::Owned(path));
+        }
+
+        let cache_control = configuration.get_section("cache_control");
+        if cache_control.exists() {
+            let mut entries = vec![];
+            for index in cache_control.get_children() {
+                let entry = cache_control.get_section(&index);
+                match (entry.get("pattern"), entry.get("value")) {
+                    (Some(pattern), Some(value)) => entries.push((Cow::Owned(pattern), Cow::Owned(value))),
+                    _ => return Err(ConfigurationError::new(entry.get_path(), "cache control entries need a pattern and a value".to_string())),
+                }
+            }
+            self.cache_control = entries;
+        }
+        Ok(())
+    }
+
+    // set the options from the "file_provider" section of the configuration service, if there is one.
+    // panics if the section is not valid.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
     }
 
     // create a new FileProviderControllerOptions struct as a service from a list of directories to serve files from and a list of files to serve mapped to their aliases.
@@ -62,14 +131,19 @@ impl FileProviderControllerOptions {
     pub fn new_service_defaults() -> Box<dyn Any> {
         Box::new(Rc::new(Self::new_defaults()) as Rc<dyn IFileProviderControllerOptions>)
     }
+
+    // create a new FileProviderControllerOptions struct as a service with default values and the "file_provider" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_defaults().with_configuration(services)) as Rc<dyn IFileProviderControllerOptions>)]
+    }
 }
 
 impl IFileProviderControllerOptions for FileProviderControllerOptions {
     fn get_file(self: &Self, path: String) -> Option<String> {
-        for (serving_file_alias, serving_file_path) in self.serving_files.entries() {
+        for (serving_file_alias, serving_file_path) in self.serving_files.iter() {
             // println!("FileProviderControllerOptions comparing serving_file_alias {} to path {}", serving_file_alias, path);
-            if serving_file_alias == &path.as_str() {
-                let full_path = Path::new(&serving_file_path);
+            if serving_file_alias == path.as_str() {
+                let full_path = Path::new(serving_file_path.as_ref());
                 // println!("FileProviderControllerOptions full_path: {}", serving_file_path);
                 if full_path.exists() && full_path.is_file() {
                     // println!("full_path.exists() && full_path.is_file()");
@@ -112,10 +186,17 @@ impl IFileProviderControllerOptions for FileProviderControllerOptions {
             })
             .flatten()
             .chain(
-                self.serving_files.entries().map(|x| (Cow::Borrowed(*x.0), Cow::Borrowed(*x.1)))
+                self.serving_files.iter().map(|x| (x.0.clone(), x.1.clone()))
             )
             .collect();
 
         all_paths
     }
+
+    fn get_cache_control(self: &Self, path: &str) -> Option<String> {
+        self.cache_control
+            .iter()
+            .find(|(pattern, _)| Pattern::new(pattern).map(|x| x.matches(path)).unwrap_or(false))
+            .map(|(_, cache_control)| cache_control.to_string())
+    }
 }
@@ -1,6 +1,11 @@
 use std::any::Any;
 use std::borrow::Cow;
 use std::rc::Rc;
+use std::time::Duration;
+
+use crate::config::configuration::{ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
 
 // this trait is used to get the http serving options.
 pub trait IHttpOptions {
@@ -12,6 +17,34 @@ pub trait IHttpOptions {
     fn get_port_https(self: &Self) -> u16;
     // get the ip address and port to serve on.
     fn get_ip_and_port(self: &Self) -> String;
+    // get whether connections are kept open for more than one request.
+    fn get_keep_alive(self: &Self) -> bool;
+    // get how long an idle persistent connection waits for the next request before it is closed.
+    fn get_keep_alive_timeout(self: &Self) -> Duration;
+    // get the maximum number of requests served on one connection before it is closed.
+    fn get_max_requests_per_connection(self: &Self) -> u32;
+    // get the number of worker threads that serve connections concurrently.
+    fn get_worker_threads(self: &Self) -> usize;
+    // get how long in-flight requests are given to finish when the program is stopping.
+    fn get_shutdown_timeout(self: &Self) -> Duration;
+    // get the path to the PEM file with the https certificate chain. https is only served if this and the private key are set.
+    fn get_https_certificate_path(self: &Self) -> Option<String>;
+    // get the path to the PEM file with the private key of the https certificate.
+    fn get_https_private_key_path(self: &Self) -> Option<String>;
+    // get the longest request target (path and query) that is accepted. longer targets get a 414 response.
+    fn get_max_url_length(self: &Self) -> usize;
+    // get the most bytes accepted in the request headers, not counting the request line. more get a 431 response.
+    fn get_max_header_bytes(self: &Self) -> usize;
+    // get the most request headers accepted. more get a 431 response.
+    fn get_max_header_count(self: &Self) -> usize;
+    // get the largest request body accepted. larger bodies get a 413 response.
+    fn get_max_body_bytes(self: &Self) -> usize;
+    // get how long a read from a client can block before the connection is considered too slow.
+    fn get_read_timeout(self: &Self) -> Duration;
+    // get how long a write to a client can block before the connection is considered too slow.
+    fn get_write_timeout(self: &Self) -> Duration;
+    // get how long a client has to send the request line and headers. slower clients get a 408 response.
+    fn get_request_headers_timeout(self: &Self) -> Duration;
 }
 
 // this struct implements IHttpOptions.
@@ -20,6 +53,20 @@ pub struct HttpOptions {
     pub ip: Cow<'static, str>,
     pub port: u16,
     pub port_https: u16,
+    pub keep_alive: bool,
+    pub keep_alive_timeout: Duration,
+    pub max_requests_per_connection: u32,
+    pub worker_threads: usize,
+    pub shutdown_timeout: Duration,
+    pub https_certificate_path: Option<Cow<'static, str>>,
+    pub https_private_key_path: Option<Cow<'static, str>>,
+    pub max_url_length: usize,
+    pub max_header_bytes: usize,
+    pub max_header_count: usize,
+    pub max_body_bytes: usize,
+    pub read_timeout: Duration,
+    pub write_timeout: Duration,
+    pub request_headers_timeout: Duration,
 }
 
 impl HttpOptions {
@@ -29,6 +76,12 @@ impl HttpOptions {
     // ip: "127.0.0.1"
     // port: 80
     // port_https: 443
+    // keep-alive is enabled with a 5 second idle timeout and at most 100 requests per connection.
+    // one worker thread is used per available CPU.
+    // in-flight requests get 30 seconds to finish when the program is stopping.
+    // https is not served until a certificate and private key are set.
+    // request targets are limited to 8 KiB, headers to 32 KiB and 100 headers, and bodies to 30 MB.
+    // reads and writes time out after 30 seconds, and the request headers must arrive within 30 seconds.
     // ip: the ip address to serve on.
     // port: the port to serve on.
     // port_https: the port to serve https on.
@@ -37,7 +90,21 @@ impl HttpOptions {
         Self {
             ip: ip.unwrap_or(Cow::Borrowed("127.0.0.1")),
             port: port.unwrap_or(80),
-            port_https: port_https.unwrap_or(443)
+            port_https: port_https.unwrap_or(443),
+            keep_alive: true,
+            keep_alive_timeout: Duration::from_secs(5),
+            max_requests_per_connection: 100,
+            worker_threads: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            shutdown_timeout: Duration::from_secs(30),
+            https_certificate_path: None,
+            https_private_key_path: None,
+            max_url_length: 8 * 1024,
+            max_header_bytes: 32 * 1024,
+            max_header_count: 100,
+            max_body_bytes: 30_000_000,
+            read_timeout: Duration::from_secs(30),
+            write_timeout: Duration::from_secs(30),
+            request_headers_timeout: Duration::from_secs(30),
         }
     }
 
@@ -46,6 +113,12 @@ impl HttpOptions {
         Self::new(None, None, None)
     }
 
+    // sockets treat a timeout of None as no timeout, and reject a timeout of zero.
+    // timeout: a timeout from the options, where zero means no timeout.
+    pub fn timeout_or_none(timeout: Duration) -> Option<Duration> {
+        if timeout.is_zero() { None } else { Some(timeout) }
+    }
+
     // create a new HttpOptions struct as a service from an ip address, port, and port for https.
     // if any of the parameters are None, the default value will be used.
     // the default values are:
@@ -64,6 +137,57 @@ impl HttpOptions {
     pub fn new_service_default() -> Box<dyn Any> {
         Self::new_service(None, None, None)
     }
+
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "http";
+
+    // set the options from a configuration section. options that are not set in the section are left unchanged.
+    // durations are given in seconds, and a https_certificate_path or https_private_key_path of "" turns https off.
+    // configuration: the section to bind from, such as the "http" section.
+    // returns: an error if a value could not be converted.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        if let Some(ip) = configuration.get("ip") {
+            self.ip = Cow::Owned(ip);
+        }
+        ConfigurationExtensions::bind(configuration, "port", &mut self.port)?;
+        ConfigurationExtensions::bind(configuration, "port_https", &mut self.port_https)?;
+        ConfigurationExtensions::bind(configuration, "keep_alive", &mut self.keep_alive)?;
+        ConfigurationExtensions::bind_seconds(configuration, "keep_alive_timeout", &mut self.keep_alive_timeout)?;
+        ConfigurationExtensions::bind(configuration, "max_requests_per_connection", &mut self.max_requests_per_connection)?;
+        ConfigurationExtensions::bind(configuration, "worker_threads", &mut self.worker_threads)?;
+        ConfigurationExtensions::bind_seconds(configuration, "shutdown_timeout", &mut self.shutdown_timeout)?;
+        if let Some(path) = configuration.get("https_certificate_path") {
+            self.https_certificate_path = if path.is_empty() { None } else { Some(Cow::Owned(path)) };
+        }
+        if let Some(path) = configuration.get("https_private_key_path") {
+            self.https_private_key_path = if path.is_empty() { None } else { Some(Cow::Owned(path)) };
+        }
+        ConfigurationExtensions::bind(configuration, "max_url_length", &mut self.max_url_length)?;
+        ConfigurationExtensions::bind(configuration, "max_header_bytes", &mut self.max_header_bytes)?;
+        ConfigurationExtensions::bind(configuration, "max_header_count", &mut self.max_header_count)?;
+        ConfigurationExtensions::bind(configuration, "max_body_bytes", &mut self.max_body_bytes)?;
+        ConfigurationExtensions::bind_seconds(configuration, "read_timeout", &mut self.read_timeout)?;
+        ConfigurationExtensions::bind_seconds(configuration, "write_timeout", &mut self.write_timeout)?;
+        ConfigurationExtensions::bind_seconds(configuration, "request_headers_timeout", &mut self.request_headers_timeout)?;
+        Ok(())
+    }
+
+    // set the options from the "http" section of the configuration service, if there is one.
+    // panics if a value could not be converted, since the program cannot start with invalid options.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
+    }
+
+    // create a new HttpOptions struct as a service with default values and the "http" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_default().with_configuration(services)) as Rc<dyn IHttpOptions>)]
+    }
 }
 
 impl IHttpOptions for HttpOptions {
@@ -82,4 +206,60 @@ impl IHttpOptions for HttpOptions {
     fn get_ip_and_port(self: &Self) -> String {
         format!("{}:{}", self.ip, self.port)
     }
+
+    fn get_keep_alive(self: &Self) -> bool {
+        self.keep_alive
+    }
+
+    fn get_keep_alive_timeout(self: &Self) -> Duration {
+        self.keep_alive_timeout
+    }
+
+    fn get_max_requests_per_connection(self: &Self) -> u32 {
+        self.max_requests_per_connection
+    }
+
+    fn get_worker_threads(self: &Self) -> usize {
+        self.worker_threads
+    }
+
+    fn get_shutdown_timeout(self: &Self) -> Duration {
+        self.shutdown_timeout
+    }
+
+    fn get_https_certificate_path(self: &Self) -> Option<String> {
+        self.https_certificate_path.as_ref().map(|x| x.to_string())
+    }
+
+    fn get_https_private_key_path(self: &Self) -> Option<String> {
+        self.https_private_key_path.as_ref().map(|x| x.to_string())
+    }
+
+    fn get_max_url_length(self: &Self) -> usize {
+        self.max_url_length
+    }
+
+    fn get_max_header_bytes(self: &Self) -> usize {
+        self.max_header_bytes
+    }
+
+    fn get_max_header_count(self: &Self) -> usize {
+        self.max_header_count
+    }
+
+    fn get_max_body_bytes(self: &Self) -> usize {
+        self.max_body_bytes
+    }
+
+    fn get_read_timeout(self: &Self) -> Duration {
+        self.read_timeout
+    }
+
+    fn get_write_timeout(self: &Self) -> Duration {
+        self.write_timeout
+    }
+
+    fn get_request_headers_timeout(self: &Self) -> Duration {
+        self.request_headers_timeout
+    }
 }
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::config::configuration::{ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
+
+
+// this trait abstracts the options for the local user store.
+pub trait IIdentityOptions {
+    // get the path of the JSON file the users are stored in.
+    fn get_users_file_path(self: &Self) -> String;
+    // get the shortest password that is accepted, in characters.
+    fn get_minimum_password_length(self: &Self) -> usize;
+    // get how many failed logins in a row lock out a user.
+    fn get_max_failed_access_attempts(self: &Self) -> u32;
+    // get how long a user is locked out for.
+    fn get_lockout_time(self: &Self) -> Duration;
+    // get how many PBKDF2 iterations passwords are hashed with.
+    fn get_password_hash_iterations(self: &Self) -> u32;
+}
+
+// this struct implements IIdentityOptions.
+#[derive(Debug, Clone)]
+pub struct IdentityOptions {
+    pub users_file_path: String,
+    pub minimum_password_length: usize,
+    pub max_failed_access_attempts: u32,
+    pub lockout_time: Duration,
+    pub password_hash_iterations: u32,
+}
+
+impl IdentityOptions {
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "identity";
+
+    // create a new IdentityOptions struct with default values.
+    // the number of iterations follows the OWASP recommendation for PBKDF2-HMAC-SHA256.
+    pub fn new_defaults() -> Self {
+        Self {
+            users_file_path: "data/authuser_dbset.json".to_string(),
+            minimum_password_length: 8,
+            max_failed_access_attempts: 5,
+            lockout_time: Duration::from_secs(5 * 60),
+            password_hash_iterations: 600_000,
+        }
+    }
+
+    // set the options from a configuration section. options that are not set in the section are left unchanged.
+    // configuration: the section to bind from, such as the "identity" section.
+    // returns: an error if a value cannot be parsed.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        ConfigurationExtensions::bind(configuration, "users_file_path", &mut self.users_file_path)?;
+        ConfigurationExtensions::bind(configuration, "minimum_password_length", &mut self.minimum_password_length)?;
+        ConfigurationExtensions::bind(configuration, "max_failed_access_attempts", &mut self.max_failed_access_attempts)?;
+        ConfigurationExtensions::bind_seconds(configuration, "lockout_time", &mut self.lockout_time)?;
+        ConfigurationExtensions::bind(configuration, "password_hash_iterations", &mut self.password_hash_iterations)?;
+        Ok(())
+    }
+
+    // set the options from the "identity" section of the configuration service, if there is one.
+    // panics if a value cannot be parsed.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
+    }
+
+    // create a new IdentityOptions struct as a service with default values and the "identity" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_defaults().with_configuration(services)) as Rc<dyn IIdentityOptions>)]
+    }
+}
+
+impl IIdentityOptions for IdentityOptions {
+    fn get_users_file_path(self: &Self) -> String {
+        self.users_file_path.clone()
+    }
+
+    fn get_minimum_password_length(self: &Self) -> usize {
+        self.minimum_password_length
+    }
+
+    fn get_max_failed_access_attempts(self: &Self) -> u32 {
+        self.max_failed_access_attempts
+    }
+
+    fn get_lockout_time(self: &Self) -> Duration {
+        self.lockout_time
+    }
+
+    fn get_password_hash_iterations(self: &Self) -> u32 {
+        self.password_hash_iterations
+    }
+}
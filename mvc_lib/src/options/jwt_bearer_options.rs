@@ -0,0 +1,197 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::config::configuration::{Configuration, ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
+
+
+// this trait abstracts the options for authenticating requests with a JWT bearer token.
+pub trait IJwtBearerOptions {
+    // get the secret keys HS256 tokens are signed with.
+    fn get_secret_keys(self: &Self) -> Vec<Vec<u8>>;
+    // get the public keys RS256 tokens are signed for, as DER encoded RSAPublicKey structures.
+    fn get_public_keys(self: &Self) -> Vec<Vec<u8>>;
+    // get the issuer the "iss" claim must match, if it is checked.
+    fn get_issuer(self: &Self) -> Option<String>;
+    // get the audience the "aud" claim must contain, if it is checked.
+    fn get_audience(self: &Self) -> Option<String>;
+    // get how far the clocks of the token issuer and the server can be apart when checking "exp" and "nbf".
+    fn get_clock_skew(self: &Self) -> Duration;
+    // get the realm of the WWW-Authenticate header of a challenge.
+    fn get_realm(self: &Self) -> String;
+}
+
+// this struct implements IJwtBearerOptions.
+#[derive(Debug, Clone)]
+pub struct JwtBearerOptions {
+    // the HS256 secret keys, which should each be at least 32 bytes.
+    pub secret_keys: Vec<Vec<u8>>,
+    pub public_keys: Vec<Vec<u8>>,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    pub clock_skew: Duration,
+    pub realm: String,
+}
+
+impl JwtBearerOptions {
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "jwt_bearer";
+    // the smallest secret key that is accepted from the configuration, in bytes.
+    pub const MINIMUM_SECRET_KEY_LENGTH: usize = 32;
+
+    // create a new JwtBearerOptions struct with default values.
+    // there are no keys by default, so no tokens are accepted until keys are set.
+    pub fn new_defaults() -> Self {
+        Self {
+            secret_keys: vec![],
+            public_keys: vec![],
+            issuer: None,
+            audience: None,
+            clock_skew: Duration::from_secs(60),
+            realm: "api".to_string(),
+        }
+    }
+
+    // read an RSA public key from a PEM file, which can be a "PUBLIC KEY" or an "RSA PUBLIC KEY".
+    // pem: the contents of the PEM file.
+    // returns: the DER encoded RSAPublicKey structure, or an error if the key cannot be read.
+    pub fn parse_public_key_pem(pem: &str) -> Result<Vec<u8>, String> {
+        let label = pem.lines().map(|x| x.trim()).find(|x| x.starts_with("-----BEGIN "))
+            .ok_or_else(|| "the key is not PEM encoded".to_string())?;
+        let body = pem.lines().map(|x| x.trim()).filter(|x| !x.is_empty() && !x.starts_with("-----")).collect::<String>();
+        let der = STANDARD.decode(body).map_err(|e| format!("the key is not valid base64: {}", e))?;
+        match label {
+            "-----BEGIN RSA PUBLIC KEY-----" => Ok(der),
+            "-----BEGIN PUBLIC KEY-----" => Self::get_rsa_public_key(&der).ok_or_else(|| "the key is not an RSA public key".to_string()),
+            _ => Err(format!("the key must be a PUBLIC KEY or RSA PUBLIC KEY, not {}", label)),
+        }
+    }
+
+    // get the RSAPublicKey from a DER encoded SubjectPublicKeyInfo, which is a sequence of the algorithm and a bit string of the key.
+    fn get_rsa_public_key(der: &[u8]) -> Option<Vec<u8>> {
+        // the object identifier of rsaEncryption, 1.2.840.113549.1.1.1.
+        const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+        let (tag, spki, _) = Self::read_der(der)?;
+        if tag != 0x30 {
+            return None;
+        }
+        let (tag, algorithm, rest) = Self::read_der(spki)?;
+        let (_, oid, _) = Self::read_der(algorithm)?;
+        if tag != 0x30 || oid != RSA_ENCRYPTION {
+            return None;
+        }
+        let (tag, key, _) = Self::read_der(rest)?;
+        // the first byte of a bit string is the number of unused bits, which is 0 for a key.
+        match (tag, key.split_first()) {
+            (0x03, Some((0, key))) => Some(key.to_vec()),
+            _ => None,
+        }
+    }
+
+    // read a DER value.
+    // returns: the tag, the contents and the bytes after the value.
+    fn read_der(der: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+        let (&tag, rest) = der.split_first()?;
+        let (&first, mut rest) = rest.split_first()?;
+        let length = if first < 0x80 {
+            first as usize
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return None;
+            }
+            let length = rest[..count].iter().fold(0usize, |acc, x| (acc << 8) | *x as usize);
+            rest = &rest[count..];
+            length
+        };
+        if rest.len() < length {
+            return None;
+        }
+        Some((tag, &rest[..length], &rest[length..]))
+    }
+
+    // set the options from a configuration section. options that are not set in the section are left unchanged.
+    // secret_keys is a list of HS256 secret keys, and public_key_files is a list of paths of PEM files with RS256 public keys.
+    // configuration: the section to bind from, such as the "jwt_bearer" section.
+    // returns: an error if a value cannot be parsed, a secret key is too short or a public key cannot be read.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        if let Some(issuer) = configuration.get("issuer") {
+            self.issuer = Some(issuer);
+        }
+        if let Some(audience) = configuration.get("audience") {
+            self.audience = Some(audience);
+        }
+        ConfigurationExtensions::bind_seconds(configuration, "clock_skew", &mut self.clock_skew)?;
+        ConfigurationExtensions::bind(configuration, "realm", &mut self.realm)?;
+
+        let secret_keys = ConfigurationExtensions::get_list(configuration, "secret_keys");
+        if !secret_keys.is_empty() {
+            if secret_keys.iter().any(|x| x.len() < Self::MINIMUM_SECRET_KEY_LENGTH) {
+                return Err(ConfigurationError::new(
+                    Configuration::combine(&configuration.get_path(), "secret_keys"),
+                    format!("each secret key must be at least {} bytes", Self::MINIMUM_SECRET_KEY_LENGTH)));
+            }
+            self.secret_keys = secret_keys.into_iter().map(|x| x.into_bytes()).collect();
+        }
+
+        let public_key_files = ConfigurationExtensions::get_list(configuration, "public_key_files");
+        if !public_key_files.is_empty() {
+            let key = Configuration::combine(&configuration.get_path(), "public_key_files");
+            self.public_keys = public_key_files.iter()
+                .map(|path| std::fs::read_to_string(path)
+                    .map_err(|e| format!("could not read {}: {}", path, e))
+                    .and_then(|pem| Self::parse_public_key_pem(&pem).map_err(|e| format!("could not read {}: {}", path, e))))
+                .collect::<Result<Vec<Vec<u8>>, String>>()
+                .map_err(|e| ConfigurationError::new(key, e))?;
+        }
+        Ok(())
+    }
+
+    // set the options from the "jwt_bearer" section of the configuration service, if there is one.
+    // panics if a value cannot be parsed, a secret key is too short or a public key cannot be read.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
+    }
+
+    // create a new JwtBearerOptions struct as a service with default values and the "jwt_bearer" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_defaults().with_configuration(services)) as Rc<dyn IJwtBearerOptions>)]
+    }
+}
+
+impl IJwtBearerOptions for JwtBearerOptions {
+    fn get_secret_keys(self: &Self) -> Vec<Vec<u8>> {
+        self.secret_keys.clone()
+    }
+
+    fn get_public_keys(self: &Self) -> Vec<Vec<u8>> {
+        self.public_keys.clone()
+    }
+
+    fn get_issuer(self: &Self) -> Option<String> {
+        self.issuer.clone()
+    }
+
+    fn get_audience(self: &Self) -> Option<String> {
+        self.audience.clone()
+    }
+
+    fn get_clock_skew(self: &Self) -> Duration {
+        self.clock_skew
+    }
+
+    fn get_realm(self: &Self) -> String {
+        self.realm.clone()
+    }
+}
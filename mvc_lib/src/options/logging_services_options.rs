@@ -1,3 +1,10 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use crate::config::configuration::{ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
+
 
 // this trait abstracts the options for logging HTTP requests.
 pub trait ILogHttpRequestsOptions {
@@ -16,6 +23,7 @@ pub trait ILogHttpRequestsOptions {
 }
 
 // this struct implements ILogHttpRequestsOptions.
+#[derive(Debug, Clone)]
 pub struct LogHttpRequestsOptions {
     pub log_request: bool,
     pub log_response: bool,
@@ -27,6 +35,53 @@ pub struct LogHttpRequestsOptions {
     pub log_response_cookies: bool,
 }
 
+impl LogHttpRequestsOptions {
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "log_http_requests";
+
+    // create a new LogHttpRequestsOptions struct that does not log anything.
+    pub fn new_defaults() -> Self {
+        Self {
+            log_request: false,
+            log_response: false,
+            log_request_headers: false,
+            log_response_headers: false,
+            log_request_cookies: false,
+            log_response_cookies: false,
+        }
+    }
+
+    // set the options from a configuration section. options that are not set in the section are left unchanged.
+    // configuration: the section to bind from, such as the "log_http_requests" section.
+    // returns: an error if a value is not a bool.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        ConfigurationExtensions::bind(configuration, "log_request", &mut self.log_request)?;
+        ConfigurationExtensions::bind(configuration, "log_response", &mut self.log_response)?;
+        ConfigurationExtensions::bind(configuration, "log_request_headers", &mut self.log_request_headers)?;
+        ConfigurationExtensions::bind(configuration, "log_response_headers", &mut self.log_response_headers)?;
+        ConfigurationExtensions::bind(configuration, "log_request_cookies", &mut self.log_request_cookies)?;
+        ConfigurationExtensions::bind(configuration, "log_response_cookies", &mut self.log_response_cookies)?;
+        Ok(())
+    }
+
+    // set the options from the "log_http_requests" section of the configuration service, if there is one.
+    // panics if a value is not a bool.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
+    }
+
+    // create a new LogHttpRequestsOptions struct as a service with default values and the "log_http_requests" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_defaults().with_configuration(services)) as Rc<dyn ILogHttpRequestsOptions>)]
+    }
+}
+
 impl ILogHttpRequestsOptions for LogHttpRequestsOptions {
     fn get_log_request(self: &Self) -> bool {
         self.log_request
@@ -1,3 +1,8 @@
+pub mod cookie_authentication_options;
 pub mod file_provider_controller_options;
 pub mod http_options;
-pub mod logging_services_options;
\ No newline at end of file
+pub mod identity_options;
+pub mod jwt_bearer_options;
+pub mod logging_services_options;
+pub mod response_compression_options;
+pub mod session_options;
\ No newline at end of file
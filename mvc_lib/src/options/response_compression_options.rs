@@ -0,0 +1,74 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::rc::Rc;
+
+use crate::http::http_body_content::ContentType;
+
+// this trait abstracts the options for compressing response bodies.
+pub trait IResponseCompressionOptions {
+    // get the smallest response body, in bytes, that is compressed when its length is known.
+    fn get_minimum_size(self: &Self) -> usize;
+
+    // get the MIME types of the responses that are compressed.
+    // a type ending with "/*" matches every subtype, such as "text/*".
+    fn get_mime_types(self: &Self) -> Vec<Cow<'static, str>>;
+
+    // returns true if responses with the content type can be compressed.
+    // content_type: the Content-Type of the response.
+    fn is_compressible(self: &Self, content_type: &ContentType) -> bool {
+        let mime_type = content_type.mime_type.split(';').next().unwrap_or("").trim().to_lowercase();
+        self.get_mime_types().iter().any(|x| match x.strip_suffix("/*") {
+            Some(prefix) => mime_type.starts_with(prefix) && mime_type[prefix.len()..].starts_with('/'),
+            None => x.eq_ignore_ascii_case(&mime_type),
+        })
+    }
+}
+
+// this struct implements IResponseCompressionOptions.
+#[derive(Debug, Clone)]
+pub struct ResponseCompressionOptions {
+    // the smallest response body, in bytes, that is compressed when its length is known.
+    pub minimum_size: usize,
+    // the MIME types of the responses that are compressed.
+    pub mime_types: Vec<Cow<'static, str>>,
+}
+
+impl ResponseCompressionOptions {
+    // create a new ResponseCompressionOptions struct.
+    // minimum_size: the smallest response body, in bytes, that is compressed when its length is known.
+    // mime_types: the MIME types of the responses that are compressed.
+    pub fn new(minimum_size: usize, mime_types: Vec<Cow<'static, str>>) -> Self {
+        Self {
+            minimum_size: minimum_size,
+            mime_types: mime_types,
+        }
+    }
+
+    // create a new ResponseCompressionOptions struct with default values.
+    // text based responses of at least 1 KiB are compressed, since smaller bodies rarely get smaller.
+    pub fn new_defaults() -> Self {
+        Self::new(1024, vec![
+            Cow::Borrowed("text/*"),
+            Cow::Borrowed("application/json"),
+            Cow::Borrowed("application/javascript"),
+            Cow::Borrowed("application/xml"),
+            Cow::Borrowed("application/wasm"),
+            Cow::Borrowed("image/svg+xml"),
+        ])
+    }
+
+    // create a new ResponseCompressionOptions struct as a service with default values.
+    pub fn new_service_defaults() -> Box<dyn Any> {
+        Box::new(Rc::new(Self::new_defaults()) as Rc<dyn IResponseCompressionOptions>)
+    }
+}
+
+impl IResponseCompressionOptions for ResponseCompressionOptions {
+    fn get_minimum_size(self: &Self) -> usize {
+        self.minimum_size
+    }
+
+    fn get_mime_types(self: &Self) -> Vec<Cow<'static, str>> {
+        self.mime_types.clone()
+    }
+}
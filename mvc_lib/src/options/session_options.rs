@@ -0,0 +1,99 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::config::configuration::{ConfigurationExtensions, IConfiguration};
+use crate::errors::ConfigurationError;
+use crate::services::service_collection::IServiceCollection;
+
+
+// this trait abstracts the options for the server-side session of a request.
+pub trait ISessionOptions {
+    // get the name of the cookie the session id is kept in.
+    fn get_cookie_name(self: &Self) -> String;
+    // get how long a session is kept after the last request that used it.
+    fn get_idle_timeout(self: &Self) -> Duration;
+    // get how long a session is kept after it was created, however often it is used.
+    fn get_absolute_timeout(self: &Self) -> Duration;
+    // get whether the session cookie is only sent over HTTPS, even for requests made over HTTP.
+    fn get_secure_cookie(self: &Self) -> bool;
+    // get the path of the json file sessions are kept in by the JsonFileSessionStore.
+    fn get_file_path(self: &Self) -> String;
+}
+
+// this struct implements ISessionOptions.
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    pub cookie_name: String,
+    pub idle_timeout: Duration,
+    pub absolute_timeout: Duration,
+    pub secure_cookie: bool,
+    pub file_path: String,
+}
+
+impl SessionOptions {
+    // the configuration section the options are bound from.
+    pub const CONFIGURATION_SECTION: &'static str = "session";
+
+    // create a new SessionOptions struct with default values.
+    pub fn new_defaults() -> Self {
+        Self {
+            cookie_name: "rshtml.session".to_string(),
+            idle_timeout: Duration::from_secs(60 * 20),
+            absolute_timeout: Duration::from_secs(60 * 60 * 8),
+            secure_cookie: false,
+            file_path: "data/session_dbset.json".to_string(),
+        }
+    }
+
+    // set the options from a configuration section. options that are not set in the section are left unchanged.
+    // configuration: the section to bind from, such as the "session" section.
+    // returns: an error if a value cannot be parsed.
+    pub fn bind(self: &mut Self, configuration: &dyn IConfiguration) -> Result<(), ConfigurationError> {
+        ConfigurationExtensions::bind(configuration, "cookie_name", &mut self.cookie_name)?;
+        ConfigurationExtensions::bind_seconds(configuration, "idle_timeout", &mut self.idle_timeout)?;
+        ConfigurationExtensions::bind_seconds(configuration, "absolute_timeout", &mut self.absolute_timeout)?;
+        ConfigurationExtensions::bind(configuration, "secure_cookie", &mut self.secure_cookie)?;
+        ConfigurationExtensions::bind(configuration, "file_path", &mut self.file_path)?;
+        Ok(())
+    }
+
+    // set the options from the "session" section of the configuration service, if there is one.
+    // panics if a value cannot be parsed.
+    // services: the services to get the configuration from.
+    pub fn with_configuration(mut self, services: &dyn IServiceCollection) -> Self {
+        if let Some(section) = ConfigurationExtensions::try_get_section(services, Self::CONFIGURATION_SECTION) {
+            if let Err(e) = self.bind(section.as_ref()) {
+                panic!("{}", e);
+            }
+        }
+        self
+    }
+
+    // create a new SessionOptions struct as a service with default values and the "session" section of the configuration.
+    pub fn new_service_from_configuration(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_defaults().with_configuration(services)) as Rc<dyn ISessionOptions>)]
+    }
+}
+
+impl ISessionOptions for SessionOptions {
+    fn get_cookie_name(self: &Self) -> String {
+        self.cookie_name.clone()
+    }
+
+    fn get_idle_timeout(self: &Self) -> Duration {
+        self.idle_timeout
+    }
+
+    fn get_absolute_timeout(self: &Self) -> Duration {
+        self.absolute_timeout
+    }
+
+    fn get_secure_cookie(self: &Self) -> bool {
+        self.secure_cookie
+    }
+
+    fn get_file_path(self: &Self) -> String {
+        self.file_path.clone()
+    }
+}
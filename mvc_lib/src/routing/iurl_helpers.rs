@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::errors::UrlGenerationError;
+
 
 
 
@@ -12,6 +14,7 @@ pub trait IUrlHelpers {
     // controller_name: the name of the controller. If None, the current or default controller is used.
     // area_name: the name of the area. If None, the current or default area is used.
     // route_values: the route values for the url. If None, the no route values are used.
+    // returns: the url, or an error if no action matches or the url cannot be made absolute.
     fn url_action(self: &Self,
         is_relative: bool,
         is_https: Option<bool>,
@@ -20,5 +23,13 @@ pub trait IUrlHelpers {
         controller_name: Option<&str>,
         area_name: Option<&str>,
         route_values: Option<&HashMap<String, String>>
-    ) -> String;
+    ) -> Result<String, UrlGenerationError>;
+
+    // get an url by route name, which is the name of the action, within the current context.
+    // the controller and area of the current request are used unless the route values have a controller or area,
+    // and the route pattern that uses the most route values is chosen. the other route values are added to the query string.
+    // route_name: the name of the action.
+    // route_values: the route values for the url.
+    // returns: the relative url, or an error if no action has the name or a route value is missing.
+    fn route(self: &Self, route_name: &str, route_values: &HashMap<String, String>) -> Result<String, UrlGenerationError>;
 }
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::contexts::irequest_context::IRequestContext;
+use crate::controller_actions::controller_action::IControllerAction;
+use crate::controller_actions::route_pattern::RoutePatternSegment;
+use crate::errors::UrlGenerationError;
+use crate::services::service_collection::IServiceCollection;
+
+
+// the route values that choose the action a url is generated for instead of filling in capture groups,
+// unless the route pattern of the action has a capture group with the same name.
+pub const AREA_KEY: &str = "area";
+pub const CONTROLLER_KEY: &str = "controller";
+pub const ACTION_KEY: &str = "action";
+
+// this struct generates urls for actions by route name or by route values.
+// the area, controller and action of the current request (the ambient values) are used for the ones
+// that are not given, so a view can link to another action of its controller by the action name alone.
+// when more than one action could be used, the one whose route pattern uses the most route values is chosen.
+pub struct LinkGenerator {
+    actions: Vec<Rc<dyn IControllerAction>>,
+}
+
+impl LinkGenerator {
+    // create a new link generator.
+    // actions: the actions urls can be generated for.
+    pub fn new(actions: Vec<Rc<dyn IControllerAction>>) -> Self {
+        Self {
+            actions: actions,
+        }
+    }
+
+    // get the ambient values of a request, which are the area, controller and action it was routed to.
+    // the area is empty for actions that are not in an area.
+    // request_context: the current request.
+    // returns: the ambient values by AREA_KEY, CONTROLLER_KEY and ACTION_KEY, or no values if the request was not routed to an action.
+    pub fn get_ambient_values(request_context: &dyn IRequestContext) -> HashMap<String, String> {
+        let action_name = request_context.get_str("ActionName");
+        if action_name.is_empty() {
+            return HashMap::new();
+        }
+        HashMap::from([
+            (AREA_KEY.to_string(), request_context.get_str("AreaName")),
+            (CONTROLLER_KEY.to_string(), request_context.get_str("ControllerName")),
+            (ACTION_KEY.to_string(), action_name),
+        ])
+    }
+
+    // generate the path of the action with a route name, which is the name of the action.
+    // the controller and area are taken from the route values, or else from the ambient values
+    // if the current controller and area have an action with the name.
+    // route_name: the name of the action.
+    // route_values: the values for the capture groups and query string.
+    // ambient_values: the ambient values of the current request.
+    // services: the services to find custom route constraints in.
+    // returns: the path and query string, or an error if no action has the name or no route pattern fits the route values.
+    pub fn get_path_by_name(
+        self: &Self,
+        route_name: &str,
+        route_values: &HashMap<String, String>,
+        ambient_values: &HashMap<String, String>,
+        services: Option<&dyn IServiceCollection>,
+    ) -> Result<String, UrlGenerationError> {
+        self.get_path(route_name, route_name, route_values, ambient_values, services)
+    }
+
+    // generate the path of the action chosen by the ACTION_KEY, CONTROLLER_KEY and AREA_KEY route values.
+    // the ambient action is only used when the controller is not given, since it belongs to the ambient controller.
+    // route_values: the values that choose the action and the values for the capture groups and query string.
+    // ambient_values: the ambient values of the current request.
+    // services: the services to find custom route constraints in.
+    // returns: the path and query string, or an error if no action matches or no route pattern fits the route values.
+    pub fn get_path_by_values(
+        self: &Self,
+        route_values: &HashMap<String, String>,
+        ambient_values: &HashMap<String, String>,
+        services: Option<&dyn IServiceCollection>,
+    ) -> Result<String, UrlGenerationError> {
+        let action_name = match route_values.get(ACTION_KEY) {
+            Some(action_name) => action_name,
+            None if !route_values.contains_key(CONTROLLER_KEY) => match ambient_values.get(ACTION_KEY) {
+                Some(action_name) => action_name,
+                None => return Err(UrlGenerationError::new("route values".to_string(), "no action was given and there is no current action".to_string())),
+            },
+            None => return Err(UrlGenerationError::new("route values".to_string(), "no action was given for the controller".to_string())),
+        };
+        self.get_path(action_name, action_name, route_values, ambient_values, services)
+    }
+
+    fn get_path(
+        self: &Self,
+        route: &str,
+        action_name: &str,
+        route_values: &HashMap<String, String>,
+        ambient_values: &HashMap<String, String>,
+        services: Option<&dyn IServiceCollection>,
+    ) -> Result<String, UrlGenerationError> {
+        let error = |message: String| UrlGenerationError::new(route.to_string(), message);
+
+        let mut candidates = self.actions.iter().filter(|x| x.get_name() == action_name).cloned().collect::<Vec<Rc<dyn IControllerAction>>>();
+        Self::filter_candidates(&mut candidates, CONTROLLER_KEY, |x| Self::get_controller_name(x.as_ref()), route_values, ambient_values);
+        Self::filter_candidates(&mut candidates, AREA_KEY, |x| x.get_area_name(), route_values, ambient_values);
+        if candidates.is_empty() {
+            return Err(error(format!(
+                "no action named {} in controller {} and area {}",
+                action_name,
+                route_values.get(CONTROLLER_KEY).or(ambient_values.get(CONTROLLER_KEY)).map(|x| x.as_str()).unwrap_or("(any)"),
+                route_values.get(AREA_KEY).or(ambient_values.get(AREA_KEY)).map(|x| x.as_str()).unwrap_or("(any)"))));
+        }
+
+        // the best route pattern fills the most capture groups with the route values,
+        // then leaves the fewest route values for the query string.
+        let mut best: Option<((Reverse<usize>, usize), String)> = None;
+        let mut first_error = None;
+        for action in candidates {
+            let route_pattern = action.get_route_pattern();
+            let values = Self::get_values_for_action(action.as_ref(), route_values);
+            match route_pattern.gen_url_with_services(&values, services) {
+                Ok(url) => {
+                    let query_count = values.keys().filter(|x| !route_pattern.captures.contains_key(x.as_str())).count();
+                    let score = (Reverse(values.len() - query_count), query_count);
+                    if best.as_ref().map(|x| score < x.0).unwrap_or(true) {
+                        best = Some((score, url));
+                    }
+                },
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                },
+            }
+        }
+
+        match (best, first_error) {
+            (Some((_, url)), _) => Ok(url),
+            (None, Some(err)) => Err(error(err.to_string())),
+            (None, None) => Err(error("no route pattern fits the route values".to_string())),
+        }
+    }
+
+    // get the name of the controller of an action without the "Controller" suffix, as in the ambient values.
+    fn get_controller_name(action: &dyn IControllerAction) -> String {
+        let controller_name = action.get_controller_name();
+        controller_name.strip_suffix("Controller").unwrap_or(&controller_name).to_string()
+    }
+
+    // keep the candidates for the route value, or else the ambient value if any candidate has it.
+    fn filter_candidates(
+        candidates: &mut Vec<Rc<dyn IControllerAction>>,
+        key: &str,
+        get_value: fn(&Rc<dyn IControllerAction>) -> String,
+        route_values: &HashMap<String, String>,
+        ambient_values: &HashMap<String, String>,
+    ) {
+        if let Some(value) = route_values.get(key) {
+            candidates.retain(|x| get_value(x).eq_ignore_ascii_case(value));
+        } else if let Some(value) = ambient_values.get(key) {
+            if candidates.iter().any(|x| get_value(x).eq_ignore_ascii_case(value)) {
+                candidates.retain(|x| get_value(x).eq_ignore_ascii_case(value));
+            }
+        }
+    }
+
+    // get the route values for the route pattern of an action: the area, controller and action are removed
+    // unless the route pattern captures them, in which case they are the names of the action.
+    fn get_values_for_action(action: &dyn IControllerAction, route_values: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut values = route_values.iter()
+            .filter(|(key, _)| ![AREA_KEY, CONTROLLER_KEY, ACTION_KEY].contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<HashMap<String, String>>();
+        for segment in action.get_route_pattern().segments.iter() {
+            if let RoutePatternSegment::Capture(capture) = segment {
+                let value = match capture.name.as_str() {
+                    AREA_KEY => action.get_area_name(),
+                    CONTROLLER_KEY => Self::get_controller_name(action),
+                    ACTION_KEY => action.get_name().to_string(),
+                    _ => continue,
+                };
+                values.insert(capture.name.clone(), value);
+            }
+        }
+        values
+    }
+}
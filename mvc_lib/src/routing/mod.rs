@@ -2,5 +2,8 @@ pub mod action_path;
 pub mod route_data;
 pub mod iurl_helpers;
 pub mod url_helpers;
+pub mod link_generator;
 pub mod path_builder;
+pub mod route_constraint;
+pub mod route_table;
 pub mod route_values_builder;
\ No newline at end of file
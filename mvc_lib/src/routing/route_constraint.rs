@@ -0,0 +1,150 @@
+use std::any::Any;
+use std::fmt;
+
+use regex::Regex;
+
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// this trait represents a custom route constraint, used in a route pattern by name like {code:postal_code}.
+// custom constraints are added to the services with their name as the key by RouteConstraint::add_custom,
+// and are checked when a request is matched to a route.
+pub trait IRouteConstraint {
+    // get whether or not a value captured from the path meets the constraint.
+    // value: the value captured from the path.
+    // args: the arguments of the constraint, such as ["1", "10"] for {id:my_constraint(1,10)}.
+    fn is_match(self: &Self, value: &str, args: &[String]) -> bool;
+}
+
+// this enum is a constraint on a capture group of a route pattern, such as int in /users/{id:int}.
+// the built in constraints are:
+// - int: a signed integer
+// - uint: an unsigned integer
+// - guid: a guid, such as 6f9619ff-8b86-d011-b42d-00c04fc964ff
+// - bool: true or false
+// - alpha: one or more letters from a to z, in either case
+// - length(n) or length(min,max): a number of characters
+// - range(min,max): an integer between min and max, inclusive
+// - regex(expression): a value the whole regular expression matches
+// any other name is a custom constraint that is looked up in the services by name.
+#[derive(Clone, Debug)]
+pub enum RouteConstraint {
+    Int,
+    UInt,
+    Guid,
+    Bool,
+    Alpha,
+    Length(usize, usize),
+    Range(i64, i64),
+    Regex(Regex),
+    Custom(String, Vec<String>),
+}
+
+impl RouteConstraint {
+    // parse a constraint, such as "int" or "length(1,10)".
+    // s: the constraint to parse.
+    // returns: the constraint, or a message describing why it could not be parsed.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (name, args) = match s.find('(') {
+            Some(start) => {
+                if !s.ends_with(')') {
+                    return Err(format!("constraint {} is missing a closing ')'", s));
+                }
+                (&s[..start], Some(&s[start + 1..s.len() - 1]))
+            },
+            None => (s, None),
+        };
+
+        if name.is_empty() {
+            return Err(format!("constraint {} has no name", s));
+        }
+
+        let constraint = match (name, args) {
+            ("int", None) => Self::Int,
+            ("uint", None) => Self::UInt,
+            ("guid", None) => Self::Guid,
+            ("bool", None) => Self::Bool,
+            ("alpha", None) => Self::Alpha,
+            ("length", Some(args)) => {
+                let values = Self::parse_numbers::<usize>(name, args)?;
+                match values.as_slice() {
+                    [length] => Self::Length(*length, *length),
+                    [min, max] if min <= max => Self::Length(*min, *max),
+                    _ => return Err(format!("length takes a length or a minimum and maximum length, not ({})", args)),
+                }
+            },
+            ("range", Some(args)) => {
+                let values = Self::parse_numbers::<i64>(name, args)?;
+                match values.as_slice() {
+                    [min, max] if min <= max => Self::Range(*min, *max),
+                    _ => return Err(format!("range takes a minimum and maximum value, not ({})", args)),
+                }
+            },
+            ("regex", Some(args)) => {
+                // the expression must match the whole value, not just part of it.
+                match Regex::new(&format!("^(?:{})$", args)) {
+                    Ok(regex) => Self::Regex(regex),
+                    Err(err) => return Err(format!("invalid regex ({}): {}", args, err)),
+                }
+            },
+            ("int" | "uint" | "guid" | "bool" | "alpha", Some(_)) => return Err(format!("constraint {} does not take arguments", name)),
+            ("length" | "range" | "regex", None) => return Err(format!("constraint {} needs arguments", name)),
+            (name, args) => Self::Custom(name.to_string(), args.map(|x| x.split(',').map(|x| x.trim().to_string()).collect()).unwrap_or_default()),
+        };
+        Ok(constraint)
+    }
+
+    fn parse_numbers<T: std::str::FromStr>(name: &str, args: &str) -> Result<Vec<T>, String> {
+        args.split(',')
+            .map(|x| x.trim().parse::<T>().map_err(|_| format!("invalid argument '{}' for {}", x.trim(), name)))
+            .collect()
+    }
+
+    // get whether or not a value meets the constraint.
+    // value: the value captured from the path.
+    // services: the services to find custom constraints in, or None if custom constraints should not match.
+    pub fn is_match(self: &Self, value: &str, services: Option<&dyn IServiceCollection>) -> bool {
+        match self {
+            Self::Int => value.parse::<i64>().is_ok(),
+            Self::UInt => value.parse::<u64>().is_ok(),
+            Self::Guid => uuid::Uuid::parse_str(value).is_ok(),
+            Self::Bool => value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false"),
+            Self::Alpha => !value.is_empty() && value.chars().all(|x| x.is_ascii_alphabetic()),
+            Self::Length(min, max) => (*min..=*max).contains(&value.chars().count()),
+            Self::Range(min, max) => value.parse::<i64>().map(|x| (*min..=*max).contains(&x)).unwrap_or(false),
+            Self::Regex(regex) => regex.is_match(value),
+            Self::Custom(name, args) => match services.and_then(|x| ServiceCollectionExtensions::try_get_keyed::<dyn IRouteConstraint>(x, name)) {
+                Some(constraint) => constraint.is_match(value, args),
+                None => false,
+            },
+        }
+    }
+
+    // add a custom constraint to the service collection with its name as the key.
+    // name: the name of the constraint, as used in route patterns.
+    // new_constraint: creates the constraint as a Rc<dyn IRouteConstraint>.
+    pub fn add_custom(services: &mut ServiceCollection, name: &str, new_constraint: fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>) {
+        services.add_keyed(name, ServiceDescriptor::new(TypeInfo::rc_of::<dyn IRouteConstraint>(), new_constraint, ServiceScope::Singleton));
+    }
+}
+
+impl fmt::Display for RouteConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Int => write!(f, "int"),
+            Self::UInt => write!(f, "uint"),
+            Self::Guid => write!(f, "guid"),
+            Self::Bool => write!(f, "bool"),
+            Self::Alpha => write!(f, "alpha"),
+            Self::Length(min, max) if min == max => write!(f, "length({})", min),
+            Self::Length(min, max) => write!(f, "length({},{})", min, max),
+            Self::Range(min, max) => write!(f, "range({},{})", min, max),
+            Self::Regex(regex) => write!(f, "regex({})", &regex.as_str()[4..regex.as_str().len() - 2]),
+            Self::Custom(name, args) if args.is_empty() => write!(f, "{}", name),
+            Self::Custom(name, args) => write!(f, "{}({})", name, args.join(",")),
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use http::Method;
+
+use crate::controller_actions::controller_action::IControllerAction;
+use crate::controller_actions::route_pattern::{ControllerActionRoutePattern, RoutePatternSegment};
+use crate::services::service_collection::IServiceCollection;
+
+
+// this enum is the result of matching a request to the route table.
+pub enum RouteMatch {
+    // an action matched the path and method of the request, with the values captured from the path.
+    Found(Rc<dyn IControllerAction>, HashMap<String, String>),
+    // actions matched the path but none of them allow the method of the request.
+    // the methods the actions allow, for the Allow header of a 405 response.
+    MethodNotAllowed(Vec<Method>),
+    // no action matched the path.
+    NotFound,
+}
+
+// this struct is two or more actions with the same route pattern and HTTP methods,
+// so a request cannot choose between them.
+#[derive(Clone, Debug)]
+pub struct RouteAmbiguity {
+    // the route pattern the actions have in common.
+    pub route_pattern: String,
+    // the actions, as strings.
+    pub actions: Vec<String>,
+}
+
+impl fmt::Display for RouteAmbiguity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ambiguous route {} is used by {}", self.route_pattern, self.actions.join(", "))
+    }
+}
+
+// an action in the route table.
+struct RouteTableEndpoint {
+    action: Rc<dyn IControllerAction>,
+    route_pattern: Rc<ControllerActionRoutePattern>,
+    // how specific each segment of the route pattern is, lower is more specific.
+    precedence: Vec<u8>,
+}
+
+// a node of the route table, for one segment of a path.
+#[derive(Default)]
+struct RouteTableNode {
+    // the nodes for literal segments, by text.
+    literals: HashMap<String, RouteTableNode>,
+    // the node for capture groups, whatever their name or constraints.
+    parameter: Option<Box<RouteTableNode>>,
+    // the endpoints that can end at this node, including those whose remaining segments can be left out.
+    endpoints: Vec<usize>,
+    // the endpoints with a .. after this node.
+    catch_all: Vec<usize>,
+}
+
+// this struct is a table of the routes of every action, built once so a request is matched
+// by walking the segments of its path instead of testing every action.
+// when more than one action matches, literal segments win over capture groups with constraints,
+// which win over capture groups without constraints, which win over optional capture groups and then ..
+// for the same route, actions for specific HTTP methods win over actions for any method.
+pub struct RouteTable {
+    root: RouteTableNode,
+    endpoints: Vec<RouteTableEndpoint>,
+    ambiguities: Vec<RouteAmbiguity>,
+}
+
+impl RouteTable {
+    // build the route table for a list of actions.
+    // actions: the actions to route requests to.
+    pub fn new(actions: Vec<Rc<dyn IControllerAction>>) -> Self {
+        let mut table = Self { root: RouteTableNode::default(), endpoints: vec![], ambiguities: vec![] };
+        for action in actions {
+            let route_pattern = action.get_route_pattern();
+            let precedence = route_pattern.segments.iter().map(Self::get_precedence).collect();
+            table.endpoints.push(RouteTableEndpoint { action: action, route_pattern: route_pattern, precedence: precedence });
+            table.insert(table.endpoints.len() - 1);
+        }
+        table.ambiguities = table.find_ambiguities();
+        table
+    }
+
+    // get how specific a segment is, lower is more specific.
+    fn get_precedence(segment: &RoutePatternSegment) -> u8 {
+        match segment {
+            RoutePatternSegment::Literal(_) => 0,
+            RoutePatternSegment::Capture(capture) if capture.optional || capture.default.is_some() => 3,
+            RoutePatternSegment::Capture(capture) if !capture.constraints.is_empty() => 1,
+            RoutePatternSegment::Capture(_) => 2,
+            RoutePatternSegment::CatchAll => 4,
+        }
+    }
+
+    fn insert(self: &mut Self, index: usize) {
+        let route_pattern = self.endpoints[index].route_pattern.clone();
+        let segments = &route_pattern.segments;
+        let mut node = &mut self.root;
+        for (i, segment) in segments.iter().enumerate() {
+            // the action can end here if the rest of its segments can be left out.
+            if segments[i..].iter().all(|x| matches!(x, RoutePatternSegment::Capture(capture) if capture.optional || capture.default.is_some())) {
+                node.endpoints.push(index);
+            }
+            node = match segment {
+                RoutePatternSegment::Literal(text) => node.literals.entry(text.clone()).or_default(),
+                RoutePatternSegment::Capture(_) => node.parameter.get_or_insert_with(Default::default).as_mut(),
+                RoutePatternSegment::CatchAll => {
+                    node.catch_all.push(index);
+                    return;
+                },
+            };
+        }
+        node.endpoints.push(index);
+    }
+
+    // find the actions that have the same route pattern and overlapping HTTP methods.
+    fn find_ambiguities(self: &Self) -> Vec<RouteAmbiguity> {
+        let mut by_shape: Vec<(String, Vec<usize>)> = vec![];
+        for (i, endpoint) in self.endpoints.iter().enumerate() {
+            let shape = Self::get_shape(&endpoint.route_pattern);
+            match by_shape.iter_mut().find(|x| x.0 == shape) {
+                Some(group) => group.1.push(i),
+                None => by_shape.push((shape, vec![i])),
+            }
+        }
+
+        let mut ambiguities = vec![];
+        for (_, group) in by_shape {
+            let methods = |i: &usize| self.endpoints[*i].action.get_http_methods_allowed();
+            let conflicting = group.iter()
+                .filter(|a| group.iter().any(|b| a != &b && Self::methods_overlap(&methods(a), &methods(b))))
+                .cloned()
+                .collect::<Vec<usize>>();
+            if !conflicting.is_empty() {
+                ambiguities.push(RouteAmbiguity {
+                    route_pattern: self.endpoints[conflicting[0]].route_pattern.to_string().to_string(),
+                    actions: conflicting.iter().map(|x| self.endpoints[*x].action.to_string()).collect(),
+                });
+            }
+        }
+        ambiguities
+    }
+
+    // get a string that is the same for route patterns that match the same paths, ignoring the names of capture groups.
+    fn get_shape(route_pattern: &ControllerActionRoutePattern) -> String {
+        route_pattern.segments.iter().map(|x| match x {
+            RoutePatternSegment::Literal(text) => text.clone(),
+            RoutePatternSegment::Capture(capture) => format!(
+                "{{:{}{}{}}}",
+                capture.constraints.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(":"),
+                if capture.optional { "?" } else { "" },
+                capture.default.as_ref().map(|x| format!("={}", x)).unwrap_or_default()),
+            RoutePatternSegment::CatchAll => "..".to_string(),
+        }).collect::<Vec<String>>().join("/")
+    }
+
+    // actions with no methods allow every method, but only conflict with each other.
+    // an action for specific methods wins over an action for every method.
+    fn methods_overlap(a: &Vec<Method>, b: &Vec<Method>) -> bool {
+        (a.is_empty() && b.is_empty()) || a.iter().any(|x| b.contains(x))
+    }
+
+    // get the actions that have the same route pattern and overlapping HTTP methods, found when the table was built.
+    pub fn get_ambiguities(self: &Self) -> &Vec<RouteAmbiguity> {
+        &self.ambiguities
+    }
+
+    // match the path and method of a request to an action.
+    // path: the path of the request.
+    // method: the method of the request.
+    // services: the services to find custom route constraints in.
+    // returns: the action and the values captured from the path, or why no action matched.
+    pub fn match_request(self: &Self, path: &str, method: &Method, services: &dyn IServiceCollection) -> RouteMatch {
+        let path_segments = path.trim().split('/').filter(|x| !x.is_empty()).collect::<Vec<&str>>();
+        let mut candidates = vec![];
+        Self::collect_candidates(&self.root, &path_segments, &mut candidates);
+        candidates.sort();
+        candidates.dedup();
+
+        // check the constraints of each candidate, then order them by precedence and then by whether they are for specific methods.
+        let mut matches = candidates.into_iter()
+            .filter_map(|i| self.endpoints[i].route_pattern.try_match_with_services(path, Some(services)).map(|values| (i, values)))
+            .collect::<Vec<(usize, HashMap<String, String>)>>();
+        matches.sort_by_key(|(i, _)| (self.endpoints[*i].precedence.clone(), self.endpoints[*i].action.get_http_methods_allowed().is_empty(), *i));
+
+        // a HEAD request is answered by a GET action when no action is for HEAD, and the body is left out of the response.
+        let mut get_fallback = None;
+        let mut allowed_methods: Vec<Method> = vec![];
+        for (i, values) in matches {
+            let methods = self.endpoints[i].action.get_http_methods_allowed();
+            if methods.is_empty() || methods.contains(method) {
+                return RouteMatch::Found(self.endpoints[i].action.clone(), values);
+            }
+            if get_fallback.is_none() && *method == Method::HEAD && methods.contains(&Method::GET) {
+                get_fallback = Some((i, values));
+            }
+            for x in methods {
+                if !allowed_methods.contains(&x) {
+                    allowed_methods.push(x);
+                }
+            }
+        }
+
+        if let Some((i, values)) = get_fallback {
+            RouteMatch::Found(self.endpoints[i].action.clone(), values)
+        } else if allowed_methods.is_empty() {
+            RouteMatch::NotFound
+        } else {
+            if allowed_methods.contains(&Method::GET) && !allowed_methods.contains(&Method::HEAD) {
+                allowed_methods.push(Method::HEAD);
+            }
+            RouteMatch::MethodNotAllowed(allowed_methods)
+        }
+    }
+
+    // collect the endpoints whose route pattern could match the path, before their constraints are checked.
+    fn collect_candidates(node: &RouteTableNode, path_segments: &[&str], candidates: &mut Vec<usize>) {
+        match path_segments.first() {
+            None => candidates.extend(node.endpoints.iter()),
+            Some(segment) => {
+                if let Some(child) = node.literals.get(*segment) {
+                    Self::collect_candidates(child, &path_segments[1..], candidates);
+                }
+                if let Some(child) = node.parameter.as_ref() {
+                    Self::collect_candidates(child, &path_segments[1..], candidates);
+                }
+                // .. matches the rest of the path, which must not be empty.
+                candidates.extend(node.catch_all.iter());
+            },
+        }
+    }
+}
@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::view_context::IViewContext;
+use crate::errors::UrlGenerationError;
 use crate::services::routemap_service::IRouteMapService;
 use crate::services::service_collection::{IServiceCollection, ServiceCollectionExtensions};
 
 use super::iurl_helpers::IUrlHelpers;
-use super::path_builder::ActionPathBuilder;
+use super::link_generator::{LinkGenerator, ACTION_KEY, AREA_KEY, CONTROLLER_KEY};
 
 
 // this struct helps with url generation and implements IUrlHelpers.
@@ -23,6 +24,8 @@ impl <'a> UrlHelpers<'a> {
         }
     }
 
+    // get an url by the action identifiers and route values, or an error if it cannot be generated.
+    // the area, controller and action that are None are filled in from the route data of the request.
     pub fn url_action_static(
         area_name: Option<&str>,
         controller_name: Option<&str>,
@@ -33,63 +36,49 @@ impl <'a> UrlHelpers<'a> {
         route_values: Option<&HashMap<String, String>>,
         request_context: Option<&dyn IRequestContext>,
         services: &'a dyn IServiceCollection,
-    ) -> String {
-        let mut path_builder = ActionPathBuilder::new();
-        path_builder
-            .add_optional(area_name)
-            .add_optional(controller_name)
-            .add_optional(action_name);
+    ) -> Result<String, UrlGenerationError> {
+        let mut values = route_values.cloned().unwrap_or_default();
+        for (key, value) in [(AREA_KEY, area_name), (CONTROLLER_KEY, controller_name), (ACTION_KEY, action_name)] {
+            if let Some(value) = value {
+                values.insert(key.to_string(), value.to_string());
+            }
+        }
+        let ambient_values = request_context.map(LinkGenerator::get_ambient_values).unwrap_or_default();
 
         let mapper = ServiceCollectionExtensions::get_required_single::<dyn IRouteMapService>(services);
-        let action = mapper.as_ref().get_mapper().get_action_at_area_controller_action_path(path_builder.path);
-
-        let mut url = String::new();
+        let path = mapper.get_link_generator().get_path_by_values(&values, &ambient_values, Some(services))?;
         if is_relative {
-            url.push_str("/");
-        } else {
-            if let Some(is_https) = is_https {
-                if is_https {
-                    url.push_str("https://");
-                } else {
-                    url.push_str("http://");
-                }
-            } else {
-                url.push_str("http://");
-            }
-            if let Some(protocol) = protocol {
-                if let Some(is_https) = is_https {
-                    if is_https {
-                        panic!("The protocol cannot be specified when the url is https.");
-                    }
-                }
+            return Ok(path);
+        }
+        Self::get_origin(&path, is_https, protocol, request_context).map(|origin| origin + &path)
+    }
 
+    // get the scheme, host and port of an absolute url.
+    fn get_origin(path: &str, is_https: Option<bool>, protocol: Option<&str>, request_context: Option<&dyn IRequestContext>) -> Result<String, UrlGenerationError> {
+        let error = |message: &str| UrlGenerationError::new(path.to_string(), message.to_string());
+        let mut url = String::new();
+        match (protocol, is_https) {
+            (Some(_), Some(true)) => return Err(error("the protocol cannot be specified when the url is https")),
+            (Some(protocol), _) => {
                 url.push_str(protocol);
                 url.push_str("://");
+            },
+            (None, Some(true)) => url.push_str("https://"),
+            (None, _) => url.push_str("http://"),
+        }
 
-            }
-
-            let host_url = request_context.unwrap().get_url();
-    
-            let host = host_url.host();
-            if let Some(host) = host {
-                url.push_str(host.to_string().as_str());
-            } else {
-                panic!("The host cannot be null.");
-            }
-
-            let port = host_url.port();
-            if let Some(port) = port {
-                if port != 80 {
-                    url.push_str(":");
-                    url.push_str(port.to_string().as_str());
-                }
+        let host_url = request_context.ok_or_else(|| error("an absolute url needs the request for its host"))?.get_url();
+        match host_url.host() {
+            Some(host) => url.push_str(host.to_string().as_str()),
+            None => return Err(error("the host cannot be null")),
+        }
+        if let Some(port) = host_url.port() {
+            if port != 80 {
+                url.push_str(":");
+                url.push_str(port.to_string().as_str());
             }
         }
-
-        // println!("route_values: {:?}", route_values);
-        url.push_str(&action.get_route_pattern().gen_url(route_values.unwrap_or(&HashMap::new())).as_str());
-
-        url
+        Ok(url)
     }
 }
 
@@ -102,7 +91,13 @@ impl <'a> IUrlHelpers for UrlHelpers<'a> {
         controller_name: Option<&str>,
         area_name: Option<&str>,
         route_values: Option<&HashMap<String, String>>
-    ) -> String {
+    ) -> Result<String, UrlGenerationError> {
         Self::url_action_static(area_name, controller_name, action_name, is_relative, is_https, protocol, route_values, Some(self.view_context.unwrap().get_request_context()), self.services)
     }
+
+    fn route(self: &Self, route_name: &str, route_values: &HashMap<String, String>) -> Result<String, UrlGenerationError> {
+        let ambient_values = LinkGenerator::get_ambient_values(self.view_context.unwrap().get_request_context());
+        let mapper = ServiceCollectionExtensions::get_required_single::<dyn IRouteMapService>(self.services);
+        mapper.get_link_generator().get_path_by_name(route_name, route_values, &ambient_values, Some(self.services))
+    }
 }
\ No newline at end of file
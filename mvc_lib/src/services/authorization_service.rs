@@ -1,9 +1,13 @@
 use std::any::Any;
 use std::collections::HashMap;
 use std::error::Error;
+use std::fmt;
 use std::rc::Rc;
 
+use http::StatusCode;
+
 use crate::auth::auth_role_json_file_dbset::JsonAuthRole;
+use crate::auth::authorization_policy::AuthorizationPolicy;
 use crate::auth::iauthroles_dbset_provider::IAuthRolesDbSetProvider;
 
 use crate::contexts::irequest_context::IRequestContext;
@@ -19,12 +23,45 @@ use crate::services::service_scope::ServiceScope;
 
 
 // this enum is used to indicate the result of an authorization rejection
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AuthRejectionReason {
+    // the request is not authenticated, so it should be challenged, such as by redirecting to the login page.
+    NotAuthenticated,
+    // the user does not have the roles that are required.
+    // all: whether all of the roles are required, or only one of them.
+    MissingRoles { required: Vec<String>, found: Vec<String>, all: bool },
+    // the user does not have a claim, or does not have one of the values it must have.
+    MissingClaim { name: String, values: Vec<String> },
+    // a requirement of a policy was not met, such as a custom check or a check of the bound model.
+    RequirementNotMet { policy: String, requirement: String },
     // the user is not authenticated for an unknown reason
     Other(String)
 }
 
+impl AuthRejectionReason {
+    // get the status code a rejection is answered with: 401 for a request that is not authenticated,
+    // and 403 for a user that is authenticated but not allowed.
+    pub fn get_status_code(self: &Self) -> StatusCode {
+        match self {
+            AuthRejectionReason::NotAuthenticated => StatusCode::UNAUTHORIZED,
+            _ => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+impl fmt::Display for AuthRejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthRejectionReason::NotAuthenticated => write!(f, "the request is not authenticated"),
+            AuthRejectionReason::MissingRoles { required, found, all } => write!(f, "{} role(s) required {:?} not found in authed role(s) {:?}", if *all { "all" } else { "one of the" }, required, found),
+            AuthRejectionReason::MissingClaim { name, values } if values.is_empty() => write!(f, "the claim {} is required", name),
+            AuthRejectionReason::MissingClaim { name, values } => write!(f, "the claim {} must be one of {:?}", name, values),
+            AuthRejectionReason::RequirementNotMet { policy, requirement } => write!(f, "the requirement {} of policy {} was not met", requirement, policy),
+            AuthRejectionReason::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
 // this enum is used to indicate the result of an authorization check
 pub enum AuthResult {
     // the user is authorized
@@ -134,7 +171,9 @@ impl IAuthClaim for CookieRoleClaim {
     }
 }
 
-// this struct is used to transform the cookie into a claim
+// this struct is used to transform the cookie into a claim.
+// the cookie is not signed, so anyone can set it; it is only for testing and is not one of the default
+// claim transformers. use the cookie authentication handler to sign in instead.
 pub struct CookieRoleClaimTransformer {
 
 }
@@ -236,7 +275,7 @@ impl IAuthRequirement for RoleAuthRequirement {
             }
         }
 
-        Ok(AuthResult::Rejection(AuthRejectionReason::Other(format!("Role(s) required {:?} not found in authed role(s) {:?}", roles, found_roles))))
+        Ok(AuthResult::Rejection(AuthRejectionReason::MissingRoles { required: roles, found: found_roles, all: false }))
     }
 
     fn get_name(self: &Self) -> String {
@@ -352,9 +391,7 @@ impl AuthorizationService {
             policies: vec![
                 RoleAuthRequirement::new_service()
             ].iter().map(|x| (x.get_name(), x.clone())).collect(),
-            claim_transformers: vec![
-                CookieRoleClaimTransformer::new_service()
-            ],
+            claim_transformers: vec![],
         }
     }
 
@@ -362,9 +399,29 @@ impl AuthorizationService {
     // services: the service collection.
     // returns: a vector containing the new instance of the service.
     pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
-        vec![Box::new(Rc::new(Self::new(
+        let mut service = Self::new(
             ServiceCollectionExtensions::get_required_single::<dyn IAuthRolesDbSetProvider>(services)
-        )) as Rc<dyn IAuthorizationService>)]
+        );
+        for policy in ServiceCollectionExtensions::get_required_multiple::<AuthorizationPolicy>(services) {
+            service.add_policy(policy);
+        }
+        vec![Box::new(Rc::new(service) as Rc<dyn IAuthorizationService>)]
+    }
+
+    // add a named policy that features can reference with #[authorize(policy = "name")].
+    // a policy with the same name as another replaces it.
+    // policy: the policy, which is found by its name.
+    pub fn add_policy(self: &mut Self, policy: Rc<dyn IAuthRequirement>) {
+        self.policies.insert(policy.get_name(), policy);
+    }
+
+    // get a policy by name.
+    // returns: the policy, or an error if no policy has the name, since a misspelled policy must not let requests through.
+    fn get_policy(self: &Self, name: &str) -> Result<Rc<dyn IAuthRequirement>, Rc<dyn Error>> {
+        match self.policies.get(name) {
+            Some(policy) => Ok(policy.clone()),
+            None => Err(Rc::new(std::io::Error::new(std::io::ErrorKind::NotFound, format!("the authorization policy {} is not registered", name)))),
+        }
     }
 
     // adds the service to the service collection.
@@ -376,32 +433,39 @@ impl AuthorizationService {
 }
 
 impl IAuthorizationService for AuthorizationService {
-    fn authenticate_role(self: &Self, _: Vec<Rc<dyn IAuthClaim>>, _: String, _: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
-        Ok(AuthResult::Ok)
+    fn authenticate_role(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, role: String, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        self.authenticate_roles(auth_claims, vec![role], request_context)
     }
 
     fn authenticate_roles(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, roles: Vec<String>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
         self.policies.get("Role").unwrap().invoke(auth_claims, roles, request_context)
     }
 
-    fn authenticate_requirements(self: &Self, _: Vec<Rc<dyn IAuthClaim>>, _: Vec<Rc<dyn IAuthRequirement>>, _: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+    fn authenticate_requirements(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, requirements: Vec<Rc<dyn IAuthRequirement>>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        // every requirement must be met, so the first rejection is the result.
+        for requirement in requirements.iter() {
+            if let AuthResult::Rejection(reason) = requirement.invoke(auth_claims.clone(), vec![], request_context)? {
+                return Ok(AuthResult::Rejection(reason));
+            }
+        }
         Ok(AuthResult::Ok)
     }
 
-    fn authenticate_requirements_by_name(self: &Self, _: Vec<Rc<dyn IAuthClaim>>, _: Vec<String>, _: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
-        Ok(AuthResult::Ok)
+    fn authenticate_requirements_by_name(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, requirements: Vec<String>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        let requirements = requirements.iter().map(|x| self.get_policy(x)).collect::<Result<Vec<Rc<dyn IAuthRequirement>>, Rc<dyn Error>>>()?;
+        self.authenticate_requirements(auth_claims, requirements, request_context)
     }
 
-    fn authenticate_policy(self: &Self, _: Vec<Rc<dyn IAuthClaim>>, _: Rc<dyn IAuthRequirement>, _: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
-        Ok(AuthResult::Ok)
+    fn authenticate_policy(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, policy: Rc<dyn IAuthRequirement>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        policy.invoke(auth_claims, vec![], request_context)
     }
 
-    fn authenticate_policy_by_name(self: &Self, _: Vec<Rc<dyn IAuthClaim>>, _: String, _: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
-        Ok(AuthResult::Ok)
+    fn authenticate_policy_by_name(self: &Self, auth_claims: Vec<Rc<dyn IAuthClaim>>, policy: String, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
+        self.authenticate_policy(auth_claims, self.get_policy(&policy)?, request_context)
     }
 
     fn get_policies(self: &Self) -> Vec<Rc<dyn IAuthRequirement>> {
-        vec![]
+        self.policies.values().cloned().collect()
     }
 
     fn get_roles(self: &Self) -> Vec<String> {
@@ -419,6 +483,7 @@ impl IAuthorizationService for AuthorizationService {
     fn authenticate_http_request(self: &Self, controller: Rc<dyn IController>, request_context: &dyn IRequestContext) -> Result<AuthResult, Rc<dyn Error>> {
         let mut required_roles = vec![];
         let mut required_policies = vec![];
+        let mut requires_authentication = false;
 
         let action_features = request_context.get_controller_action().get_features();
         let controller_features = controller.get_features();
@@ -441,12 +506,13 @@ impl IAuthorizationService for AuthorizationService {
                 let mut apply_requirement = true;
                 if let Some(req_filters) = &req.filters {
                     for req_filter in req_filters {
-                        if !req_filter.use_requirement() {
+                        if !req_filter.use_requirement(request_context) {
                             apply_requirement = false;
                         }
                     }
                 }
                 if apply_requirement {
+                    requires_authentication = true;
                     required_roles.extend_from_slice(&req.roles);
                     if let Some(policy) = &req.policy {
                         required_policies.push(policy);
@@ -465,6 +531,11 @@ impl IAuthorizationService for AuthorizationService {
             tokens = it.transform_tokens(tokens, request_context);
         }
 
+        // an authorize feature without roles or policies still needs the request to be authenticated.
+        if requires_authentication && claims.is_empty() {
+            return Ok(AuthResult::Rejection(AuthRejectionReason::NotAuthenticated));
+        }
+
         if required_roles.len() > 0 {
             match self.authenticate_roles(claims.clone(), required_roles.clone(), request_context)? {
                 AuthResult::Ok => {
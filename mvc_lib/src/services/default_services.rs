@@ -1,8 +1,13 @@
+use std::rc::Rc;
+
 use crate::core::type_info::TypeInfo;
 
 use crate::app::http_request_pipeline::HttpRequestPipeline;
 use crate::diagnostics::logging::log_http_requests::LogHttpRequestsMiddleware;
 use crate::diagnostics::logging::logging_service::{LoggingService, ILoggingService};
+use crate::diagnostics::logging::sinks::consoleloggersink::ConsoleLoggerSink;
+use crate::diagnostics::logging::sinks::fileloggersink::FileLoggerSink;
+use crate::diagnostics::performance::in_memory_perf_logger::InMemoryLogger;
 use crate::diagnostics::performance::iperformance_logger_service::IPerformanceLoggerService;
 use crate::diagnostics::performance::performance_logger_service::PerformanceLoggerService;
 use crate::error::error_handler_middleware::ErrorHandlerMiddleware;
@@ -27,7 +32,7 @@ use crate::services::routemap_service::RouteMapService;
 
 use crate::view::view_renderer::ViewRenderer;
 
-use crate::controllers::icontroller::IController;
+use crate::controllers::controller_registry::ControllerRegistration;
 use crate::controllers::file_provider_controller::FileProviderController;
 
 use super::controller_action_execute_service::ControllerActionExecuteService;
@@ -52,11 +57,15 @@ impl DefaultServices {
     pub fn add_logging(services: &mut ServiceCollection) {
         // services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ILogHttpRequestsOptions>(), LogHttpRequestsOptions::new_service, ServiceScope::Singleton));
         services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ILoggingService>(), LoggingService::new_service, ServiceScope::Singleton));
+        // the sinks can be replaced by adding another sink with the same id.
+        LoggingService::add_sink(services, "console", || Rc::new(ConsoleLoggerSink {}));
+        LoggingService::add_sink(services, "file", || Rc::new(FileLoggerSink { file_path: String::from("log.txt") }));
     }
 
     // add the default performance logging services to the service collection.
     pub fn add_performance_logging(services: &mut ServiceCollection) {
         // services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ILogHttpRequestsOptions>(), LogHttpRequestsOptions::new_service, ServiceScope::Singleton));
+        InMemoryLogger::add_to_services(services);
         services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IPerformanceLoggerService>(), PerformanceLoggerService::new_service, ServiceScope::Singleton));
     }
 
@@ -67,7 +76,12 @@ impl DefaultServices {
 
     // add the default controllers to the service collection.
     pub fn add_controllers(services: &mut ServiceCollection) {
-        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), FileProviderController::new_service, ServiceScope::Singleton));
+        RouteMapService::add_controller(services, nameof::name_of_type!(FileProviderController), FileProviderController::new_service);
+    }
+
+    // add every controller marked #[controller] in the crates linked into the program.
+    pub fn add_discovered_controllers(services: &mut ServiceCollection) {
+        ControllerRegistration::add_all_to_services(services);
     }
 
     // add the default request middleware services to the service collection.
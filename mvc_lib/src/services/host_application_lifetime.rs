@@ -0,0 +1,157 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::contexts::ihttpconnection_context::IHttpConnectionContext;
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+
+// a function called when the application reaches a point in its lifetime.
+pub type LifetimeCallback = Box<dyn Fn()>;
+// a function called after a response has been written to a client.
+pub type ResponseWrittenCallback = Box<dyn Fn(&dyn IHttpConnectionContext)>;
+
+// this trait lets services subscribe to events in the lifetime of the application host.
+// the host raises started once it is serving requests, stopping when it has been asked to stop
+// and no longer accepts connections, and stopped once in-flight requests are done and right before
+// the services are disposed.
+pub trait IHostApplicationLifetime {
+    // subscribe to the application started event.
+    fn on_started(self: &Self, callback: LifetimeCallback);
+    // subscribe to the application stopping event.
+    fn on_stopping(self: &Self, callback: LifetimeCallback);
+    // subscribe to the application stopped event.
+    fn on_stopped(self: &Self, callback: LifetimeCallback);
+    // subscribe to the response written event, raised after each response is sent to a client.
+    fn on_response_written(self: &Self, callback: ResponseWrittenCallback);
+
+    // raise the application started event.
+    fn notify_started(self: &Self);
+    // raise the application stopping event. the event is only raised once.
+    fn notify_stopping(self: &Self);
+    // raise the application stopped event. the event is only raised once.
+    fn notify_stopped(self: &Self);
+    // raise the response written event.
+    // connection_context: the connection the response was written to.
+    fn notify_response_written(self: &Self, connection_context: &dyn IHttpConnectionContext);
+
+    // ask the application to stop. this is what the host does when it receives SIGINT or SIGTERM.
+    fn stop_application(self: &Self);
+    // returns true if the application has been asked to stop.
+    fn is_stopping(self: &Self) -> bool;
+}
+
+// the default host application lifetime.
+// the stop request is shared between all the lifetimes of the same host (one per worker thread),
+// while the subscribers are local to the thread that subscribed them.
+pub struct HostApplicationLifetime {
+    stop_requested: Arc<AtomicBool>,
+    started: RefCell<Vec<LifetimeCallback>>,
+    stopping: RefCell<Vec<LifetimeCallback>>,
+    stopped: RefCell<Vec<LifetimeCallback>>,
+    response_written: RefCell<Vec<ResponseWrittenCallback>>,
+    // whether the stopping and stopped events have been raised.
+    stopping_raised: RefCell<bool>,
+    stopped_raised: RefCell<bool>,
+}
+
+impl HostApplicationLifetime {
+    // create a new host application lifetime with its own stop request.
+    pub fn new() -> Self {
+        Self::new_shared(Arc::new(AtomicBool::new(false)))
+    }
+
+    // create a new host application lifetime that shares a stop request with other lifetimes.
+    // stop_requested: set to true when the application should stop.
+    pub fn new_shared(stop_requested: Arc<AtomicBool>) -> Self {
+        Self {
+            stop_requested: stop_requested,
+            started: RefCell::new(Vec::new()),
+            stopping: RefCell::new(Vec::new()),
+            stopped: RefCell::new(Vec::new()),
+            response_written: RefCell::new(Vec::new()),
+            stopping_raised: RefCell::new(false),
+            stopped_raised: RefCell::new(false),
+        }
+    }
+
+    // get the stop request so it can be shared with other lifetimes or set from another thread.
+    pub fn get_stop_requested(self: &Self) -> Arc<AtomicBool> {
+        self.stop_requested.clone()
+    }
+
+    // add the lifetime to the service collection. every request for the service gets the same lifetime.
+    // services: the service collection to add the lifetime to.
+    // lifetime: the lifetime of the host that owns the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection, lifetime: Rc<HostApplicationLifetime>) {
+        services.add(ServiceDescriptor::new_closure(
+            TypeInfo::rc_of::<dyn IHostApplicationLifetime>(),
+            move |_| -> Vec<Box<dyn Any>> { vec![Box::new(lifetime.clone() as Rc<dyn IHostApplicationLifetime>)] },
+            ServiceScope::Singleton));
+    }
+
+    // get the host application lifetime from the service collection.
+    pub fn get_service(services: &dyn IServiceCollection) -> Rc<dyn IHostApplicationLifetime> {
+        ServiceCollectionExtensions::get_required_single::<dyn IHostApplicationLifetime>(services)
+    }
+
+    fn raise(callbacks: &RefCell<Vec<LifetimeCallback>>) {
+        for callback in callbacks.borrow().iter() {
+            callback();
+        }
+    }
+}
+
+impl IHostApplicationLifetime for HostApplicationLifetime {
+    fn on_started(self: &Self, callback: LifetimeCallback) {
+        self.started.borrow_mut().push(callback);
+    }
+
+    fn on_stopping(self: &Self, callback: LifetimeCallback) {
+        self.stopping.borrow_mut().push(callback);
+    }
+
+    fn on_stopped(self: &Self, callback: LifetimeCallback) {
+        self.stopped.borrow_mut().push(callback);
+    }
+
+    fn on_response_written(self: &Self, callback: ResponseWrittenCallback) {
+        self.response_written.borrow_mut().push(callback);
+    }
+
+    fn notify_started(self: &Self) {
+        Self::raise(&self.started);
+    }
+
+    fn notify_stopping(self: &Self) {
+        self.stop_application();
+        if !self.stopping_raised.replace(true) {
+            Self::raise(&self.stopping);
+        }
+    }
+
+    fn notify_stopped(self: &Self) {
+        if !self.stopped_raised.replace(true) {
+            Self::raise(&self.stopped);
+        }
+    }
+
+    fn notify_response_written(self: &Self, connection_context: &dyn IHttpConnectionContext) {
+        for callback in self.response_written.borrow().iter() {
+            callback(connection_context);
+        }
+    }
+
+    fn stop_application(self: &Self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+    }
+
+    fn is_stopping(self: &Self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+}
@@ -1,4 +1,12 @@
+use std::any::Any;
+use std::rc::Rc;
 
+use crate::config::configuration::{Configuration, IConfiguration};
+use crate::config::configuration_builder::{APPLICATION_NAME_KEY, CONTENT_ROOT_KEY, DEFAULT_ENVIRONMENT, ENVIRONMENT_KEY};
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
 
 // this trait represents the host environment.
 pub trait IHostEnvironment {
@@ -10,30 +18,107 @@ pub trait IHostEnvironment {
     fn get_environment_name(self: &Self) -> String;
 }
 
+// this struct implements IHostEnvironment.
+#[derive(Debug, Clone)]
+pub struct HostEnvironment {
+    app_name: String,
+    content_root_path: String,
+    environment_name: String,
+}
+
+impl HostEnvironment {
+    // create a new HostEnvironment struct.
+    // app_name: the application name.
+    // content_root_path: the directory the application files, such as views, wwwroot and appsettings.json, are read from.
+    // environment_name: the environment name, such as "Development" or "Production".
+    pub fn new(app_name: String, content_root_path: String, environment_name: String) -> Self {
+        Self { app_name: app_name, content_root_path: content_root_path, environment_name: environment_name }
+    }
+
+    // create a new HostEnvironment struct from the configuration, which is set by RSHTML_ENVIRONMENT, RSHTML_CONTENT_ROOT
+    // and RSHTML_APPLICATION_NAME, or by --environment, --content_root and --application_name.
+    // the environment defaults to "Production", the content root to the current directory and the application name to the executable name.
+    // configuration: the configuration to read from.
+    pub fn new_from_configuration(configuration: &dyn IConfiguration) -> Self {
+        let non_empty = |key: &str| configuration.get(key).filter(|x| !x.is_empty());
+        Self::new(
+            non_empty(APPLICATION_NAME_KEY).unwrap_or_else(Self::get_default_app_name),
+            non_empty(CONTENT_ROOT_KEY).unwrap_or_else(|| std::env::current_dir().map(|x| x.to_string_lossy().to_string()).unwrap_or(".".to_string())),
+            non_empty(ENVIRONMENT_KEY).unwrap_or(DEFAULT_ENVIRONMENT.to_string()),
+        )
+    }
+
+    fn get_default_app_name() -> String {
+        std::env::current_exe()
+            .ok()
+            .and_then(|x| x.file_stem().map(|x| x.to_string_lossy().to_string()))
+            .unwrap_or_default()
+    }
+
+    // create a new HostEnvironment struct as a service from the configuration service, or from an empty configuration if there is none.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        let environment = match ServiceCollectionExtensions::try_get_single::<dyn IConfiguration>(services).ok().flatten() {
+            Some(configuration) => Self::new_from_configuration(configuration.as_ref()),
+            None => Self::new_from_configuration(&Configuration::new(vec![])),
+        };
+        vec![Box::new(Rc::new(environment) as Rc<dyn IHostEnvironment>)]
+    }
+
+    // add the host environment to the service collection as a singleton.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IHostEnvironment>(), Self::new_service, ServiceScope::Singleton));
+    }
+}
+
+impl IHostEnvironment for HostEnvironment {
+    fn get_app_name(self: &Self) -> String {
+        self.app_name.clone()
+    }
+
+    fn get_content_root_path(self: &Self) -> String {
+        self.content_root_path.clone()
+    }
+
+    fn get_environment_name(self: &Self) -> String {
+        self.environment_name.clone()
+    }
+}
+
 // extension methods for IHostEnvironment.
-struct IHostEnvironmentExtensions {}
+pub struct IHostEnvironmentExtensions {}
 
 impl IHostEnvironmentExtensions {
     // is_development returns true if the environment name is "Development".
-    #[allow(dead_code)]
     pub fn is_development(env: &dyn IHostEnvironment) -> bool {
         Self::is_environment(env, "Development")
     }
 
-    // is_environment returns true if the environment name is the specified name.
-    #[allow(dead_code)]
+    // is_environment returns true if the environment name is the specified name, ignoring case.
     pub fn is_environment(env: &dyn IHostEnvironment, env_name: &str) -> bool {
-        env.get_environment_name() == env_name
+        env.get_environment_name().eq_ignore_ascii_case(env_name)
+    }
+
+    // is_environment_tag_rendered returns true if the children of an <environment> tag should be rendered.
+    // the children are rendered if the environment is in the include list, if there is one, and is not in the exclude list.
+    // services: the services to get the host environment from. the environment is "Production" if there is no host environment.
+    // include: a comma separated list of environment names, or None to include all environments.
+    // exclude: a comma separated list of environment names, or None to exclude no environments.
+    pub fn is_environment_tag_rendered(services: &dyn IServiceCollection, include: Option<&str>, exclude: Option<&str>) -> bool {
+        let env_name = ServiceCollectionExtensions::try_get_single::<dyn IHostEnvironment>(services)
+            .ok()
+            .flatten()
+            .map(|x| x.get_environment_name())
+            .unwrap_or(DEFAULT_ENVIRONMENT.to_string());
+        let is_listed = |list: &str| list.split(',').any(|x| x.trim().eq_ignore_ascii_case(&env_name));
+        include.map(is_listed).unwrap_or(true) && !exclude.map(is_listed).unwrap_or(false)
     }
 
     // is_production returns true if the environment name is "Production".
-    #[allow(dead_code)]
     pub fn is_production(env: &dyn IHostEnvironment) -> bool {
         Self::is_environment(env, "Production")
     }
 
     // is_staging returns true if the environment name is "Staging".
-    #[allow(dead_code)]
     pub fn is_staging(env: &dyn IHostEnvironment) -> bool {
         Self::is_environment(env, "Staging")
     }
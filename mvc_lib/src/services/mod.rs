@@ -2,6 +2,7 @@ pub mod controller_action_execute_service;
 pub mod authorization_service;
 pub mod default_services;
 pub mod file_provider_service;
+pub mod host_application_lifetime;
 pub mod host_environment;
 pub mod request_middleware_service;
 pub mod routing_service;
@@ -9,4 +10,5 @@ pub mod routemap_service;
 pub mod service_collection;
 pub mod service_descriptor;
 pub mod service_instance;
-pub mod service_scope;
\ No newline at end of file
+pub mod service_scope;
+pub mod service_validator;
\ No newline at end of file
@@ -4,9 +4,12 @@ use std::rc::Rc;
 use crate::contexts::irequest_context::IRequestContext;
 
 use crate::controllers::icontroller::IController;
+use crate::controllers::icontroller_extensions::IControllerExtensions;
 use crate::controllers::controller_actions_map::{ControllerActionsMap, IControllerActionsMap};
 
 use crate::core::type_info::TypeInfo;
+use crate::routing::link_generator::LinkGenerator;
+use crate::routing::route_table::RouteTable;
 use crate::services::service_collection::{ IServiceCollection, ServiceCollectionExtensions };
 
 use super::service_collection::ServiceCollection;
@@ -17,6 +20,10 @@ use super::service_scope::ServiceScope;
 pub trait IRouteMapService {
     // gets the mapper.
     fn get_mapper(self: &Self) -> Rc<dyn IControllerActionsMap>;
+    // gets the route table built from the actions of the mapper, used to match requests to actions.
+    fn get_route_table(self: &Self) -> Rc<RouteTable>;
+    // gets the link generator for the actions of the mapper, used to generate urls by route name or route values.
+    fn get_link_generator(self: &Self) -> Rc<LinkGenerator>;
 }
 
 // implementation of the route map service.
@@ -25,6 +32,10 @@ pub struct RouteMapService {
     controllers: Vec<Rc<dyn IController>>,
     // the mapper.
     mapper: Rc<ControllerActionsMap>,
+    // the route table, built once from the actions of the mapper.
+    route_table: Rc<RouteTable>,
+    // the link generator, built once from the actions of the mapper.
+    link_generator: Rc<LinkGenerator>,
 }
 
 impl RouteMapService {
@@ -33,9 +44,13 @@ impl RouteMapService {
     pub fn new(controllers: Vec<Rc<dyn IController>>) -> Self {
         let mapper = Rc::new(ControllerActionsMap::from_controllers(controllers.clone()));
         // println!("{}", mapper.clone().as_ref());
+        let route_table = Rc::new(RouteTable::new(mapper.get_all_actions()));
+        let link_generator = Rc::new(LinkGenerator::new(mapper.get_all_actions()));
         Self {
             controllers: controllers.clone(),
             mapper: mapper,
+            route_table: route_table,
+            link_generator: link_generator,
         }
     }
 
@@ -44,10 +59,36 @@ impl RouteMapService {
     // returns a vector containing the new instance of the service.
     pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
         vec![Box::new(Rc::new(Self::new(
-            ServiceCollectionExtensions::get_required_multiple::<dyn IController>(services)
+            Self::load_controllers(services)
         )) as Rc<dyn IRouteMapService>)]
     }
 
+    // adds a controller to the given service collection, keyed by the name of the controller.
+    // adding another controller with the same name replaces the first.
+    // services: the service collection.
+    // type_name: the type name of the controller, such as "HomeController".
+    // new_service: creates the controller as a service.
+    pub fn add_controller(services: &mut ServiceCollection, type_name: &str, new_service: fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>) {
+        services.add_keyed(IControllerExtensions::get_name_of_type(type_name), ServiceDescriptor::new(TypeInfo::rc_of::<dyn IController>(), new_service, ServiceScope::Singleton));
+    }
+
+    // gets the controller added with the given name, without looking through the other controllers.
+    // services: the service collection.
+    // name: the name of the controller, such as "Home" or "HomeController".
+    // returns: the controller, or None if no controller was added with that name.
+    pub fn try_get_controller(services: &dyn IServiceCollection, name: &str) -> Option<Rc<dyn IController>> {
+        ServiceCollectionExtensions::try_get_keyed::<dyn IController>(services, IControllerExtensions::get_name_of_type(name))
+    }
+
+    // get the controllers added without a key and the controllers added with their name as the key.
+    fn load_controllers(services: &dyn IServiceCollection) -> Vec<Rc<dyn IController>> {
+        let mut controllers = ServiceCollectionExtensions::get_required_multiple::<dyn IController>(services);
+        for name in ServiceCollectionExtensions::get_keys::<dyn IController>(services) {
+            controllers.push(ServiceCollectionExtensions::get_required_keyed::<dyn IController>(services, &name));
+        }
+        controllers
+    }
+
     // adds the route map service to the given service collection.
     pub fn add_to_services(services: &mut ServiceCollection) {
         services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IRouteMapService>(), RouteMapService::new_service, ServiceScope::Singleton));
@@ -76,4 +117,12 @@ impl IRouteMapService for RouteMapService {
     fn get_mapper(self: &Self) -> Rc<dyn IControllerActionsMap> {
         self.mapper.clone()
     }
+
+    fn get_route_table(self: &Self) -> Rc<RouteTable> {
+        self.route_table.clone()
+    }
+
+    fn get_link_generator(self: &Self) -> Rc<LinkGenerator> {
+        self.link_generator.clone()
+    }
 }
\ No newline at end of file
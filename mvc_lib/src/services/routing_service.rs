@@ -4,15 +4,19 @@ use std::error::Error;
 use std::rc::Rc;
 use std::result::Result;
 
+use http::StatusCode;
+
 use crate::contexts::irequest_context::IRequestContext;
 use crate::contexts::iresponse_context::IResponseContext;
 
+use crate::action_results::http_result::StatusCodeResult;
 use crate::controllers::route_data_controller_action_matcher::RouteDataControllerActionMatcher;
 
 use crate::core::type_info::TypeInfo;
+use crate::routing::route_table::RouteMatch;
 use crate::services::service_collection::{ IServiceCollection, ServiceCollectionExtensions };
 use crate::services::request_middleware_service::{ IRequestMiddlewareService, MiddlewareResult };
-use crate::services::routemap_service::IRouteMapService;
+use crate::services::routemap_service::{ IRouteMapService, RouteMapService };
 
 use super::service_collection::ServiceCollection;
 use super::service_descriptor::ServiceDescriptor;
@@ -57,24 +61,44 @@ impl IRequestMiddlewareService for RoutingService {
     }
 
     fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
-        let route_matcher = RouteDataControllerActionMatcher::new(self.routemap.get_mapper().clone());
-        let action_option = route_matcher.get_action_for_request(response_context, request_context, services)?;
+        let route_matcher = RouteDataControllerActionMatcher::new(self.routemap.get_route_table());
+        match route_matcher.match_request(request_context, services) {
+            RouteMatch::Found(action, values) => {
+                let mut controller_name = action.get_controller_name().to_string();
+                if controller_name.ends_with("Controller") {
+                    controller_name = controller_name[..controller_name.len() - "Controller".len()].to_string();
+                }
 
-        if let Some(action) = action_option {
-            let mut controller_name = action.get_controller_name().to_string();
-            if controller_name.ends_with("Controller") {
-                controller_name = controller_name[..controller_name.len() - "Controller".len()].to_string();
-            }
-            
-            request_context.mut_route_data().borrow_mut().map.insert("ActionName".to_string(), action.get_name().into_owned());
-            request_context.mut_route_data().borrow_mut().map.insert("ControllerName".to_string(), controller_name);
-            request_context.mut_route_data().borrow_mut().map.insert("AreaName".to_string(), action.get_area_name());
-            request_context.set_controller_action(Some(action.clone()));
-        } else {
-            // 404 not found
-            // panic!("404 not found");
+                request_context.mut_route_data().borrow_mut().map.insert("ActionName".to_string(), action.get_name().into_owned());
+                request_context.mut_route_data().borrow_mut().map.insert("ControllerName".to_string(), controller_name);
+                request_context.mut_route_data().borrow_mut().map.insert("AreaName".to_string(), action.get_area_name());
+                // the values of capture groups such as {id} are added to the route data by name.
+                request_context.mut_route_data().borrow_mut().map.extend(values.into_iter().filter(|x| x.0 != ".."));
+                // the controller is found once here and shared with the middleware after routing.
+                // controllers added by name are found by key, the others by looking through the mapper.
+                let controller = match RouteMapService::try_get_controller(services, &action.get_controller_name()) {
+                    Some(controller) => controller,
+                    None => self.routemap.get_mapper().get_controller(action.get_controller_name().to_string()),
+                };
+                request_context.set_controller(Some(controller));
+                request_context.set_controller_action(Some(action.clone()));
+            },
+            RouteMatch::MethodNotAllowed(allowed_methods) => {
+                // the path matches, but not for this method.
+                let allow = allowed_methods.iter().map(|x| x.as_str()).collect::<Vec<&str>>().join(", ");
+                response_context.add_header_str("Allow", &allow);
+                response_context.set_action_result(Some(Rc::new(StatusCodeResult::new(
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    format!("Method {} is not allowed, use {}", request_context.get_method(), allow),
+                ))));
+                return Ok(MiddlewareResult::OkBreak);
+            },
+            RouteMatch::NotFound => {
+                // 404 not found
+                // panic!("404 not found");
+            },
         }
-                
+
         if let Some(next) = self.next.borrow().as_ref() {
             next.handle_request(response_context, request_context, services)
         } else {
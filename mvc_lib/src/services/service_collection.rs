@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::any::{Any, TypeId};
 use std::error::Error;
@@ -8,6 +9,7 @@ use crate::core::type_info::TypeInfo;
 use crate::services::service_scope::ServiceScope;
 use crate::services::service_descriptor::ServiceDescriptor;
 use crate::services::service_instance::ServiceInstance;
+use crate::services::service_validator::{ServiceValidationResult, ServiceValidator};
 
 
 // this interface is used to store services and dependencies in a collection.
@@ -31,11 +33,17 @@ pub trait IServiceCollection: Send + Sync {
     // get the items in the collection
     fn get_items(&self) -> Vec<Rc<ServiceDescriptor>>;
 
-    // get the singletons in the collection
-    fn get_singletons(&self) -> &Vec<Rc<ServiceInstance>>;
+    // get the instances kept alive by the scope of the collection
+    fn get_instances(&self) -> Vec<Rc<ServiceInstance>>;
 
-    // get the per-request instances in the collection
-    fn get_request_instances(&self) -> &Vec<Rc<ServiceInstance>>;
+    // get the collection, either this one or an outer one, that keeps alive instances for a scope.
+    // scope: the scope of the service.
+    // returns: the nearest collection with the scope, or None if there is none.
+    fn find_scope(&self, scope: ServiceScope) -> Option<&dyn IServiceCollection>;
+
+    // get the instances of a service kept alive by this collection, or instantiate and keep them if there are none yet.
+    // descriptor: the service descriptor, which has to have the same scope as the collection.
+    fn get_or_instantiate_in_scope(&self, descriptor: &Rc<ServiceDescriptor>) -> Vec<Box<dyn Any>>;
 
     // find a service descriptor by type info
     fn find_descriptor(self: &Self, type_info: Box<TypeInfo>) -> Vec<Rc<ServiceDescriptor>>;
@@ -43,6 +51,14 @@ pub trait IServiceCollection: Send + Sync {
     fn find_descriptor_by_id(self: &Self, type_id: TypeId) -> Vec<Rc<ServiceDescriptor>>;
     // try to find a service descriptor by type id
     fn try_find_descriptor_by_id(self: &Self, type_id: TypeId) -> Option<&Vec<Rc<ServiceDescriptor>>>;
+
+    // try to get a service that was added with a key. keyed services are only found by their key.
+    // returns: the instances of the service, or an empty list if no service was added with the key.
+    fn try_get_keyed(&self, type_info: Box<TypeInfo>, key: &str) -> Result<Vec<Box<dyn Any>>, &str>;
+    // find the descriptor of a service that was added with a key, in this collection or an outer one.
+    fn find_keyed_descriptor(self: &Self, type_info: Box<TypeInfo>, key: &str) -> Option<Rc<ServiceDescriptor>>;
+    // get the keys that services of a type were added with, in this collection and outer ones.
+    fn get_keys(self: &Self, type_info: Box<TypeInfo>) -> Vec<String>;
 }
 
 
@@ -65,11 +81,12 @@ pub struct ServiceCollection<'a> {
     type_id_to_descriptor: HashMap<TypeId, Vec<Rc<ServiceDescriptor>>>,
     type_id_to_type_info: HashMap<TypeId, Rc<Box<TypeInfo>>>,
 
-    // singletons live in this scope
-    singletons: Vec<Rc<ServiceInstance>>,
+    // type descriptors added with a key, by type and key. they are not in type_id_to_descriptor.
+    keyed_descriptors: HashMap<TypeId, HashMap<String, Rc<ServiceDescriptor>>>,
 
-    // per-request instances live in this scope
-    request_instances: Vec<Rc<ServiceInstance>>,
+    // instances kept alive by this scope, such as the singletons of the root or the services of a request.
+    // clones of the collection share them, so a copy of the root still hands out the same singletons.
+    instances: Rc<RefCell<Vec<Rc<ServiceInstance>>>>,
 }
 unsafe impl <'a> Send for ServiceCollection<'a> {}
 unsafe impl <'a> Sync for ServiceCollection<'a> {}
@@ -82,8 +99,8 @@ impl <'a> ServiceCollection<'a> {
             items: Vec::new(),
             type_id_to_descriptor: HashMap::new(),
             type_id_to_type_info: HashMap::new(),
-            request_instances: Vec::new(),
-            singletons: Vec::new(),
+            keyed_descriptors: HashMap::new(),
+            instances: Rc::new(RefCell::new(Vec::new())),
             parent: None,
             root: None,
         }
@@ -98,8 +115,8 @@ impl <'a> ServiceCollection<'a> {
             items: Vec::new(),
             type_id_to_descriptor: HashMap::new(),
             type_id_to_type_info: HashMap::new(),
-            request_instances: Vec::new(),
-            singletons: Vec::new(),
+            keyed_descriptors: HashMap::new(),
+            instances: Rc::new(RefCell::new(Vec::new())),
         }
     }
 
@@ -119,23 +136,61 @@ impl <'a> ServiceCollection<'a> {
         self
     }
 
-    // pub fn add_instance<T, TInterface: ?Sized>(self: &mut Self, type_info: Box<TypeInfo>, item: &'static T) {
-    //     self.add(ServiceDescriptor::new_singleton::<T, TInterface>(type_info, item));
-    // }
+    // add a service descriptor to the collection with a key, such as the path of a view or the name of a controller.
+    // keyed services are only found by their key, so several services of the same type can be added side by side.
+    // a service added with a key that is already used for the type replaces the one added before.
+    pub fn add_keyed(self: &mut Self, key: &str, item: ServiceDescriptor) -> &mut Self {
+        let mut item = item;
+        item.key = Some(key.to_string());
+        let boxed_item = Rc::new(item);
+        let keyed = self.keyed_descriptors.entry(boxed_item.type_info.type_id).or_insert_with(HashMap::new);
+        if let Some(replaced) = keyed.insert(key.to_string(), boxed_item.clone()) {
+            self.items.retain(|x| !Rc::ptr_eq(x, &replaced));
+        }
+        self.items.push(boxed_item);
+        self
+    }
 
-    #[allow(dead_code)]
-    fn get_or_instantiate(self: &Self, descriptor: &ServiceDescriptor) -> Vec<Box<dyn Any>> {
-        self.instantiate(descriptor)
+    // dispose of the collection. this drops the service descriptors and the instances kept alive by this scope,
+    // so services holding onto resources should release them when they are dropped.
+    pub fn dispose(self: &mut Self) {
+        // the instances are taken out first, so services that use the collection while being dropped do not find it borrowed.
+        let instances = self.instances.replace(Vec::new());
+        drop(instances);
+        self.type_id_to_descriptor.clear();
+        self.type_id_to_type_info.clear();
+        self.keyed_descriptors.clear();
+        self.items.clear();
     }
 
-    // get or instantiate a service for a HTTP request that is kept alive for the duration of the request
-    fn get_or_instantiate_for_request(self: &Self, descriptor: &ServiceDescriptor) -> Vec<Box<dyn Any>> {
-        self.instantiate(descriptor)
+    // create every service once in a dry run scope and check for missing dependencies, dependency cycles,
+    // and long lived services that depend on request services.
+    // returns: the graph of services and their dependencies, with every problem that was found.
+    pub fn validate(self: &Self) -> ServiceValidationResult {
+        ServiceValidator::new(self).validate()
     }
 
-    // get or instantiate a service that is kept alive for the duration of the current scope
-    fn get_or_instantiate_singleton(self: &Self, descriptor: &ServiceDescriptor) -> Vec<Box<dyn Any>> {
-        self.instantiate(descriptor)
+    // pub fn add_instance<T, TInterface: ?Sized>(self: &mut Self, type_info: Box<TypeInfo>, item: &'static T) {
+    //     self.add(ServiceDescriptor::new_singleton::<T, TInterface>(type_info, item));
+    // }
+
+    // get or instantiate a service using its scope.
+    // services are kept alive by the nearest collection with the same scope, so a singleton is shared by the whole application
+    // and a request service by the request. each worker thread configures its own root collection, which is also its host,
+    // so Host services are kept alive by the root. AlwaysNew services, and Request services outside of a request, are created every time.
+    // descriptor: the service descriptor
+    // panics if an Area, Controller or Scope service is requested outside of a collection with its scope.
+    fn get_or_instantiate(self: &Self, descriptor: &Rc<ServiceDescriptor>) -> Vec<Box<dyn Any>> {
+        let scope = match descriptor.scope {
+            ServiceScope::AlwaysNew => return self.instantiate(descriptor),
+            ServiceScope::Host => ServiceScope::Singleton,
+            scope => scope,
+        };
+        match self.find_scope(scope) {
+            Some(scope) => scope.get_or_instantiate_in_scope(descriptor),
+            None if scope == ServiceScope::Request => self.instantiate(descriptor),
+            None => panic!("{} has the {:?} scope, but it was requested outside of a collection with that scope", descriptor, scope),
+        }
     }
 
     // instantiate a service
@@ -193,17 +248,7 @@ impl <'a> IServiceCollection for ServiceCollection<'a> {
 
     fn try_get(&self, type_info: Box<TypeInfo>) -> Result<Vec<Box<dyn Any>>, &str> {
         let descriptors = self.find_descriptor(type_info);
-        Ok(descriptors.iter().cloned().map(|descriptor| {
-            match descriptor.scope {
-                ServiceScope::AlwaysNew |
-                ServiceScope::Area |
-                ServiceScope::Controller |
-                ServiceScope::Scope |
-                ServiceScope::Host => self.instantiate(&descriptor),
-                ServiceScope::Request => self.get_or_instantiate_for_request(&descriptor),
-                ServiceScope::Singleton => self.get_or_instantiate_singleton(&descriptor),
-            }
-        }).flatten().collect())
+        Ok(descriptors.iter().map(|descriptor| self.get_or_instantiate(descriptor)).flatten().collect())
     }
 
     fn get_required(&self, type_info: Box<TypeInfo>) -> Vec<Box<dyn Any>> {
@@ -250,12 +295,39 @@ impl <'a> IServiceCollection for ServiceCollection<'a> {
         }
     }
 
-    fn get_singletons(&self) -> &Vec<Rc<ServiceInstance>> {
-        &self.singletons
+    fn get_instances(&self) -> Vec<Rc<ServiceInstance>> {
+        self.instances.borrow().clone()
     }
 
-    fn get_request_instances(&self) -> &Vec<Rc<ServiceInstance>> {
-        &self.request_instances
+    fn find_scope(&self, scope: ServiceScope) -> Option<&dyn IServiceCollection> {
+        if self.current_scope == scope {
+            Some(self)
+        } else {
+            match self.parent {
+                Some(parent) => parent.find_scope(scope),
+                None => None,
+            }
+        }
+    }
+
+    fn get_or_instantiate_in_scope(&self, descriptor: &Rc<ServiceDescriptor>) -> Vec<Box<dyn Any>> {
+        let existing = self.instances.borrow().iter().find(|x| Rc::ptr_eq(&x.descriptor, descriptor)).cloned();
+        if let Some(existing) = existing {
+            if let Some(instances) = existing.try_clone_instances() {
+                return instances;
+            }
+        }
+
+        // the collection is not borrowed while instantiating, since the factory can get other services from it.
+        let service_instance = ServiceInstance::new(descriptor.clone(), self.instantiate(descriptor));
+        match service_instance.try_clone_instances() {
+            Some(instances) => {
+                self.instances.borrow_mut().push(Rc::new(service_instance));
+                instances
+            },
+            // instances that cannot be cloned cannot be shared, so they are handed out as they are.
+            None => service_instance.instances,
+        }
     }
 
     fn find_descriptor(self: &Self, type_info: Box<TypeInfo>) -> Vec<Rc<ServiceDescriptor>> {
@@ -305,6 +377,35 @@ impl <'a> IServiceCollection for ServiceCollection<'a> {
             }
         }
     }
+
+    fn try_get_keyed(&self, type_info: Box<TypeInfo>, key: &str) -> Result<Vec<Box<dyn Any>>, &str> {
+        Ok(match self.find_keyed_descriptor(type_info, key) {
+            Some(descriptor) => self.get_or_instantiate(&descriptor),
+            None => vec![],
+        })
+    }
+
+    fn find_keyed_descriptor(self: &Self, type_info: Box<TypeInfo>, key: &str) -> Option<Rc<ServiceDescriptor>> {
+        match self.keyed_descriptors.get(&type_info.type_id).and_then(|x| x.get(key)) {
+            Some(descriptor) => Some(descriptor.clone()),
+            None => match self.parent {
+                Some(parent) => parent.find_keyed_descriptor(type_info, key),
+                None => None,
+            },
+        }
+    }
+
+    fn get_keys(self: &Self, type_info: Box<TypeInfo>) -> Vec<String> {
+        let mut keys = match self.parent {
+            Some(parent) => parent.get_keys(type_info.clone()),
+            None => vec![],
+        };
+        if let Some(keyed) = self.keyed_descriptors.get(&type_info.type_id) {
+            keys.extend(keyed.keys().filter(|x| !keys.contains(x)).cloned().collect::<Vec<String>>());
+        }
+        keys.sort();
+        keys
+    }
 }
 
 // extension methods for IServiceCollection (leave empty)
@@ -370,6 +471,36 @@ impl ServiceCollectionExtensions {
             .collect::<Vec<Rc<T>>>()
     }
 
+    // try to get a service that was added with a key.
+    // returns: the service, or None if no service of the type was added with the key.
+    pub fn try_get_keyed<T: 'static + ?Sized>(services: &dyn IServiceCollection, key: &str) -> Option<Rc<T>> {
+        let type_info = TypeInfo::rc_of::<T>();
+        services
+            .try_get_keyed(type_info, key)
+            .unwrap_or(vec![])
+            .iter()
+            .map(|x| x.downcast_ref::<Rc<T>>().expect(Self::format_error_could_not_downcast::<T>(services, x.type_id()).as_str()))
+            .next()
+            .cloned()
+    }
+
+    // get a service that was added with a key. if it is not found, panic.
+    pub fn get_required_keyed<T: 'static + ?Sized>(services: &dyn IServiceCollection, key: &str) -> Rc<T> {
+        match Self::try_get_keyed::<T>(services, key) {
+            Some(x) => x,
+            None => {
+                let type_info = TypeInfo::rc_of::<T>();
+                let keys = services.get_keys(type_info.clone());
+                panic!("No service found for type {} with key '{}'. Available keys: {:?}", type_info.type_name, key, keys)
+            },
+        }
+    }
+
+    // get the keys that services of a type were added with.
+    pub fn get_keys<T: 'static + ?Sized>(services: &dyn IServiceCollection) -> Vec<String> {
+        services.get_keys(TypeInfo::rc_of::<T>())
+    }
+
     // get multiple services from the collection. if it is not found, panic.
     pub fn get_required_one_or_more<T: 'static + ?Sized>(services: &dyn IServiceCollection) -> Vec<Rc<T>> {
         let found = Self::get_required_multiple::<T>(services);
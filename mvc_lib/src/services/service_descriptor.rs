@@ -16,18 +16,20 @@ pub struct ServiceDescriptor {
     pub type_factory_closure: Option<Box<dyn Fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>>>,
     // this is the scope of the service
     pub scope: ServiceScope,
+    // this is the key the service was added with, or None if it was not added with a key
+    pub key: Option<String>,
 }
 
 impl ServiceDescriptor {
     // create a new service descriptor with a factory function
     pub fn new(type_info: Box<TypeInfo>, type_factory: fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>, scope: ServiceScope) -> Self {
-        Self { type_info: type_info, type_factory: Some(type_factory), type_factory_closure: None, scope: scope }
+        Self { type_info: type_info, type_factory: Some(type_factory), type_factory_closure: None, scope: scope, key: None }
     }
 
     // create a new service descriptor with a closure for the factory function
     pub fn new_closure<T>(type_info: Box<TypeInfo>, type_factory: T, scope: ServiceScope) -> Self
      where T: Fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>> + 'static {
-        Self { type_info: type_info, type_factory: None, type_factory_closure: Some(Box::new(type_factory)), scope: scope }
+        Self { type_info: type_info, type_factory: None, type_factory_closure: Some(Box::new(type_factory)), scope: scope, key: None }
     }
 
     pub fn new_from<TService: 'static + ?Sized, TImplementation>(type_factory: fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>, scope: ServiceScope) -> Self {
@@ -41,6 +43,9 @@ impl ServiceDescriptor {
 
 impl fmt::Display for ServiceDescriptor {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?} - {:?}", self.scope, self.type_info)
+        match &self.key {
+            Some(key) => write!(f, "{:?} - {:?} [{}]", self.scope, self.type_info, key),
+            None => write!(f, "{:?} - {:?}", self.scope, self.type_info),
+        }
     }
 }
\ No newline at end of file
@@ -3,10 +3,25 @@ use std::rc::Rc;
 
 use crate::services::service_descriptor::ServiceDescriptor;
 
-// the service instance struct holds the service descriptor and the instance of the service.
+// the service instance struct holds the service descriptor and the instances of the service that are kept alive by a scope.
 pub struct ServiceInstance {
     // descriptor for the service
-    pub descriptor: ServiceDescriptor,
-    // instance of the service
-    pub instance: Rc<dyn Any>,
-}
\ No newline at end of file
+    pub descriptor: Rc<ServiceDescriptor>,
+    // instances of the service, as returned by the descriptor's factory
+    pub instances: Vec<Box<dyn Any>>,
+}
+
+impl ServiceInstance {
+    pub fn new(descriptor: Rc<ServiceDescriptor>, instances: Vec<Box<dyn Any>>) -> Self {
+        Self { descriptor: descriptor, instances: instances }
+    }
+
+    // clone the instances to hand out from the collection.
+    // returns: the clones, or None if an instance cannot be cloned because its type was not created with TypeInfo::rc_of.
+    pub fn try_clone_instances(&self) -> Option<Vec<Box<dyn Any>>> {
+        self.instances
+            .iter()
+            .map(|x| self.descriptor.type_info.try_clone_rc(x.as_ref()))
+            .collect()
+    }
+}
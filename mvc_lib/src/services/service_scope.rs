@@ -2,19 +2,23 @@
 
 // the scope of the service instance tells the service collection how to create the service instance
 // and when to let go or dispose of it.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ServiceScope {
     // the service instance is created once and then held onto for the lifetime of the application.
     Singleton,
     // the service instance is created once per host and then disposed of at the end of the host's lifetime.
+    // each worker thread has its own root collection, which is the host of its services.
     Host,
     // the service instance is created once per request and then disposed of at the end of the request.
     Request,
     // the service instance is created once per area and then disposed of at the end of the area's lifetime.
+    // it has to be requested from a collection created with this scope.
     Area,
     // the service instance is created once per controller and then disposed of at the end of the controller's lifetime.
+    // it has to be requested from a collection created with this scope.
     Controller,
     // the service instance is created once per scope and then disposed of at the end of the scope.
+    // it has to be requested from a collection created with this scope.
     Scope,
     // the service instance is created per call to the service collection.
     AlwaysNew
@@ -0,0 +1,385 @@
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_instance::ServiceInstance;
+use crate::services::service_scope::ServiceScope;
+
+
+// this enum represents a problem found while validating the services of a service collection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServiceValidationError {
+    // a service needs another service that is not registered.
+    MissingDependency { service: String, dependency: String },
+    // services need each other, so none of them can be created. the first service is repeated at the end.
+    Cycle { services: Vec<String> },
+    // a long lived service, such as a singleton, holds onto a service that should only live as long as a request.
+    CaptiveDependency { service: String, scope: ServiceScope, dependency: String, dependency_scope: ServiceScope },
+    // the factory of a service panicked for another reason.
+    FactoryPanicked { service: String, message: String },
+}
+
+impl fmt::Display for ServiceValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingDependency { service, dependency } =>
+                write!(f, "{} depends on {}, which is not registered", service, dependency),
+            Self::Cycle { services } =>
+                write!(f, "dependency cycle: {}", services.join(" -> ")),
+            Self::CaptiveDependency { service, scope, dependency, dependency_scope } =>
+                write!(f, "{} ({:?}) depends on {} ({:?}), which would be kept alive longer than its scope", service, scope, dependency, dependency_scope),
+            Self::FactoryPanicked { service, message } =>
+                write!(f, "{} could not be created: {}", service, message),
+        }
+    }
+}
+
+// this struct is the list of problems found while validating the services of a service collection.
+#[derive(Debug, Clone)]
+pub struct ServiceValidationErrors(pub Vec<ServiceValidationError>);
+
+impl Error for ServiceValidationErrors {}
+impl fmt::Display for ServiceValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} service validation error(s):", self.0.len())?;
+        for error in self.0.iter() {
+            write!(f, "\n\t{}", error)?;
+        }
+        Ok(())
+    }
+}
+
+// this struct is a dependency of a service in the service graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceGraphDependency {
+    // the name of the type that was requested.
+    pub type_name: String,
+    // the scope of the service that was resolved, or None if no service is registered for the type.
+    pub scope: Option<ServiceScope>,
+}
+
+// this struct is a service registration in the service graph.
+#[derive(Debug, Clone)]
+pub struct ServiceGraphNode {
+    pub type_name: String,
+    pub scope: ServiceScope,
+    // the services requested by the factory of the service, in the order they were requested.
+    pub dependencies: Vec<ServiceGraphDependency>,
+}
+
+// this struct is the graph of services and the services they depend on, found by creating every service once.
+#[derive(Debug, Clone)]
+pub struct ServiceGraph {
+    pub nodes: Vec<ServiceGraphNode>,
+}
+
+impl fmt::Display for ServiceGraph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for node in self.nodes.iter() {
+            writeln!(f, "{} ({:?})", node.type_name, node.scope)?;
+            for dependency in node.dependencies.iter() {
+                match dependency.scope {
+                    Some(scope) => writeln!(f, "\t-> {} ({:?})", dependency.type_name, scope)?,
+                    None => writeln!(f, "\t-> {} (not registered)", dependency.type_name)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// this struct is the graph of the services of a service collection, with the problems found while building it.
+// it is built once when the program is configured, and added to the services so it can be shown without creating every service again.
+#[derive(Debug, Clone)]
+pub struct ServiceValidationResult {
+    pub graph: ServiceGraph,
+    // the problems that were found, or None if there were none.
+    pub errors: Option<ServiceValidationErrors>,
+}
+
+impl ServiceValidationResult {
+    // add the result to the services as a singleton.
+    // each worker thread has its own services, so the result is shared between them and copied into each.
+    // services: the service collection.
+    // result: the result of validating the services.
+    pub fn add_to_services(services: &mut ServiceCollection, result: Arc<Self>) {
+        services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<Self>(), move |_| vec![Box::new(Rc::new(result.as_ref().clone()))], ServiceScope::Singleton));
+    }
+}
+
+// held while the panic hook is replaced, so validators on other threads do not restore each other's hooks.
+static PANIC_HOOK_LOCK: Mutex<()> = Mutex::new(());
+
+// a panic payload used to unwind out of services that depend on a service that could not be created.
+// the problem has already been recorded when it is raised.
+struct ServiceNotCreated;
+
+// a service being created by the validator.
+struct ServiceFrame {
+    descriptor: Rc<ServiceDescriptor>,
+    // the types that were requested but are not registered.
+    missing: Vec<String>,
+}
+
+// this struct validates the services of a service collection by creating every service once in a dry run scope.
+// it is passed to the service factories in place of the real collection, so it can record what each factory requests.
+// the instances created are kept by the validator and dropped with it, so nothing is added to the real collection.
+pub struct ServiceValidator<'a> {
+    services: &'a dyn IServiceCollection,
+    // the services being created, with the one being created last at the end.
+    stack: RefCell<Vec<ServiceFrame>>,
+    // the services created so far.
+    instances: RefCell<Vec<Rc<ServiceInstance>>>,
+    // the services that could not be created.
+    failed: RefCell<Vec<Rc<ServiceDescriptor>>>,
+    nodes: RefCell<Vec<(Rc<ServiceDescriptor>, Vec<ServiceGraphDependency>)>>,
+    errors: RefCell<Vec<ServiceValidationError>>,
+}
+unsafe impl <'a> Send for ServiceValidator<'a> {}
+unsafe impl <'a> Sync for ServiceValidator<'a> {}
+
+impl <'a> ServiceValidator<'a> {
+    // create a new validator for the services of a collection, including the services of its parents.
+    pub fn new(services: &'a dyn IServiceCollection) -> Self {
+        Self {
+            services: services,
+            stack: RefCell::new(vec![]),
+            instances: RefCell::new(vec![]),
+            failed: RefCell::new(vec![]),
+            nodes: RefCell::new(vec![]),
+            errors: RefCell::new(vec![]),
+        }
+    }
+
+    // create every service once and check the services they request.
+    // panics from service factories are caught and reported, and are not printed while validating.
+    // the panic hook is process wide, so panics on other threads are not printed while validating either.
+    // returns: the service graph, with every problem that was found.
+    pub fn validate(self: &Self) -> ServiceValidationResult {
+        let graph = self.build_graph();
+        let errors = self.errors.borrow().clone();
+        ServiceValidationResult {
+            graph: graph,
+            errors: if errors.is_empty() { None } else { Some(ServiceValidationErrors(errors)) },
+        }
+    }
+
+    // create every service once and get the services they request.
+    fn build_graph(self: &Self) -> ServiceGraph {
+        let _lock = PANIC_HOOK_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        for descriptor in self.services.get_items() {
+            // a failure has already been recorded by the service that failed.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| self.resolve(&descriptor)));
+            self.stack.borrow_mut().clear();
+        }
+        panic::set_hook(hook);
+
+        ServiceGraph {
+            nodes: self.nodes.borrow().iter().map(|(descriptor, dependencies)| ServiceGraphNode {
+                type_name: Self::get_keyed_name(&descriptor.type_info.type_name, descriptor.key.as_deref()),
+                scope: descriptor.scope,
+                dependencies: dependencies.clone(),
+            }).collect(),
+        }
+    }
+
+    // get the instances of a service, creating them if they have not been created yet.
+    fn resolve(self: &Self, descriptor: &Rc<ServiceDescriptor>) -> Vec<Box<dyn Any>> {
+        if self.failed.borrow().iter().any(|x| Rc::ptr_eq(x, descriptor)) {
+            panic::panic_any(ServiceNotCreated);
+        }
+
+        let existing = self.instances.borrow().iter().find(|x| Rc::ptr_eq(&x.descriptor, descriptor)).cloned();
+        if let Some(instances) = existing.and_then(|x| x.try_clone_instances()) {
+            return instances;
+        }
+
+        let cycle_start = self.stack.borrow().iter().position(|x| Rc::ptr_eq(&x.descriptor, descriptor));
+        if let Some(cycle_start) = cycle_start {
+            let mut services = self.stack.borrow()[cycle_start..].iter().map(|x| x.descriptor.type_info.type_name.to_string()).collect::<Vec<String>>();
+            services.push(descriptor.type_info.type_name.to_string());
+            self.add_error(ServiceValidationError::Cycle { services: services });
+            for frame in self.stack.borrow()[cycle_start..].iter() {
+                self.failed.borrow_mut().push(frame.descriptor.clone());
+            }
+            panic::panic_any(ServiceNotCreated);
+        }
+
+        self.stack.borrow_mut().push(ServiceFrame { descriptor: descriptor.clone(), missing: vec![] });
+        if !self.nodes.borrow().iter().any(|x| Rc::ptr_eq(&x.0, descriptor)) {
+            self.nodes.borrow_mut().push((descriptor.clone(), vec![]));
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.instantiate(descriptor)));
+        let frame = self.stack.borrow_mut().pop().unwrap();
+
+        match result {
+            Ok(instances) => {
+                let service_instance = ServiceInstance::new(descriptor.clone(), instances);
+                match service_instance.try_clone_instances() {
+                    Some(instances) => {
+                        self.instances.borrow_mut().push(Rc::new(service_instance));
+                        instances
+                    },
+                    None => service_instance.instances,
+                }
+            },
+            Err(payload) => {
+                if !payload.is::<ServiceNotCreated>() {
+                    let service = descriptor.type_info.type_name.to_string();
+                    self.add_error(match frame.missing.last() {
+                        Some(dependency) => ServiceValidationError::MissingDependency { service: service, dependency: dependency.clone() },
+                        None => ServiceValidationError::FactoryPanicked { service: service, message: Self::get_panic_message(payload.as_ref()) },
+                    });
+                }
+                self.failed.borrow_mut().push(descriptor.clone());
+                panic::resume_unwind(Box::new(ServiceNotCreated));
+            },
+        }
+    }
+
+    fn instantiate(self: &Self, descriptor: &ServiceDescriptor) -> Vec<Box<dyn Any>> {
+        match (&descriptor.type_factory, &descriptor.type_factory_closure) {
+            (Some(regular_fn), _) => (regular_fn)(self),
+            (None, Some(closure_fn)) => (closure_fn)(self),
+            (None, None) => panic!("No type factory function available for {}", descriptor),
+        }
+    }
+
+    // record the services requested by the service being created, and check that it can hold onto them.
+    fn add_dependencies(self: &Self, type_name: &str, descriptors: &Vec<Rc<ServiceDescriptor>>) {
+        let mut stack = self.stack.borrow_mut();
+        let frame = match stack.last_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        let mut nodes = self.nodes.borrow_mut();
+        let dependencies = &mut nodes.iter_mut().find(|x| Rc::ptr_eq(&x.0, &frame.descriptor)).unwrap().1;
+        // a service that is created more than once, because its instances cannot be shared, records its dependencies once.
+        let is_recorded = dependencies.iter().any(|x| x.type_name == type_name);
+        if descriptors.is_empty() {
+            frame.missing.push(type_name.to_string());
+            if !is_recorded {
+                dependencies.push(ServiceGraphDependency { type_name: type_name.to_string(), scope: None });
+            }
+        }
+
+        for dependency in descriptors.iter().filter(|_| !is_recorded) {
+            dependencies.push(ServiceGraphDependency { type_name: type_name.to_string(), scope: Some(dependency.scope) });
+            if Self::is_captive(frame.descriptor.scope, dependency.scope) {
+                self.add_error(ServiceValidationError::CaptiveDependency {
+                    service: frame.descriptor.type_info.type_name.to_string(),
+                    scope: frame.descriptor.scope,
+                    dependency: dependency.type_info.type_name.to_string(),
+                    dependency_scope: dependency.scope,
+                });
+            }
+        }
+    }
+
+    // whether a service with a scope would keep a service with another scope alive for longer than its scope.
+    fn is_captive(scope: ServiceScope, dependency_scope: ServiceScope) -> bool {
+        let is_long_lived = |x: ServiceScope| x == ServiceScope::Singleton || x == ServiceScope::Host;
+        is_long_lived(scope) && !is_long_lived(dependency_scope) && dependency_scope != ServiceScope::AlwaysNew
+    }
+
+    fn add_error(self: &Self, error: ServiceValidationError) {
+        let mut errors = self.errors.borrow_mut();
+        if !errors.contains(&error) {
+            errors.push(error);
+        }
+    }
+
+    // get the name of a service for the graph, with its key if it was added with one.
+    fn get_keyed_name(type_name: &str, key: Option<&str>) -> String {
+        match key {
+            Some(key) => format!("{} [{}]", type_name, key),
+            None => type_name.to_string(),
+        }
+    }
+
+    fn get_panic_message(payload: &(dyn Any + Send)) -> String {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        }
+    }
+}
+
+impl <'a> IServiceCollection for ServiceValidator<'a> {
+    fn try_get(&self, type_info: Box<TypeInfo>) -> Result<Vec<Box<dyn Any>>, &str> {
+        let descriptors = self.services.find_descriptor(type_info.clone());
+        self.add_dependencies(&type_info.type_name, &descriptors);
+        Ok(descriptors.iter().map(|descriptor| self.resolve(descriptor)).flatten().collect())
+    }
+
+    fn get_required(&self, type_info: Box<TypeInfo>) -> Vec<Box<dyn Any>> {
+        self.try_get(type_info).unwrap()
+    }
+
+    fn get_current_scope(&self) -> ServiceScope {
+        ServiceScope::Request
+    }
+
+    fn get_parent(&self) -> Option<&dyn IServiceCollection> {
+        None
+    }
+
+    fn get_root(&self) -> Option<&dyn IServiceCollection> {
+        None
+    }
+
+    fn get_items(&self) -> Vec<Rc<ServiceDescriptor>> {
+        self.services.get_items()
+    }
+
+    fn get_instances(&self) -> Vec<Rc<ServiceInstance>> {
+        self.instances.borrow().clone()
+    }
+
+    fn find_scope(&self, _scope: ServiceScope) -> Option<&dyn IServiceCollection> {
+        Some(self)
+    }
+
+    fn get_or_instantiate_in_scope(&self, descriptor: &Rc<ServiceDescriptor>) -> Vec<Box<dyn Any>> {
+        self.resolve(descriptor)
+    }
+
+    fn find_descriptor(self: &Self, type_info: Box<TypeInfo>) -> Vec<Rc<ServiceDescriptor>> {
+        self.services.find_descriptor(type_info)
+    }
+
+    fn find_descriptor_by_id(self: &Self, type_id: TypeId) -> Vec<Rc<ServiceDescriptor>> {
+        self.services.find_descriptor_by_id(type_id)
+    }
+
+    fn try_find_descriptor_by_id(self: &Self, type_id: TypeId) -> Option<&Vec<Rc<ServiceDescriptor>>> {
+        self.services.try_find_descriptor_by_id(type_id)
+    }
+
+    fn try_get_keyed(&self, type_info: Box<TypeInfo>, key: &str) -> Result<Vec<Box<dyn Any>>, &str> {
+        let descriptors = self.services.find_keyed_descriptor(type_info.clone(), key).into_iter().collect::<Vec<Rc<ServiceDescriptor>>>();
+        self.add_dependencies(&Self::get_keyed_name(&type_info.type_name, Some(key)), &descriptors);
+        Ok(descriptors.iter().map(|descriptor| self.resolve(descriptor)).flatten().collect())
+    }
+
+    fn find_keyed_descriptor(self: &Self, type_info: Box<TypeInfo>, key: &str) -> Option<Rc<ServiceDescriptor>> {
+        self.services.find_keyed_descriptor(type_info, key)
+    }
+
+    fn get_keys(self: &Self, type_info: Box<TypeInfo>) -> Vec<String> {
+        self.services.get_keys(type_info)
+    }
+}
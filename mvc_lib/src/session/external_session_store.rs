@@ -0,0 +1,90 @@
+use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
+
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+use super::session_record::SessionRecord;
+use super::session_store::ISessionStore;
+
+
+// this trait abstracts an external key value cache that sessions can be kept in, such as Redis or memcached,
+// so sessions are shared by every server of the program. the cache is expected to remove entries once they expire.
+pub trait IExternalSessionCache {
+    // get an entry of the cache.
+    // key: the key of the entry.
+    // returns the value, or None if the cache does not have it.
+    fn get(self: &Self, key: &str) -> Result<Option<String>, Rc<dyn Error>>;
+    // set an entry of the cache, replacing the entry with the same key.
+    // key: the key of the entry.
+    // value: the value.
+    // expires_at: when the entry expires, in seconds since the unix epoch.
+    fn set(self: &Self, key: &str, value: String, expires_at: u64) -> Result<(), Rc<dyn Error>>;
+    // remove an entry of the cache, if it has it.
+    // key: the key of the entry.
+    fn remove(self: &Self, key: &str) -> Result<(), Rc<dyn Error>>;
+}
+
+// this struct implements ISessionStore with an IExternalSessionCache, keeping each session as json in its own entry.
+pub struct ExternalSessionStore {
+    cache: Rc<dyn IExternalSessionCache>,
+    key_prefix: String,
+}
+
+impl ExternalSessionStore {
+    // the prefix of the keys of sessions, so they do not collide with other entries of the cache.
+    pub const DEFAULT_KEY_PREFIX: &'static str = "rshtml.session:";
+
+    // create a new store.
+    // cache: the cache the sessions are kept in.
+    // key_prefix: the prefix of the keys of sessions.
+    pub fn new(cache: Rc<dyn IExternalSessionCache>, key_prefix: String) -> Self {
+        Self { cache: cache, key_prefix: key_prefix }
+    }
+
+    // create a new store as a service with the IExternalSessionCache from the services.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new(
+            ServiceCollectionExtensions::get_required_single::<dyn IExternalSessionCache>(services),
+            Self::DEFAULT_KEY_PREFIX.to_string(),
+        )) as Rc<dyn ISessionStore>)]
+    }
+
+    // add the store to the services. an IExternalSessionCache must also be added, which the store keeps sessions in.
+    // services: the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ISessionStore>(), Self::new_service, ServiceScope::Singleton));
+    }
+
+    fn get_key(self: &Self, id: &str) -> String {
+        format!("{}{}", self.key_prefix, id)
+    }
+}
+
+impl ISessionStore for ExternalSessionStore {
+    fn load(self: &Self, id: &str) -> Result<Option<SessionRecord>, Rc<dyn Error>> {
+        match self.cache.get(&self.get_key(id))? {
+            Some(value) => {
+                let json = serde_json::from_str::<serde_json::Value>(&value).map_err(|e| Rc::new(e) as Rc<dyn Error>)?;
+                Ok(Some(SessionRecord::parse_json(json)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn save(self: &Self, record: &SessionRecord) -> Result<(), Rc<dyn Error>> {
+        self.cache.set(&self.get_key(&record.id), SessionRecord::to_json(record.clone()).to_string(), record.expires_at)
+    }
+
+    fn remove(self: &Self, id: &str) -> Result<(), Rc<dyn Error>> {
+        self.cache.remove(&self.get_key(id))
+    }
+
+    fn remove_expired(self: &Self, _now: u64) -> Result<usize, Rc<dyn Error>> {
+        // the cache removes entries once they expire.
+        Ok(0)
+    }
+}
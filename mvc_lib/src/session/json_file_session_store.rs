@@ -0,0 +1,124 @@
+use std::any::Any;
+use std::error::Error;
+use std::rc::Rc;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::core::type_info::TypeInfo;
+use crate::entity::idbset::IDbSet;
+use crate::options::session_options::ISessionOptions;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+use super::session_record::{SessionRecord, SessionRecordJsonFileDbSet};
+use super::session_store::ISessionStore;
+
+
+// this struct implements ISessionStore with a json file, so sessions are kept when the program restarts.
+// each worker thread has its own copy of the dbset, so the file is read again before each change,
+// and changes are made one at a time so that a thread does not save over the change of another.
+// the whole file is written for each change, so a session that is used again without changing
+// is only written once its expiry has moved forward by more than the expiry threshold.
+pub struct JsonFileSessionStore {
+    dbset: SessionRecordJsonFileDbSet,
+    // how far the expiry of an unchanged session moves forward before it is written, in seconds.
+    expiry_threshold: u64,
+}
+
+impl JsonFileSessionStore {
+    // create a new store with a json file, which is created if it does not exist.
+    // file_path: the path of the json file.
+    pub fn open(file_path: String) -> std::io::Result<Self> {
+        Ok(Self {
+            dbset: SessionRecordJsonFileDbSet::open(file_path, SessionRecord::new_empty, SessionRecord::parse_json, SessionRecord::to_json)?,
+            expiry_threshold: 0,
+        })
+    }
+
+    // set how far the expiry of an unchanged session moves forward before it is written.
+    // this should be well under the idle timeout, or a session in use could expire in the file.
+    // threshold: the expiry threshold, which is zero by default so every change to the expiry is written.
+    pub fn with_expiry_threshold(mut self, threshold: Duration) -> Self {
+        self.expiry_threshold = threshold.as_secs();
+        self
+    }
+
+    // create a new store as a service with the file path from the session options.
+    // an unchanged session is written once its expiry has moved forward by a tenth of the idle timeout.
+    // panics if the file cannot be opened or created.
+    pub fn new_service(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        let options = ServiceCollectionExtensions::get_required_single::<dyn ISessionOptions>(services);
+        let store = match Self::open(options.get_file_path()) {
+            Ok(store) => store.with_expiry_threshold(options.get_idle_timeout() / 10),
+            Err(e) => panic!("could not open the session store {}: {}", options.get_file_path(), e),
+        };
+        vec![Box::new(Rc::new(store) as Rc<dyn ISessionStore>)]
+    }
+
+    // add the store to the services. the session options are added with SessionMiddleware::add_to_services.
+    // services: the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ISessionStore>(), Self::new_service, ServiceScope::Singleton));
+    }
+
+    // wait for the other threads to finish changing the file.
+    fn lock() -> MutexGuard<'static, ()> {
+        static LOCK: Mutex<()> = Mutex::new(());
+        LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // remove the sessions for which keep returns false, and save the file if any were removed.
+    // returns how many sessions were removed.
+    fn retain(self: &Self, keep: impl Fn(&SessionRecord) -> bool) -> Result<usize, Rc<dyn Error>> {
+        let removed = self.dbset.find().into_iter().filter(|x| !keep(x)).collect::<Vec<SessionRecord>>();
+        if !removed.is_empty() {
+            let count = removed.len();
+            self.dbset.remove_range(removed);
+            self.dbset.upcast().save_changes()?;
+            Ok(count)
+        } else {
+            Ok(0)
+        }
+    }
+
+    // get whether a session needs to be written over the stored one.
+    // stored: the session in the file.
+    // record: the session to save.
+    fn needs_save(self: &Self, stored: &SessionRecord, record: &SessionRecord) -> bool {
+        stored.values != record.values
+            || stored.created_at != record.created_at
+            || record.expires_at < stored.expires_at
+            || record.expires_at > stored.expires_at.saturating_add(self.expiry_threshold)
+    }
+}
+
+impl ISessionStore for JsonFileSessionStore {
+    fn load(self: &Self, id: &str) -> Result<Option<SessionRecord>, Rc<dyn Error>> {
+        let _lock = Self::lock();
+        Ok(self.dbset.find().into_iter().find(|x| x.id == id))
+    }
+
+    fn save(self: &Self, record: &SessionRecord) -> Result<(), Rc<dyn Error>> {
+        let _lock = Self::lock();
+        if let Some(stored) = self.dbset.find().into_iter().find(|x| x == record) {
+            if !self.needs_save(&stored, record) {
+                return Ok(());
+            }
+            self.dbset.remove(record);
+        }
+        self.dbset.add(record);
+        self.dbset.upcast().save_changes()
+    }
+
+    fn remove(self: &Self, id: &str) -> Result<(), Rc<dyn Error>> {
+        let _lock = Self::lock();
+        self.retain(|x| x.id != id)?;
+        Ok(())
+    }
+
+    fn remove_expired(self: &Self, now: u64) -> Result<usize, Rc<dyn Error>> {
+        let _lock = Self::lock();
+        self.retain(|x| !x.is_expired(now))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod external_session_store;
+pub mod json_file_session_store;
+pub mod session_record;
+pub mod session_state;
+pub mod session_store;
\ No newline at end of file
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use crate::entity::json_file_dbset::JsonFileDbSet;
+
+
+// this struct is the state of a session as it is kept in a session store.
+// times are in seconds since the unix epoch.
+#[derive(Clone, Debug)]
+pub struct SessionRecord {
+    // the id of the session, which is the value of the session cookie.
+    pub id: String,
+    // the values of the session, by their key.
+    pub values: serde_json::Map<String, serde_json::Value>,
+    // when the session was created.
+    pub created_at: u64,
+    // when a request last used the session.
+    pub last_accessed_at: u64,
+    // when the session expires, which is the earlier of the idle and absolute timeouts.
+    pub expires_at: u64,
+}
+
+impl SessionRecord {
+    // create a new session without values.
+    // id: the id of the session.
+    // now: the current time.
+    // idle_timeout: how long the session is kept after the last request that used it.
+    // absolute_timeout: how long the session is kept after it was created.
+    pub fn new(id: String, now: u64, idle_timeout: Duration, absolute_timeout: Duration) -> Self {
+        let mut record = Self {
+            id: id,
+            values: serde_json::Map::new(),
+            created_at: now,
+            last_accessed_at: now,
+            expires_at: now,
+        };
+        record.touch(now, idle_timeout, absolute_timeout);
+        record
+    }
+
+    // create a new session without an id or values, for the JsonFileDbSet.
+    pub fn new_empty() -> Self {
+        Self::new(String::new(), 0, Duration::ZERO, Duration::ZERO)
+    }
+
+    // mark the session as used by a request, which slides its expiry forward by the idle timeout
+    // but never past the absolute timeout from when it was created.
+    // now: the current time.
+    // idle_timeout: how long the session is kept after the last request that used it.
+    // absolute_timeout: how long the session is kept after it was created.
+    pub fn touch(self: &mut Self, now: u64, idle_timeout: Duration, absolute_timeout: Duration) {
+        self.last_accessed_at = now;
+        self.expires_at = std::cmp::min(
+            now.saturating_add(idle_timeout.as_secs()),
+            self.created_at.saturating_add(absolute_timeout.as_secs()));
+    }
+
+    // get whether the session has expired.
+    // now: the current time.
+    pub fn is_expired(self: &Self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    // this is used to parse a serde_json::Value from a session store.
+    // v: the serde_json::Value to parse.
+    // returns a SessionRecord struct.
+    pub fn parse_json(v: serde_json::Value) -> Self {
+        let get_u64 = |key: &str| v.get(key).and_then(|x| x.as_u64()).unwrap_or_default();
+        Self {
+            id: v.get("id").and_then(|x| x.as_str()).unwrap_or_default().to_string(),
+            values: v.get("values").and_then(|x| x.as_object()).cloned().unwrap_or_default(),
+            created_at: get_u64("created_at"),
+            last_accessed_at: get_u64("last_accessed_at"),
+            expires_at: get_u64("expires_at"),
+        }
+    }
+
+    // this is used to convert a SessionRecord struct to a serde_json::Value.
+    // v: the SessionRecord struct to convert.
+    // returns a serde_json::Value.
+    pub fn to_json(v: Self) -> serde_json::Value {
+        serde_json::json!({
+            "id": v.id,
+            "values": v.values,
+            "created_at": v.created_at,
+            "last_accessed_at": v.last_accessed_at,
+            "expires_at": v.expires_at,
+        })
+    }
+}
+
+// records are the same session if they have the same id, so a changed session can replace the stored one.
+impl PartialEq for SessionRecord {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+// this type is used to store sessions in a json file.
+pub type SessionRecordJsonFileDbSet = JsonFileDbSet<SessionRecord>;
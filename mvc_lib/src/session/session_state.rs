@@ -0,0 +1,138 @@
+use std::cell::{Cell, RefCell};
+use std::str::FromStr;
+
+use super::session_record::SessionRecord;
+
+
+// this trait represents the server-side session of a request, which is a bag of values kept between requests.
+// the values are json values, so anything that can be converted to json can be kept in the session.
+// use SessionExtensions to get and set values as Rust types.
+pub trait ISession {
+    // get the id of the session.
+    fn get_id(self: &Self) -> String;
+    // get a value of the session.
+    // key: the key of the value.
+    fn get_value(self: &Self, key: &str) -> Option<serde_json::Value>;
+    // set a value of the session, replacing the value with the same key.
+    // key: the key of the value.
+    // value: the value.
+    fn set_value(self: &Self, key: &str, value: serde_json::Value);
+    // remove a value from the session.
+    // key: the key of the value.
+    // returns the value that was removed, if there was one.
+    fn remove(self: &Self, key: &str) -> Option<serde_json::Value>;
+    // remove all values from the session.
+    fn clear(self: &Self);
+    // get the keys of the values of the session.
+    fn get_keys(self: &Self) -> Vec<String>;
+    // end the session, so it is removed from the store and its cookie is removed once the request is handled.
+    fn abandon(self: &Self);
+    // get whether the session was ended by abandon.
+    fn is_abandoned(self: &Self) -> bool;
+    // get whether the session was created by this request.
+    fn is_new(self: &Self) -> bool;
+    // get whether the values of the session were changed by this request.
+    fn is_changed(self: &Self) -> bool;
+    // get the state of the session as it is kept in the session store.
+    fn get_record(self: &Self) -> SessionRecord;
+}
+
+// this struct implements ISession for the record of a session loaded from, or to be saved to, a session store.
+pub struct Session {
+    record: RefCell<SessionRecord>,
+    is_new: bool,
+    changed: Cell<bool>,
+    abandoned: Cell<bool>,
+}
+
+impl Session {
+    // create a new session.
+    // record: the state of the session.
+    // is_new: whether the session was created by the request, rather than loaded from the store.
+    pub fn new(record: SessionRecord, is_new: bool) -> Self {
+        Self {
+            record: RefCell::new(record),
+            is_new: is_new,
+            changed: Cell::new(false),
+            abandoned: Cell::new(false),
+        }
+    }
+}
+
+impl ISession for Session {
+    fn get_id(self: &Self) -> String {
+        self.record.borrow().id.clone()
+    }
+
+    fn get_value(self: &Self, key: &str) -> Option<serde_json::Value> {
+        self.record.borrow().values.get(key).cloned()
+    }
+
+    fn set_value(self: &Self, key: &str, value: serde_json::Value) {
+        self.record.borrow_mut().values.insert(key.to_string(), value);
+        self.changed.set(true);
+    }
+
+    fn remove(self: &Self, key: &str) -> Option<serde_json::Value> {
+        let value = self.record.borrow_mut().values.remove(key);
+        if value.is_some() {
+            self.changed.set(true);
+        }
+        value
+    }
+
+    fn clear(self: &Self) {
+        self.record.borrow_mut().values.clear();
+        self.changed.set(true);
+    }
+
+    fn get_keys(self: &Self) -> Vec<String> {
+        self.record.borrow().values.keys().cloned().collect()
+    }
+
+    fn abandon(self: &Self) {
+        self.record.borrow_mut().values.clear();
+        self.abandoned.set(true);
+    }
+
+    fn is_abandoned(self: &Self) -> bool {
+        self.abandoned.get()
+    }
+
+    fn is_new(self: &Self) -> bool {
+        self.is_new
+    }
+
+    fn is_changed(self: &Self) -> bool {
+        self.changed.get()
+    }
+
+    fn get_record(self: &Self) -> SessionRecord {
+        self.record.borrow().clone()
+    }
+}
+
+// this struct has helpers to get and set the values of a session as Rust types.
+pub struct SessionExtensions {}
+
+impl SessionExtensions {
+    // get a value of the session parsed as a T, such as a number or a string.
+    // session: the session.
+    // key: the key of the value.
+    // returns the value, or None if there is no value or it could not be parsed.
+    pub fn get<T: FromStr>(session: &dyn ISession, key: &str) -> Option<T> {
+        match session.get_value(key)? {
+            serde_json::Value::String(value) => value.parse::<T>().ok(),
+            serde_json::Value::Null => None,
+            value => value.to_string().parse::<T>().ok(),
+        }
+    }
+
+    // set a value of the session from a T that converts to json, such as a number, string, bool or Vec of them.
+    // session: the session.
+    // key: the key of the value.
+    // value: the value.
+    pub fn set<T: Into<serde_json::Value>>(session: &dyn ISession, key: &str, value: T) {
+        session.set_value(key, value.into());
+    }
+}
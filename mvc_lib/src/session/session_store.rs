@@ -0,0 +1,89 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::core::type_info::TypeInfo;
+use crate::services::service_collection::{IServiceCollection, ServiceCollection};
+use crate::services::service_descriptor::ServiceDescriptor;
+use crate::services::service_scope::ServiceScope;
+
+use super::session_record::SessionRecord;
+
+
+// this trait is where sessions are kept between requests, such as in memory, a json file or an external cache.
+// the services are created for each worker thread, so a store must share its sessions between threads.
+pub trait ISessionStore {
+    // load a session by its id.
+    // id: the id of the session.
+    // returns the session, or None if the store does not have it. sessions that expired may be returned.
+    fn load(self: &Self, id: &str) -> Result<Option<SessionRecord>, Rc<dyn Error>>;
+    // save a session, replacing the session with the same id.
+    // record: the session.
+    fn save(self: &Self, record: &SessionRecord) -> Result<(), Rc<dyn Error>>;
+    // remove a session by its id, if the store has it.
+    // id: the id of the session.
+    fn remove(self: &Self, id: &str) -> Result<(), Rc<dyn Error>>;
+    // remove the sessions that expired.
+    // now: the current time, in seconds since the unix epoch.
+    // returns how many sessions were removed.
+    fn remove_expired(self: &Self, now: u64) -> Result<usize, Rc<dyn Error>>;
+}
+
+// this struct implements ISessionStore by keeping sessions in memory, so they are lost when the program restarts.
+pub struct InMemorySessionStore {
+    records: Arc<Mutex<HashMap<String, SessionRecord>>>,
+}
+
+impl InMemorySessionStore {
+    // create a new store with its own sessions.
+    pub fn new() -> Self {
+        Self { records: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    // create a new store with the sessions of the program, which every worker thread uses.
+    pub fn new_shared() -> Self {
+        static RECORDS: OnceLock<Arc<Mutex<HashMap<String, SessionRecord>>>> = OnceLock::new();
+        Self { records: RECORDS.get_or_init(|| Arc::new(Mutex::new(HashMap::new()))).clone() }
+    }
+
+    // create a new store as a service with the sessions of the program.
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self::new_shared()) as Rc<dyn ISessionStore>)]
+    }
+
+    // add the store to the services.
+    // services: the service collection.
+    pub fn add_to_services(services: &mut ServiceCollection) {
+        services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn ISessionStore>(), Self::new_service, ServiceScope::Singleton));
+    }
+
+    fn lock(self: &Self) -> std::sync::MutexGuard<'_, HashMap<String, SessionRecord>> {
+        // a thread that panicked while holding the lock cannot have left a record half written, so the sessions are still used.
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ISessionStore for InMemorySessionStore {
+    fn load(self: &Self, id: &str) -> Result<Option<SessionRecord>, Rc<dyn Error>> {
+        Ok(self.lock().get(id).cloned())
+    }
+
+    fn save(self: &Self, record: &SessionRecord) -> Result<(), Rc<dyn Error>> {
+        self.lock().insert(record.id.clone(), record.clone());
+        Ok(())
+    }
+
+    fn remove(self: &Self, id: &str) -> Result<(), Rc<dyn Error>> {
+        self.lock().remove(id);
+        Ok(())
+    }
+
+    fn remove_expired(self: &Self, now: u64) -> Result<usize, Rc<dyn Error>> {
+        let mut records = self.lock();
+        let count = records.len();
+        records.retain(|_, x| !x.is_expired(now));
+        Ok(count - records.len())
+    }
+}
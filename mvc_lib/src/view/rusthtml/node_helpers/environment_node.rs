@@ -1,11 +1,15 @@
 use std::rc::Rc;
 
+use proc_macro2::{Delimiter, Group, Ident, Span};
+use quote::quote;
+
 use crate::view::rusthtml::{html_tag_parse_context::HtmlTagParseContext, rusthtml_parser_context::IRustHtmlParserContext, rusthtml_token::{RustHtmlToken, RustHtmlIdentOrPunct}, rusthtml_error::RustHtmlError};
 
 use super::inode_parsed::IHtmlNodeParsed;
 
 // The EnvironmentHtmlNodeParsed struct is used to parse the environment tag.
 // The environment tag is used to conditionally render a section of the view based on the environment name.
+// include and exclude are comma separated lists of environment names, checked against IHostEnvironment when the view is rendered.
 pub struct EnvironmentHtmlNodeParsed {}
 
 impl EnvironmentHtmlNodeParsed {
@@ -21,178 +25,98 @@ impl IHtmlNodeParsed for EnvironmentHtmlNodeParsed {
         return tag_name == "environment";
     }
 
-    fn on_node_parsed(&self, tag_context: &HtmlTagParseContext, html_context: Rc<dyn IRustHtmlParserContext>, output: &mut Vec<RustHtmlToken>) -> Result<bool, RustHtmlError> {
+    fn on_node_parsed(&self, tag_context: &HtmlTagParseContext, _html_context: Rc<dyn IRustHtmlParserContext>, output: &mut Vec<RustHtmlToken>) -> Result<bool, RustHtmlError> {
         // look for include or exclude attributes
-        let mut keep_or_remove: Option<bool> = None;
+        let include = Self::get_attribute_value(tag_context, "include")?;
+        let exclude = Self::get_attribute_value(tag_context, "exclude")?;
+        if include.is_none() && exclude.is_none() {
+            return Err(RustHtmlError::from_string(format!("rust html tag environment expects attribute 'include' or 'exclude' to be defined (attrs: {:?})", tag_context.html_attrs)));
+        }
 
-        match tag_context.html_attrs.get("include") {
-            Some(token) => {
-                match token.clone().unwrap() {
-                    RustHtmlToken::HtmlTagAttributeValue(value_string, value_literal, v_parts, rust_value) => {
-                        if let Some(rust_value) = rust_value {
-                            for v in rust_value {
-                                match v {
-                                    RustHtmlToken::Literal(literal, string) => {
-                                        let literal_as_str = snailquote::unescape(& if let Some(literal) = literal { literal.to_string() } else { string.unwrap_or_default() }).unwrap();
-                                        keep_or_remove = Some(html_context.get_environment_name() == literal_as_str);
-                                    },
-                                    _ => panic!("Unexpected token for environment tag value (rust value): {:?}", token),
-                                }
-                            }
-                        } else if let Some(v_parts) = v_parts {
-                            for v in v_parts {
-                                match v {
-                                    RustHtmlIdentOrPunct::Ident(ident) => {
-                                        if html_context.get_environment_name() == ident.to_string() {
-                                            keep_or_remove = Some(true);
-                                        } else {
-                                            // println!("self.environment_name ({}) DOES NOT match literal_as_str ({})", self.environment_name, literal_as_str);
-                                            keep_or_remove = Some(false);
-                                        }
-                                    },
-                                    _ => panic!("Unexpected token for environment tag value (v_parts): {:?}", token),
-                                }
-                            }
-                        } else {
-                            if let Some(v) = value_string {
-                                let v_as_str = snailquote::unescape(&v).unwrap();
-                                // println!("v_as_str: {}", v_as_str);
-    
-                                keep_or_remove = Some(html_context.get_environment_name() == v_as_str);
-                            } else if let Some(value_literal) = value_literal {
-                                keep_or_remove = Some(html_context.get_environment_name() == value_literal.to_string());
-                            } else {
-                                panic!("Unexpected token for environment tag (value_string): {:?}", token);
-                            }
-                        }
-                    }
-                    RustHtmlToken::Literal(literal, string) => {
-                        let literal_as_str = snailquote::unescape(& if let Some(literal) = literal { literal.to_string() } else { string.unwrap_or_default() }).unwrap();
-                        // println!("literal_as_str: {}", literal_as_str);
-                        keep_or_remove = Some(html_context.get_environment_name() == literal_as_str);
-                    },
-                    _ => panic!("Unexpected token for environment tag (token): {:?}", token),
-                }
-            },
-            None => {
-                // println!("environment tag does not have include field");
+        // don't add outer environment tags but do add inner elements
+        loop {
+            match output.first() {
+                Some(RustHtmlToken::HtmlTagCloseVoidPunct(_)) |
+                Some(RustHtmlToken::HtmlTagCloseSelfContainedPunct) |
+                Some(RustHtmlToken::HtmlTagCloseStartChildrenPunct) => {
+                    output.remove(0);
+                    break;
+                },
+                Some(_) => {
+                    output.remove(0);
+                },
+                None => break,
             }
         }
-        
-        match tag_context.html_attrs.get("exclude") {
-            Some(token) => {
-                match token.clone().unwrap() {
-                    RustHtmlToken::HtmlTagAttributeValue(value_string, value_literal, v_parts, rust_value) => {
-                        if let Some(v_parts) = v_parts {
-                            for v in v_parts {
-                                match v {
-                                    RustHtmlIdentOrPunct::Ident(ident) => {
-                                        if html_context.get_environment_name() != ident.to_string() {
-                                            keep_or_remove = Some(true);
-                                        } else {
-                                            // println!("self.environment_name ({}) DOES match literal_as_str ({})", self.environment_name, literal_as_str);
-                                            keep_or_remove = Some(false);
-                                        }
-                                    },
-                                    _ => panic!("Unexpected token for environment tag value (v_parts): {:?}", token),
-                                }
-                            }
-                        } else if let Some(rust_value) = rust_value {
-                            for v in rust_value {
-                                match v {
-                                    RustHtmlToken::Literal(literal, string) => {
-                                        let literal_as_str = snailquote::unescape(& if let Some(literal) = literal { literal.to_string() } else { string.unwrap_or(String::default()) }).unwrap();
-                                        // println!("literal_as_str: {}", literal_as_str);
-                                        if html_context.get_environment_name() != literal_as_str {
-                                            keep_or_remove = Some(true);
-                                        } else {
-                                            // println!("self.environment_name ({}) DOES match literal_as_str ({})", self.environment_name, literal_as_str);
-                                            keep_or_remove = Some(false);
-                                        }
-                                    },
-                                    _ => panic!("Unexpected token for environment tag value (rust_value): {:?}", token),
-                                }
-                            }
-                        }
-                        if let Some(value) = value_string {
-                            let value_as_str = snailquote::unescape(&value).unwrap();
-                            // println!("value_as_str: {}", value_as_str);
 
-                            if html_context.get_environment_name() != value_as_str {
-                                keep_or_remove = Some(true);
-                            } else {
-                                // println!("self.environment_name ({}) DOES match value_as_str ({})", self.environment_name, value_as_str);
-                                keep_or_remove = Some(false);
-                            }
-                        } else if let Some(value_literal) = value_literal {
-                            if html_context.get_environment_name() != value_literal.to_string() {
-                                keep_or_remove = Some(true);
-                            } else {
-                                // println!("self.environment_name ({}) DOES match value_as_str ({})", self.environment_name, value_as_str);
-                                keep_or_remove = Some(false);
-                            }
-                        } else {
-                            panic!("Unexpected token for environment tag (invalid or unsupported): {:?}", token);
-                        }
-                    }
-                    RustHtmlToken::Literal(literal, string) => {
-                        let literal_as_str = snailquote::unescape(& if let Some(literal) = literal { literal.to_string() } else { string.unwrap_or_default() }).unwrap();
-                        // println!("literal_as_str: {}", literal_as_str);
-                        if html_context.get_environment_name() != literal_as_str {
-                            keep_or_remove = Some(true);
-                        } else {
-                            // println!("self.environment_name ({}) DOES match literal_as_str ({})", self.environment_name, literal_as_str);
-                            keep_or_remove = Some(false);
-                        }
-                    },
-                    _ => panic!("Unexpected token for environment tag (token): {:?}", token),
+        match output.last() {
+            Some(RustHtmlToken::HtmlTagEnd(tag_end, _tag_end_tokens)) => {
+                if tag_end == &tag_context.tag_name_as_str() {
+                    output.pop();
+                } else {
+                    println!("mismatch while processing environment HTML tag (found {})", tag_end);
                 }
             },
-            None => {
-                // println!("environment tag does not have exclude field");
-            }
+            _ => {}
         }
-        
-        return match keep_or_remove {
-            Some(keep_or_remove) => {
-                if keep_or_remove {
-                    // keep - don't add outer environment tags but do add inner elements
-                    loop {
-                        match output.first().unwrap() {
-                            RustHtmlToken::HtmlTagCloseVoidPunct(_) |
-                            RustHtmlToken::HtmlTagCloseSelfContainedPunct |
-                            RustHtmlToken::HtmlTagCloseStartChildrenPunct => {
-                                output.remove(0);
-                                break;
+
+        // the environment is only known when the view is rendered, so the inner elements are wrapped in a check of the host environment.
+        let include = match include { Some(x) => quote! { Some(#x) }, None => quote! { None } };
+        let exclude = match exclude { Some(x) => quote! { Some(#x) }, None => quote! { None } };
+        let condition = quote! {
+            mvc_lib::services::host_environment::IHostEnvironmentExtensions::is_environment_tag_rendered(services, #include, #exclude)
+        };
+        let inner = output.drain(..).collect::<Vec<RustHtmlToken>>();
+        output.push(RustHtmlToken::Identifier(Ident::new("if", Span::call_site())));
+        output.push(RustHtmlToken::Group(Delimiter::None, Group::new(Delimiter::None, condition)));
+        output.push(RustHtmlToken::GroupParsed(Delimiter::Brace, inner));
+        Ok(true)
+    }
+}
+
+impl EnvironmentHtmlNodeParsed {
+    // get the value of an include or exclude attribute, such as "Development" or "Development, Staging".
+    // tag_context: the parsed environment tag.
+    // name: the name of the attribute.
+    // returns: the value, None if the attribute is not set, or an error if the value is not a literal or name.
+    fn get_attribute_value(tag_context: &HtmlTagParseContext, name: &str) -> Result<Option<String>, RustHtmlError<'static>> {
+        let unescape = |x: &str| snailquote::unescape(x).map_err(|e| RustHtmlError::from_string(format!("could not unescape environment tag attribute {}: {}", name, e)));
+        let token = match tag_context.html_attrs.get(name) {
+            Some(Some(token)) => token,
+            Some(None) => return Err(RustHtmlError::from_string(format!("environment tag attribute {} needs a value", name))),
+            None => return Ok(None),
+        };
+
+        match token {
+            RustHtmlToken::HtmlTagAttributeValue(value_string, value_literal, v_parts, rust_value) => {
+                if let Some(rust_value) = rust_value {
+                    let mut value = String::new();
+                    for v in rust_value {
+                        match v {
+                            RustHtmlToken::Literal(literal, string) => {
+                                value.push_str(&unescape(& if let Some(literal) = literal { literal.to_string() } else { string.clone().unwrap_or_default() })?);
                             },
-                            _ => {
-                                output.remove(0);
-                            }
+                            _ => return Err(RustHtmlError::from_string(format!("Unexpected token for environment tag value (rust value): {:?}", token))),
                         }
                     }
-                    
-                    match output.last().unwrap() {
-                        RustHtmlToken::HtmlTagEnd(tag_end, _tag_end_tokens) => {
-                            if tag_end == &tag_context.tag_name_as_str() {
-                                let _pop_result = output.pop();
-                                // println!("output.pop(): {:?}", pop_result);
-                            } else {
-                                println!("mismatch while processing environment HTML tag (found {})", tag_end);
-                            }
-                        },
-                        _ => {}
-                    }
-
-                    // do not add environment tag start but do add child nodes
-                    Ok(true)
+                    Ok(Some(value))
+                } else if let Some(v_parts) = v_parts {
+                    Ok(Some(v_parts.iter().map(|v| match v {
+                        RustHtmlIdentOrPunct::Ident(ident) => ident.to_string(),
+                        RustHtmlIdentOrPunct::Punct(punct) => punct.as_char().to_string(),
+                    }).collect::<String>()))
+                } else if let Some(v) = value_string {
+                    Ok(Some(unescape(v)?))
+                } else if let Some(value_literal) = value_literal {
+                    Ok(Some(unescape(&value_literal.to_string())?))
                 } else {
-                    // do not add anything
-                    Ok(false)
+                    Err(RustHtmlError::from_string(format!("Unexpected token for environment tag (value_string): {:?}", token)))
                 }
             },
-            None => {
-                Err(RustHtmlError::from_string(format!("rust html tag environment expects attribute 'include' or 'exclude' to be defined (attrs: {:?})", tag_context.html_attrs)))
-            }
+            RustHtmlToken::Literal(literal, string) => {
+                Ok(Some(unescape(& if let Some(literal) = literal { literal.to_string() } else { string.clone().unwrap_or_default() })?))
+            },
+            _ => Err(RustHtmlError::from_string(format!("Unexpected token for environment tag (token): {:?}", token))),
         }
     }
-}
\ No newline at end of file
+}
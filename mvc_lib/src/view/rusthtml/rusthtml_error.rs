@@ -2,6 +2,8 @@ use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 
+use crate::errors::UrlGenerationError;
+
 
 // this struct is used to represent an error that occurs while parsing RustHTML.
 #[derive(Debug, Clone)]
@@ -21,4 +23,11 @@ impl <'a> RustHtmlError<'a> {
     pub fn from_string(s: String) -> RustHtmlError<'a> {
         return Self(Cow::Owned(s));
     }
+}
+
+// a url that could not be generated in a view, such as by url.route, is an error rendering the view.
+impl <'a> From<UrlGenerationError> for RustHtmlError<'a> {
+    fn from(err: UrlGenerationError) -> Self {
+        Self::from_string(err.to_string())
+    }
 }
\ No newline at end of file
@@ -39,7 +39,6 @@ use super::iviews_path_resolver::IViewsPathResolver;
 use super::node_helpers::environment_node::EnvironmentHtmlNodeParsed;
 use super::node_helpers::inode_parsed::IHtmlNodeParsed;
 use super::processors::post_process_combine_static_str::PostProcessCombineStaticStr;
-use super::tag_helpers::environment_tag::EnvironmentHtmlTagParsed;
 use super::tag_helpers::itag_parsed::IHtmlTagParsed;
 use super::views_path_resolver::RegularViewsPathResolver;
 
@@ -298,7 +297,6 @@ impl RustHtmlParserContext {
                 Rc::new(MarkdownFileNoCacheDirective::new()),
             ],
             tag_parsed_handlers: vec![
-                Rc::new(EnvironmentHtmlTagParsed::new()),
                 // Rc::new(DoctypeTagParsed::new()),
             ],
             node_parsed_handlers: vec![
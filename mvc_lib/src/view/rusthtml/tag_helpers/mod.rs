@@ -1,2 +1 @@
-pub mod environment_tag;
 pub mod itag_parsed;
\ No newline at end of file
@@ -103,6 +103,22 @@ impl ViewRenderer  {
     pub fn add_to_services(services: &mut ServiceCollection) {
         services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IViewRenderer>(), ViewRenderer::new_service, ServiceScope::Singleton));
     }
+
+    // add a view to the service collection with its path as the key, so it is found without searching every view.
+    // path: the path of the view relative to the views directory, such as "home/index.rs".
+    // new_view: creates the view, such as the new_service function generated for a view.
+    pub fn add_view(services: &mut ServiceCollection, path: &str, new_view: fn() -> Box<dyn Any>) {
+        services.add_keyed(path, ServiceDescriptor::new_closure(TypeInfo::rc_of::<dyn IView>(), move |_| vec![new_view()], ServiceScope::Singleton));
+    }
+
+    // get the views added without a key and the views added with their path as the key.
+    fn load_views(services: &dyn IServiceCollection) -> Vec<Rc<dyn IView>> {
+        let mut views = ServiceCollectionExtensions::get_required_multiple::<dyn IView>(services);
+        for path in ServiceCollectionExtensions::get_keys::<dyn IView>(services) {
+            views.push(ServiceCollectionExtensions::get_required_keyed::<dyn IView>(services, &path));
+        }
+        views
+    }
 }
 
 impl IViewRenderer for ViewRenderer {
@@ -152,9 +168,7 @@ impl IViewRenderer for ViewRenderer {
     fn get_all_views(self: &Self, services: &dyn IServiceCollection) -> Vec<Rc<dyn IView>> {
         self.cached_views
             .borrow_mut()
-            .get_or_insert_with(|| 
-                ServiceCollectionExtensions::get_required_multiple::<dyn IView>(services)
-            )
+            .get_or_insert_with(|| Self::load_views(services))
             .clone()
             .iter()
             .map(|x| x.clone())
@@ -164,9 +178,7 @@ impl IViewRenderer for ViewRenderer {
     fn get_views(self: &Self, path: &String, services: &dyn IServiceCollection) -> Vec<Rc<dyn IView>> {
         self.cached_views
             .borrow_mut()
-            .get_or_insert_with(|| 
-                ServiceCollectionExtensions::get_required_multiple::<dyn IView>(services)
-            )
+            .get_or_insert_with(|| Self::load_views(services))
             .clone()
             .iter()
             .filter(|x| string_ends_with_any(x.get_path(), &[path, format!("{}.rs", path).as_str(), format!("{}.rshtml", path).as_str()]))
@@ -175,6 +187,10 @@ impl IViewRenderer for ViewRenderer {
     }
 
     fn get_view(self: &Self, path: &String, services: &dyn IServiceCollection) -> Rc<dyn IView> {
+        if let Some(view) = ServiceCollectionExtensions::try_get_keyed::<dyn IView>(services, path) {
+            return view;
+        }
+
         match self.get_views(path, services).first() {
             Some(x) => {
                 x.clone()
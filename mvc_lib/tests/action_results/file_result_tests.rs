@@ -0,0 +1,135 @@
+use std::rc::Rc;
+
+use mvc_lib::action_results::file_result::FileResult;
+use mvc_lib::action_results::iaction_result::IActionResult;
+use mvc_lib::contexts::fromstring_connection_context::FromStringConnectionContext;
+use mvc_lib::contexts::httpconnection_context::HttpConnectionContext;
+use mvc_lib::contexts::ihttpconnection_context::IHttpConnectionContext;
+use mvc_lib::contexts::request_context::RequestContext;
+use mvc_lib::contexts::response_context::ResponseContext;
+use mvc_lib::http::byte_range::ByteRange;
+use mvc_lib::http::cache_validators::CacheValidators;
+use mvc_lib::services::service_collection::ServiceCollection;
+
+
+// write a file with the alphabet in it for a test, and return its path.
+fn new_test_file(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("rshtml_file_result_{}.txt", name));
+    std::fs::write(&path, "abcdefghijklmnopqrstuvwxyz").unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+// serve a file for a request and return the response that was written.
+fn serve_file(path: &str, request_headers: &str) -> String {
+    let data = format!("GET /file.txt HTTP/1.1\r\nHost: localhost\r\n{}\r\n", request_headers);
+    let tcp_context = Rc::new(FromStringConnectionContext::new(data, 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    let request_context = RequestContext::parse(&connection_context).unwrap();
+    let response_context = ResponseContext::new(&request_context);
+    let services = ServiceCollection::new_root();
+
+    let result = FileResult::new(path.to_string().into(), None).with_cache_control(Some("public, max-age=60".to_string()));
+    result.configure_response(&response_context, &request_context, &services).unwrap();
+    connection_context.end_writing().unwrap();
+    tcp_context.get_output_string()
+}
+
+fn get_etag(path: &str) -> String {
+    CacheValidators::from_metadata(&std::fs::metadata(path).unwrap()).etag
+}
+
+#[test]
+fn file_result_writes_validators() {
+    let path = new_test_file("validators");
+    let output = serve_file(&path, "");
+
+    assert!(output.starts_with("HTTP/1.1 200 OK"));
+    assert!(output.contains(&format!("etag: {}\r\n", get_etag(&path))));
+    assert!(output.contains("last-modified: "));
+    assert!(output.contains("cache-control: public, max-age=60\r\n"));
+    assert!(output.contains("accept-ranges: bytes\r\n"));
+    assert!(output.ends_with("\r\n\r\nabcdefghijklmnopqrstuvwxyz"));
+}
+
+#[test]
+fn file_result_not_modified() {
+    let path = new_test_file("not_modified");
+    let output = serve_file(&path, &format!("If-None-Match: \"other\", {}\r\n", get_etag(&path)));
+    assert!(output.starts_with("HTTP/1.1 304 Not Modified"));
+    assert!(output.ends_with("\r\n\r\n"));
+
+    let output = serve_file(&path, "If-Modified-Since: Sun, 06 Nov 1994 08:49:37 GMT\r\n");
+    assert!(output.starts_with("HTTP/1.1 200 OK"));
+}
+
+#[test]
+fn file_result_serves_single_range() {
+    let path = new_test_file("single_range");
+    let output = serve_file(&path, "Range: bytes=2-4\r\n");
+
+    assert!(output.starts_with("HTTP/1.1 206 Partial Content"));
+    assert!(output.contains("content-range: bytes 2-4/26\r\n"));
+    assert!(output.contains("content-length: 3\r\n"));
+    assert!(output.ends_with("\r\n\r\ncde"));
+}
+
+#[test]
+fn file_result_serves_multiple_ranges() {
+    let path = new_test_file("multiple_ranges");
+    let output = serve_file(&path, "Range: bytes=0-1, -2\r\n");
+
+    assert!(output.starts_with("HTTP/1.1 206 Partial Content"));
+    assert!(output.contains("content-type: multipart/byteranges; boundary="));
+    assert!(output.contains("Content-Range: bytes 0-1/26\r\n\r\nab\r\n"));
+    assert!(output.contains("Content-Range: bytes 24-25/26\r\n\r\nyz\r\n"));
+
+    let body_start = output.find("\r\n\r\n").unwrap() + 4;
+    let content_length = output.lines().find(|x| x.starts_with("content-length: ")).unwrap()["content-length: ".len()..].parse::<usize>().unwrap();
+    assert_eq!(content_length, output.len() - body_start);
+}
+
+#[test]
+fn file_result_rejects_unsatisfiable_range() {
+    let path = new_test_file("unsatisfiable_range");
+    let output = serve_file(&path, "Range: bytes=100-\r\n");
+
+    assert!(output.starts_with("HTTP/1.1 416 Range Not Satisfiable"));
+    assert!(output.contains("content-range: bytes */26\r\n"));
+}
+
+#[test]
+fn file_result_ignores_stale_if_range() {
+    let path = new_test_file("if_range");
+    let output = serve_file(&path, "Range: bytes=2-4\r\nIf-Range: \"stale\"\r\n");
+    assert!(output.starts_with("HTTP/1.1 200 OK"));
+
+    let output = serve_file(&path, &format!("Range: bytes=2-4\r\nIf-Range: {}\r\n", get_etag(&path)));
+    assert!(output.starts_with("HTTP/1.1 206 Partial Content"));
+}
+
+#[test]
+fn byte_range_parse_works() {
+    assert_eq!(Some(vec![ByteRange::new(0, 9)]), ByteRange::parse("bytes=0-9", 100));
+    assert_eq!(Some(vec![ByteRange::new(90, 99)]), ByteRange::parse("bytes=90-", 100));
+    assert_eq!(Some(vec![ByteRange::new(95, 99)]), ByteRange::parse("bytes=-5", 100));
+    assert_eq!(Some(vec![ByteRange::new(0, 99)]), ByteRange::parse("bytes=0-500", 100));
+    assert_eq!(Some(vec![]), ByteRange::parse("bytes=100-200", 100));
+    assert_eq!(None, ByteRange::parse("bytes=5-1", 100));
+    assert_eq!(None, ByteRange::parse("items=0-1", 100));
+}
+
+#[test]
+fn file_result_reports_errors_writing_the_body() {
+    // a directory can be opened like a file, but reading it fails once the headers are written.
+    let path = std::env::temp_dir().join("rshtml_file_result_directory");
+    std::fs::create_dir_all(&path).unwrap();
+    let data = "GET /file.txt HTTP/1.1\r\nHost: localhost\r\n\r\n".to_string();
+    let connection_context = HttpConnectionContext::new(Rc::new(FromStringConnectionContext::new(data, 1)));
+    let request_context = RequestContext::parse(&connection_context).unwrap();
+    let response_context = ResponseContext::new(&request_context);
+    connection_context.set_keep_alive(true);
+
+    let result = FileResult::new(path.to_str().unwrap().to_string().into(), None);
+    assert!(result.configure_response(&response_context, &request_context, &ServiceCollection::new_root()).is_err());
+    assert!(!connection_context.get_keep_alive());
+}
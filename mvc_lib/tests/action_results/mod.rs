@@ -1 +1,2 @@
-pub mod http_result_tests;
\ No newline at end of file
+pub mod http_result_tests;
+pub mod file_result_tests;
@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+use std::time::Duration;
+
+use mvc_lib::app::background_service::{BackgroundService, BackgroundServiceHost};
+use mvc_lib::core::cancellation_token::CancellationToken;
+
+
+// a periodic service that fails every other run and cancels the token after a number of runs.
+struct CountingService {
+    runs: RefCell<u32>,
+    max_runs: u32,
+}
+
+impl BackgroundService for CountingService {
+    fn get_interval(self: &Self) -> Option<Duration> {
+        Some(Duration::from_millis(1))
+    }
+
+    fn run(self: &Self, cancellation_token: &CancellationToken) -> Result<(), Rc<dyn Error>> {
+        *self.runs.borrow_mut() += 1;
+        let runs = *self.runs.borrow();
+        if runs >= self.max_runs {
+            cancellation_token.cancel();
+        }
+
+        if runs % 2 == 0 {
+            Err(Rc::new(std::io::Error::new(std::io::ErrorKind::Other, "failed")))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn background_service_host_runs_periodic_service_until_cancelled() {
+    let service = CountingService { runs: RefCell::new(0), max_runs: 5 };
+    let cancellation_token = CancellationToken::new();
+
+    BackgroundServiceHost::run_service(&service, None, &cancellation_token);
+
+    assert_eq!(5, *service.runs.borrow());
+}
+
+#[test]
+fn background_service_host_stops_threads_when_cancelled() {
+    let host = BackgroundServiceHost::new();
+    host.spawn("test-background".to_string(), |cancellation_token| {
+        while !cancellation_token.wait(Duration::from_secs(60)) {}
+    });
+    assert_eq!(1, host.count());
+
+    assert!(host.stop(Duration::from_secs(5)));
+    assert!(host.get_cancellation_token().is_cancelled());
+}
+
+#[test]
+fn cancellation_token_wait_times_out() {
+    let cancellation_token = CancellationToken::new();
+    assert!(!cancellation_token.wait(Duration::from_millis(1)));
+
+    cancellation_token.clone().cancel();
+    assert!(cancellation_token.wait(Duration::from_secs(60)));
+}
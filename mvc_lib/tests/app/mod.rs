@@ -0,0 +1,2 @@
+pub mod background_service_tests;
+pub mod web_program_tests;
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::any::Any;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned};
+use rustls::pki_types::ServerName;
+
+use mvc_lib::app::web_program::{IWebProgram, WebProgram};
+use mvc_lib::core::tls_tcpstream::TlsTcpStream;
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::options::http_options::{HttpOptions, IHttpOptions};
+use mvc_lib::services::default_services::DefaultServices;
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection};
+use mvc_lib::services::service_descriptor::ServiceDescriptor;
+use mvc_lib::services::service_scope::ServiceScope;
+
+
+// a self-signed certificate for localhost, used by both ends of the connection.
+const CERTIFICATE_PATH: &str = "tests/app/tls/localhost.pem";
+const PRIVATE_KEY_PATH: &str = "tests/app/tls/localhost.key";
+
+fn on_configure_services(services: &mut ServiceCollection) {
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<dyn IHttpOptions>(), HttpOptions::new_service_from_configuration, ServiceScope::Singleton));
+    DefaultServices::add_logging(services);
+    DefaultServices::add_error_handling(services);
+    DefaultServices::use_error_handling(services);
+}
+
+// connect to the server with TLS, trusting only the self-signed certificate.
+fn connect_tls(port: u16) -> StreamOwned<ClientConnection, TcpStream> {
+    let mut roots = RootCertStore::empty();
+    for certificate in rustls_pemfile::certs(&mut std::io::BufReader::new(std::fs::File::open(CERTIFICATE_PATH).unwrap())) {
+        roots.add(certificate.unwrap()).unwrap();
+    }
+    let config = ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let connection = ClientConnection::new(Arc::new(config), ServerName::try_from("localhost").unwrap()).unwrap();
+    let socket = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(30))).unwrap();
+    StreamOwned::new(connection, socket)
+}
+
+// how many times the counted service was created.
+static COUNTED_SERVICE_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+fn new_counted_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+    COUNTED_SERVICE_CREATED.fetch_add(1, Ordering::SeqCst);
+    vec![Box::new(Rc::new(String::from("counted")))]
+}
+
+fn on_configure_counted_services(services: &mut ServiceCollection) {
+    on_configure_services(services);
+    services.add(ServiceDescriptor::new(TypeInfo::rc_of::<String>(), new_counted_service, ServiceScope::Singleton));
+}
+
+#[test]
+fn web_program_builds_service_graph_once_when_configured() {
+    let mut program = WebProgram::new();
+    program.on_configure_services(on_configure_counted_services);
+    program.configure_services();
+
+    // the graph is built by creating every service once, and is not built again to show it.
+    assert_eq!(1, COUNTED_SERVICE_CREATED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn web_program_answers_pipelined_https_requests() {
+    let mut program = WebProgram::new();
+    program.on_configure_services(on_configure_services);
+    program.configure_services();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let client = std::thread::spawn(move || {
+        let mut stream = connect_tls(port);
+        // both requests are sent at once, so the server decrypts the second one while reading the first.
+        stream.write_all(b"GET /first HTTP/1.1\r\nHost: localhost\r\n\r\nGET /second HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").unwrap();
+        let mut response = vec![];
+        let _ = stream.read_to_end(&mut response);
+        String::from_utf8_lossy(&response).to_string()
+    });
+
+    let tls_config = TlsTcpStream::load_server_config(CERTIFICATE_PATH, PRIVATE_KEY_PATH).unwrap();
+    program.client_connected(listener.accept().map(|x| x.0), Some(tls_config));
+
+    let response = client.join().unwrap();
+    assert_eq!(2, response.matches("HTTP/1.1 ").count(), "{}", response);
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use http::{Method, StatusCode};
+use core_macro_lib::{IModel, IHazAttributes, reflect_attributes, reflect_properties, reflect_methods};
+use mvc_lib::auth::auth_role_json_file_dbset::JsonAuthRole;
+use mvc_lib::auth::authorization_policy::AuthorizationPolicy;
+use mvc_lib::auth::claim::Claim;
+use mvc_lib::auth::iauthroles_dbset_provider::IAuthRolesDbSetProvider;
+use mvc_lib::contexts::irequest_context::MockIRequestContext;
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::entity::idbset::IDbSetAny;
+use mvc_lib::entity::vec_dbset::VecDbSet;
+use mvc_lib::errors::AuthorizationError;
+use mvc_lib::model_binder::ihaz_attributes::IHazAttributes;
+use mvc_lib::model_binder::imodel::{AnyIModel, IModel};
+use mvc_lib::model_binder::imodel_attribute::IAttribute;
+use mvc_lib::model_binder::imodel_method::IModelMethod;
+use mvc_lib::model_binder::imodel_property::IModelProperty;
+use mvc_lib::model_binder::model_validation_result::ModelValidationResult;
+use mvc_lib::model_binder::reflected_attribute::ReflectedAttribute;
+use mvc_lib::model_binder::reflected_method::ReflectedMethod;
+use mvc_lib::model_binder::reflected_property::ReflectedProperty;
+use mvc_lib::services::authorization_service::{AuthRejectionReason, AuthResult, AuthorizationService, IAuthClaim, IAuthorizationService};
+
+
+struct TestAuthRolesDbSetProvider {
+    roles: VecDbSet<JsonAuthRole>,
+}
+
+impl IAuthRolesDbSetProvider for TestAuthRolesDbSetProvider {
+    fn get_authroles_dbset(self: &Self) -> &dyn IDbSetAny {
+        &self.roles
+    }
+}
+
+#[reflect_attributes]
+#[reflect_properties]
+#[derive(Clone, IHazAttributes, IModel)]
+pub struct Article {
+    pub owner_id: String,
+}
+
+#[reflect_methods]
+impl Article {
+    pub fn new(owner_id: String) -> Self {
+        Self { owner_id: owner_id }
+    }
+}
+
+fn new_service(policies: Vec<AuthorizationPolicy>) -> AuthorizationService {
+    let mut service = AuthorizationService::new(Rc::new(TestAuthRolesDbSetProvider { roles: VecDbSet::new() }));
+    for policy in policies {
+        service.add_policy(Rc::new(policy));
+    }
+    service
+}
+
+fn new_claim(name: &str, value: &str) -> Rc<dyn IAuthClaim> {
+    Rc::new(Claim::new(name.to_string(), HashMap::from([(name.to_string(), value.to_string())])))
+}
+
+fn get_reason(result: AuthResult) -> Option<AuthRejectionReason> {
+    match result {
+        AuthResult::Ok => None,
+        AuthResult::Rejection(reason) => Some(reason),
+    }
+}
+
+#[test]
+fn named_policy_needs_all_of_its_requirements() {
+    let service = new_service(vec![
+        AuthorizationPolicy::new("newsroom")
+            .require_any_role(vec!["editor".to_string(), "admin".to_string()])
+            .require_claim("Department", vec!["news".to_string()]),
+    ]);
+    let request_context = MockIRequestContext::new();
+
+    let claims = vec![Claim::new_role("editor"), new_claim("Department", "news")];
+    assert!(get_reason(service.authenticate_policy_by_name(claims, "newsroom".to_string(), &request_context).unwrap()).is_none());
+
+    let claims = vec![Claim::new_role("editor"), new_claim("Department", "sports")];
+    match get_reason(service.authenticate_policy_by_name(claims, "newsroom".to_string(), &request_context).unwrap()) {
+        Some(AuthRejectionReason::MissingClaim { name, values }) => {
+            assert_eq!("Department", name);
+            assert_eq!(vec!["news".to_string()], values);
+        },
+        other => panic!("expected a missing claim, got {:?}", other),
+    }
+
+    let claims = vec![Claim::new_role("reader"), new_claim("Department", "news")];
+    match get_reason(service.authenticate_policy_by_name(claims, "newsroom".to_string(), &request_context).unwrap()) {
+        Some(AuthRejectionReason::MissingRoles { required, found, all }) => {
+            assert_eq!(vec!["editor".to_string(), "admin".to_string()], required);
+            assert_eq!(vec!["reader".to_string()], found);
+            assert!(!all);
+        },
+        other => panic!("expected missing roles, got {:?}", other),
+    }
+
+    // a misspelled policy is an error rather than a policy that lets every request through.
+    assert!(service.authenticate_policy_by_name(vec![Claim::new_role("editor")], "newsrom".to_string(), &request_context).is_err());
+    assert!(service.get_policies().iter().any(|x| x.get_name() == "newsroom"));
+}
+
+#[test]
+fn all_of_roles_and_assertions_are_rejected_with_their_reasons() {
+    let service = new_service(vec![
+        AuthorizationPolicy::new("publisher").require_all_roles(vec!["editor".to_string(), "publisher".to_string()]),
+        AuthorizationPolicy::new("read_only").require_assertion("get_only", |_, request_context| request_context.get_method() == Method::GET),
+    ]);
+    let mut request_context = MockIRequestContext::new();
+    request_context.expect_get_method().return_const(Method::POST);
+
+    let reason = get_reason(service.authenticate_policy_by_name(vec![Claim::new_role("editor")], "publisher".to_string(), &request_context).unwrap()).unwrap();
+    assert!(matches!(&reason, AuthRejectionReason::MissingRoles { all: true, .. }));
+    assert_eq!(StatusCode::FORBIDDEN, reason.get_status_code());
+    assert!(get_reason(service.authenticate_policy_by_name(vec![Claim::new_role("editor"), Claim::new_role("publisher")], "publisher".to_string(), &request_context).unwrap()).is_none());
+
+    match get_reason(service.authenticate_policy_by_name(vec![Claim::new_role("editor")], "read_only".to_string(), &request_context).unwrap()) {
+        Some(AuthRejectionReason::RequirementNotMet { policy, requirement }) => {
+            assert_eq!("read_only", policy);
+            assert_eq!("get_only", requirement);
+        },
+        other => panic!("expected a requirement that was not met, got {:?}", other),
+    }
+
+    // a request that is not authenticated is answered with 401 so it can be challenged, the others with 403.
+    assert_eq!(StatusCode::UNAUTHORIZED, AuthRejectionReason::NotAuthenticated.get_status_code());
+    let error = AuthorizationError::new(AuthRejectionReason::NotAuthenticated);
+    assert_eq!("401 Unauthorized: the request is not authenticated", error.to_string());
+    assert_eq!(Some(StatusCode::UNAUTHORIZED), AuthorizationError::get_status_code(&error));
+    assert_eq!(None, AuthorizationError::get_status_code(&std::io::Error::new(std::io::ErrorKind::Other, "other")));
+}
+
+#[test]
+fn resource_requirement_checks_the_bound_model() {
+    let service = new_service(vec![
+        AuthorizationPolicy::new("article_owner").require_resource::<Article, _>("owns_article", |claims, article| {
+            claims.iter().any(|x| x.get_name() == "UserId" && x.get_tokens()["UserId"] == article.owner_id)
+        }),
+    ]);
+    let mut request_context = MockIRequestContext::new();
+    request_context.expect_get_model_validation_result()
+        .returning_st(|| Some(ModelValidationResult::Ok(AnyIModel::new(Rc::new(Article::new("42".to_string()))))));
+
+    assert!(get_reason(service.authenticate_policy_by_name(vec![new_claim("UserId", "42")], "article_owner".to_string(), &request_context).unwrap()).is_none());
+    assert!(matches!(
+        get_reason(service.authenticate_policy_by_name(vec![new_claim("UserId", "7")], "article_owner".to_string(), &request_context).unwrap()),
+        Some(AuthRejectionReason::RequirementNotMet { .. })));
+
+    // a request without a bound model has no resource to check, so it does not meet the requirement.
+    let mut request_context = MockIRequestContext::new();
+    request_context.expect_get_model_validation_result().returning_st(|| None);
+    assert!(get_reason(service.authenticate_policy_by_name(vec![new_claim("UserId", "42")], "article_owner".to_string(), &request_context).unwrap()).is_some());
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use mvc_lib::auth::authentication_handler::{AuthenticateResult, IAuthenticationHandler};
+use mvc_lib::auth::claim::Claim;
+use mvc_lib::auth::cookie_authentication_handler::{AuthenticationTicket, CookieAuthenticationHandler};
+use mvc_lib::contexts::irequest_context::MockIRequestContext;
+use mvc_lib::contexts::iresponse_context::MockIResponseContext;
+use mvc_lib::options::cookie_authentication_options::CookieAuthenticationOptions;
+
+
+fn new_handler(encrypt: bool, secret_key: &str) -> CookieAuthenticationHandler {
+    let mut options = CookieAuthenticationOptions::new_defaults();
+    options.encrypt = encrypt;
+    options.secret_key = secret_key.as_bytes().to_vec();
+    options.expire_time = Duration::from_secs(100);
+    CookieAuthenticationHandler::new(Rc::new(options))
+}
+
+fn new_claims() -> Vec<Claim> {
+    let mut secret = Claim::new("ApiKey".to_string(), HashMap::from([("ApiKey".to_string(), "abc".to_string())]));
+    secret.secret = true;
+    vec![Claim::from_claim(Claim::new_role("admin").as_ref()), Claim::from_claim(Claim::new_identifier("Name", "viveret").as_ref()), secret]
+}
+
+#[test]
+fn cookie_ticket_round_trips_signed_and_encrypted() {
+    let handler = new_handler(true, "0123456789abcdef0123456789abcdef");
+    let ticket = handler.new_ticket(new_claims(), 1000);
+    let value = handler.protect(&ticket).unwrap();
+    assert!(!value.contains("admin"));
+    assert_eq!(ticket, handler.unprotect(&value, 1050).unwrap());
+
+    // secret claims are only kept in tickets that are encrypted.
+    let handler = new_handler(false, "0123456789abcdef0123456789abcdef");
+    let value = handler.protect(&ticket).unwrap();
+    let claims = handler.unprotect(&value, 1050).unwrap().claims;
+    assert_eq!(vec!["Role", "Name"], claims.iter().map(|x| x.name.as_str()).collect::<Vec<&str>>());
+}
+
+#[test]
+fn cookie_ticket_rejects_tampered_and_expired_values() {
+    let handler = new_handler(false, "0123456789abcdef0123456789abcdef");
+    let value = handler.protect(&handler.new_ticket(new_claims(), 1000)).unwrap();
+    let (body, signature) = value.split_once('.').unwrap();
+
+    let mut tampered = body.as_bytes().to_vec();
+    tampered[10] = if tampered[10] == b'A' { b'B' } else { b'A' };
+    assert!(handler.unprotect(&format!("{}.{}", String::from_utf8(tampered).unwrap(), signature), 1050).is_err());
+    assert!(handler.unprotect(body, 1050).is_err());
+    assert!(handler.unprotect(&format!("{}.", body), 1050).is_err());
+    assert!(new_handler(false, "another secret key that is long enough").unprotect(&value, 1050).is_err());
+    assert!(handler.unprotect(&value, 1100).is_err());
+    assert!(handler.unprotect(&value, 1099).is_ok());
+}
+
+#[test]
+fn cookie_ticket_is_renewed_after_half_its_lifetime() {
+    let ticket = AuthenticationTicket::new(vec![], 1000, 1100);
+    assert!(!ticket.should_renew(1049));
+    assert!(ticket.should_renew(1050));
+    assert!(!ticket.is_expired(1099));
+    assert!(ticket.is_expired(1100));
+}
+
+#[test]
+fn cookie_authentication_rejects_and_removes_a_tampered_cookie() {
+    let handler = new_handler(true, "0123456789abcdef0123456789abcdef");
+    let mut request_context = MockIRequestContext::new();
+    request_context.expect_get_cookies_parsed().returning(|| Some(HashMap::from([("rshtml.auth".to_string(), "e30.c2lnbmF0dXJl".to_string())])));
+    request_context.expect_get_scheme().return_const("http".to_string());
+    let mut response_context = MockIResponseContext::new();
+    response_context.expect_add_header_string()
+        .withf(|name, value| name == "Set-Cookie" && value.starts_with("rshtml.auth=; Path=/; Max-Age=0;"))
+        .times(1)
+        .return_const(());
+
+    assert!(matches!(handler.authenticate(&request_context, &response_context), AuthenticateResult::Fail(_)));
+}
@@ -0,0 +1,4 @@
+pub mod authorization_policy_tests;
+pub mod cookie_authentication_handler_tests;
+pub mod jwt_bearer_authentication_handler_tests;
+pub mod user_manager_tests;
\ No newline at end of file
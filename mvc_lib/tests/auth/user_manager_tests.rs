@@ -0,0 +1,106 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use mvc_lib::auth::auth_role_json_file_dbset::JsonAuthRole;
+use mvc_lib::auth::auth_user_json_file_dbset::JsonAuthUser;
+use mvc_lib::auth::authentication_handler::IAuthClaimsValidator;
+use mvc_lib::auth::iauthroles_dbset_provider::IAuthRolesDbSetProvider;
+use mvc_lib::auth::iauthusers_dbset_provider::GenericAuthUsersDbSetProvider;
+use mvc_lib::auth::password_hasher::{IPasswordHasher, PasswordVerificationResult, Pbkdf2PasswordHasher};
+use mvc_lib::auth::user_manager::{IUserManager, UserManager};
+use mvc_lib::entity::idbset::{IDbSet, IDbSetAny};
+use mvc_lib::entity::vec_dbset::VecDbSet;
+use mvc_lib::options::identity_options::IdentityOptions;
+
+
+struct TestAuthRolesDbSetProvider {
+    roles: VecDbSet<JsonAuthRole>,
+}
+
+impl IAuthRolesDbSetProvider for TestAuthRolesDbSetProvider {
+    fn get_authroles_dbset(self: &Self) -> &dyn IDbSetAny {
+        &self.roles
+    }
+}
+
+fn new_user_manager() -> UserManager {
+    let mut options = IdentityOptions::new_defaults();
+    options.max_failed_access_attempts = 3;
+    options.lockout_time = Duration::from_secs(60);
+    options.password_hash_iterations = 1000;
+
+    let roles = VecDbSet::new();
+    roles.add(&JsonAuthRole::parse_str("admin"));
+    UserManager::new(
+        Rc::new(GenericAuthUsersDbSetProvider::new(Box::new(VecDbSet::<JsonAuthUser>::new()))),
+        Rc::new(TestAuthRolesDbSetProvider { roles: roles }),
+        Rc::new(Pbkdf2PasswordHasher::new(options.password_hash_iterations)),
+        Rc::new(options),
+    )
+}
+
+#[test]
+fn password_hasher_verifies_and_asks_for_rehash_of_weaker_hashes() {
+    let hasher = Pbkdf2PasswordHasher::new(1000);
+    let hash = hasher.hash_password("correct horse");
+    assert!(hash.starts_with("pbkdf2-sha256$1000$"));
+    assert!(!hash.contains("correct horse"));
+    assert_ne!(hash, hasher.hash_password("correct horse"));
+
+    assert_eq!(PasswordVerificationResult::Success, hasher.verify_hashed_password(&hash, "correct horse"));
+    assert_eq!(PasswordVerificationResult::Failed, hasher.verify_hashed_password(&hash, "wrong horse"));
+    assert_eq!(PasswordVerificationResult::Failed, hasher.verify_hashed_password("not a hash", "correct horse"));
+    assert_eq!(PasswordVerificationResult::SuccessRehashNeeded, Pbkdf2PasswordHasher::new(2000).verify_hashed_password(&hash, "correct horse"));
+}
+
+#[test]
+fn user_manager_creates_unique_users_with_long_enough_passwords() {
+    let user_manager = new_user_manager();
+    let user = user_manager.create("Viveret", "password1").unwrap();
+    assert_eq!("VIVERET", user.normalized_user_name);
+    assert_eq!(Some(user.id.clone()), user_manager.find_by_name(" viveret ").map(|x| x.id));
+
+    assert!(user_manager.create("VIVERET", "password2").is_err());
+    assert!(user_manager.create("someone", "short").is_err());
+    assert!(user_manager.create("", "password1").is_err());
+
+    assert!(user_manager.add_to_role(&user.id, "missing").is_err());
+    assert_eq!(vec!["admin".to_string()], user_manager.add_to_role(&user.id, "admin").unwrap().roles);
+}
+
+#[test]
+fn user_manager_locks_out_after_too_many_failed_logins() {
+    let user_manager = new_user_manager();
+    user_manager.create("viveret", "password1").unwrap();
+    assert!(user_manager.check_password_sign_in("nobody", "password1", 1000).is_err());
+
+    assert!(user_manager.check_password_sign_in("viveret", "wrong", 1000).is_err());
+    assert!(user_manager.check_password_sign_in("viveret", "password1", 1000).is_ok());
+    assert_eq!(0, user_manager.find_by_name("viveret").unwrap().access_failed_count);
+
+    for _ in 0..3 {
+        assert!(user_manager.check_password_sign_in("viveret", "wrong", 1000).is_err());
+    }
+    assert_eq!(Some(1060), user_manager.find_by_name("viveret").unwrap().lockout_end);
+    assert!(user_manager.check_password_sign_in("viveret", "password1", 1059).is_err());
+    assert!(user_manager.check_password_sign_in("viveret", "password1", 1060).is_ok());
+    assert_eq!(None, user_manager.find_by_name("viveret").unwrap().lockout_end);
+}
+
+#[test]
+fn user_manager_change_password_invalidates_old_claims() {
+    let user_manager = new_user_manager();
+    let user = user_manager.create("viveret", "password1").unwrap();
+    let claims = user_manager.get_claims(&user);
+    assert!(user_manager.validate_claims(&claims));
+
+    assert!(user_manager.change_password(&user.id, "wrong", "password2").is_err());
+    assert!(user_manager.change_password(&user.id, "password1", "short").is_err());
+    let changed = user_manager.change_password(&user.id, "password1", "password2").unwrap();
+    assert_ne!(user.security_stamp, changed.security_stamp);
+
+    assert!(!user_manager.validate_claims(&claims));
+    assert!(user_manager.validate_claims(&user_manager.get_claims(&changed)));
+    assert!(user_manager.check_password_sign_in("viveret", "password1", 1000).is_err());
+    assert!(user_manager.check_password_sign_in("viveret", "password2", 1000).is_ok());
+}
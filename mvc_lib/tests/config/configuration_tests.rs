@@ -0,0 +1,164 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use mvc_lib::config::command_line_configuration_provider::CommandLineConfigurationProvider;
+use mvc_lib::config::configuration::{Configuration, IConfiguration};
+use mvc_lib::config::configuration_builder::ConfigurationBuilder;
+use mvc_lib::config::configuration_provider::IConfigurationProvider;
+use mvc_lib::config::environment_variables_configuration_provider::EnvironmentVariablesConfigurationProvider;
+use mvc_lib::config::json_file_configuration_provider::JsonFileConfigurationProvider;
+use mvc_lib::options::file_provider_controller_options::{FileProviderControllerOptions, IFileProviderControllerOptions};
+use mvc_lib::options::http_options::{HttpOptions, IHttpOptions};
+use mvc_lib::options::logging_services_options::LogHttpRequestsOptions;
+use mvc_lib::services::service_collection::ServiceCollection;
+
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|x| x.to_string()).collect()
+}
+
+#[test]
+fn json_configuration_flattens_objects_and_arrays() {
+    let values = JsonFileConfigurationProvider::parse(r#"{
+        "http": { "port": 8080, "keep_alive": true, "https_certificate_path": null },
+        "file_provider": { "serving_directories": [ "wwwroot/", "public/" ] }
+    }"#).unwrap();
+    let configuration = Configuration::new(values);
+
+    assert_eq!(Some("8080".to_string()), configuration.get("http:port"));
+    assert_eq!(Some("true".to_string()), configuration.get("HTTP:Keep_Alive"));
+    assert_eq!(Some("".to_string()), configuration.get("http:https_certificate_path"));
+    assert_eq!(Some("public/".to_string()), configuration.get_section("file_provider").get("serving_directories:1"));
+    assert_eq!(vec!["file_provider", "http"], configuration.get_children());
+    assert!(!configuration.get_section("missing").exists());
+
+    assert!(JsonFileConfigurationProvider::parse("[1, 2]").is_err());
+    assert!(JsonFileConfigurationProvider::parse("{ \"http\": ").is_err());
+}
+
+#[test]
+fn json_configuration_optional_file_can_be_missing() {
+    assert!(JsonFileConfigurationProvider::new("missing_appsettings.json".to_string(), true).load().unwrap().is_empty());
+    assert!(JsonFileConfigurationProvider::new("missing_appsettings.json".to_string(), false).load().is_err());
+}
+
+#[test]
+fn environment_variables_configuration_strips_prefix() {
+    let provider = EnvironmentVariablesConfigurationProvider::new("RSHTML_".to_string());
+    let values = provider.from_variables(vec![
+        ("RSHTML_HTTP__PORT".to_string(), "8000".to_string()),
+        ("rshtml_environment".to_string(), "Development".to_string()),
+        ("PATH".to_string(), "/bin".to_string()),
+    ].into_iter());
+
+    assert_eq!(vec![
+        ("HTTP:PORT".to_string(), "8000".to_string()),
+        ("environment".to_string(), "Development".to_string()),
+    ], values);
+}
+
+#[test]
+fn environment_variables_configuration_matches_prefix_by_ascii_case() {
+    // the kelvin sign lowercases to 'k', but is not the ascii letter of the prefix.
+    let provider = EnvironmentVariablesConfigurationProvider::new("KUBE_".to_string());
+    let values = provider.from_variables(vec![
+        ("\u{212A}UBE_PORT".to_string(), "1".to_string()),
+        ("kube_port".to_string(), "2".to_string()),
+        ("KUBE_".to_string(), "3".to_string()),
+    ].into_iter());
+
+    assert_eq!(vec![("port".to_string(), "2".to_string())], values);
+}
+
+#[cfg(unix)]
+#[test]
+fn environment_variables_configuration_skips_variables_that_are_not_unicode() {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::env::set_var("RSHTML_TEST_NOT_UNICODE", std::ffi::OsStr::from_bytes(&[0x66, 0x6f, 0x80]));
+    std::env::set_var("RSHTML_TEST_UNICODE", "value");
+    let values = EnvironmentVariablesConfigurationProvider::new("RSHTML_TEST_".to_string()).load().unwrap();
+    std::env::remove_var("RSHTML_TEST_NOT_UNICODE");
+    std::env::remove_var("RSHTML_TEST_UNICODE");
+
+    assert_eq!(vec![("UNICODE".to_string(), "value".to_string())], values);
+}
+
+#[test]
+fn command_line_configuration_parses_arguments() {
+    let provider = CommandLineConfigurationProvider::new(to_strings(&[
+        "target/debug/app", "--http:port=8000", "--http__ip", "0.0.0.0", "--verbose", "--environment=Development", "ignored",
+    ]));
+
+    assert_eq!(vec![
+        ("http:port".to_string(), "8000".to_string()),
+        ("http:ip".to_string(), "0.0.0.0".to_string()),
+        ("verbose".to_string(), "true".to_string()),
+        ("environment".to_string(), "Development".to_string()),
+    ], provider.load().unwrap());
+}
+
+#[test]
+fn configuration_builder_later_layers_override_earlier_layers() {
+    let configuration = ConfigurationBuilder::new()
+        .add_in_memory(vec![("http:port", "80"), ("http:ip", "127.0.0.1")])
+        .add_in_memory(vec![("HTTP:PORT", "8080")])
+        .add_command_line(&to_strings(&["--http:port=9090"]))
+        .build()
+        .unwrap();
+
+    assert_eq!(Some("9090".to_string()), configuration.get("http:port"));
+    assert_eq!(Some("127.0.0.1".to_string()), configuration.get("http:ip"));
+    assert_eq!("Development", ConfigurationBuilder::get_environment_name(&to_strings(&["--environment=Development"])).unwrap());
+}
+
+#[test]
+fn http_options_bind_from_configuration() {
+    let configuration = ConfigurationBuilder::new()
+        .add_in_memory(vec![
+            ("http:port", "8000"),
+            ("http:keep_alive", "false"),
+            ("http:read_timeout", "2.5"),
+            ("http:https_certificate_path", "cert.pem"),
+        ])
+        .build()
+        .unwrap();
+    let mut options = HttpOptions::new_default();
+    options.bind(configuration.get_section(HttpOptions::CONFIGURATION_SECTION).as_ref()).unwrap();
+
+    assert_eq!(8000, options.get_port());
+    assert_eq!(443, options.get_port_https());
+    assert!(!options.get_keep_alive());
+    assert_eq!(Duration::from_millis(2500), options.get_read_timeout());
+    assert_eq!(Some("cert.pem".to_string()), options.get_https_certificate_path());
+
+    let invalid = Configuration::new(vec![("port".to_string(), "eighty".to_string())]);
+    let error = HttpOptions::new_default().bind(&invalid).unwrap_err();
+    assert_eq!("port", error.key);
+}
+
+#[test]
+fn options_bind_from_configuration_service() {
+    let mut services = ServiceCollection::new_root();
+    let configuration = ConfigurationBuilder::new()
+        .add_in_memory(vec![
+            ("log_http_requests:log_request", "true"),
+            ("file_provider:serving_directories:0", "public/"),
+            ("file_provider:serving_files:/Stacks.css", "ts/stacks.css"),
+            ("file_provider:cache_control:0:pattern", "*.css"),
+            ("file_provider:cache_control:0:value", "max-age=60"),
+        ])
+        .build()
+        .unwrap();
+    Configuration::add_to_services(&mut services, Rc::new(configuration));
+
+    let log_options = LogHttpRequestsOptions::new_defaults().with_configuration(&services);
+    assert!(log_options.log_request);
+    assert!(!log_options.log_response);
+
+    let file_options = FileProviderControllerOptions::new_defaults().with_configuration(&services);
+    assert_eq!(vec!["public/"], file_options.serving_directories);
+    assert_eq!(Some("ts/stacks.css"), file_options.serving_files.get("/Stacks.css").map(|x| x.as_ref()));
+    assert_eq!(Some("max-age=60".to_string()), file_options.get_cache_control("/site.css"));
+    assert_eq!(None, file_options.get_cache_control("/site.js"));
+}
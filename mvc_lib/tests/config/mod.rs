@@ -0,0 +1 @@
+pub mod configuration_tests;
\ No newline at end of file
@@ -0,0 +1,253 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mvc_lib::contexts::fromstring_connection_context::FromStringConnectionContext;
+use mvc_lib::contexts::httpconnection_context::HttpConnectionContext;
+use mvc_lib::contexts::ihttpconnection_context::IHttpConnectionContext;
+use mvc_lib::contexts::irequest_context::IRequestContext;
+use mvc_lib::contexts::itcpconnection_context::ITcpConnectionContext;
+use mvc_lib::contexts::request_context::RequestContext;
+use mvc_lib::http::chunked_body_stream::{ChunkedBodyStream, ChunkedBodyStreamFormat};
+use mvc_lib::core::itcp_stream_wrapper::ITcpStreamWrapper;
+use mvc_lib::core::memory_stream::MemoryStream;
+use mvc_lib::errors::BadRequestError;
+use mvc_lib::http::content_coding_body_stream::{ContentCoding, ContentCodingBodyStream, ContentCodingBodyStreamFormat};
+use mvc_lib::http::http_body_content::ContentType;
+use mvc_lib::http::response_encoder_middleware::ResponseCompression;
+use mvc_lib::options::response_compression_options::ResponseCompressionOptions;
+
+
+#[test]
+fn http_connection_context_keep_alive_http_10_writes_content_length() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_pending_http_version(http::Version::HTTP_10);
+    connection_context.set_keep_alive(true);
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+
+    connection_context.write_str("hello").unwrap();
+    // nothing is sent until the body is complete
+    assert_eq!("", tcp_context.get_output_string());
+
+    connection_context.end_writing().unwrap();
+    let output = tcp_context.get_output_string();
+    assert!(output.starts_with("HTTP/1.1 200 OK"));
+    assert!(output.contains("content-length: 5\r\n"));
+    assert!(output.ends_with("\r\n\r\nhello"));
+}
+
+#[test]
+fn http_connection_context_without_keep_alive_http_10_writes_immediately() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_pending_http_version(http::Version::HTTP_10);
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+
+    connection_context.write_str("hello").unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output_string();
+    assert!(!output.contains("content-length"));
+    assert!(output.ends_with("\r\n\r\nhello"));
+}
+
+#[test]
+fn http_connection_context_head_request_writes_only_the_head() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_pending_request_method(http::Method::HEAD);
+    connection_context.set_keep_alive(true);
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+    connection_context.add_header_str("Content-Length", "5");
+
+    connection_context.write_str("hello").unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output_string();
+    assert!(output.contains("content-length: 5\r\n"));
+    assert!(output.ends_with("\r\n\r\n"));
+    assert!(!output.contains("hello"));
+}
+
+#[test]
+fn http_connection_context_unknown_length_writes_chunks() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_keep_alive(true);
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+
+    connection_context.write_str("hello").unwrap();
+    connection_context.write_str(" world").unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output_string();
+    assert!(output.contains("transfer-encoding: chunked\r\n"));
+    assert!(!output.contains("content-length"));
+    assert!(output.ends_with("\r\n\r\nb\r\nhello world\r\n0\r\n\r\n"));
+}
+
+#[test]
+fn http_connection_context_known_length_is_not_chunked() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_keep_alive(true);
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+    connection_context.set_header_str("Content-Length", "5");
+
+    connection_context.write_str("hello").unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output_string();
+    assert!(!output.contains("transfer-encoding"));
+    assert!(output.ends_with("\r\n\r\nhello"));
+}
+
+#[test]
+fn http_connection_context_reads_chunked_request_body() {
+    let data = "POST /a HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;name=value\r\n world\r\n0\r\nX-Trailer: a\r\n\r\nGET /b HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let tcp_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+
+    let first_context = HttpConnectionContext::new(tcp_context.clone());
+    let first_request = RequestContext::parse(&first_context).unwrap();
+    assert_eq!(first_request.get_path(), "/a");
+    tcp_context.begin_request_body_format(Rc::new(ChunkedBodyStreamFormat::new()), &ContentType::parse("chunked"));
+
+    let mut body = Vec::new();
+    let mut buffer = [0; 4];
+    loop {
+        let n = first_context.read(&mut buffer).unwrap();
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&buffer[..n]);
+    }
+    assert_eq!("hello world", String::from_utf8(body).unwrap());
+    tcp_context.end_request_body().unwrap();
+
+    let second_context = HttpConnectionContext::new(tcp_context.clone());
+    let second_request = RequestContext::parse(&second_context).unwrap();
+    assert_eq!(second_request.get_path(), "/b");
+}
+
+#[test]
+fn chunked_body_stream_parses_chunk_size() {
+    assert_eq!(26, ChunkedBodyStream::parse_chunk_size("1A;name=value").unwrap());
+    assert!(ChunkedBodyStream::parse_chunk_size("hello").is_err());
+}
+
+#[test]
+fn http_connection_context_limits_chunked_request_body() {
+    let data = "POST /a HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n";
+    let tcp_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    RequestContext::parse(&connection_context).unwrap();
+    tcp_context.begin_request_body_format(Rc::new(ChunkedBodyStreamFormat::new()), &ContentType::parse("chunked"));
+    tcp_context.limit_request_body(8);
+
+    let mut buffer = [0; 16];
+    assert_eq!(5, connection_context.read(&mut buffer).unwrap());
+    let error = connection_context.read(&mut buffer).err().unwrap();
+    assert_eq!(http::StatusCode::PAYLOAD_TOO_LARGE, BadRequestError::from_io_error(&error).unwrap().status_code);
+}
+
+#[test]
+fn http_connection_context_reads_consecutive_requests() {
+    let data = "POST /a HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhelloGET /b HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let tcp_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+
+    let first_context = HttpConnectionContext::new(tcp_context.clone());
+    let first_request = RequestContext::parse(&first_context).unwrap();
+    assert_eq!(first_request.get_path(), "/a");
+    tcp_context.begin_request_body(first_request.get_content_length().unwrap());
+
+    // the body cannot be read past its end
+    let mut body = [0; 16];
+    assert_eq!(3, first_context.read(&mut body[..3]).unwrap());
+    tcp_context.end_request_body().unwrap();
+
+    let second_context = HttpConnectionContext::new(tcp_context.clone());
+    let second_request = RequestContext::parse(&second_context).unwrap();
+    assert_eq!(second_request.get_path(), "/b");
+    assert_eq!(second_request.get_method(), http::Method::GET);
+}
+
+
+fn new_gzip_compression() -> Rc<ResponseCompression> {
+    Rc::new(ResponseCompression::new(
+        Some(("gzip".to_string(), Rc::new(ContentCodingBodyStreamFormat::new(ContentCoding::Gzip)))),
+        Rc::new(ResponseCompressionOptions::new_defaults()),
+    ))
+}
+
+#[test]
+fn http_connection_context_compresses_accepted_response() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+    connection_context.set_header_str("Content-Type", "text/plain");
+    connection_context.set_response_compression(Some(new_gzip_compression()));
+
+    let body = "hello world ".repeat(1000);
+    connection_context.write_str(&body).unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output();
+    let head_length = output.windows(4).position(|x| x == b"\r\n\r\n").unwrap() + 4;
+    let head = String::from_utf8_lossy(&output[..head_length]).to_string();
+    assert!(head.contains("content-encoding: gzip\r\n"));
+    assert!(head.contains("vary: Accept-Encoding\r\n"));
+    assert!(head.contains("transfer-encoding: chunked\r\n"));
+
+    let chunks = Rc::new(RefCell::new(MemoryStream::new()));
+    chunks.borrow().write(&output[head_length..]).unwrap();
+    let decoder = ContentCodingBodyStream::new(Rc::new(RefCell::new(ChunkedBodyStream::new(chunks))), ContentCoding::Gzip);
+    let mut decoded = Vec::new();
+    let mut buffer = [0; 1024];
+    loop {
+        let n = decoder.read(&mut buffer).unwrap();
+        if n == 0 {
+            break;
+        }
+        decoded.extend_from_slice(&buffer[..n]);
+    }
+    assert_eq!(body.as_bytes(), decoded.as_slice());
+}
+
+#[test]
+fn http_connection_context_does_not_compress_small_response() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+    connection_context.set_header_str("Content-Type", "text/plain");
+    connection_context.set_header_str("Content-Length", "5");
+    connection_context.set_response_compression(Some(new_gzip_compression()));
+
+    connection_context.write_str("hello").unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output_string();
+    assert!(!output.contains("content-encoding"));
+    assert!(output.contains("vary: Accept-Encoding\r\n"));
+    assert!(output.ends_with("\r\n\r\nhello"));
+}
+
+#[test]
+fn http_connection_context_keeps_each_set_cookie_header() {
+    let tcp_context = Rc::new(FromStringConnectionContext::new(String::new(), 1));
+    let connection_context = HttpConnectionContext::new(tcp_context.clone());
+    connection_context.set_pending_status_code(http::StatusCode::OK);
+    connection_context.add_header_str("Set-Cookie", "a=1; Path=/");
+    connection_context.add_header_str("Set-Cookie", "b=2; Path=/");
+    connection_context.add_header_str("Content-Type", "text/plain");
+    connection_context.add_header_str("Content-Type", "text/html");
+
+    connection_context.write_str("hello").unwrap();
+    connection_context.end_writing().unwrap();
+
+    let output = tcp_context.get_output_string();
+    assert!(output.contains("set-cookie: a=1; Path=/\r\n"));
+    assert!(output.contains("set-cookie: b=2; Path=/\r\n"));
+    assert!(output.contains("content-type: text/html\r\n"));
+    assert!(!output.contains("text/plain"));
+}
@@ -1,2 +1,3 @@
 pub mod request_context_tests;
-pub mod response_context_tests;
\ No newline at end of file
+pub mod response_context_tests;
+pub mod httpconnection_context_tests;
\ No newline at end of file
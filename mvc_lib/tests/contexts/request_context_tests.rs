@@ -6,6 +6,8 @@ use mvc_lib::contexts::fromstring_connection_context::FromStringConnectionContex
 use mvc_lib::contexts::httpconnection_context::HttpConnectionContext;
 use mvc_lib::contexts::irequest_context::IRequestContext;
 use mvc_lib::contexts::request_context::RequestContext;
+use mvc_lib::errors::BadRequestError;
+use mvc_lib::options::http_options::HttpOptions;
 
 
 
@@ -189,4 +191,98 @@ fn request_context_parse_minimal_works() {
     assert_eq!(request.get_http_version(), http::version::Version::HTTP_11);
     assert_eq!(request.get_method(), http::Method::GET);
     assert_eq!(request.get_path(), "/");
-}
\ No newline at end of file
+}
+#[test]
+fn request_context_parse_accepts_put_delete_and_patch() {
+    for method in [http::Method::PUT, http::Method::DELETE, http::Method::PATCH] {
+        let data = format!("{} /items/1 HTTP/1.1\r\nHost: localhost\r\n", method);
+        let connection_context = Rc::new(FromStringConnectionContext::new(data, 1));
+        let http_context = HttpConnectionContext::new(connection_context);
+        let request = RequestContext::parse(&http_context).unwrap();
+
+        assert_eq!(request.get_method(), method);
+    }
+}
+#[test]
+fn request_context_parse_reports_https_for_secure_connection() {
+    let data = "GET / HTTP/1.1\r\nHost: localhost\r\n";
+    let connection_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+    connection_context.set_secure(true);
+    let http_context = HttpConnectionContext::new(connection_context);
+    let request = RequestContext::parse(&http_context).unwrap();
+
+    assert_eq!(request.get_scheme(), "https");
+    assert_eq!(request.get_url().scheme(), "https");
+}
+
+// parse a request with the given options and return the status of the bad request it was rejected with.
+fn parse_bad_request_status(data: &str, options: &HttpOptions) -> http::StatusCode {
+    let connection_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+    let http_context = HttpConnectionContext::new(connection_context);
+    let error = RequestContext::parse_with_options(&http_context, options).err().unwrap();
+    BadRequestError::from_io_error(&error).unwrap().status_code
+}
+
+#[test]
+fn request_context_parse_rejects_long_url() {
+    let options = HttpOptions { max_url_length: 16, ..HttpOptions::new_default() };
+    let data = format!("GET /{} HTTP/1.1\r\nHost: localhost\r\n\r\n", "a".repeat(16));
+    assert_eq!(http::StatusCode::URI_TOO_LONG, parse_bad_request_status(&data, &options));
+
+    let data = format!("GET /{} HTTP/1.1\r\nHost: localhost\r\n\r\n", "a".repeat(1000));
+    assert_eq!(http::StatusCode::URI_TOO_LONG, parse_bad_request_status(&data, &options));
+}
+
+#[test]
+fn request_context_parse_rejects_too_many_headers() {
+    let options = HttpOptions { max_header_count: 2, ..HttpOptions::new_default() };
+    let data = "GET / HTTP/1.1\r\nHost: localhost\r\nA: 1\r\nB: 2\r\n\r\n";
+    assert_eq!(http::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE, parse_bad_request_status(data, &options));
+
+    let data = "GET / HTTP/1.1\r\nHost: localhost\r\nA: 1\r\n\r\n";
+    let connection_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+    let http_context = HttpConnectionContext::new(connection_context);
+    assert!(RequestContext::parse_with_options(&http_context, &options).is_ok());
+}
+
+#[test]
+fn request_context_parse_rejects_large_headers() {
+    let options = HttpOptions { max_header_bytes: 64, ..HttpOptions::new_default() };
+    let data = format!("GET / HTTP/1.1\r\nHost: localhost\r\nCookie: {}\r\n\r\n", "a".repeat(64));
+    assert_eq!(http::StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE, parse_bad_request_status(&data, &options));
+}
+
+#[test]
+fn request_context_parse_rejects_malformed_requests() {
+    let options = HttpOptions::new_default();
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("GET / HTTP/1.1\r\n\r\n", &options));
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("GET / HTTP/1.1\r\nHost localhost\r\n\r\n", &options));
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("GET /\r\nHost: localhost\r\n\r\n", &options));
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("GET / HTTP/7.0\r\nHost: localhost\r\n\r\n", &options));
+}
+
+#[test]
+fn request_context_parse_rejects_invalid_content_length() {
+    let options = HttpOptions::new_default();
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: abc\r\n\r\n", &options));
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: -1\r\n\r\n", &options));
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: +5\r\n\r\n", &options));
+    assert_eq!(http::StatusCode::BAD_REQUEST, parse_bad_request_status("POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\nContent-Length: 6\r\n\r\n", &options));
+
+    // the same length sent more than once is the same body.
+    let data = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5, 5\r\n\r\nhello";
+    let connection_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+    let http_context = HttpConnectionContext::new(connection_context);
+    assert_eq!(Some(5), RequestContext::parse_with_options(&http_context, &options).unwrap().get_content_length());
+}
+
+#[test]
+fn request_context_parse_http_10_without_host_works() {
+    let data = "GET /index.html HTTP/1.0\r\n\r\n";
+    let connection_context = Rc::new(FromStringConnectionContext::new(data.to_string(), 1));
+    let http_context = HttpConnectionContext::new(connection_context);
+    let request = RequestContext::parse(&http_context).unwrap();
+
+    assert_eq!(request.get_http_version(), http::version::Version::HTTP_10);
+    assert_eq!(request.get_path(), "/index.html");
+}
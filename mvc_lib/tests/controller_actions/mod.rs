@@ -0,0 +1 @@
+pub mod route_pattern_tests;
\ No newline at end of file
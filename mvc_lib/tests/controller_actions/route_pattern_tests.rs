@@ -0,0 +1,151 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use http::HeaderMap;
+use mvc_lib::contexts::ihttpconnection_context::MockIHttpConnectionContext;
+use mvc_lib::contexts::request_context::RequestContext;
+use mvc_lib::controller_actions::closure::ControllerActionClosure;
+use mvc_lib::controller_actions::controller_action::{IControllerAction, IControllerActionExtensions};
+use mvc_lib::controller_actions::route_pattern::ControllerActionRoutePattern;
+use mvc_lib::routing::route_constraint::{IRouteConstraint, RouteConstraint};
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection};
+
+
+fn is_match(pattern: &'static str, path: &str) -> bool {
+    ControllerActionRoutePattern::parse(pattern.into()).unwrap().try_match(path).is_some()
+}
+
+fn get_value(pattern: &'static str, path: &str, name: &str) -> Option<String> {
+    ControllerActionRoutePattern::parse(pattern.into()).unwrap().try_match(path).and_then(|x| x.get(name).cloned())
+}
+
+// a custom constraint that matches even numbers.
+struct EvenConstraint {}
+
+impl IRouteConstraint for EvenConstraint {
+    fn is_match(self: &Self, value: &str, _args: &[String]) -> bool {
+        value.parse::<i64>().map(|x| x % 2 == 0).unwrap_or(false)
+    }
+}
+
+fn new_even_constraint(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+    vec![Box::new(Rc::new(EvenConstraint {}) as Rc<dyn IRouteConstraint>)]
+}
+
+#[test]
+fn route_pattern_parse_returns_errors() {
+    for pattern in ["/users/{id", "/users/id}", "/users/{id:range(1)}", "/users/{id:regex(()}", "/users/{id:int(1)}",
+                    "/users/{id?}/details", "/users/{id}/{id}", "/users/{}", "/docs/../more", "/users/x{id}", "/users/{id?=1}"] {
+        assert!(ControllerActionRoutePattern::parse(pattern.into()).is_err(), "{} should not parse", pattern);
+    }
+}
+
+#[test]
+fn route_pattern_checks_built_in_constraints() {
+    assert!(is_match("/users/{id:int}", "/users/-12"));
+    assert!(!is_match("/users/{id:int}", "/users/abc"));
+    assert!(is_match("/users/{id:uint}", "/users/12"));
+    assert!(!is_match("/users/{id:uint}", "/users/-12"));
+    assert!(is_match("/users/{id:guid}", "/users/6f9619ff-8b86-d011-b42d-00c04fc964ff"));
+    assert!(!is_match("/users/{id:guid}", "/users/6f9619ff"));
+    assert!(is_match("/users/{active:bool}", "/users/True"));
+    assert!(!is_match("/users/{active:bool}", "/users/yes"));
+    assert!(is_match("/users/{name:alpha}", "/users/Alice"));
+    assert!(!is_match("/users/{name:alpha}", "/users/alice1"));
+    assert!(is_match("/users/{name:length(2,4)}", "/users/abc"));
+    assert!(!is_match("/users/{name:length(2,4)}", "/users/abcde"));
+    assert!(is_match("/users/{id:range(1,10)}", "/users/10"));
+    assert!(!is_match("/users/{id:range(1,10)}", "/users/11"));
+    assert!(is_match("/users/{code:regex(^[a-z]{2}\\d{3}$)}", "/users/ab123"));
+    assert!(!is_match("/users/{code:regex([a-z]{2}\\d{3})}", "/users/ab1234"));
+    assert!(is_match("/users/{id:int:range(1,10)}", "/users/5"));
+    assert!(!is_match("/users/{id:int:range(1,10)}", "/users/50"));
+}
+
+#[test]
+fn route_pattern_matches_optional_and_default_segments() {
+    assert!(is_match("/users/{id?}", "/users"));
+    assert_eq!(None, get_value("/users/{id?}", "/users", "id"));
+    assert_eq!(Some("5".to_string()), get_value("/users/{id?}", "/users/5", "id"));
+    assert_eq!(Some("index".to_string()), get_value("/users/{action=index}", "/users", "action"));
+    assert_eq!(Some("edit".to_string()), get_value("/users/{action=index}/{id:int?}", "/users/edit", "action"));
+    assert!(!is_match("/users/{action=index}/{id:int?}", "/users/edit/abc"));
+    assert!(!is_match("/docs/..", "/docs"));
+}
+
+#[test]
+fn route_pattern_gen_url_uses_defaults_and_skips_optional_segments() {
+    let pattern = ControllerActionRoutePattern::parse("/users/{action=index}/{id?}".into()).unwrap();
+    assert_eq!("/users/index", pattern.gen_url(&Default::default()).unwrap());
+    assert_eq!("/", ControllerActionRoutePattern::parse("/".into()).unwrap().gen_url(&Default::default()).unwrap());
+}
+
+#[test]
+fn route_pattern_gen_url_encodes_values_and_sorts_query() {
+    let pattern = ControllerActionRoutePattern::parse("/docs/{name}/..".into()).unwrap();
+    let values = HashMap::from([
+        ("name".to_string(), "a b/c?".to_string()),
+        ("..".to_string(), "x y/z".to_string()),
+        ("q".to_string(), "1&2".to_string()),
+        ("b".to_string(), "yes".to_string()),
+    ]);
+    assert_eq!("/docs/a%20b%2Fc%3F/x%20y/z?b=yes&q=1%262", pattern.gen_url(&values).unwrap());
+}
+
+#[test]
+fn route_pattern_decodes_captured_segments() {
+    let pattern = ControllerActionRoutePattern::parse("/docs/{name}/..".into()).unwrap();
+    let values = pattern.try_match("/docs/a%20b%2Fc%3F/x%20y/z").unwrap();
+    assert_eq!("a b/c?", values["name"]);
+    assert_eq!("x y/z", values[".."]);
+    assert!(pattern.try_match("/docs/%FF/z").is_none());
+
+    // constraints are checked against the decoded value.
+    let pattern = ControllerActionRoutePattern::parse("/tags/{tag:alpha}".into()).unwrap();
+    assert_eq!("Abc", pattern.try_match("/tags/%41bc").unwrap()["tag"]);
+    assert!(pattern.try_match("/tags/a%20b").is_none());
+}
+
+#[test]
+fn route_pattern_gen_url_reports_missing_and_invalid_values() {
+    let pattern = ControllerActionRoutePattern::parse("/users/{id:int}/{tab?}/{page?}".into()).unwrap();
+    assert!(pattern.gen_url(&Default::default()).is_err());
+    assert!(pattern.gen_url(&HashMap::from([("id".to_string(), "abc".to_string())])).is_err());
+    assert!(pattern.gen_url(&HashMap::from([("id".to_string(), "1".to_string()), ("page".to_string(), "2".to_string())])).is_err());
+    assert_eq!("/users/1", pattern.gen_url(&HashMap::from([("id".to_string(), "1".to_string())])).unwrap());
+}
+
+#[test]
+fn route_pattern_checks_custom_constraints_from_services() {
+    let mut services = ServiceCollection::new_root();
+    RouteConstraint::add_custom(&mut services, "even", new_even_constraint);
+
+    let pattern = ControllerActionRoutePattern::parse("/numbers/{n:even}".into()).unwrap();
+    assert!(pattern.try_match_with_services("/numbers/4", Some(&services)).is_some());
+    assert!(pattern.try_match_with_services("/numbers/5", Some(&services)).is_none());
+    // custom constraints that are not registered do not match.
+    assert!(pattern.try_match("/numbers/4").is_none());
+}
+
+#[test]
+fn route_that_fails_constraint_falls_through_to_next_action() {
+    let actions: Vec<Rc<dyn IControllerAction>> = vec![
+        Rc::new(ControllerActionClosure::new_not_validated(vec![], None, "/items/{id:int}".into(), "by_id".into(), "Items".into(), String::new(), &|_, _| Ok(None))),
+        Rc::new(ControllerActionClosure::new_not_validated(vec![], None, "/items/{name:alpha}".into(), "by_name".into(), "Items".into(), String::new(), &|_, _| Ok(None))),
+    ];
+    let services = ServiceCollection::new_root();
+    let connection_context = MockIHttpConnectionContext::new();
+    let get_matches = |path: &str| {
+        let request_context = RequestContext::new(&connection_context, http::Version::HTTP_11, None, None, Some(http::Method::GET),
+            Box::new("localhost".to_string()), 80, Box::new(path.to_string()), Box::new(String::new()), HeaderMap::new());
+        actions.iter()
+            .filter(|x| IControllerActionExtensions::is_route_match(x.as_ref(), &request_context, &services))
+            .map(|x| x.get_name().to_string())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(vec!["by_id".to_string()], get_matches("/items/12"));
+    assert_eq!(vec!["by_name".to_string()], get_matches("/items/twelve"));
+    assert!(get_matches("/items/12b").is_empty());
+}
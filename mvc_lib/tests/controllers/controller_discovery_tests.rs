@@ -0,0 +1,129 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+use http::Method;
+use core_macro_lib::{IModel, IHazAttributes, reflect_attributes, reflect_properties, reflect_methods};
+use mvc_macro_lib::controller;
+use mvc_lib::action_results::iaction_result::IActionResult;
+use mvc_lib::contexts::controller_context::IControllerContext;
+use mvc_lib::controller_action_features::authorize::AuthorizeControllerActionFeature;
+use mvc_lib::controller_actions::route_pattern::ControllerActionRoutePattern;
+use mvc_lib::controllers::controller_registry::ControllerRegistration;
+use mvc_lib::controllers::icontroller::IController;
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::model_binder::ihaz_attributes::IHazAttributes;
+use mvc_lib::model_binder::imodel::IModel;
+use mvc_lib::model_binder::imodel_attribute::IAttribute;
+use mvc_lib::model_binder::imodel_method::IModelMethod;
+use mvc_lib::model_binder::imodel_property::IModelProperty;
+use mvc_lib::model_binder::reflected_attribute::ReflectedAttribute;
+use mvc_lib::model_binder::reflected_method::ReflectedMethod;
+use mvc_lib::model_binder::reflected_property::ReflectedProperty;
+use mvc_lib::services::default_services::DefaultServices;
+use mvc_lib::services::routemap_service::{IRouteMapService, RouteMapService};
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+
+
+#[reflect_attributes]
+#[reflect_properties]
+#[derive(Clone, IHazAttributes, IModel)]
+pub struct DiscoveredController {
+}
+
+#[controller(area = "dev")]
+#[reflect_methods]
+impl DiscoveredController {
+    pub fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Rc::new(Self {}) as Rc<dyn IController>)]
+    }
+
+    #[http_get]
+    pub fn index(_controller: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        Ok(None)
+    }
+
+    #[http_get("/dev/log/{id:int}")]
+    #[authorize(roles = "admin", schemes = "Bearer")]
+    pub fn log(_controller: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        Ok(None)
+    }
+
+    #[http_get("/dev/log")]
+    #[http_post("/dev/log")]
+    pub fn clear_log(_controller: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        Ok(None)
+    }
+
+    #[http_post("/dev/log/add", name = "add_post")]
+    pub fn add(_controller: &Self, _controller_ctx: &dyn IControllerContext, _services: &dyn IServiceCollection) -> Result<Option<Rc<dyn IActionResult>>, Rc<dyn Error>> {
+        Ok(None)
+    }
+
+    // not an action.
+    pub fn helper() -> usize {
+        1
+    }
+}
+
+fn new_controller() -> Rc<dyn IController> {
+    let mut services = ServiceCollection::new_root();
+    DefaultServices::add_discovered_controllers(&mut services);
+    RouteMapService::try_get_controller(&services, "Discovered").expect("controller was not discovered")
+}
+
+#[test]
+fn controller_macro_registers_controller() {
+    assert!(ControllerRegistration::get_all().iter().any(|x| x.type_name == "DiscoveredController"));
+    assert_eq!("dev", new_controller().get_route_area());
+    assert_eq!(1, DiscoveredController::helper());
+}
+
+#[test]
+fn discovered_controllers_are_keyed_by_name() {
+    let mut services = ServiceCollection::new_root();
+    DefaultServices::add_discovered_controllers(&mut services);
+    DefaultServices::add_routing(&mut services);
+
+    assert!(ServiceCollectionExtensions::get_keys::<dyn IController>(&services).contains(&"Discovered".to_string()));
+    let by_name = RouteMapService::try_get_controller(&services, "Discovered").expect("controller was not found by name");
+    let by_type_name = RouteMapService::try_get_controller(&services, "DiscoveredController").expect("controller was not found by type name");
+    assert!(Rc::ptr_eq(&by_name, &by_type_name));
+    assert!(RouteMapService::try_get_controller(&services, "Missing").is_none());
+
+    // the route map still sees the controllers added by name.
+    let route_map = ServiceCollectionExtensions::get_required_single::<dyn IRouteMapService>(&services);
+    assert!(route_map.get_mapper().get_controllers().iter().any(|x| Rc::ptr_eq(x, &by_name)));
+}
+
+#[test]
+fn controller_macro_creates_actions_from_attributes() {
+    let controller = new_controller();
+    let actions = controller.get_actions();
+    let summary = actions.iter()
+        .map(|x| (x.get_name().to_string(), x.get_route_pattern().to_string().to_string(), x.get_http_methods_allowed()))
+        .collect::<Vec<(String, String, Vec<Method>)>>();
+
+    assert_eq!(vec![
+        ("index".to_string(), "/discovered".to_string(), vec![Method::GET]),
+        ("log".to_string(), "/dev/log/{id:int}".to_string(), vec![Method::GET]),
+        ("clear_log".to_string(), "/dev/log".to_string(), vec![Method::GET, Method::POST]),
+        ("add_post".to_string(), "/dev/log/add".to_string(), vec![Method::POST]),
+    ], summary);
+    assert_eq!(0, actions[0].get_features().len());
+    assert_eq!(1, actions[1].get_features().len());
+    let authorize = actions[1].get_features()[0].as_any().downcast_ref::<AuthorizeControllerActionFeature>().map(|x| (x.roles.clone(), x.schemes.clone()));
+    assert_eq!(Some((vec!["admin".to_string()], vec!["Bearer".to_string()])), authorize);
+}
+
+#[test]
+fn route_pattern_try_match_captures_values() {
+    let pattern = ControllerActionRoutePattern::parse("/dev/log/{id:int}".into()).unwrap();
+    assert_eq!(Some("7".to_string()), pattern.try_match("/dev/log/7").and_then(|x| x.get("id").cloned()));
+    assert_eq!(None, pattern.try_match("/dev/log"));
+    assert_eq!(None, pattern.try_match("/dev/log/7/more"));
+
+    let pattern = ControllerActionRoutePattern::parse("/docs/..".into()).unwrap();
+    assert_eq!(Some("a/b".to_string()), pattern.try_match("/docs/a/b").and_then(|x| x.get("..").cloned()));
+}
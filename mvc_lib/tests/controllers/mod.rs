@@ -0,0 +1 @@
+pub mod controller_discovery_tests;
\ No newline at end of file
@@ -0,0 +1,64 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mvc_lib::diagnostics::logging::iloggersink::ILoggerSink;
+use mvc_lib::diagnostics::logging::logging_service::LoggingService;
+use mvc_lib::services::default_services::DefaultServices;
+use mvc_lib::services::service_collection::{ServiceCollection, ServiceCollectionExtensions};
+
+
+// a sink that keeps the messages logged to it.
+struct MemoryLoggerSink {
+    messages: RefCell<Vec<String>>,
+}
+
+impl MemoryLoggerSink {
+    fn new_sink() -> Rc<dyn ILoggerSink> {
+        Rc::new(Self { messages: RefCell::new(vec![]) })
+    }
+}
+
+impl ILoggerSink for MemoryLoggerSink {
+    fn log(&self, _level: log::Level, message: &str) {
+        self.messages.borrow_mut().push(message.to_string());
+    }
+
+    fn supports_read(&self) -> bool {
+        true
+    }
+
+    fn read_logs(&self) -> Vec<String> {
+        self.messages.borrow().clone()
+    }
+
+    fn supports_clear(&self) -> bool {
+        true
+    }
+
+    fn clear_logs(&self) {
+        self.messages.borrow_mut().clear();
+    }
+}
+
+#[test]
+fn logging_service_keys_sinks_by_id() {
+    let mut services = ServiceCollection::new_root();
+    DefaultServices::add_logging(&mut services);
+    assert_eq!(vec!["console".to_string(), "file".to_string()], ServiceCollectionExtensions::get_keys::<dyn ILoggerSink>(&services));
+
+    // the default sinks are replaced by adding sinks with the same ids.
+    LoggingService::add_sink(&mut services, "console", MemoryLoggerSink::new_sink);
+    LoggingService::add_sink(&mut services, "file", MemoryLoggerSink::new_sink);
+    LoggingService::add_sink(&mut services, "audit", MemoryLoggerSink::new_sink);
+    assert_eq!(vec!["audit".to_string(), "console".to_string(), "file".to_string()], ServiceCollectionExtensions::get_keys::<dyn ILoggerSink>(&services));
+
+    LoggingService::get_service(&services).log_info("hello");
+
+    for id in ["audit", "console", "file"] {
+        let sink = LoggingService::try_get_sink(&services, id).expect("sink was not found by id");
+        let logs = sink.read_logs();
+        assert_eq!(1, logs.len());
+        assert!(logs[0].ends_with(" hello"));
+    }
+    assert!(LoggingService::try_get_sink(&services, "missing").is_none());
+}
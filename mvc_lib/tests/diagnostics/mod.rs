@@ -0,0 +1 @@
+pub mod logging_service_tests;
\ No newline at end of file
@@ -48,7 +48,7 @@ impl<T> DbSetTestSuite<T> where T: IDbSet<TestPerson> {
 
         // add the person to the database set
         self.dbset.add(&person);
-        if save_changes { self.dbset.upcast().save_changes(); }
+        if save_changes { self.dbset.upcast().save_changes().unwrap(); }
 
         {
             // should be one person
@@ -58,7 +58,7 @@ impl<T> DbSetTestSuite<T> where T: IDbSet<TestPerson> {
 
         // remove the person from the database set
         self.dbset.remove(&person);
-        if save_changes { self.dbset.upcast().save_changes(); }
+        if save_changes { self.dbset.upcast().save_changes().unwrap(); }
 
         {
             // should be empty
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mvc_lib::contexts::fromstring_connection_context::FromStringConnectionContext;
+use mvc_lib::contexts::httpconnection_context::HttpConnectionContext;
+use mvc_lib::contexts::itcpconnection_context::ITcpConnectionContext;
+use mvc_lib::contexts::tcp_connection_context::TcpConnectionContext;
+use mvc_lib::contexts::request_context::RequestContext;
+use mvc_lib::core::itcp_stream_wrapper::ITcpStreamWrapper;
+use mvc_lib::core::memory_stream::MemoryStream;
+use mvc_lib::errors::BadRequestError;
+use mvc_lib::http::content_coding_body_stream::{ContentCoding, ContentCodingBodyStream, ContentCodingBodyStreamFormat};
+use mvc_lib::http::http_body_content::ContentType;
+use mvc_lib::http::http_body_format_resolver::HttpBodyFormatResolver;
+use mvc_lib::http::http_body_format_service::HttpBodyFormatService;
+use mvc_lib::http::ihttp_body_stream_format::IHttpBodyStreamFormat;
+use mvc_lib::http::request_decoder_middleware::RequestDecoderMiddleware;
+use mvc_lib::http::response_encoder_middleware::ResponseEncoderMiddleware;
+use mvc_lib::options::response_compression_options::{IResponseCompressionOptions, ResponseCompressionOptions};
+
+
+fn read_to_end(stream: &dyn ITcpStreamWrapper) -> Vec<u8> {
+    let mut body = Vec::new();
+    let mut buffer = [0; 256];
+    loop {
+        let n = stream.read(&mut buffer).unwrap();
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&buffer[..n]);
+    }
+    body
+}
+
+#[test]
+fn content_coding_body_stream_round_trips_each_coding() {
+    let body = "hello world ".repeat(100);
+    for content_coding in ContentCoding::PREFERRED {
+        let compressed = Rc::new(RefCell::new(MemoryStream::new()));
+        let encoder = ContentCodingBodyStream::new(compressed.clone(), content_coding);
+        encoder.write(body.as_bytes()).unwrap();
+        encoder.write(&[]).unwrap();
+        let compressed_bytes = compressed.borrow().take();
+        assert!(compressed_bytes.len() < body.len());
+
+        compressed.borrow().write(&compressed_bytes).unwrap();
+        let decoder = ContentCodingBodyStream::new(compressed.clone(), content_coding);
+        assert_eq!(body.as_bytes(), read_to_end(&decoder).as_slice(), "{:?}", content_coding);
+    }
+}
+
+#[test]
+fn content_coding_parse_works() {
+    assert_eq!(Some(ContentCoding::Gzip), ContentCoding::parse("GZIP"));
+    assert_eq!(Some(ContentCoding::Gzip), ContentCoding::parse("x-gzip"));
+    assert_eq!(Some(ContentCoding::Brotli), ContentCoding::parse(" br "));
+    assert_eq!(None, ContentCoding::parse("compress"));
+}
+
+#[test]
+fn response_encoder_negotiates_content_coding_by_q_value() {
+    let available = ["br", "gzip", "deflate"];
+    assert_eq!(Some("br".to_string()), ResponseEncoderMiddleware::negotiate_content_coding("gzip, deflate, br", &available));
+    assert_eq!(Some("gzip".to_string()), ResponseEncoderMiddleware::negotiate_content_coding("gzip;q=0.9, br;q=0.5", &available));
+    assert_eq!(Some("deflate".to_string()), ResponseEncoderMiddleware::negotiate_content_coding("*;q=0.1, br;q=0, gzip;q=0", &available));
+    assert_eq!(None, ResponseEncoderMiddleware::negotiate_content_coding("identity", &available));
+    assert_eq!(None, ResponseEncoderMiddleware::negotiate_content_coding("", &available));
+    assert_eq!(None, ResponseEncoderMiddleware::negotiate_content_coding("br", &["gzip"]));
+}
+
+#[test]
+fn response_compression_options_matches_mime_types() {
+    let options = ResponseCompressionOptions::new_defaults();
+    assert!(options.is_compressible(&ContentType::parse("text/html; charset=utf-8")));
+    assert!(options.is_compressible(&ContentType::parse("application/json")));
+    assert!(!options.is_compressible(&ContentType::parse("image/png")));
+    assert!(!options.is_compressible(&ContentType::parse("textual/plain")));
+}
+
+#[test]
+fn request_decoder_resolves_content_encoding_in_reverse_order() {
+    let format_service = Rc::new(HttpBodyFormatService::new(vec![Rc::new(HttpBodyFormatResolver::new(vec![]))]));
+    let middleware = RequestDecoderMiddleware::new(format_service);
+
+    let data = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Encoding: gzip, br\r\n\r\n";
+    let connection_context = HttpConnectionContext::new(Rc::new(FromStringConnectionContext::new(data.to_string(), 1)));
+    let request_context = RequestContext::parse(&connection_context).unwrap();
+    let decoders = middleware.resolve_content_decoders(&request_context).unwrap();
+    assert_eq!(2, decoders.len());
+    assert!(decoders[0].matches_content_type(&ContentType::parse("br")));
+    assert!(decoders[1].matches_content_type(&ContentType::parse("gzip")));
+
+    let data = "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Encoding: compress\r\n\r\n";
+    let connection_context = HttpConnectionContext::new(Rc::new(FromStringConnectionContext::new(data.to_string(), 1)));
+    let request_context = RequestContext::parse(&connection_context).unwrap();
+    assert_eq!(Err("compress".to_string()), middleware.resolve_content_decoders(&request_context).map(|x| x.len()));
+}
+
+#[test]
+fn content_coding_parse_rejects_media_types() {
+    assert_eq!(None, ContentCoding::parse("application/gzip"));
+}
+
+#[test]
+fn decompressed_request_body_is_limited() {
+    let body = "0".repeat(10_000);
+    let compressed = Rc::new(RefCell::new(MemoryStream::new()));
+    let encoder = ContentCodingBodyStream::new(compressed.clone(), ContentCoding::Gzip);
+    encoder.write(body.as_bytes()).unwrap();
+    encoder.write(&[]).unwrap();
+    let compressed_bytes = compressed.borrow().take();
+    assert!(compressed_bytes.len() < 1000);
+    compressed.borrow().write(&compressed_bytes).unwrap();
+
+    // the compressed body is within the limit, but it decodes to a body that is not.
+    let connection_context = TcpConnectionContext::new(compressed, 1);
+    connection_context.begin_request_body(compressed_bytes.len());
+    connection_context.limit_request_body(1000);
+    let decoder: Rc<dyn IHttpBodyStreamFormat> = Rc::new(ContentCodingBodyStreamFormat::new(ContentCoding::Gzip));
+    connection_context.add_stream_decoders(&[decoder], &ContentType::parse("gzip"));
+
+    let mut buffer = [0; 256];
+    let error = loop {
+        match connection_context.read(&mut buffer) {
+            Ok(0) => panic!("the decoded body was not limited"),
+            Ok(_) => {},
+            Err(err) => break err,
+        }
+    };
+    assert_eq!(Some(http::StatusCode::PAYLOAD_TOO_LARGE), BadRequestError::from_io_error(&error).map(|x| x.status_code));
+    assert!(connection_context.is_request_body_too_large());
+
+    connection_context.end_request_body().unwrap();
+    assert!(!connection_context.is_request_body_too_large());
+}
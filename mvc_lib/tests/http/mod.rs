@@ -0,0 +1 @@
+pub mod content_coding_tests;
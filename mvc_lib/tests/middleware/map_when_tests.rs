@@ -0,0 +1,95 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+
+use mvc_lib::contexts::irequest_context::{IRequestContext, MockIRequestContext};
+use mvc_lib::contexts::iresponse_context::{IResponseContext, MockIResponseContext};
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::middleware::map_when::MapWhenMiddleware;
+use mvc_lib::services::request_middleware_service::{IRequestMiddlewareService, MiddlewareResult};
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+
+
+// a middleware that records its name when it handles a request, then continues unless it breaks.
+struct RecordingMiddleware {
+    name: &'static str,
+    should_break: bool,
+    log: Rc<RefCell<Vec<&'static str>>>,
+    next: RefCell<Option<Rc<dyn IRequestMiddlewareService>>>,
+}
+
+impl RecordingMiddleware {
+    fn new(name: &'static str, should_break: bool, log: Rc<RefCell<Vec<&'static str>>>) -> Rc<dyn IRequestMiddlewareService> {
+        Rc::new(Self { name: name, should_break: should_break, log: log, next: RefCell::new(None) })
+    }
+
+    fn new_service(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        vec![Box::new(Self::new("branch", false, Rc::new(RefCell::new(vec![]))))]
+    }
+}
+
+impl IRequestMiddlewareService for RecordingMiddleware {
+    fn get_type_info(&self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<RecordingMiddleware>())
+    }
+
+    fn set_next(self: &Self, next: Option<Rc<dyn IRequestMiddlewareService>>) {
+        self.next.replace(next);
+    }
+
+    fn handle_request(self: &Self, response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
+        self.log.borrow_mut().push(self.name);
+        if self.should_break {
+            return Ok(MiddlewareResult::OkBreak);
+        }
+        match self.next.borrow().as_ref() {
+            Some(next) => next.handle_request(response_context, request_context, services),
+            None => Ok(MiddlewareResult::OkContinue),
+        }
+    }
+}
+
+fn handle_path(pipeline: &dyn IRequestMiddlewareService, path: &'static str) {
+    let mut request_context = MockIRequestContext::new();
+    request_context.expect_get_path().return_const(path.to_string());
+    let response_context = MockIResponseContext::new();
+    let services = ServiceCollection::new_root();
+    pipeline.handle_request(&response_context, &request_context, &services).unwrap();
+}
+
+#[test]
+fn map_when_runs_branch_then_rejoins_pipeline() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let branch = MapWhenMiddleware::new(
+        Rc::new(|request_context: &dyn IRequestContext| MapWhenMiddleware::is_under_path(&request_context.get_path(), "/dev")),
+        vec![RecordingMiddleware::new("auth", false, log.clone()), RecordingMiddleware::new("log", false, log.clone())]);
+    branch.set_next(Some(RecordingMiddleware::new("execute", false, log.clone())));
+
+    handle_path(&branch, "/dev/log");
+    assert_eq!(vec!["auth", "log", "execute"], log.replace(vec![]));
+    handle_path(&branch, "/DEV");
+    assert_eq!(vec!["auth", "log", "execute"], log.replace(vec![]));
+    handle_path(&branch, "/developer");
+    assert_eq!(vec!["execute"], log.replace(vec![]));
+}
+
+#[test]
+fn map_when_branch_can_short_circuit() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let branch = MapWhenMiddleware::new(Rc::new(|_: &dyn IRequestContext| true), vec![RecordingMiddleware::new("deny", true, log.clone())]);
+    branch.set_next(Some(RecordingMiddleware::new("execute", false, log.clone())));
+
+    handle_path(&branch, "/dev");
+    assert_eq!(vec!["deny"], log.replace(vec![]));
+}
+
+#[test]
+fn map_adds_one_middleware_for_the_branch() {
+    let mut services = ServiceCollection::new_root();
+    MapWhenMiddleware::map(&mut services, "/static/", vec![RecordingMiddleware::new_service]);
+
+    let middleware = ServiceCollectionExtensions::get_required_multiple::<dyn IRequestMiddlewareService>(&services);
+    assert_eq!(1, middleware.len());
+    assert_eq!(TypeInfo::of::<MapWhenMiddleware>().type_name, middleware[0].get_type_info().type_name);
+}
@@ -0,0 +1 @@
+pub mod map_when_tests;
\ No newline at end of file
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mvc_lib::controller_actions::closure::ControllerActionClosure;
+use mvc_lib::controller_actions::controller_action::IControllerAction;
+use mvc_lib::routing::link_generator::LinkGenerator;
+
+
+fn new_action(route_pattern: &'static str, name: &'static str, controller_name: &'static str, area_name: &str) -> Rc<dyn IControllerAction> {
+    Rc::new(ControllerActionClosure::new_not_validated(vec![], None, route_pattern.into(), name.into(), controller_name.into(), area_name.to_string(), &|_, _| Ok(None)))
+}
+
+fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|x| (x.0.to_string(), x.1.to_string())).collect()
+}
+
+fn new_link_generator() -> LinkGenerator {
+    LinkGenerator::new(vec![
+        new_action("/", "index", "Home", ""),
+        new_action("/items", "index", "Items", ""),
+        new_action("/items/{id:int}", "details", "Items", ""),
+        new_action("/items/{id:int}/{slug}", "details", "Items", ""),
+        new_action("/admin/items", "index", "Items", "admin"),
+    ])
+}
+
+#[test]
+fn link_generator_uses_ambient_controller_and_area() {
+    let links = new_link_generator();
+    let in_items = values(&[("area", ""), ("controller", "Items"), ("action", "details")]);
+    let in_admin = values(&[("area", "admin"), ("controller", "Items"), ("action", "index")]);
+
+    assert_eq!("/items", links.get_path_by_name("index", &HashMap::new(), &in_items, None).unwrap());
+    assert_eq!("/", links.get_path_by_name("index", &HashMap::new(), &values(&[("controller", "Home")]), None).unwrap());
+    assert_eq!("/admin/items", links.get_path_by_name("index", &HashMap::new(), &in_admin, None).unwrap());
+    assert_eq!("/", links.get_path_by_name("index", &values(&[("controller", "Home")]), &in_items, None).unwrap());
+    // an action that only one controller has is found from anywhere.
+    assert_eq!("/items/3", links.get_path_by_name("details", &values(&[("id", "3")]), &HashMap::new(), None).unwrap());
+    assert_eq!("/items/3", links.get_path_by_name("details", &values(&[("id", "3")]), &in_admin, None).unwrap());
+    assert_eq!("/items", links.get_path_by_name("index", &values(&[("area", "")]), &in_admin, None).unwrap());
+}
+
+#[test]
+fn link_generator_picks_the_route_that_uses_the_most_values() {
+    let links = new_link_generator();
+    let ambient = values(&[("controller", "Items"), ("action", "index")]);
+
+    assert_eq!("/items/3", links.get_path_by_values(&values(&[("action", "details"), ("id", "3")]), &ambient, None).unwrap());
+    assert_eq!("/items/3/red-chair", links.get_path_by_values(&values(&[("action", "details"), ("id", "3"), ("slug", "red-chair")]), &ambient, None).unwrap());
+    assert_eq!("/items?page=2", links.get_path_by_values(&values(&[("page", "2")]), &ambient, None).unwrap());
+}
+
+#[test]
+fn link_generator_reports_unknown_actions_and_missing_values() {
+    let links = new_link_generator();
+
+    assert!(links.get_path_by_name("missing", &HashMap::new(), &HashMap::new(), None).is_err());
+    assert!(links.get_path_by_name("details", &HashMap::new(), &HashMap::new(), None).is_err());
+    assert!(links.get_path_by_name("details", &values(&[("id", "abc")]), &HashMap::new(), None).is_err());
+    assert!(links.get_path_by_values(&values(&[("controller", "Home")]), &HashMap::new(), None).is_err());
+}
@@ -0,0 +1,2 @@
+pub mod link_generator_tests;
+pub mod route_table_tests;
\ No newline at end of file
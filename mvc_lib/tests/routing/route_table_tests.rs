@@ -0,0 +1,100 @@
+use std::rc::Rc;
+
+use http::Method;
+use mvc_lib::controller_actions::closure::ControllerActionClosure;
+use mvc_lib::controller_actions::controller_action::IControllerAction;
+use mvc_lib::routing::route_table::{RouteMatch, RouteTable};
+use mvc_lib::services::service_collection::ServiceCollection;
+
+
+fn new_action(methods: &[Method], route_pattern: &'static str, name: &'static str) -> Rc<dyn IControllerAction> {
+    Rc::new(ControllerActionClosure::new_not_validated(methods.to_vec(), None, route_pattern.into(), name.into(), "Items".into(), String::new(), &|_, _| Ok(None)))
+}
+
+fn get_match(table: &RouteTable, method: Method, path: &str) -> String {
+    let services = ServiceCollection::new_root();
+    match table.match_request(path, &method, &services) {
+        RouteMatch::Found(action, values) => {
+            let mut values = values.into_iter().map(|x| format!("{}={}", x.0, x.1)).collect::<Vec<String>>();
+            values.sort();
+            format!("{} {}", action.get_name(), values.join(","))
+        },
+        RouteMatch::MethodNotAllowed(methods) => format!("405 {:?}", methods),
+        RouteMatch::NotFound => "404".to_string(),
+    }
+}
+
+#[test]
+fn route_table_prefers_literals_then_constraints_then_parameters_then_catch_all() {
+    let table = RouteTable::new(vec![
+        new_action(&[], "/items/..", "any"),
+        new_action(&[], "/items/{name}", "by_name"),
+        new_action(&[], "/items/{id:int}", "by_id"),
+        new_action(&[], "/items/new", "new"),
+    ]);
+
+    assert_eq!("new ", get_match(&table, Method::GET, "/items/new"));
+    assert_eq!("by_id id=12", get_match(&table, Method::GET, "/items/12"));
+    assert_eq!("by_name name=twelve", get_match(&table, Method::GET, "/items/twelve"));
+    assert_eq!("any ..=12/parts", get_match(&table, Method::GET, "/items/12/parts"));
+    assert_eq!("404", get_match(&table, Method::GET, "/other"));
+    assert!(table.get_ambiguities().is_empty());
+}
+
+#[test]
+fn route_table_prefers_specific_methods_and_matches_optional_segments() {
+    let table = RouteTable::new(vec![
+        new_action(&[], "/items/{action=index}/{id?}", "any_method"),
+        new_action(&[Method::POST], "/items/{action=index}/{id?}", "post"),
+        new_action(&[], "/", "home"),
+    ]);
+
+    assert_eq!("post action=index", get_match(&table, Method::POST, "/items"));
+    assert_eq!("any_method action=index", get_match(&table, Method::GET, "/items"));
+    assert_eq!("any_method action=edit,id=5", get_match(&table, Method::GET, "/items/edit/5"));
+    assert_eq!("home ", get_match(&table, Method::GET, "/"));
+}
+
+#[test]
+fn route_table_returns_allowed_methods_when_method_does_not_match() {
+    let table = RouteTable::new(vec![
+        new_action(&[Method::GET], "/items", "list"),
+        new_action(&[Method::POST, Method::PUT], "/items", "save"),
+    ]);
+
+    // GET actions also answer HEAD requests.
+    assert_eq!("405 [GET, POST, PUT, HEAD]", get_match(&table, Method::DELETE, "/items"));
+    assert_eq!("save ", get_match(&table, Method::PUT, "/items"));
+}
+
+#[test]
+fn route_table_answers_head_with_get_actions() {
+    let table = RouteTable::new(vec![
+        new_action(&[Method::GET], "/items", "list"),
+        new_action(&[Method::POST], "/items", "save"),
+        new_action(&[Method::GET], "/files", "download"),
+        new_action(&[Method::HEAD], "/files", "file_info"),
+        new_action(&[Method::POST], "/upload", "upload"),
+    ]);
+
+    assert_eq!("list ", get_match(&table, Method::HEAD, "/items"));
+    // an action for HEAD wins over a GET action.
+    assert_eq!("file_info ", get_match(&table, Method::HEAD, "/files"));
+    assert_eq!("405 [POST]", get_match(&table, Method::HEAD, "/upload"));
+}
+
+#[test]
+fn route_table_reports_ambiguous_routes() {
+    let table = RouteTable::new(vec![
+        new_action(&[Method::GET], "/items/{id:int}", "first"),
+        new_action(&[Method::GET, Method::POST], "/items/{number:int}", "second"),
+        new_action(&[Method::DELETE], "/items/{id:int}", "delete"),
+        new_action(&[], "/items/{id}", "unconstrained"),
+    ]);
+
+    let ambiguities = table.get_ambiguities();
+    assert_eq!(1, ambiguities.len());
+    assert_eq!(2, ambiguities[0].actions.len());
+    // the first action added wins.
+    assert_eq!("first id=1", get_match(&table, Method::GET, "/items/1"));
+}
@@ -1,6 +1,17 @@
 pub mod action_results;
+pub mod app;
+pub mod auth;
+pub mod config;
 pub mod contexts;
+pub mod controller_actions;
+pub mod controllers;
+pub mod diagnostics;
 pub mod entity;
+pub mod http;
+pub mod middleware;
+pub mod routing;
+pub mod services;
+pub mod session;
 pub mod view;
 
 
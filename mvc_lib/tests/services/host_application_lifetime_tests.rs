@@ -0,0 +1,52 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mvc_lib::contexts::fromstring_connection_context::FromStringConnectionContext;
+use mvc_lib::contexts::httpconnection_context::HttpConnectionContext;
+use mvc_lib::contexts::ihttpconnection_context::IHttpConnectionContext;
+use mvc_lib::services::host_application_lifetime::{HostApplicationLifetime, IHostApplicationLifetime};
+
+
+#[test]
+fn host_application_lifetime_raises_stopping_and_stopped_once() {
+    let lifetime = HostApplicationLifetime::new();
+    let events = Rc::new(RefCell::new(Vec::new()));
+
+    let stopping_events = events.clone();
+    lifetime.on_stopping(Box::new(move || stopping_events.borrow_mut().push("stopping")));
+    let stopped_events = events.clone();
+    lifetime.on_stopped(Box::new(move || stopped_events.borrow_mut().push("stopped")));
+
+    assert!(!lifetime.is_stopping());
+    lifetime.notify_stopping();
+    lifetime.notify_stopping();
+    lifetime.notify_stopped();
+    lifetime.notify_stopped();
+
+    assert!(lifetime.is_stopping());
+    assert_eq!(vec!["stopping", "stopped"], *events.borrow());
+}
+
+#[test]
+fn host_application_lifetime_shares_stop_request() {
+    let main_lifetime = HostApplicationLifetime::new();
+    let worker_lifetime = HostApplicationLifetime::new_shared(main_lifetime.get_stop_requested());
+
+    main_lifetime.stop_application();
+    assert!(worker_lifetime.is_stopping());
+}
+
+#[test]
+fn host_application_lifetime_raises_response_written() {
+    let lifetime = HostApplicationLifetime::new();
+    let status_codes = Rc::new(RefCell::new(Vec::new()));
+
+    let written_status_codes = status_codes.clone();
+    lifetime.on_response_written(Box::new(move |connection_context| written_status_codes.borrow_mut().push(connection_context.get_pending_status_code())));
+
+    let connection_context = HttpConnectionContext::new(Rc::new(FromStringConnectionContext::new(String::new(), 1)));
+    connection_context.set_pending_status_code(http::StatusCode::NOT_FOUND);
+    lifetime.notify_response_written(&connection_context);
+
+    assert_eq!(vec![http::StatusCode::NOT_FOUND], *status_codes.borrow());
+}
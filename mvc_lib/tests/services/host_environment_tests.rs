@@ -0,0 +1,53 @@
+use std::rc::Rc;
+
+use mvc_lib::config::configuration::Configuration;
+use mvc_lib::config::configuration_builder::ConfigurationBuilder;
+use mvc_lib::services::host_environment::{HostEnvironment, IHostEnvironment, IHostEnvironmentExtensions};
+use mvc_lib::services::service_collection::{ServiceCollection, ServiceCollectionExtensions};
+
+
+// create services with a host environment read from command line arguments.
+fn new_services(args: &[&str]) -> ServiceCollection<'static> {
+    let args = args.iter().map(|x| x.to_string()).collect::<Vec<String>>();
+    let mut services = ServiceCollection::new_root();
+    Configuration::add_to_services(&mut services, Rc::new(ConfigurationBuilder::new().add_command_line(&args).build().unwrap()));
+    HostEnvironment::add_to_services(&mut services);
+    services
+}
+
+#[test]
+fn host_environment_reads_configuration() {
+    let services = new_services(&["--environment=Development", "--content_root=/srv/app", "--application_name=web"]);
+    let environment = ServiceCollectionExtensions::get_required_single::<dyn IHostEnvironment>(&services);
+
+    assert_eq!("Development", environment.get_environment_name());
+    assert_eq!("/srv/app", environment.get_content_root_path());
+    assert_eq!("web", environment.get_app_name());
+    assert!(IHostEnvironmentExtensions::is_development(environment.as_ref()));
+    assert!(IHostEnvironmentExtensions::is_environment(environment.as_ref(), "development"));
+    assert!(!IHostEnvironmentExtensions::is_production(environment.as_ref()));
+}
+
+#[test]
+fn host_environment_defaults_to_production() {
+    let services = new_services(&[]);
+    let environment = ServiceCollectionExtensions::get_required_single::<dyn IHostEnvironment>(&services);
+
+    assert!(IHostEnvironmentExtensions::is_production(environment.as_ref()));
+    assert_eq!(std::env::current_dir().unwrap().to_string_lossy(), environment.get_content_root_path());
+}
+
+#[test]
+fn host_environment_environment_tag_include_and_exclude() {
+    let services = new_services(&["--environment=Staging"]);
+
+    assert!(IHostEnvironmentExtensions::is_environment_tag_rendered(&services, Some("Development, staging"), None));
+    assert!(!IHostEnvironmentExtensions::is_environment_tag_rendered(&services, Some("Development"), None));
+    assert!(IHostEnvironmentExtensions::is_environment_tag_rendered(&services, None, Some("Production")));
+    assert!(!IHostEnvironmentExtensions::is_environment_tag_rendered(&services, None, Some("Staging")));
+    assert!(!IHostEnvironmentExtensions::is_environment_tag_rendered(&services, Some("Staging"), Some("Staging")));
+
+    // without a host environment the environment is production.
+    let services = ServiceCollection::new_root();
+    assert!(IHostEnvironmentExtensions::is_environment_tag_rendered(&services, Some("Production"), None));
+}
@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use core_macro_lib::injectable;
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use mvc_lib::services::service_scope::ServiceScope;
+
+
+pub trait IGreeting {
+    fn get_text(&self) -> String;
+}
+
+pub trait IGreeter {
+    fn greet(&self) -> String;
+}
+
+// a service that is never registered.
+pub trait IFarewell {
+}
+
+struct HelloGreeting {
+}
+
+#[injectable(as = "dyn IGreeting")]
+impl HelloGreeting {
+    fn new() -> Self {
+        Self {}
+    }
+}
+
+impl IGreeting for HelloGreeting {
+    fn get_text(&self) -> String {
+        "hello".to_string()
+    }
+}
+
+// the fields are in a different order than the parameters of the constructor, which decide the dependencies.
+struct Greeter {
+    times_called: RefCell<usize>,
+    missing: Option<Rc<dyn IFarewell>>,
+    all_greetings: Vec<Rc<dyn IGreeting>>,
+    greeting: Rc<dyn IGreeting>,
+}
+
+#[injectable(as = "dyn IGreeter", scope = "Request", constructor = "new_greeter")]
+impl Greeter {
+    fn new_greeter(greeting: Rc<dyn IGreeting>, all_greetings: Vec<Rc<dyn IGreeting>>, missing: Option<Rc<dyn IFarewell>>) -> Self {
+        Self { greeting: greeting, all_greetings: all_greetings, missing: missing, times_called: RefCell::new(0) }
+    }
+}
+
+impl IGreeter for Greeter {
+    fn greet(&self) -> String {
+        *self.times_called.borrow_mut() += 1;
+        format!("{} ({} greetings, missing: {})", self.greeting.get_text(), self.all_greetings.len(), self.missing.is_some())
+    }
+}
+
+#[test]
+fn injectable_registers_service_with_scope() {
+    let mut services = ServiceCollection::new_root();
+    HelloGreeting::add_to_services(&mut services);
+    Greeter::add_to_services(&mut services);
+
+    let descriptors = services.get_items();
+    assert_eq!(ServiceScope::Singleton, descriptors[0].scope);
+    assert_eq!(ServiceScope::Request, descriptors[1].scope);
+}
+
+#[test]
+fn injectable_passes_dependencies_to_constructor() {
+    let mut services = ServiceCollection::new_root();
+    HelloGreeting::add_to_services(&mut services);
+    HelloGreeting::add_to_services(&mut services);
+    Greeter::add_to_services(&mut services);
+
+    let request = ServiceCollection::new(ServiceScope::Request, &services, &services);
+    let greeter = ServiceCollectionExtensions::get_required_single::<dyn IGreeter>(&request);
+    assert_eq!("hello (2 greetings, missing: false)", greeter.greet());
+}
+
+#[test]
+#[should_panic]
+fn injectable_panics_when_required_dependency_is_missing() {
+    let mut services = ServiceCollection::new_root();
+    Greeter::add_to_services(&mut services);
+
+    let request = ServiceCollection::new(ServiceScope::Request, &services, &services);
+    let services: &dyn IServiceCollection = &request;
+    ServiceCollectionExtensions::get_required_single::<dyn IGreeter>(services);
+}
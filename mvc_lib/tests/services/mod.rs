@@ -0,0 +1,5 @@
+pub mod host_application_lifetime_tests;
+pub mod host_environment_tests;
+pub mod injectable_tests;
+pub mod service_collection_tests;
+pub mod service_validator_tests;
\ No newline at end of file
@@ -0,0 +1,168 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use mvc_lib::services::service_descriptor::ServiceDescriptor;
+use mvc_lib::services::service_scope::ServiceScope;
+
+
+// a service that records when it is dropped.
+struct TrackedService {
+    dropped: Rc<RefCell<Vec<ServiceScope>>>,
+    scope: ServiceScope,
+}
+
+impl Drop for TrackedService {
+    fn drop(&mut self) {
+        self.dropped.borrow_mut().push(self.scope);
+    }
+}
+
+// create a root collection with a tracked service registered for each scope.
+fn new_tracked_services(dropped: &Rc<RefCell<Vec<ServiceScope>>>) -> ServiceCollection<'static> {
+    let mut services = ServiceCollection::new_root();
+    for scope in [ServiceScope::Singleton, ServiceScope::Request, ServiceScope::AlwaysNew] {
+        let dropped = dropped.clone();
+        services.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<TrackedService>(), move |_| {
+            vec![Box::new(Rc::new(TrackedService { dropped: dropped.clone(), scope: scope }))]
+        }, scope));
+    }
+    services
+}
+
+// get the tracked service with a scope.
+fn get_tracked(services: &dyn IServiceCollection, scope: ServiceScope) -> Rc<TrackedService> {
+    ServiceCollectionExtensions::get_required_multiple::<TrackedService>(services)
+        .into_iter()
+        .find(|x| x.scope == scope)
+        .unwrap()
+}
+
+#[test]
+fn service_collection_keeps_one_singleton() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let root = new_tracked_services(&dropped);
+    let first = get_tracked(&root, ServiceScope::Singleton);
+    let second = get_tracked(&root, ServiceScope::Singleton);
+    assert!(Rc::ptr_eq(&first, &second));
+
+    // a copy of the root, as used by each request, shares the singletons.
+    let root_copy = root.clone();
+    let request = ServiceCollection::new(ServiceScope::Request, &root_copy, &root);
+    assert!(Rc::ptr_eq(&first, &get_tracked(&request, ServiceScope::Singleton)));
+}
+
+#[test]
+fn service_collection_keeps_one_instance_per_request() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let root = new_tracked_services(&dropped);
+    let first_request = ServiceCollection::new(ServiceScope::Request, &root, &root);
+    let second_request = ServiceCollection::new(ServiceScope::Request, &root, &root);
+
+    let first = get_tracked(&first_request, ServiceScope::Request);
+    assert!(Rc::ptr_eq(&first, &get_tracked(&first_request, ServiceScope::Request)));
+    assert!(!Rc::ptr_eq(&first, &get_tracked(&second_request, ServiceScope::Request)));
+}
+
+#[test]
+fn service_collection_always_new_is_transient() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    let root = new_tracked_services(&dropped);
+    let first = get_tracked(&root, ServiceScope::AlwaysNew);
+    let second = get_tracked(&root, ServiceScope::AlwaysNew);
+    assert!(!Rc::ptr_eq(&first, &second));
+}
+
+#[test]
+fn service_collection_nested_scope_keeps_its_own_instances() {
+    let mut root = ServiceCollection::new_root();
+    root.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<String>(), |_| vec![Box::new(Rc::new(String::from("scoped")))], ServiceScope::Scope));
+    let first_scope = ServiceCollection::new(ServiceScope::Scope, &root, &root);
+    let second_scope = ServiceCollection::new(ServiceScope::Scope, &root, &root);
+
+    let first = ServiceCollectionExtensions::get_required_single::<String>(&first_scope);
+    assert!(Rc::ptr_eq(&first, &ServiceCollectionExtensions::get_required_single::<String>(&first_scope)));
+    assert!(!Rc::ptr_eq(&first, &ServiceCollectionExtensions::get_required_single::<String>(&second_scope)));
+}
+
+#[test]
+fn service_collection_keeps_host_services_in_the_root() {
+    let mut root = ServiceCollection::new_root();
+    root.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<String>(), |_| vec![Box::new(Rc::new(String::from("host")))], ServiceScope::Host));
+    let request = ServiceCollection::new(ServiceScope::Request, &root, &root);
+
+    let first = ServiceCollectionExtensions::get_required_single::<String>(&root);
+    assert!(Rc::ptr_eq(&first, &ServiceCollectionExtensions::get_required_single::<String>(&request)));
+    assert_eq!(1, root.get_instances().len());
+}
+
+#[test]
+#[should_panic(expected = "outside of a collection with that scope")]
+fn service_collection_panics_when_scope_is_missing() {
+    let mut root = ServiceCollection::new_root();
+    root.add(ServiceDescriptor::new_closure(TypeInfo::rc_of::<String>(), |_| vec![Box::new(Rc::new(String::from("controller")))], ServiceScope::Controller));
+    let request = ServiceCollection::new(ServiceScope::Request, &root, &root);
+    ServiceCollectionExtensions::get_required_single::<String>(&request);
+}
+
+#[test]
+fn service_collection_disposes_instances_when_scope_ends() {
+    let dropped = Rc::new(RefCell::new(Vec::new()));
+    // AlwaysNew instances are not kept, so they are dropped as soon as they are no longer used.
+    let kept_dropped = || dropped.borrow().iter().cloned().filter(|x| *x != ServiceScope::AlwaysNew).collect::<Vec<ServiceScope>>();
+    let mut root = new_tracked_services(&dropped);
+    {
+        let mut request = ServiceCollection::new(ServiceScope::Request, &root, &root);
+        get_tracked(&request, ServiceScope::Singleton);
+        get_tracked(&request, ServiceScope::Request);
+        assert!(kept_dropped().is_empty());
+
+        request.dispose();
+        assert_eq!(vec![ServiceScope::Request], kept_dropped());
+    }
+
+    root.dispose();
+    assert_eq!(vec![ServiceScope::Request, ServiceScope::Singleton], kept_dropped());
+}
+
+
+// create a service descriptor for a string singleton.
+fn new_string_service(value: &'static str) -> ServiceDescriptor {
+    ServiceDescriptor::new_closure(TypeInfo::rc_of::<String>(), move |_| vec![Box::new(Rc::new(String::from(value)))], ServiceScope::Singleton)
+}
+
+#[test]
+fn service_collection_gets_keyed_services_by_key() {
+    let mut root = ServiceCollection::new_root();
+    root.add(new_string_service("unkeyed"));
+    root.add_keyed("first", new_string_service("first value"));
+    root.add_keyed("second", new_string_service("second value"));
+    let request = ServiceCollection::new(ServiceScope::Request, &root, &root);
+
+    assert_eq!("first value", ServiceCollectionExtensions::get_required_keyed::<String>(&request, "first").as_str());
+    assert_eq!("second value", ServiceCollectionExtensions::get_required_keyed::<String>(&request, "second").as_str());
+    assert!(ServiceCollectionExtensions::try_get_keyed::<String>(&request, "third").is_none());
+    assert_eq!(vec!["first".to_string(), "second".to_string()], ServiceCollectionExtensions::get_keys::<String>(&request));
+
+    // keyed services are not found without their key.
+    assert_eq!(vec!["unkeyed".to_string()], ServiceCollectionExtensions::get_required_multiple::<String>(&request).iter().map(|x| x.to_string()).collect::<Vec<String>>());
+}
+
+#[test]
+fn service_collection_replaces_keyed_service_with_same_key() {
+    let mut root = ServiceCollection::new_root();
+    root.add_keyed("key", new_string_service("old"));
+    root.add_keyed("key", new_string_service("new"));
+
+    assert_eq!("new", ServiceCollectionExtensions::get_required_keyed::<String>(&root, "key").as_str());
+    assert_eq!(1, root.get_items().len());
+}
+
+#[test]
+#[should_panic(expected = "with key 'missing'")]
+fn service_collection_panics_when_required_keyed_service_is_missing() {
+    let mut root = ServiceCollection::new_root();
+    root.add_keyed("key", new_string_service("value"));
+    ServiceCollectionExtensions::get_required_keyed::<String>(&root, "missing");
+}
@@ -0,0 +1,125 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection, ServiceCollectionExtensions};
+use mvc_lib::services::service_descriptor::ServiceDescriptor;
+use mvc_lib::services::service_scope::ServiceScope;
+use mvc_lib::services::service_validator::{ServiceGraphDependency, ServiceValidationError};
+
+
+struct Options {}
+struct Repository { _options: Rc<Options> }
+struct Controller { _repository: Rc<Repository> }
+
+fn new_options(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+    vec![Box::new(Rc::new(Options {}))]
+}
+
+fn new_repository(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+    vec![Box::new(Rc::new(Repository { _options: ServiceCollectionExtensions::get_required_single::<Options>(services) }))]
+}
+
+fn new_controller(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+    vec![Box::new(Rc::new(Controller { _repository: ServiceCollectionExtensions::get_required_single::<Repository>(services) }))]
+}
+
+fn new_services(registrations: &[(Box<TypeInfo>, fn(&dyn IServiceCollection) -> Vec<Box<dyn Any>>, ServiceScope)]) -> ServiceCollection<'static> {
+    let mut services = ServiceCollection::new_root();
+    for (type_info, factory, scope) in registrations.iter() {
+        services.add(ServiceDescriptor::new(type_info.clone(), *factory, *scope));
+    }
+    services
+}
+
+#[test]
+fn service_validator_builds_graph_of_valid_services() {
+    let services = new_services(&[
+        (TypeInfo::rc_of::<Controller>(), new_controller, ServiceScope::Request),
+        (TypeInfo::rc_of::<Repository>(), new_repository, ServiceScope::Singleton),
+        (TypeInfo::rc_of::<Options>(), new_options, ServiceScope::Singleton),
+    ]);
+
+    let result = services.validate();
+    assert!(result.errors.is_none());
+    let graph = result.graph;
+    assert_eq!(3, graph.nodes.len());
+    let controller = graph.nodes.iter().find(|x| x.type_name.contains("Controller")).unwrap();
+    assert_eq!(ServiceScope::Request, controller.scope);
+    assert_eq!(vec![ServiceGraphDependency { type_name: TypeInfo::rc_of::<Repository>().type_name.to_string(), scope: Some(ServiceScope::Singleton) }], controller.dependencies);
+    assert!(graph.to_string().contains("Repository> (Singleton)"));
+
+    // nothing is kept by the collection itself.
+    assert!(services.get_instances().is_empty());
+}
+
+#[test]
+fn service_validator_reports_missing_dependency() {
+    let services = new_services(&[
+        (TypeInfo::rc_of::<Controller>(), new_controller, ServiceScope::Request),
+        (TypeInfo::rc_of::<Repository>(), new_repository, ServiceScope::Singleton),
+    ]);
+
+    let errors = services.validate().errors.unwrap();
+    assert_eq!(vec![ServiceValidationError::MissingDependency {
+        service: TypeInfo::rc_of::<Repository>().type_name.to_string(),
+        dependency: TypeInfo::rc_of::<Options>().type_name.to_string(),
+    }], errors.0);
+}
+
+#[test]
+fn service_validator_reports_cycle() {
+    fn new_options_needing_controller(services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        ServiceCollectionExtensions::get_required_single::<Controller>(services);
+        new_options(services)
+    }
+    let services = new_services(&[
+        (TypeInfo::rc_of::<Controller>(), new_controller, ServiceScope::Singleton),
+        (TypeInfo::rc_of::<Repository>(), new_repository, ServiceScope::Singleton),
+        (TypeInfo::rc_of::<Options>(), new_options_needing_controller, ServiceScope::Singleton),
+    ]);
+
+    let errors = services.validate().errors.unwrap();
+    assert_eq!(1, errors.0.len());
+    match &errors.0[0] {
+        ServiceValidationError::Cycle { services } => {
+            assert_eq!(4, services.len());
+            assert_eq!(services.first(), services.last());
+        },
+        x => panic!("expected a cycle, not {}", x),
+    }
+}
+
+#[test]
+fn service_validator_reports_captive_dependency() {
+    let services = new_services(&[
+        (TypeInfo::rc_of::<Repository>(), new_repository, ServiceScope::Singleton),
+        (TypeInfo::rc_of::<Options>(), new_options, ServiceScope::Request),
+    ]);
+
+    let errors = services.validate().errors.unwrap();
+    assert_eq!(vec![ServiceValidationError::CaptiveDependency {
+        service: TypeInfo::rc_of::<Repository>().type_name.to_string(),
+        scope: ServiceScope::Singleton,
+        dependency: TypeInfo::rc_of::<Options>().type_name.to_string(),
+        dependency_scope: ServiceScope::Request,
+    }], errors.0);
+}
+
+#[test]
+fn service_validator_reports_factory_panic() {
+    fn new_broken_options(_services: &dyn IServiceCollection) -> Vec<Box<dyn Any>> {
+        panic!("options file is missing")
+    }
+    let services = new_services(&[
+        (TypeInfo::rc_of::<Repository>(), new_repository, ServiceScope::Singleton),
+        (TypeInfo::rc_of::<Options>(), new_broken_options, ServiceScope::Singleton),
+    ]);
+
+    let errors = services.validate().errors.unwrap();
+    assert_eq!(vec![ServiceValidationError::FactoryPanicked {
+        service: TypeInfo::rc_of::<Options>().type_name.to_string(),
+        message: "options file is missing".to_string(),
+    }], errors.0);
+    assert!(errors.to_string().starts_with("1 service validation error(s):"));
+}
@@ -0,0 +1,2 @@
+pub mod session_middleware_tests;
+pub mod session_store_tests;
\ No newline at end of file
@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+use std::time::Duration;
+
+use mvc_lib::contexts::irequest_context::{IRequestContext, MockIRequestContext};
+use mvc_lib::contexts::iresponse_context::{IResponseContext, MockIResponseContext};
+use mvc_lib::core::type_info::TypeInfo;
+use mvc_lib::middleware::session_middleware::SessionMiddleware;
+use mvc_lib::options::session_options::SessionOptions;
+use mvc_lib::services::request_middleware_service::{IRequestMiddlewareService, MiddlewareResult};
+use mvc_lib::services::service_collection::{IServiceCollection, ServiceCollection};
+use mvc_lib::session::session_record::SessionRecord;
+use mvc_lib::session::session_state::{ISession, SessionExtensions};
+use mvc_lib::session::session_store::{ISessionStore, InMemorySessionStore};
+
+
+// a middleware that uses the session of the request, as an action would.
+struct SessionUsingMiddleware {
+    action: Box<dyn Fn(&dyn ISession)>,
+}
+
+impl IRequestMiddlewareService for SessionUsingMiddleware {
+    fn get_type_info(&self) -> Box<TypeInfo> {
+        Box::new(TypeInfo::of::<SessionUsingMiddleware>())
+    }
+
+    fn set_next(self: &Self, _next: Option<Rc<dyn IRequestMiddlewareService>>) {
+    }
+
+    fn handle_request(self: &Self, _response_context: &dyn IResponseContext, request_context: &dyn IRequestContext, _services: &dyn IServiceCollection) -> Result<MiddlewareResult, Rc<dyn Error>> {
+        (self.action)(request_context.get_session().unwrap().as_ref());
+        Ok(MiddlewareResult::OkContinue)
+    }
+}
+
+fn new_middleware(store: Rc<InMemorySessionStore>, action: impl Fn(&dyn ISession) + 'static) -> SessionMiddleware {
+    let mut options = SessionOptions::new_defaults();
+    options.idle_timeout = Duration::from_secs(100);
+    let middleware = SessionMiddleware::new(Rc::new(options), store);
+    middleware.set_next(Some(Rc::new(SessionUsingMiddleware { action: Box::new(action) })));
+    middleware
+}
+
+// handle a request with the session cookie, if there is one, and return the Set-Cookie header written, if there was one.
+fn handle_request(middleware: &SessionMiddleware, cookie: Option<String>) -> Option<String> {
+    let session = Rc::new(RefCell::new(None::<Rc<dyn ISession>>));
+    let mut request_context = MockIRequestContext::new();
+    request_context.expect_get_cookies_parsed()
+        .returning(move || cookie.clone().map(|x| HashMap::from([("rshtml.session".to_string(), x)])));
+    request_context.expect_get_scheme().return_const("https".to_string());
+    let set_session = session.clone();
+    request_context.expect_set_session().returning_st(move |x| { set_session.replace(x); });
+    request_context.expect_get_session().returning_st(move || session.borrow().clone());
+
+    let set_cookie = Rc::new(RefCell::new(None));
+    let mut response_context = MockIResponseContext::new();
+    let written = set_cookie.clone();
+    response_context.expect_add_header_string()
+        .withf(|name, _| name == "Set-Cookie")
+        .returning_st(move |_, value| { written.replace(Some(value)); });
+
+    let services = ServiceCollection::new_root();
+    middleware.handle_request(&response_context, &request_context, &services).unwrap();
+    let value = set_cookie.borrow().clone();
+    value
+}
+
+fn get_cookie_value(set_cookie: &str) -> String {
+    set_cookie.split(';').next().unwrap().trim_start_matches("rshtml.session=").to_string()
+}
+
+#[test]
+fn session_middleware_keeps_values_between_requests() {
+    let store = Rc::new(InMemorySessionStore::new());
+    let middleware = new_middleware(store.clone(), |session| {
+        let count = SessionExtensions::get::<i32>(session, "count").unwrap_or(0);
+        SessionExtensions::set(session, "count", count + 1);
+    });
+
+    let set_cookie = handle_request(&middleware, None).unwrap();
+    assert!(set_cookie.ends_with("; Path=/; HttpOnly; SameSite=Lax; Secure"));
+    let id = get_cookie_value(&set_cookie);
+    assert!(SessionMiddleware::is_session_id(&id));
+
+    // the cookie is only written when the session is created.
+    assert_eq!(None, handle_request(&middleware, Some(id.clone())));
+    assert_eq!(Some(serde_json::json!(2)), store.load(&id).unwrap().unwrap().values.get("count").cloned());
+}
+
+#[test]
+fn session_middleware_does_not_save_sessions_without_values() {
+    let store = Rc::new(InMemorySessionStore::new());
+    let middleware = new_middleware(store.clone(), |_| {});
+    assert_eq!(None, handle_request(&middleware, None));
+    assert_eq!(0, store.remove_expired(u64::MAX).unwrap());
+}
+
+#[test]
+fn session_middleware_does_not_reuse_expired_or_unknown_ids() {
+    let store = Rc::new(InMemorySessionStore::new());
+    let middleware = new_middleware(store.clone(), |session| SessionExtensions::set(session, "seen", true));
+
+    let expired_id = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string();
+    assert!(SessionMiddleware::is_session_id(&expired_id));
+    store.save(&SessionRecord::new(expired_id.clone(), 1000, Duration::from_secs(100), Duration::from_secs(100))).unwrap();
+
+    let id = get_cookie_value(&handle_request(&middleware, Some(expired_id.clone())).unwrap());
+    assert_ne!(expired_id, id);
+    assert!(store.load(&expired_id).unwrap().is_none());
+
+    // a value that an id is never made like is not looked up, and a new session is created instead.
+    assert!(!SessionMiddleware::is_session_id("../../authuser_dbset"));
+    assert!(handle_request(&middleware, Some("../../authuser_dbset".to_string())).is_some());
+}
+
+#[test]
+fn session_middleware_removes_abandoned_sessions_and_their_cookie() {
+    let store = Rc::new(InMemorySessionStore::new());
+    let id = get_cookie_value(&handle_request(&new_middleware(store.clone(), |session| SessionExtensions::set(session, "user", "viveret")), None).unwrap());
+    assert!(store.load(&id).unwrap().is_some());
+
+    let set_cookie = handle_request(&new_middleware(store.clone(), |session| session.abandon()), Some(id.clone())).unwrap();
+    assert!(set_cookie.starts_with("rshtml.session=; Path=/; Max-Age=0;"));
+    assert!(store.load(&id).unwrap().is_none());
+}
@@ -0,0 +1,183 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+use std::time::Duration;
+
+use mvc_lib::session::external_session_store::{ExternalSessionStore, IExternalSessionCache};
+use mvc_lib::session::json_file_session_store::JsonFileSessionStore;
+use mvc_lib::session::session_record::SessionRecord;
+use mvc_lib::session::session_state::{ISession, Session, SessionExtensions};
+use mvc_lib::session::session_store::{ISessionStore, InMemorySessionStore};
+
+
+// a cache that keeps entries in memory, standing in for an external cache such as Redis.
+struct TestExternalSessionCache {
+    entries: RefCell<HashMap<String, (String, u64)>>,
+}
+
+impl IExternalSessionCache for TestExternalSessionCache {
+    fn get(self: &Self, key: &str) -> Result<Option<String>, Rc<dyn Error>> {
+        Ok(self.entries.borrow().get(key).map(|x| x.0.clone()))
+    }
+
+    fn set(self: &Self, key: &str, value: String, expires_at: u64) -> Result<(), Rc<dyn Error>> {
+        self.entries.borrow_mut().insert(key.to_string(), (value, expires_at));
+        Ok(())
+    }
+
+    fn remove(self: &Self, key: &str) -> Result<(), Rc<dyn Error>> {
+        self.entries.borrow_mut().remove(key);
+        Ok(())
+    }
+}
+
+fn new_record(id: &str, now: u64) -> SessionRecord {
+    let mut record = SessionRecord::new(id.to_string(), now, Duration::from_secs(100), Duration::from_secs(250));
+    record.values.insert("cart".to_string(), serde_json::json!(["apple", "pear"]));
+    record
+}
+
+fn assert_store_round_trips(store: &dyn ISessionStore) {
+    assert!(store.load("a").unwrap().is_none());
+    store.save(&new_record("a", 1000)).unwrap();
+    store.save(&new_record("b", 2000)).unwrap();
+
+    let mut record = store.load("a").unwrap().unwrap();
+    assert_eq!(serde_json::json!(["apple", "pear"]), record.values["cart"]);
+    assert_eq!(1100, record.expires_at);
+
+    // saving a session again replaces it rather than adding another.
+    record.values.insert("user".to_string(), serde_json::json!("viveret"));
+    store.save(&record).unwrap();
+    assert_eq!(serde_json::json!("viveret"), store.load("a").unwrap().unwrap().values["user"]);
+
+    store.remove("b").unwrap();
+    assert!(store.load("b").unwrap().is_none());
+    assert!(store.load("a").unwrap().is_some());
+}
+
+#[test]
+fn session_record_slides_until_its_absolute_timeout() {
+    let mut record = SessionRecord::new("a".to_string(), 1000, Duration::from_secs(100), Duration::from_secs(250));
+    assert_eq!(1100, record.expires_at);
+    assert!(!record.is_expired(1099));
+    assert!(record.is_expired(1100));
+
+    record.touch(1090, Duration::from_secs(100), Duration::from_secs(250));
+    assert_eq!(1190, record.expires_at);
+    record.touch(1180, Duration::from_secs(100), Duration::from_secs(250));
+    assert_eq!(1250, record.expires_at);
+
+    assert_eq!(record, SessionRecord::parse_json(SessionRecord::to_json(record.clone())));
+    assert_eq!(1180, SessionRecord::parse_json(SessionRecord::to_json(record.clone())).last_accessed_at);
+}
+
+#[test]
+fn session_values_are_typed_with_session_extensions() {
+    let session = Session::new(new_record("a", 1000), false);
+    assert!(!session.is_changed());
+
+    SessionExtensions::set(&session, "count", 3);
+    SessionExtensions::set(&session, "name", "viveret");
+    assert_eq!(Some(3), SessionExtensions::get::<i32>(&session, "count"));
+    assert_eq!(Some("viveret".to_string()), SessionExtensions::get::<String>(&session, "name"));
+    assert_eq!(None, SessionExtensions::get::<i32>(&session, "name"));
+    assert_eq!(None, SessionExtensions::get::<i32>(&session, "missing"));
+    assert!(session.is_changed());
+
+    assert!(session.remove("count").is_some());
+    let mut keys = session.get_keys();
+    keys.sort();
+    assert_eq!(vec!["cart".to_string(), "name".to_string()], keys);
+
+    session.abandon();
+    assert!(session.is_abandoned());
+    assert!(session.get_keys().is_empty());
+}
+
+#[test]
+fn in_memory_session_store_is_shared_by_worker_threads() {
+    assert_store_round_trips(&InMemorySessionStore::new());
+
+    // each worker thread creates its own services, so the shared store must see the sessions saved by other threads.
+    std::thread::spawn(|| InMemorySessionStore::new_shared().save(&new_record("in_memory_shared", 1000)).unwrap()).join().unwrap();
+    assert!(InMemorySessionStore::new_shared().load("in_memory_shared").unwrap().is_some());
+    assert!(InMemorySessionStore::new().load("in_memory_shared").unwrap().is_none());
+
+    let store = InMemorySessionStore::new();
+    store.save(&new_record("a", 1000)).unwrap();
+    store.save(&new_record("b", 2000)).unwrap();
+    assert_eq!(1, store.remove_expired(1500).unwrap());
+    assert!(store.load("a").unwrap().is_none());
+    assert!(store.load("b").unwrap().is_some());
+}
+
+#[test]
+fn json_file_session_store_reads_the_changes_of_other_threads() {
+    let file_path = std::env::temp_dir().join(format!("rshtml_session_{}.json", uuid::Uuid::new_v4().simple()));
+    let file_path = file_path.to_str().unwrap().to_string();
+    let store = JsonFileSessionStore::open(file_path.clone()).unwrap();
+    assert_store_round_trips(&store);
+
+    // another worker thread has its own copy of the dbset, which sees the sessions saved since it was opened.
+    let other_store = JsonFileSessionStore::open(file_path.clone()).unwrap();
+    store.save(&new_record("c", 3000)).unwrap();
+    assert!(other_store.load("c").unwrap().is_some());
+    other_store.remove("c").unwrap();
+    assert!(store.load("c").unwrap().is_none());
+    assert!(store.load("a").unwrap().is_some());
+
+    assert_eq!(1, store.remove_expired(1500).unwrap());
+    assert!(other_store.load("a").unwrap().is_none());
+    std::fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn json_file_session_store_writes_unchanged_sessions_past_the_expiry_threshold() {
+    let file_path = std::env::temp_dir().join(format!("rshtml_session_{}.json", uuid::Uuid::new_v4().simple()));
+    let file_path = file_path.to_str().unwrap().to_string();
+    let store = JsonFileSessionStore::open(file_path.clone()).unwrap().with_expiry_threshold(Duration::from_secs(60));
+    let mut record = new_record("a", 1000);
+    store.save(&record).unwrap();
+
+    // the expiry moved forward by less than the threshold, so the file is not written.
+    record.touch(1030, Duration::from_secs(100), Duration::from_secs(250));
+    store.save(&record).unwrap();
+    assert_eq!(1100, store.load("a").unwrap().unwrap().expires_at);
+
+    record.touch(1070, Duration::from_secs(100), Duration::from_secs(250));
+    store.save(&record).unwrap();
+    assert_eq!(1170, store.load("a").unwrap().unwrap().expires_at);
+
+    // a change to the values is always written.
+    record.values.insert("user".to_string(), serde_json::json!("viveret"));
+    store.save(&record).unwrap();
+    assert_eq!(serde_json::json!("viveret"), store.load("a").unwrap().unwrap().values["user"]);
+    std::fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn json_file_session_store_reports_errors_writing_the_file() {
+    let file_path = std::env::temp_dir().join(format!("rshtml_session_{}.json", uuid::Uuid::new_v4().simple()));
+    let file_path = file_path.to_str().unwrap().to_string();
+    let store = JsonFileSessionStore::open(file_path.clone()).unwrap();
+
+    // a directory where the file was cannot be written over.
+    std::fs::remove_file(&file_path).unwrap();
+    std::fs::create_dir(&file_path).unwrap();
+    assert!(store.save(&new_record("a", 1000)).is_err());
+    std::fs::remove_dir(file_path).unwrap();
+}
+
+#[test]
+fn external_session_store_keeps_sessions_in_the_cache() {
+    let cache = Rc::new(TestExternalSessionCache { entries: RefCell::new(HashMap::new()) });
+    let store = ExternalSessionStore::new(cache.clone(), ExternalSessionStore::DEFAULT_KEY_PREFIX.to_string());
+    assert_store_round_trips(&store);
+
+    // the cache is told when the session expires, so it can remove the entry itself.
+    let entries = cache.entries.borrow();
+    assert_eq!(vec!["rshtml.session:a"], entries.keys().collect::<Vec<&String>>());
+    assert_eq!(1100, entries["rshtml.session:a"].1);
+}
@@ -0,0 +1,41 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use mvc_lib::view::rusthtml::rusthtml_parser::RustHtmlParser;
+
+
+// expand RustHtml into the Rust code that renders it.
+fn expand(html: &str) -> Result<String, String> {
+    let parser = RustHtmlParser::new(false, "test".to_string());
+    let html_tokenstream: TokenStream = html.parse().unwrap();
+    parser.expand_tokenstream(html_tokenstream).map(|x| x.to_string()).map_err(|e| format!("{:?}", e))
+}
+
+#[test]
+fn environment_node_include_wraps_children_in_environment_check() {
+    let expected = quote! {
+        html_output . write_html_str ("<div>") ;
+        if mvc_lib :: services :: host_environment :: IHostEnvironmentExtensions :: is_environment_tag_rendered (services , Some ("Development") , None) {
+            html_output . write_html_str ("<i>Beta</i>") ;
+        }
+        html_output . write_html_str ("</div>") ;
+    };
+
+    assert_eq!(expected.to_string(), expand("<div><environment include=\"Development\"><i>Beta</i></environment></div>").unwrap());
+}
+
+#[test]
+fn environment_node_exclude_wraps_children_in_environment_check() {
+    let expected = quote! {
+        if mvc_lib :: services :: host_environment :: IHostEnvironmentExtensions :: is_environment_tag_rendered (services , None , Some ("Development,Staging")) {
+            html_output . write_html_str ("<b>min</b>") ;
+        }
+    };
+
+    assert_eq!(expected.to_string(), expand("<environment exclude=\"Development,Staging\"><b>min</b></environment>").unwrap());
+}
+
+#[test]
+fn environment_node_without_include_or_exclude_is_an_error() {
+    assert!(expand("<environment names=\"Development\"><b>x</b></environment>").is_err());
+}
@@ -0,0 +1 @@
+pub mod environment_node_tests;
\ No newline at end of file
@@ -0,0 +1,238 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use mvc_lib::controller_actions::route_pattern::ControllerActionRoutePattern;
+
+
+// the attributes that map an action to HTTP methods, and the methods they map to.
+// route maps an action to every method.
+const HTTP_METHOD_ATTRIBUTES: [(&str, Option<&str>); 6] = [
+    ("http_get", Some("GET")),
+    ("http_post", Some("POST")),
+    ("http_put", Some("PUT")),
+    ("http_delete", Some("DELETE")),
+    ("http_patch", Some("PATCH")),
+    ("route", None),
+];
+
+// an action found on a method of the controller.
+struct ControllerActionAttribute {
+    // the route pattern, or None to use the default route for the action.
+    route: Option<String>,
+    // the name of the action, or None to use the name of the method.
+    name: Option<String>,
+    // the HTTP methods allowed, or empty to allow every method.
+    methods: Vec<String>,
+}
+
+// expands #[controller] on an impl block. the #[http_get], #[http_post], #[http_put], #[http_delete], #[http_patch],
+// #[route] and #[authorize] attributes on its methods are removed and turned into the actions of an IController impl,
+// and the controller is submitted to the controller registry so it is added by DefaultServices::add_discovered_controllers.
+pub(crate) fn impl_controller(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut impl_block: syn::ItemImpl = syn::parse2(item)?;
+    let self_ty = impl_block.self_ty.clone();
+    let type_name = match self_ty.as_ref() {
+        syn::Type::Path(path) => path.path.segments.last().map(|x| x.ident.to_string()),
+        _ => None,
+    }.ok_or_else(|| syn::Error::new_spanned(&self_ty, "#[controller] must be used on an impl block for a named type"))?;
+
+    let mut area = String::new();
+    let mut features_fn: Option<syn::Ident> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("area") {
+            area = meta.value()?.parse::<syn::LitStr>()?.value();
+        } else if meta.path.is_ident("features") {
+            features_fn = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+        } else {
+            return Err(meta.error("expected `area` or `features`"));
+        }
+        Ok(())
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+
+    // features for the whole controller.
+    let mut controller_features = vec![];
+    for attr in take_attributes(&mut impl_block.attrs, &["authorize"]) {
+        let feature = parse_authorize(&attr)?;
+        controller_features.push(quote! { features.push(#feature); });
+    }
+    if let Some(features_fn) = features_fn {
+        controller_features.push(quote! { features.extend(Self::#features_fn(self)); });
+    }
+
+    let mut actions = vec![];
+    for impl_item in impl_block.items.iter_mut() {
+        let method = match impl_item {
+            syn::ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+
+        let mut action_attributes: Vec<ControllerActionAttribute> = vec![];
+        for attr in take_attributes(&mut method.attrs, &HTTP_METHOD_ATTRIBUTES.map(|x| x.0)) {
+            let parsed = parse_action_attribute(&attr)?;
+            // methods with the same route are one action that allows each of their methods.
+            match action_attributes.iter_mut().find(|x| x.route == parsed.route && x.name == parsed.name) {
+                Some(existing) if !existing.methods.is_empty() && !parsed.methods.is_empty() => existing.methods.extend(parsed.methods),
+                Some(existing) => existing.methods.clear(),
+                None => action_attributes.push(parsed),
+            }
+        }
+
+        let mut action_features = vec![];
+        for attr in take_attributes(&mut method.attrs, &["authorize"]) {
+            action_features.push(parse_authorize(&attr)?);
+        }
+
+        if action_attributes.is_empty() {
+            if !action_features.is_empty() {
+                return Err(syn::Error::new_spanned(&method.sig.ident, "#[authorize] on a method needs a #[http_get], #[http_post], #[http_put], #[http_delete], #[http_patch] or #[route] attribute"));
+            }
+            continue;
+        }
+
+        let new_fn = get_member_fn_constructor(&method.sig)?;
+        let fn_ident = &method.sig.ident;
+        for action in action_attributes {
+            let name = action.name.unwrap_or_else(|| fn_ident.to_string());
+            let route = match action.route {
+                Some(route) => quote! { #route.into() },
+                None => quote! { mvc_lib::core::string_extensions::action_name_to_path(&controller_name, #name) },
+            };
+            let methods = action.methods;
+            actions.push(quote! {
+                std::rc::Rc::new(mvc_lib::controller_actions::member_fn::ControllerActionMemberFn::#new_fn(
+                    mvc_lib::controllers::controller_registry::ControllerRegistration::parse_methods(&[#(#methods),*]),
+                    Some(vec![#(#action_features),*]),
+                    #route,
+                    #name.into(),
+                    controller_name.clone().into(),
+                    mvc_lib::controllers::icontroller::IController::get_route_area(self),
+                    Box::new(Self::#fn_ident),
+                )) as std::rc::Rc<dyn mvc_lib::controller_actions::controller_action::IControllerAction>
+            });
+        }
+    }
+
+    Ok(quote! {
+        #impl_block
+
+        impl mvc_lib::controllers::icontroller::IController for #self_ty {
+            fn get_route_area(self: &Self) -> String {
+                #area.to_string()
+            }
+
+            fn get_type_name(self: &Self) -> &'static str {
+                #type_name
+            }
+
+            fn get_actions(self: &Self) -> Vec<std::rc::Rc<dyn mvc_lib::controller_actions::controller_action::IControllerAction>> {
+                let controller_name = mvc_lib::controllers::icontroller_extensions::IControllerExtensions::get_name(self);
+                vec![#(#actions),*]
+            }
+
+            fn get_features(self: &Self) -> Vec<std::rc::Rc<dyn mvc_lib::controller_action_features::controller_action_feature::IControllerActionFeature>> {
+                let mut features: Vec<std::rc::Rc<dyn mvc_lib::controller_action_features::controller_action_feature::IControllerActionFeature>> = vec![];
+                #(#controller_features)*
+                features
+            }
+        }
+
+        mvc_lib::inventory::submit! {
+            mvc_lib::controllers::controller_registry::ControllerRegistration::new(#type_name, <#self_ty>::new_service)
+        }
+    })
+}
+
+// remove the attributes with any of the names from a list of attributes and return them.
+fn take_attributes(attrs: &mut Vec<syn::Attribute>, names: &[&str]) -> Vec<syn::Attribute> {
+    let (taken, kept) = attrs.drain(..).partition(|x| names.iter().any(|name| x.path().is_ident(name)));
+    *attrs = kept;
+    taken
+}
+
+// parse an attribute such as #[http_get], #[http_get("/users/{id}")] or #[http_post("/users", name = "add_user")].
+fn parse_action_attribute(attr: &syn::Attribute) -> syn::Result<ControllerActionAttribute> {
+    let method = HTTP_METHOD_ATTRIBUTES.iter().find(|x| attr.path().is_ident(x.0)).and_then(|x| x.1);
+    let mut action = ControllerActionAttribute { route: None, name: None, methods: method.map(|x| vec![x.to_string()]).unwrap_or_default() };
+    if let syn::Meta::Path(_) = attr.meta {
+        return Ok(action);
+    }
+
+    attr.parse_args_with(|input: syn::parse::ParseStream| {
+        if input.peek(syn::LitStr) {
+            let route = input.parse::<syn::LitStr>()?;
+            // report invalid route patterns when compiling instead of when the controller is created.
+            if let Err(err) = ControllerActionRoutePattern::parse(route.value().into()) {
+                return Err(syn::Error::new_spanned(&route, err.to_string()));
+            }
+            action.route = Some(route.value());
+            if input.is_empty() {
+                return Ok(());
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            let value: syn::LitStr = input.parse()?;
+            if key == "name" {
+                action.name = Some(value.value());
+            } else {
+                return Err(syn::Error::new_spanned(key, "expected `name`"));
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(action)
+}
+
+// parse an attribute such as #[authorize], #[authorize(roles = "admin,dev")], #[authorize(policy = "admin")]
+// or #[authorize(schemes = "Bearer")] into an expression that creates the feature.
+fn parse_authorize(attr: &syn::Attribute) -> syn::Result<TokenStream> {
+    let mut roles = String::new();
+    let mut policy = quote! { None };
+    let mut schemes = String::new();
+    if let syn::Meta::List(_) = attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("roles") {
+                roles = meta.value()?.parse::<syn::LitStr>()?.value();
+            } else if meta.path.is_ident("policy") {
+                let value = meta.value()?.parse::<syn::LitStr>()?.value();
+                policy = quote! { Some(#value.to_string()) };
+            } else if meta.path.is_ident("schemes") {
+                schemes = meta.value()?.parse::<syn::LitStr>()?.value();
+            } else {
+                return Err(meta.error("expected `roles`, `policy` or `schemes`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(quote! {
+        std::rc::Rc::new(mvc_lib::controller_action_features::authorize::AuthorizeControllerActionFeature::new(
+            #roles.split(',').map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect(),
+            #policy,
+            None,
+        ).with_schemes(
+            #schemes.split(',').map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect()
+        )) as std::rc::Rc<dyn mvc_lib::controller_action_features::controller_action_feature::IControllerActionFeature>
+    })
+}
+
+// get the ControllerActionMemberFn constructor for the signature of an action method.
+// (self, context, services) is not validated, and (self, model, context, services) is validated,
+// with a typed model unless the model is ModelValidationResult<AnyIModel>.
+fn get_member_fn_constructor(sig: &syn::Signature) -> syn::Result<syn::Ident> {
+    let name = match sig.inputs.len() {
+        3 => "new_not_validated",
+        4 => match sig.inputs.iter().nth(1) {
+            Some(syn::FnArg::Typed(arg)) if quote!(#arg).to_string().replace(' ', "").ends_with("ModelValidationResult<AnyIModel>") => "new_validated",
+            _ => "new_validated_typed",
+        },
+        _ => return Err(syn::Error::new_spanned(sig, "an action takes the controller, the controller context and the services, with the model result after the controller if the model is validated")),
+    };
+    Ok(syn::Ident::new(name, sig.ident.span()))
+}
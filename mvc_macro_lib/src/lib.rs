@@ -10,6 +10,8 @@ use quote::quote;
 
 use mvc_lib::view::rusthtml::rusthtml_parser::RustHtmlParser;
 
+mod controller_macro;
+
 #[proc_macro]
 pub fn rusthtml_macro(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let parser = RustHtmlParser::new(false, "Development".to_string());
@@ -215,4 +217,15 @@ pub fn rc_controller_action_validate(input: proc_macro::TokenStream) -> proc_mac
 #[proc_macro]
 pub fn rc_controller_action_validate_typed(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     rc_controller_action_impl(&Ident::new("new_validated_typed", proc_macro2::Span::call_site()), input.into()).into()
+}
+
+// turns the methods of an impl block marked #[http_get], #[http_post], #[http_put], #[http_delete], #[http_patch] or #[route]
+// into controller actions, and registers the controller so DefaultServices::add_discovered_controllers adds it.
+// usage: #[controller(area = "dev", features = "get_controller_features")] on impl MyController.
+#[proc_macro_attribute]
+pub fn controller(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match controller_macro::impl_controller(attr.into(), item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
\ No newline at end of file